@@ -0,0 +1,70 @@
+//!
+//! Compares `ObjectWriter::write_to_string` with the previous implementation, which wrote into a
+//! `Cursor<Vec<u8>>`, for a small object written many times.
+//!
+//! Run with `cargo bench --bench write_to_string`.
+//!
+
+use objio::ObjectWriter;
+use std::hint::black_box;
+use std::io::{Cursor, Write};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 2_000_000;
+
+#[derive(Debug, Default)]
+struct PointWriter {
+    size_hint: bool,
+}
+
+impl ObjectWriter<(i32, i32)> for PointWriter {
+    type Error = std::io::Error;
+
+    fn write<W>(&self, w: &mut W, object: &(i32, i32)) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        write!(w, "({}, {})", object.0, object.1)
+    }
+
+    fn write_size_hint(&self, _object: &(i32, i32)) -> usize {
+        if self.size_hint {
+            16
+        } else {
+            0
+        }
+    }
+}
+
+fn cursor_write_to_string(writer: &PointWriter, object: &(i32, i32)) -> String {
+    let mut buffer = Cursor::new(Vec::new());
+    writer.write(&mut buffer, object).unwrap();
+    String::from_utf8(buffer.into_inner()).unwrap()
+}
+
+fn measure<F>(name: &str, f: F) -> Duration
+where
+    F: Fn(&(i32, i32)) -> String,
+{
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        let _ = black_box(f(black_box(&(i as i32, -(i as i32)))));
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<28} {:>8.1} ns/iter",
+        elapsed.as_nanos() as f64 / f64::from(ITERATIONS)
+    );
+    elapsed
+}
+
+fn main() {
+    let plain = PointWriter::default();
+    let hinted = PointWriter { size_hint: true };
+
+    let _ = measure("cursor (previous)", |p| cursor_write_to_string(&plain, p));
+    let _ = measure("write_to_string", |p| plain.write_to_string(p).unwrap());
+    let _ = measure("write_to_string (size hint)", |p| {
+        hinted.write_to_string(p).unwrap()
+    });
+}
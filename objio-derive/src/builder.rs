@@ -0,0 +1,128 @@
+/*!
+Implementation of the `OptionsBuilder` and `ObjectBuilder` derives.
+ */
+
+use crate::common::{check_flags, field_flags, name_values, named_fields};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Type};
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`OptionsBuilder` may not be derived for generic types",
+        ));
+    }
+    expand_builder(input, "OptionsBuilder", "options")
+}
+
+///
+/// Expand the `ObjectBuilder` derive which, unlike `OptionsBuilder`, supports generic types and
+/// skips `PhantomData` fields, as commonly used by readers and writers.
+///
+pub(crate) fn expand_object(input: DeriveInput) -> syn::Result<TokenStream> {
+    expand_builder(input, "ObjectBuilder", "value")
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn expand_builder(input: DeriveInput, derive_name: &str, built: &str) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let builder_name = format_ident!("{name}Builder");
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut validators = Vec::new();
+    for (key, value) in name_values(&input.attrs, "builder")? {
+        if key == "validate" {
+            validators.push(quote! {
+                #value(&self.inner)?;
+            });
+        } else {
+            return Err(syn::Error::new_spanned(
+                key,
+                "unknown key, expected: validate",
+            ));
+        }
+    }
+
+    let mut setters = Vec::new();
+    for field in named_fields(&input, derive_name)? {
+        let flags = field_flags(field, "builder")?;
+        check_flags(&flags, &["skip"])?;
+        if flags.iter().any(|f| f == "skip") || is_phantom_data(&field.ty) {
+            continue;
+        }
+        let field_name = field.ident.as_ref().unwrap();
+        let field_type = &field.ty;
+        let doc = format!("Set the value of the field `{field_name}`.");
+        setters.push(quote! {
+            #[doc = #doc]
+            pub fn #field_name<V>(self, value: V) -> Self
+            where
+                V: ::std::convert::Into<#field_type>,
+            {
+                let mut self_mut = self;
+                self_mut.inner.#field_name = value.into();
+                self_mut
+            }
+        });
+    }
+
+    let builder_doc = format!("A builder for instances of `{name}`, starting from the default.");
+    let builder_name_str = builder_name.to_string();
+    let build_doc = format!("Validate, and return, the {built} constructed by this builder.");
+    Ok(quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder_name #generics #where_clause {
+            inner: #name #ty_generics,
+        }
+
+        impl #impl_generics ::std::fmt::Debug for #builder_name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(#builder_name_str).finish_non_exhaustive()
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns a new builder, starting from the default value of this type.
+            #vis fn builder() -> #builder_name #ty_generics
+            where
+                Self: ::std::default::Default,
+            {
+                #builder_name {
+                    inner: ::std::default::Default::default(),
+                }
+            }
+        }
+
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            #(#setters)*
+
+            #[doc = #build_doc]
+            pub fn build(self) -> ::std::result::Result<#name #ty_generics, ::objio::Error> {
+                #(#validators)*
+                ::std::result::Result::Ok(self.inner)
+            }
+        }
+    })
+}
+
+fn is_phantom_data(field_type: &Type) -> bool {
+    match field_type {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData"),
+        _ => false,
+    }
+}
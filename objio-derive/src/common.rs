@@ -0,0 +1,112 @@
+/*!
+Helpers shared by the derive implementations.
+ */
+
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Data, DeriveInput, Expr, Field, Fields, Ident, Meta, Token};
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the named fields of the struct described by `input`, or an error spanning the input
+/// if it is not a struct with named fields.
+///
+pub(crate) fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derive_name: &str,
+) -> syn::Result<Vec<&'a Field>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().collect()),
+            _ => Err(unsupported(input, derive_name)),
+        },
+        _ => Err(unsupported(input, derive_name)),
+    }
+}
+
+///
+/// Returns the list of identifiers within all attributes named `attribute_name` on `field`, for
+/// example `#[merge(nested, skip)]` returns `nested` and `skip`.
+///
+pub(crate) fn field_flags(field: &Field, attribute_name: &str) -> syn::Result<Vec<Ident>> {
+    let mut flags = Vec::new();
+    for meta in attribute_metas(&field.attrs, attribute_name)? {
+        match meta {
+            Meta::Path(path) if path.get_ident().is_some() => {
+                flags.push(path.get_ident().unwrap().clone())
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    format!("unsupported `{attribute_name}` attribute"),
+                ))
+            }
+        }
+    }
+    Ok(flags)
+}
+
+///
+/// Returns the list of `name = value` pairs within all attributes named `attribute_name` in
+/// `attributes`, for example `#[builder(validate = check)]` returns `validate` and `check`.
+///
+pub(crate) fn name_values(
+    attributes: &[Attribute],
+    attribute_name: &str,
+) -> syn::Result<Vec<(Ident, Expr)>> {
+    let mut pairs = Vec::new();
+    for meta in attribute_metas(attributes, attribute_name)? {
+        match meta {
+            Meta::NameValue(pair) if pair.path.get_ident().is_some() => {
+                pairs.push((pair.path.get_ident().unwrap().clone(), pair.value))
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    format!("unsupported `{attribute_name}` attribute"),
+                ))
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+///
+/// Returns an error if any flag in `flags` is not one of `allowed`.
+///
+pub(crate) fn check_flags(flags: &[Ident], allowed: &[&str]) -> syn::Result<()> {
+    match flags.iter().find(|f| !allowed.iter().any(|a| f == a)) {
+        None => Ok(()),
+        Some(flag) => Err(syn::Error::new_spanned(
+            flag,
+            format!(
+                "unknown flag `{flag}`, expected one of: {}",
+                allowed.join(", ")
+            ),
+        )),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn attribute_metas(attributes: &[Attribute], attribute_name: &str) -> syn::Result<Vec<Meta>> {
+    let mut metas = Vec::new();
+    for attribute in attributes
+        .iter()
+        .filter(|a| a.path().is_ident(attribute_name))
+    {
+        metas.extend(attribute.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?);
+    }
+    Ok(metas)
+}
+
+fn unsupported(input: &DeriveInput, derive_name: &str) -> syn::Error {
+    syn::Error::new_spanned(
+        &input.ident,
+        format!("`{derive_name}` may only be derived for structs with named fields"),
+    )
+}
@@ -0,0 +1,101 @@
+/*!
+Implementation of the `DescribeOptions` derive.
+ */
+
+use crate::common::{check_flags, field_flags, named_fields};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{Attribute, DeriveInput, Expr, ExprLit, Lit, Meta, Type};
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`DescribeOptions` may not be derived for generic types",
+        ));
+    }
+
+    let mut statements = Vec::new();
+    for field in named_fields(&input, "DescribeOptions")? {
+        let flags = field_flags(field, "describe")?;
+        check_flags(&flags, &["nested", "skip"])?;
+        if flags.iter().any(|f| f == "skip") {
+            continue;
+        }
+        let field_name = field.ident.as_ref().unwrap();
+        let field_name_str = field_name.to_string();
+        let field_type = &field.ty;
+        if flags.iter().any(|f| f == "nested") {
+            statements.push(quote! {
+                descriptors.extend(
+                    <#field_type as ::objio::options::DescribeOptions>::describe_options()
+                        .into_iter()
+                        .map(|descriptor| descriptor.with_prefix(#field_name_str)),
+                );
+            });
+        } else {
+            let type_name = type_name(field_type);
+            let documentation = documentation(&field.attrs);
+            statements.push(quote! {
+                descriptors.push(::objio::options::OptionDescriptor::new(
+                    #field_name_str,
+                    #type_name,
+                    ::std::format!("{:?}", defaults.#field_name),
+                    #documentation,
+                ));
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl ::objio::options::DescribeOptions for #name {
+            #[allow(unused_variables)]
+            fn describe_options() -> ::std::vec::Vec<::objio::options::OptionDescriptor> {
+                let defaults = <Self as ::std::default::Default>::default();
+                let mut descriptors = ::std::vec::Vec::new();
+                #(#statements)*
+                descriptors
+            }
+        }
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn type_name(field_type: &Type) -> String {
+    field_type
+        .to_token_stream()
+        .to_string()
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace(" :: ", "::")
+        .replace("& ", "&")
+}
+
+fn documentation(attributes: &[Attribute]) -> String {
+    attributes
+        .iter()
+        .filter_map(|attribute| match &attribute.meta {
+            Meta::NameValue(pair) if pair.path.is_ident("doc") => match &pair.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }) => Some(value.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,169 @@
+/*!
+This crate provides derive macros for the traits in the [objio](https://docs.rs/objio) crate.
+
+These macros are re-exported by `objio` when its `derive` feature is enabled, and they generate
+paths relative to `::objio`; this crate is not intended to be used directly.
+ */
+
+#![warn(
+    unknown_lints,
+    // ---------- Stylistic
+    absolute_paths_not_starting_with_crate,
+    elided_lifetimes_in_paths,
+    explicit_outlives_requirements,
+    macro_use_extern_crate,
+    nonstandard_style, /* group */
+    noop_method_call,
+    rust_2018_idioms,
+    single_use_lifetimes,
+    trivial_casts,
+    trivial_numeric_casts,
+    // ---------- Future
+    future_incompatible, /* group */
+    rust_2021_compatibility, /* group */
+    // ---------- Public
+    missing_debug_implementations,
+    // missing_docs,
+    unreachable_pub,
+    // ---------- Unsafe
+    unsafe_code,
+    unsafe_op_in_unsafe_fn,
+    // ---------- Unused
+    unused, /* group */
+)]
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+mod builder;
+
+mod common;
+
+mod describe;
+
+mod merge;
+
+mod option_strings;
+
+mod options_env;
+
+// ------------------------------------------------------------------------------------------------
+// Public Macros
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Derive an implementation of `objio::options::MergeOptions` for a struct with named fields.
+///
+/// This generates an overlay struct, named by appending `Overlay` to the struct's name, with the
+/// same visibility as the struct and a public `Option` field for each field of the struct. The
+/// following field attributes are supported:
+///
+/// * `#[merge(nested)]` the field's type itself implements `MergeOptions`, and the overlay field
+///   has the type of its overlay rather than an `Option`.
+/// * `#[merge(skip)]` the field is not included in the overlay.
+///
+#[proc_macro_derive(MergeOptions, attributes(merge))]
+pub fn derive_merge_options(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    merge::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+///
+/// Derive an implementation of `objio::options::DescribeOptions` for a struct with named fields
+/// that implements `Default`.
+///
+/// Each field is described by its name, its type as written, the `Debug` representation of its
+/// value in the default instance of the struct, and its documentation comment. The following
+/// field attributes are supported:
+///
+/// * `#[describe(nested)]` the field's type itself implements `DescribeOptions`, and its options
+///   are included with names prefixed by the field name, as in `style.color`.
+/// * `#[describe(skip)]` the field is not described.
+///
+#[proc_macro_derive(DescribeOptions, attributes(describe))]
+pub fn derive_describe_options(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    describe::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+///
+/// Derive an implementation of `objio::options::FromOptionStrings` for a struct with named
+/// fields.
+///
+/// Each field is identified by its name, and its value is parsed with `FromStr`; the error type
+/// of the parse must convert into `objio::BoxedError`. The following field attributes are
+/// supported:
+///
+/// * `#[option_strings(nested)]` the field's type itself implements `FromOptionStrings`, and its
+///   options are identified by keys prefixed by the field name, as in `style.color`.
+/// * `#[option_strings(skip)]` the field may not be set, its key is reported as unknown.
+///
+#[proc_macro_derive(FromOptionStrings, attributes(option_strings))]
+pub fn derive_from_option_strings(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    option_strings::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+///
+/// Derive an implementation of `objio::options::OptionsFromEnv` for a type that also implements
+/// `objio::options::FromOptionStrings`.
+///
+/// The prefix of environment variables is set with the attribute
+/// `#[options_env(prefix = "...")]` on the type; if not set, the type name in upper snake case is
+/// used, for example `WriterOptions` has the prefix `WRITER_OPTIONS`.
+///
+#[proc_macro_derive(OptionsFromEnv, attributes(options_env))]
+pub fn derive_options_from_env(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    options_env::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+///
+/// Derive a fluent builder for a struct with named fields that implements `Default`.
+///
+/// This generates a builder struct, named by appending `Builder` to the struct's name, with the
+/// same visibility as the struct, along with an associated function `builder()` on the struct
+/// itself. The builder has a setter method for each field, accepting any value convertible into
+/// the field's type, and a `build()` method returning `Result<Self, objio::Error>`. The
+/// following attributes are supported:
+///
+/// * `#[builder(validate = path)]` on the struct, the function at `path`, with the signature
+///   `fn(&Self) -> Result<(), objio::Error>`, is called by `build()` before returning; this
+///   attribute may be repeated.
+/// * `#[builder(skip)]` on a field, no setter is generated for the field.
+///
+#[proc_macro_derive(OptionsBuilder, attributes(builder))]
+pub fn derive_options_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    builder::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+///
+/// Derive a fluent builder for a reader or writer struct with named fields, such as
+/// `MyWriter::builder().options(options).pretty(true).build()`.
+///
+/// The generated builder is as for [`OptionsBuilder`](macro@OptionsBuilder), and supports the same
+/// attributes, but the struct may be generic, requiring only that it implement `Default` for
+/// `builder()` to be available, and fields of type `PhantomData` have no setter.
+///
+#[proc_macro_derive(ObjectBuilder, attributes(builder))]
+pub fn derive_object_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    builder::expand_object(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
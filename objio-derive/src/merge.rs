@@ -0,0 +1,92 @@
+/*!
+Implementation of the `MergeOptions` derive.
+ */
+
+use crate::common::{check_flags, field_flags, named_fields};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let overlay_name = format_ident!("{name}Overlay");
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`MergeOptions` may not be derived for generic types",
+        ));
+    }
+
+    let mut overlay_fields = Vec::new();
+    let mut merge_statements = Vec::new();
+    let mut overlay_statements = Vec::new();
+    for field in named_fields(&input, "MergeOptions")? {
+        let flags = field_flags(field, "merge")?;
+        check_flags(&flags, &["nested", "skip"])?;
+        if flags.iter().any(|f| f == "skip") {
+            continue;
+        }
+        let field_name = field.ident.as_ref().unwrap();
+        let field_type = &field.ty;
+        let doc = format!("Overlay value for the field `{field_name}`.");
+        if flags.iter().any(|f| f == "nested") {
+            overlay_fields.push(quote! {
+                #[doc = #doc]
+                pub #field_name: <#field_type as ::objio::options::MergeOptions>::Overlay
+            });
+            merge_statements.push(quote! {
+                ::objio::options::MergeOptions::merge(&mut self.#field_name, overlay.#field_name);
+            });
+            overlay_statements.push(quote! {
+                #field_name: ::objio::options::OptionsOverlay::overlay(
+                    self.#field_name,
+                    other.#field_name,
+                )
+            });
+        } else {
+            overlay_fields.push(quote! {
+                #[doc = #doc]
+                pub #field_name: ::std::option::Option<#field_type>
+            });
+            merge_statements.push(quote! {
+                if let ::std::option::Option::Some(value) = overlay.#field_name {
+                    self.#field_name = value;
+                }
+            });
+            overlay_statements.push(quote! {
+                #field_name: other.#field_name.or(self.#field_name)
+            });
+        }
+    }
+
+    let overlay_doc = format!("A partial set of values to be merged into an instance of `{name}`.");
+    Ok(quote! {
+        #[doc = #overlay_doc]
+        #[derive(Clone, Debug, Default)]
+        #vis struct #overlay_name {
+            #(#overlay_fields,)*
+        }
+
+        impl ::objio::options::OptionsOverlay for #overlay_name {
+            fn overlay(self, other: Self) -> Self {
+                Self {
+                    #(#overlay_statements,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::objio::options::MergeOptions for #name #type_generics #where_clause {
+            type Overlay = #overlay_name;
+
+            fn merge(&mut self, overlay: Self::Overlay) {
+                #(#merge_statements)*
+            }
+        }
+    })
+}
@@ -0,0 +1,74 @@
+/*!
+Implementation of the `FromOptionStrings` derive.
+ */
+
+use crate::common::{check_flags, field_flags, named_fields};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`FromOptionStrings` may not be derived for generic types",
+        ));
+    }
+
+    let mut arms = Vec::new();
+    let mut nested = Vec::new();
+    for field in named_fields(&input, "FromOptionStrings")? {
+        let flags = field_flags(field, "option_strings")?;
+        check_flags(&flags, &["nested", "skip"])?;
+        if flags.iter().any(|f| f == "skip") {
+            continue;
+        }
+        let field_name = field.ident.as_ref().unwrap();
+        let field_name_str = field_name.to_string();
+        if flags.iter().any(|f| f == "nested") {
+            let prefix = format!("{field_name_str}.");
+            nested.push(quote! {
+                if let ::std::option::Option::Some(nested_key) = normalized.strip_prefix(#prefix) {
+                    return ::objio::options::FromOptionStrings::set_option(
+                        &mut self.#field_name,
+                        nested_key,
+                        value,
+                    )
+                    .map_err(|e| e.with_option_prefix(#field_name_str));
+                }
+            });
+        } else {
+            arms.push(quote! {
+                #field_name_str => {
+                    self.#field_name = value
+                        .parse()
+                        .map_err(|e| ::objio::Error::invalid_option_value(key, value, e))?;
+                }
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl ::objio::options::FromOptionStrings for #name {
+            fn set_option(
+                &mut self,
+                key: &str,
+                value: &str,
+            ) -> ::std::result::Result<(), ::objio::Error> {
+                let normalized = key.replace('-', "_");
+                #(#nested)*
+                match normalized.as_str() {
+                    #(#arms)*
+                    _ => return ::std::result::Result::Err(::objio::Error::unknown_option(key)),
+                }
+                #[allow(unreachable_code)]
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
@@ -0,0 +1,62 @@
+/*!
+Implementation of the `OptionsFromEnv` derive.
+ */
+
+use crate::common::name_values;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Expr, ExprLit, Lit};
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut prefix = upper_snake_case(&name.to_string());
+    for (key, value) in name_values(&input.attrs, "options_env")? {
+        match (key.to_string().as_str(), value) {
+            (
+                "prefix",
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }),
+            ) => prefix = value.value(),
+            ("prefix", value) => {
+                return Err(syn::Error::new_spanned(value, "expected a string literal"))
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "unknown key, expected: prefix",
+                ))
+            }
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::objio::options::OptionsFromEnv for #name #type_generics #where_clause {
+            fn env_prefix() -> &'static str {
+                #prefix
+            }
+        }
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn upper_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.extend(c.to_uppercase());
+    }
+    result
+}
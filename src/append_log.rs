@@ -0,0 +1,441 @@
+/*!
+Provides [`AppendLogWriter`], which appends serialized objects as framed records to a log file,
+such as an event log or write-ahead journal, with a configurable [`SyncPolicy`].
+
+Records are framed either with the 4-byte length prefix of the [`framing`](crate::framing)
+module or by a delimiter byte, as described by [`RecordFraming`]. Each record is written to the
+file with a single call, but a crash may still leave the final record incomplete; opening a log
+performs a recovery scan which finds the end of the last complete record and truncates any torn
+record that follows it, reporting what was found as a [`LogScan`]. The function [`read_log`]
+reads the complete records of a log, ignoring a torn final record.
+
+# Example
+
+```rust
+use objio::append_log::{read_log, AppendLogWriter, RecordFraming, SyncPolicy};
+use objio::raw::{RawReader, RawWriter};
+
+let path = std::env::temp_dir().join(format!("objio-log-doc-{}.log", std::process::id()));
+# let _ = std::fs::remove_file(&path);
+let mut log = AppendLogWriter::open(&path, RawWriter, RecordFraming::Delimited(b'\n'))
+    .unwrap()
+    .with_sync_policy(SyncPolicy::EveryRecords(2));
+log.append(&"started".to_string()).unwrap();
+log.append(&"stopped".to_string()).unwrap();
+assert_eq!(log.records(), 2);
+
+let events: Vec<String> = read_log(&RawReader, &path, RecordFraming::Delimited(b'\n')).unwrap();
+assert_eq!(events, vec!["started", "stopped"]);
+# std::fs::remove_file(&path).unwrap();
+```
+ */
+
+use crate::framing::FRAME_HEADER_LEN;
+use crate::{ObjectReader, ObjectWriter};
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Read, Write};
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The framing of each record in a log.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RecordFraming {
+    /// Each record is preceded by its length, as a 4-byte big-endian value.
+    #[default]
+    Length,
+    /// Each record is followed by the delimiter byte, which must not appear within a record.
+    Delimited(u8),
+}
+
+///
+/// Determines when an [`AppendLogWriter`] forces appended records to durable storage.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SyncPolicy {
+    /// Never sync explicitly, leaving it to the operating system or to calls to
+    /// [`AppendLogWriter::sync`].
+    Never,
+    /// Sync after every record.
+    #[default]
+    EveryRecord,
+    /// Sync after every given number of records.
+    EveryRecords(usize),
+}
+
+///
+/// The result of the recovery scan of a log.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LogScan {
+    records: usize,
+    valid_len: u64,
+    torn_len: u64,
+}
+
+///
+/// A writer that appends records to a log file.
+///
+#[derive(Debug)]
+pub struct AppendLogWriter<O> {
+    inner: O,
+    file: File,
+    framing: RecordFraming,
+    sync_policy: SyncPolicy,
+    recovery: LogScan,
+    records: usize,
+    unsynced: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+enum Record {
+    Complete(Vec<u8>),
+    Torn(u64),
+    End,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Scan the log read from `r`, returning the number of complete records and the length of any
+/// torn record that follows them.
+///
+pub fn scan_log<R>(r: &mut R, framing: RecordFraming) -> Result<LogScan, IoError>
+where
+    R: BufRead,
+{
+    let mut scan = LogScan::default();
+    loop {
+        match read_record(r, framing)? {
+            Record::Complete(record) => {
+                scan.records += 1;
+                scan.valid_len += framed_len(record.len(), framing);
+            }
+            Record::Torn(len) => {
+                scan.torn_len = len;
+                return Ok(scan);
+            }
+            Record::End => return Ok(scan),
+        }
+    }
+}
+
+///
+/// Read the complete records of the log at `path` using `reader`, ignoring a torn final record.
+///
+pub fn read_log<R, T, P>(reader: &R, path: P, framing: RecordFraming) -> Result<Vec<T>, R::Error>
+where
+    R: ObjectReader<T> + ?Sized,
+    P: AsRef<Path>,
+{
+    reader.validate_options()?;
+    let file = OpenOptions::new().read(true).open(path.as_ref())?;
+    let mut file = BufReader::new(file);
+    let mut objects = Vec::new();
+    while let Record::Complete(record) = read_record(&mut file, framing)? {
+        objects.push(reader.read_from_bytes(record)?);
+    }
+    Ok(objects)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for RecordFraming {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Length => write!(f, "length-prefixed"),
+            Self::Delimited(delimiter) => write!(f, "delimited by {delimiter:#04x}"),
+        }
+    }
+}
+
+impl Display for SyncPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Never => write!(f, "never"),
+            Self::EveryRecord => write!(f, "every record"),
+            Self::EveryRecords(n) => write!(f, "every {n} records"),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl LogScan {
+    ///
+    /// Returns the number of complete records.
+    ///
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    ///
+    /// Returns the length, in bytes, of the complete records.
+    ///
+    pub fn valid_len(&self) -> u64 {
+        self.valid_len
+    }
+
+    ///
+    /// Returns the length, in bytes, of the torn record following the complete records, or zero.
+    ///
+    pub fn torn_len(&self) -> u64 {
+        self.torn_len
+    }
+
+    ///
+    /// Returns `true` if the log ends with a torn record.
+    ///
+    pub fn is_torn(&self) -> bool {
+        self.torn_len > 0
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O> AppendLogWriter<O> {
+    ///
+    /// Open, or create, the log at `path`, recovering it by truncating any torn final record,
+    /// and append records serialized by `inner` with the given framing.
+    ///
+    pub fn open<P>(path: P, inner: O, framing: RecordFraming) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path.as_ref())?;
+        let recovery = scan_log(&mut BufReader::new(&mut file), framing)?;
+        if recovery.is_torn() {
+            file.set_len(recovery.valid_len)?;
+            file.sync_all()?;
+        }
+        Ok(Self {
+            inner,
+            file,
+            framing,
+            sync_policy: SyncPolicy::default(),
+            recovery,
+            records: recovery.records,
+            unsynced: 0,
+        })
+    }
+
+    ///
+    /// Set the policy determining when appended records are synced.
+    ///
+    pub fn with_sync_policy(self, sync_policy: SyncPolicy) -> Self {
+        let mut self_mut = self;
+        self_mut.sync_policy = sync_policy;
+        self_mut
+    }
+
+    ///
+    /// Returns the policy determining when appended records are synced.
+    ///
+    pub fn sync_policy(&self) -> SyncPolicy {
+        self.sync_policy
+    }
+
+    ///
+    /// Returns the framing of each record.
+    ///
+    pub fn framing(&self) -> RecordFraming {
+        self.framing
+    }
+
+    ///
+    /// Returns the result of the recovery scan performed when the log was opened.
+    ///
+    pub fn recovery(&self) -> LogScan {
+        self.recovery
+    }
+
+    ///
+    /// Returns the number of records in the log, including those appended by this writer.
+    ///
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    ///
+    /// Returns the writer used to serialize each record.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Serialize `object` and append it to the log as a single record, syncing according to the
+    /// sync policy. A delimited record that contains the delimiter is an error of kind
+    /// `InvalidInput`, and nothing is appended.
+    ///
+    pub fn append<T>(&mut self, object: &T) -> Result<(), O::Error>
+    where
+        O: ObjectWriter<T>,
+    {
+        let record = self.inner.write_to_bytes(object)?;
+        let mut framed = Vec::with_capacity(framed_len(record.len(), self.framing) as usize);
+        match self.framing {
+            RecordFraming::Length => crate::framing::write_frame(&mut framed, &record)?,
+            RecordFraming::Delimited(delimiter) => {
+                if record.contains(&delimiter) {
+                    return Err(IoError::new(
+                        ErrorKind::InvalidInput,
+                        format!("the record contains the delimiter {delimiter:#04x}"),
+                    )
+                    .into());
+                }
+                framed.extend_from_slice(&record);
+                framed.push(delimiter);
+            }
+        }
+        self.file.write_all(&framed)?;
+        self.records += 1;
+        self.unsynced += 1;
+        let sync = match self.sync_policy {
+            SyncPolicy::Never => false,
+            SyncPolicy::EveryRecord => true,
+            SyncPolicy::EveryRecords(n) => self.unsynced >= n,
+        };
+        if sync {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Force all appended records to durable storage.
+    ///
+    pub fn sync(&mut self) -> Result<(), IoError> {
+        self.file.sync_data()?;
+        self.unsynced = 0;
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn framed_len(len: usize, framing: RecordFraming) -> u64 {
+    match framing {
+        RecordFraming::Length => (FRAME_HEADER_LEN + len) as u64,
+        RecordFraming::Delimited(_) => (len + 1) as u64,
+    }
+}
+
+fn read_record<R>(r: &mut R, framing: RecordFraming) -> Result<Record, IoError>
+where
+    R: BufRead,
+{
+    match framing {
+        RecordFraming::Length => {
+            let mut header = Vec::with_capacity(FRAME_HEADER_LEN);
+            let _ = r
+                .by_ref()
+                .take(FRAME_HEADER_LEN as u64)
+                .read_to_end(&mut header)?;
+            if header.is_empty() {
+                return Ok(Record::End);
+            } else if header.len() < FRAME_HEADER_LEN {
+                return Ok(Record::Torn(header.len() as u64));
+            }
+            let length = u32::from_be_bytes(header.try_into().expect("header length checked"));
+            let mut record = Vec::new();
+            let _ = r.by_ref().take(length as u64).read_to_end(&mut record)?;
+            if record.len() < length as usize {
+                Ok(Record::Torn(framed_len(record.len(), framing)))
+            } else {
+                Ok(Record::Complete(record))
+            }
+        }
+        RecordFraming::Delimited(delimiter) => {
+            let mut record = Vec::new();
+            let _ = r.read_until(delimiter, &mut record)?;
+            match record.pop() {
+                None => Ok(Record::End),
+                Some(last) if last == delimiter => Ok(Record::Complete(record)),
+                Some(_) => Ok(Record::Torn(record.len() as u64 + 1)),
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::{RawReader, RawWriter};
+
+    #[test]
+    fn test_torn_length_record() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("length");
+        {
+            let mut log = AppendLogWriter::open(&path, RawWriter, RecordFraming::Length).unwrap();
+            log.append(&vec![1u8, 2, 3]).unwrap();
+            log.append(&Vec::new()).unwrap();
+            log.append(&vec![4u8, 5]).unwrap();
+        }
+        let full_len = std::fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(full_len - 1)
+            .unwrap();
+
+        let records: Vec<Vec<u8>> = read_log(&RawReader, &path, RecordFraming::Length).unwrap();
+        assert_eq!(records, vec![vec![1, 2, 3], vec![]]);
+
+        let mut log = AppendLogWriter::open(&path, RawWriter, RecordFraming::Length).unwrap();
+        assert_eq!(log.recovery().records(), 2);
+        assert_eq!(log.recovery().torn_len(), 5);
+        log.append(&vec![6u8]).unwrap();
+        let records: Vec<Vec<u8>> = read_log(&RawReader, &path, RecordFraming::Length).unwrap();
+        assert_eq!(records, vec![vec![1, 2, 3], vec![], vec![6]]);
+    }
+
+    #[test]
+    fn test_torn_delimited_record() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("delimited");
+        std::fs::write(&path, b"one\ntwo\nthr").unwrap();
+        let framing = RecordFraming::Delimited(b'\n');
+
+        let mut log = AppendLogWriter::open(&path, RawWriter, framing)
+            .unwrap()
+            .with_sync_policy(SyncPolicy::Never);
+        assert_eq!(
+            log.recovery(),
+            LogScan {
+                records: 2,
+                valid_len: 8,
+                torn_len: 3
+            }
+        );
+        let error = log.append(&"a\nb".to_string()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        log.append(&"three".to_string()).unwrap();
+        log.sync().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"one\ntwo\nthree\n");
+    }
+}
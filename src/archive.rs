@@ -0,0 +1,247 @@
+/*!
+Provides [`ArchivePath`], enabled by the `zip` feature, which identifies a file within a ZIP
+archive so that it may be read without first extracting it.
+
+An archive path is written as the path of the archive and the name of the entry separated by
+`!`, as in `data.zip!subdir/file.ttl`. With this feature enabled
+[`ObjectReader::read_from_file`], and `read_from_file_or_default`, accept such paths, falling back
+to the archive only when no file exists at the literal path, so file names containing `!` continue
+to work; the entry is streamed, decompressing as it goes, into the reader.
+
+# Example
+
+```rust
+use objio::archive::ArchivePath;
+
+let path: ArchivePath = "data.zip!subdir/file.ttl".parse().unwrap();
+assert_eq!(path.archive().to_str(), Some("data.zip"));
+assert_eq!(path.entry(), "subdir/file.ttl");
+assert_eq!(path.to_string(), "data.zip!subdir/file.ttl");
+```
+ */
+
+use crate::ObjectReader;
+use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::{BufReader, Error as IoError, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The character separating the path of an archive from the name of an entry within it.
+///
+pub const ARCHIVE_PATH_SEPARATOR: char = '!';
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The path of an entry within a ZIP archive.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArchivePath {
+    archive: PathBuf,
+    entry: String,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for ArchivePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{ARCHIVE_PATH_SEPARATOR}{}",
+            self.archive.display(),
+            self.entry
+        )
+    }
+}
+
+impl FromStr for ArchivePath {
+    type Err = IoError;
+
+    ///
+    /// Parse an archive path, splitting at the first separator; use [`ArchivePath::detect`] to
+    /// find the archive when the directories in its path may also contain the separator.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(ARCHIVE_PATH_SEPARATOR) {
+            Some((archive, entry)) => Self::new(archive, entry),
+            None => Err(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("the path `{s}` does not name an entry within an archive"),
+            )),
+        }
+    }
+}
+
+impl ArchivePath {
+    ///
+    /// Construct a new path to `entry` within `archive`; leading `/` characters are removed from
+    /// the entry name, which may not then be empty.
+    ///
+    pub fn new<P, S>(archive: P, entry: S) -> Result<Self, IoError>
+    where
+        P: Into<PathBuf>,
+        S: AsRef<str>,
+    {
+        let archive = archive.into();
+        let entry = entry.as_ref().trim_start_matches('/');
+        if archive.as_os_str().is_empty() || entry.is_empty() {
+            Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "an archive path requires both an archive and an entry name",
+            ))
+        } else {
+            Ok(Self {
+                archive,
+                entry: entry.to_string(),
+            })
+        }
+    }
+
+    ///
+    /// Returns the archive path named by `path` if no file exists at `path` itself and some
+    /// prefix of it, ending before a separator, names an existing file.
+    ///
+    pub fn detect<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let s = path.to_str()?;
+        if !s.contains(ARCHIVE_PATH_SEPARATOR) || path.exists() {
+            return None;
+        }
+        s.match_indices(ARCHIVE_PATH_SEPARATOR)
+            .map(|(i, _)| (&s[..i], &s[i + 1..]))
+            .find(|(archive, _)| Path::new(archive).is_file())
+            .and_then(|(archive, entry)| Self::new(archive, entry).ok())
+    }
+
+    ///
+    /// Returns the path of the archive.
+    ///
+    pub fn archive(&self) -> &Path {
+        &self.archive
+    }
+
+    ///
+    /// Returns the name of the entry within the archive.
+    ///
+    pub fn entry(&self) -> &str {
+        &self.entry
+    }
+
+    ///
+    /// Read an instance of `T` from the entry using `reader`. An entry that does not exist is
+    /// reported as an I/O error of kind `NotFound`.
+    ///
+    pub fn read_with<R, T>(&self, reader: &R) -> Result<T, R::Error>
+    where
+        R: ObjectReader<T> + ?Sized,
+    {
+        self.read_entry(reader, |e| Err(e.into()))
+    }
+
+    ///
+    /// Read an instance of `T` from the entry using `reader`, or return `T::default()` if the
+    /// entry does not exist.
+    ///
+    pub(crate) fn read_with_or_default<R, T>(&self, reader: &R) -> Result<T, R::Error>
+    where
+        R: ObjectReader<T> + ?Sized,
+        T: Default,
+    {
+        self.read_entry(reader, |_| Ok(T::default()))
+    }
+
+    fn read_entry<R, T, F>(&self, reader: &R, if_missing: F) -> Result<T, R::Error>
+    where
+        R: ObjectReader<T> + ?Sized,
+        F: FnOnce(IoError) -> Result<T, R::Error>,
+    {
+        reader.validate_options()?;
+        let file = OpenOptions::new().read(true).open(&self.archive)?;
+        let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(IoError::from)?;
+        let entry = match archive.by_name(&self.entry).map_err(IoError::from) {
+            Ok(entry) => entry,
+            Err(e) if e.kind() == ErrorKind::NotFound => return if_missing(e),
+            Err(e) => return Err(e.into()),
+        };
+        if entry.is_dir() {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("the archive entry `{self}` is a directory"),
+            )
+            .into());
+        }
+        let mut entry = BufReader::new(entry);
+        reader.read_buffered(&mut entry)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::RawReader;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn create_archive(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("objio-{name}-{}.zip", std::process::id()));
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        writer
+            .add_directory("subdir/", SimpleFileOptions::default())
+            .unwrap();
+        writer
+            .start_file("subdir/file.ttl", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"<a> <b> <c> .\n").unwrap();
+        let _ = writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_archive_path() {
+        assert!("data.zip".parse::<ArchivePath>().is_err());
+        assert!("data.zip!".parse::<ArchivePath>().is_err());
+        let path: ArchivePath = "data.zip!/a!b".parse().unwrap();
+        assert_eq!(path.entry(), "a!b");
+    }
+
+    #[test]
+    fn test_read_from_archive() {
+        let archive = create_archive("read");
+        let path = format!("{}!subdir/file.ttl", archive.display());
+
+        let detected = ArchivePath::detect(&path).unwrap();
+        assert_eq!(detected.archive(), archive);
+        let text: String = RawReader.read_from_file(&path).unwrap();
+        assert_eq!(text, "<a> <b> <c> .\n");
+
+        let missing = format!("{}!subdir/other.ttl", archive.display());
+        let error = ObjectReader::<String>::read_from_file(&RawReader, &missing).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        let text: String = RawReader.read_from_file_or_default(&path).unwrap();
+        assert_eq!(text, "<a> <b> <c> .\n");
+        let text: String = RawReader.read_from_file_or_default(&missing).unwrap();
+        assert!(text.is_empty());
+        let directory = ArchivePath::new(&archive, "subdir/").unwrap();
+        assert!(ObjectReader::<String>::read_from_file(&RawReader, directory.to_string()).is_err());
+
+        assert_eq!(ArchivePath::detect(&archive), None);
+        std::fs::remove_file(&archive).unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+/*!
+Provides the [`ArenaObjectReader`] trait, enabled by the `bumpalo` feature, for readers that
+allocate the objects they read, or transient values used while reading, in a
+[`bumpalo::Bump`] arena.
+
+Objects read into an arena may borrow from it, and so avoid individual heap allocations for each
+node of a large document; the entire arena is freed, or reset for reuse, at once. The convenience
+methods that read from strings and bytes first copy the content into the arena and then call
+[`read_slice_in`](ArenaObjectReader::read_slice_in), which a reader may override so that the
+returned object borrows directly from its source text.
+
+# Example
+
+```rust
+use bumpalo::Bump;
+use objio::arena::ArenaObjectReader;
+use std::io::Read;
+
+#[derive(Debug, Default)]
+struct WordsReader;
+
+impl<'bump> ArenaObjectReader<'bump, &'bump [&'bump str]> for WordsReader {
+    type Error = std::io::Error;
+
+    fn read_in<R: Read>(
+        &self,
+        r: &mut R,
+        arena: &'bump Bump,
+    ) -> Result<&'bump [&'bump str], Self::Error> {
+        let mut bytes = Vec::new();
+        r.read_to_end(&mut bytes)?;
+        self.read_slice_in(arena.alloc_slice_copy(&bytes), arena)
+    }
+
+    fn read_slice_in(
+        &self,
+        bytes: &'bump [u8],
+        arena: &'bump Bump,
+    ) -> Result<&'bump [&'bump str], Self::Error> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        let words: Vec<&'bump str> = text.split_whitespace().collect();
+        Ok(arena.alloc_slice_copy(&words))
+    }
+}
+
+let arena = Bump::new();
+let words = WordsReader.read_from_string_in("the quick  brown fox", &arena).unwrap();
+assert_eq!(words, &["the", "quick", "brown", "fox"]);
+```
+ */
+
+use bumpalo::Bump;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by types which read instances of `T`, allocating them in an arena with
+/// the lifetime `'bump`.
+///
+pub trait ArenaObjectReader<'bump, T>
+where
+    T: 'bump,
+{
+    ///
+    /// The type indicating errors, this **must** implement the conversion from `io::Error` as for
+    /// [`ObjectReader::Error`](crate::ObjectReader::Error).
+    ///
+    type Error: From<::std::io::Error>;
+
+    ///
+    /// Read an instance of `T` from the provided implementation of `Read`, allocating in `arena`.
+    ///
+    fn read_in<R>(&self, r: &mut R, arena: &'bump Bump) -> Result<T, Self::Error>
+    where
+        R: Read;
+
+    ///
+    /// Read an instance of `T` from `bytes`, which live at least as long as the arena, allocating
+    /// in `arena`. The default implementation calls `read_in`; readers may override this method
+    /// so that the returned object borrows from `bytes` rather than copying from them.
+    ///
+    fn read_slice_in(&self, bytes: &'bump [u8], arena: &'bump Bump) -> Result<T, Self::Error> {
+        let mut data = bytes;
+        self.read_in(&mut data, arena)
+    }
+
+    ///
+    /// Check that the options for this reader, if any, are consistent; the default
+    /// implementation does nothing.
+    ///
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    ///
+    /// Read an instance of `T` from the provided string, which is first copied into `arena`.
+    ///
+    fn read_from_string_in<S>(&self, string: S, arena: &'bump Bump) -> Result<T, Self::Error>
+    where
+        S: AsRef<str>,
+    {
+        self.read_from_bytes_in(string.as_ref().as_bytes(), arena)
+    }
+
+    ///
+    /// Read an instance of `T` from the provided bytes, which are first copied into `arena`.
+    ///
+    fn read_from_bytes_in<B>(&self, bytes: B, arena: &'bump Bump) -> Result<T, Self::Error>
+    where
+        B: AsRef<[u8]>,
+    {
+        self.validate_options()?;
+        self.read_slice_in(arena.alloc_slice_copy(bytes.as_ref()), arena)
+    }
+
+    ///
+    /// Read an instance of `T` from the file identified by `path`, allocating in `arena`.
+    ///
+    fn read_from_file_in<P>(&self, path: P, arena: &'bump Bump) -> Result<T, Self::Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.validate_options()?;
+        let mut file = OpenOptions::new().read(true).open(path.as_ref())?;
+        self.read_in(&mut file, arena)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    enum Node<'bump> {
+        Leaf(u32),
+        List(&'bump [Node<'bump>]),
+    }
+
+    #[derive(Debug, Default)]
+    struct ListReader;
+
+    impl ListReader {
+        fn parse<'bump>(
+            &self,
+            tokens: &mut std::iter::Peekable<std::str::Chars<'_>>,
+            arena: &'bump Bump,
+        ) -> Result<Node<'bump>, std::io::Error> {
+            let invalid = || std::io::Error::from(std::io::ErrorKind::InvalidData);
+            match tokens.next().ok_or_else(invalid)? {
+                '(' => {
+                    let mut children = Vec::new();
+                    while tokens.peek() != Some(&')') {
+                        children.push(self.parse(tokens, arena)?);
+                    }
+                    let _ = tokens.next();
+                    Ok(Node::List(arena.alloc_slice_fill_iter(children)))
+                }
+                c => c.to_digit(10).map(Node::Leaf).ok_or_else(invalid),
+            }
+        }
+    }
+
+    impl<'bump> ArenaObjectReader<'bump, Node<'bump>> for ListReader {
+        type Error = std::io::Error;
+
+        fn read_in<R: Read>(
+            &self,
+            r: &mut R,
+            arena: &'bump Bump,
+        ) -> Result<Node<'bump>, Self::Error> {
+            let mut text = String::new();
+            r.read_to_string(&mut text)?;
+            self.parse(&mut text.chars().peekable(), arena)
+        }
+    }
+
+    #[test]
+    fn test_nested_nodes() {
+        let mut arena = Bump::new();
+        {
+            let node = ListReader.read_from_string_in("(1(23)4)", &arena).unwrap();
+            match node {
+                Node::List(children) => {
+                    assert_eq!(children.len(), 3);
+                    assert!(matches!(children[1], Node::List(inner) if inner.len() == 2));
+                    assert!(matches!(children[2], Node::Leaf(4)));
+                }
+                Node::Leaf(_) => panic!("expected a list"),
+            }
+        }
+        assert!(arena.allocated_bytes() > 0);
+        arena.reset();
+        assert!(ListReader.read_from_string_in("(x)", &arena).is_err());
+    }
+}
@@ -0,0 +1,778 @@
+/*!
+Provides adapters between the streaming traits in [`stream`](crate::stream) and the asynchronous
+I/O traits of the [futures](https://docs.rs/futures) crate, enabled by the `futures` feature.
+
+The [`ObjectStream`] type reads from any `AsyncRead` into an internal buffer and uses an
+[`ObjectsReader`] to produce a `Stream` of objects. Input is only read when the buffered content
+does not yet contain a complete object, so a consumer that stops polling the stream also stops the
+reading of input.
+
+An object is considered complete when the reader returns it without consuming all of the
+buffered content, or when the input has ended. Readers must therefore fail, or consume all
+available content, when given a truncated object; readers that consume a trailing separator or
+line ending satisfy this naturally.
+
+The [`ObjectSink`] type is the counterpart for writing; it uses an [`ObjectsWriter`] to serialize
+each object sent to it into an internal buffer, which is written to any `AsyncWrite` once it
+reaches a high-water mark, when the sink is flushed, and when it is closed. Closing the sink
+writes any content required at the end of the stream.
+
+The [`ChunkedWriter`] type writes a single, possibly very large, object to any `AsyncWrite`
+without first serializing it into memory. The object is serialized on a background thread into
+chunks of a configurable size, and only a bounded number of chunks are held before the
+destination accepts them; the writing task yields while waiting for each chunk.
+
+# Example
+
+```rust
+use futures::executor::block_on;
+use futures::stream::TryStreamExt;
+use objio::async_io::ObjectStream;
+use objio::stream::ObjectsReader;
+use std::io::BufRead;
+
+#[derive(Debug, Default)]
+struct LinesReader;
+
+impl ObjectsReader<String> for LinesReader {
+    type Error = std::io::Error;
+
+    fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<String>, Self::Error> {
+        let mut line = String::new();
+        Ok(match r.read_line(&mut line)? {
+            0 => None,
+            _ => Some(line.trim_end().to_string()),
+        })
+    }
+}
+
+let source = futures::io::Cursor::new(b"one\ntwo\nthree".to_vec());
+let stream = ObjectStream::new(LinesReader, source);
+
+let lines: Vec<String> = block_on(stream.try_collect()).unwrap();
+assert_eq!(lines, vec!["one", "two", "three"]);
+```
+
+Objects may be forwarded from any stream directly into a sink.
+
+```rust
+use futures::executor::block_on;
+use futures::stream::{self, StreamExt};
+use objio::async_io::ObjectSink;
+use objio::stream::ObjectsWriter;
+use std::io::Write;
+
+#[derive(Debug, Default)]
+struct LinesWriter;
+
+impl ObjectsWriter<String> for LinesWriter {
+    type Error = std::io::Error;
+
+    fn write_next<W: Write>(&self, w: &mut W, object: &String, _: usize) -> Result<(), Self::Error> {
+        writeln!(w, "{object}")
+    }
+}
+
+let mut sink = ObjectSink::new(LinesWriter, Vec::new());
+let lines = stream::iter(["one", "two"]).map(|s| Ok(s.to_string()));
+block_on(lines.forward(&mut sink)).unwrap();
+
+assert_eq!(sink.into_inner().1, b"one\ntwo\n");
+```
+ */
+
+use crate::stream::{ObjectsReader, ObjectsWriter};
+use crate::ObjectWriter;
+use futures::channel::{mpsc, oneshot};
+use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use futures::sink::{Sink, SinkExt};
+use futures::stream::{FusedStream, Stream, StreamExt};
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter};
+use std::io::{Error as IoError, ErrorKind, Write};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The default number of bytes requested from the source by each read.
+///
+pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+
+///
+/// The default upper bound on the buffered content of a single object.
+///
+pub const DEFAULT_MAX_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A `Stream` of the objects read by an [`ObjectsReader`] from an `AsyncRead` source.
+///
+pub struct ObjectStream<O, T, R> {
+    reader: O,
+    source: R,
+    buffer: Vec<u8>,
+    start: usize,
+    chunk_size: usize,
+    max_buffer_size: usize,
+    eof: bool,
+    done: bool,
+    object: PhantomData<fn() -> T>,
+}
+
+///
+/// A `Sink` of objects written by an [`ObjectsWriter`] to an `AsyncWrite` destination.
+///
+pub struct ObjectSink<O, W> {
+    writer: O,
+    destination: W,
+    buffer: Vec<u8>,
+    written: usize,
+    high_water_mark: usize,
+    count: usize,
+    started: bool,
+    ended: bool,
+}
+
+///
+/// A writer of single objects to an `AsyncWrite` destination, which serializes the object in
+/// chunks on a background thread using an inner [`ObjectWriter`].
+///
+#[derive(Debug)]
+pub struct ChunkedWriter<O> {
+    writer: Arc<O>,
+    chunk_size: usize,
+    max_pending_chunks: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+struct ChunkSender {
+    sender: mpsc::Sender<Vec<u8>>,
+    chunk: Vec<u8>,
+    chunk_size: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<O, T, R> Debug for ObjectStream<O, T, R>
+where
+    O: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectStream")
+            .field("reader", &self.reader)
+            .field("buffered", &(self.buffer.len() - self.start))
+            .field("chunk_size", &self.chunk_size)
+            .field("max_buffer_size", &self.max_buffer_size)
+            .field("eof", &self.eof)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O, T, R> Stream for ObjectStream<O, T, R>
+where
+    O: ObjectsReader<T> + Unpin,
+    R: AsyncRead + Unpin,
+{
+    type Item = Result<T, O::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            let pending = &this.buffer[this.start..];
+            if !pending.is_empty() || this.eof {
+                let mut remaining = pending;
+                match this.reader.read_next(&mut remaining) {
+                    Ok(Some(object)) if this.eof || !remaining.is_empty() => {
+                        this.start += pending.len() - remaining.len();
+                        return Poll::Ready(Some(Ok(object)));
+                    }
+                    Ok(None) if this.eof => {
+                        this.done = true;
+                        return Poll::Ready(None);
+                    }
+                    Err(e) if this.eof => {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    // The object may be incomplete, so read more content and try again.
+                    _ => {}
+                }
+            }
+
+            if this.buffer.len() - this.start >= this.max_buffer_size {
+                this.done = true;
+                return Poll::Ready(Some(Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    "object exceeds the maximum buffer size",
+                )
+                .into())));
+            }
+            if this.start > 0 {
+                let _ = this.buffer.drain(..this.start);
+                this.start = 0;
+            }
+            let filled = this.buffer.len();
+            this.buffer.resize(filled + this.chunk_size, 0);
+            let result = Pin::new(&mut this.source).poll_read(cx, &mut this.buffer[filled..]);
+            let read = match &result {
+                Poll::Ready(Ok(read)) => *read,
+                _ => 0,
+            };
+            this.buffer.truncate(filled + read);
+            match result {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(0)) => this.eof = true,
+                Poll::Ready(Ok(_)) => {}
+                Poll::Ready(Err(e)) if e.kind() == ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+            }
+        }
+    }
+}
+
+impl<O, T, R> FusedStream for ObjectStream<O, T, R>
+where
+    O: ObjectsReader<T> + Unpin,
+    R: AsyncRead + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl<O, T, R> ObjectStream<O, T, R> {
+    ///
+    /// Construct a new stream of the objects read by `reader` from `source`.
+    ///
+    pub fn new(reader: O, source: R) -> Self {
+        Self {
+            reader,
+            source,
+            buffer: Vec::new(),
+            start: 0,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_buffer_size: DEFAULT_MAX_BUFFER_SIZE,
+            eof: false,
+            done: false,
+            object: PhantomData,
+        }
+    }
+
+    ///
+    /// Returns this stream with the number of bytes requested from the source by each read set to
+    /// `chunk_size`, which must not be zero.
+    ///
+    pub fn with_chunk_size(self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must not be zero");
+        let mut self_mut = self;
+        self_mut.chunk_size = chunk_size;
+        self_mut
+    }
+
+    ///
+    /// Returns this stream with the upper bound on the buffered content of a single object set to
+    /// `max_buffer_size`; an object that exceeds this bound ends the stream with an error.
+    ///
+    pub fn with_max_buffer_size(self, max_buffer_size: usize) -> Self {
+        let mut self_mut = self;
+        self_mut.max_buffer_size = max_buffer_size;
+        self_mut
+    }
+
+    ///
+    /// Returns the reader and source, discarding any buffered content.
+    ///
+    pub fn into_inner(self) -> (O, R) {
+        (self.reader, self.source)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O, W> Debug for ObjectSink<O, W>
+where
+    O: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectSink")
+            .field("writer", &self.writer)
+            .field("buffered", &(self.buffer.len() - self.written))
+            .field("high_water_mark", &self.high_water_mark)
+            .field("count", &self.count)
+            .field("ended", &self.ended)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O, T, W> Sink<T> for ObjectSink<O, W>
+where
+    O: ObjectsWriter<T> + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    type Error = O::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.buffer.len() >= this.high_water_mark {
+            ready!(this.poll_write_buffer(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        if this.ended {
+            return Err(IoError::new(ErrorKind::BrokenPipe, "sink has been closed").into());
+        }
+        if !this.started {
+            this.writer.write_start(&mut this.buffer)?;
+            this.started = true;
+        }
+        this.writer
+            .write_next(&mut this.buffer, &item, this.count)?;
+        this.count += 1;
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+        Poll::Ready(ready!(Pin::new(&mut this.destination).poll_flush(cx)).map_err(Into::into))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if !this.ended {
+            if !this.started {
+                this.writer.write_start(&mut this.buffer)?;
+                this.started = true;
+            }
+            this.writer.write_end(&mut this.buffer, this.count)?;
+            this.ended = true;
+        }
+        ready!(this.poll_write_buffer(cx))?;
+        Poll::Ready(ready!(Pin::new(&mut this.destination).poll_close(cx)).map_err(Into::into))
+    }
+}
+
+impl<O, W> ObjectSink<O, W> {
+    ///
+    /// Construct a new sink of the objects written by `writer` to `destination`.
+    ///
+    pub fn new(writer: O, destination: W) -> Self {
+        Self {
+            writer,
+            destination,
+            buffer: Vec::new(),
+            written: 0,
+            high_water_mark: DEFAULT_CHUNK_SIZE,
+            count: 0,
+            started: false,
+            ended: false,
+        }
+    }
+
+    ///
+    /// Returns this sink with the number of buffered bytes at which the sink writes to its
+    /// destination before accepting another object set to `high_water_mark`.
+    ///
+    pub fn with_high_water_mark(self, high_water_mark: usize) -> Self {
+        let mut self_mut = self;
+        self_mut.high_water_mark = high_water_mark;
+        self_mut
+    }
+
+    ///
+    /// Returns the number of objects sent to this sink.
+    ///
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    ///
+    /// Returns the writer and destination, discarding any buffered content.
+    ///
+    pub fn into_inner(self) -> (O, W) {
+        (self.writer, self.destination)
+    }
+
+    fn poll_write_buffer(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), IoError>>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        while self.written < self.buffer.len() {
+            let written = ready!(
+                Pin::new(&mut self.destination).poll_write(cx, &self.buffer[self.written..])
+            )?;
+            if written == 0 {
+                return Poll::Ready(Err(ErrorKind::WriteZero.into()));
+            }
+            self.written += written;
+        }
+        self.buffer.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O> Clone for ChunkedWriter<O> {
+    fn clone(&self) -> Self {
+        Self {
+            writer: self.writer.clone(),
+            chunk_size: self.chunk_size,
+            max_pending_chunks: self.max_pending_chunks,
+        }
+    }
+}
+
+impl<O> ChunkedWriter<O> {
+    ///
+    /// Construct a new chunked writer using `writer` to serialize objects.
+    ///
+    pub fn new(writer: O) -> Self {
+        Self {
+            writer: Arc::new(writer),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_pending_chunks: 1,
+        }
+    }
+
+    ///
+    /// Returns this writer with the size of each chunk passed to the destination set to
+    /// `chunk_size`, which must not be zero.
+    ///
+    pub fn with_chunk_size(self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must not be zero");
+        let mut self_mut = self;
+        self_mut.chunk_size = chunk_size;
+        self_mut
+    }
+
+    ///
+    /// Returns this writer with the number of serialized chunks that may wait for the destination,
+    /// in addition to the chunk being written, set to `max_pending_chunks`.
+    ///
+    pub fn with_max_pending_chunks(self, max_pending_chunks: usize) -> Self {
+        let mut self_mut = self;
+        self_mut.max_pending_chunks = max_pending_chunks;
+        self_mut
+    }
+
+    ///
+    /// Write `object` to `destination`, which is flushed once the object has been written. The
+    /// object, which may be a `T` or any owning reference to one such as `Arc<T>`, is moved to
+    /// the background thread for serialization.
+    ///
+    pub async fn write<T, B, W>(&self, destination: &mut W, object: B) -> Result<(), O::Error>
+    where
+        O: ObjectWriter<T> + Send + Sync + 'static,
+        O::Error: Send + 'static,
+        B: Borrow<T> + Send + 'static,
+        W: AsyncWrite + Unpin,
+    {
+        let (sender, mut chunks) = mpsc::channel(self.max_pending_chunks);
+        let (result_sender, result) = oneshot::channel();
+        let writer = self.writer.clone();
+        let chunk_size = self.chunk_size;
+        let _ = std::thread::spawn(move || {
+            let mut w = ChunkSender {
+                sender,
+                chunk: Vec::with_capacity(chunk_size),
+                chunk_size,
+            };
+            let result = writer
+                .write(&mut w, object.borrow())
+                .and_then(|_| Ok(w.flush()?));
+            let _ = result_sender.send(result);
+        });
+
+        while let Some(chunk) = chunks.next().await {
+            destination.write_all(&chunk).await?;
+        }
+        result
+            .await
+            .map_err(|_| IoError::other("the serialization thread panicked"))??;
+        Ok(destination.flush().await?)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Write for ChunkSender {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let accepted = buf.len().min(self.chunk_size - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..accepted]);
+        if self.chunk.len() == self.chunk_size {
+            self.send_chunk()?;
+        }
+        Ok(accepted)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.chunk.is_empty() {
+            Ok(())
+        } else {
+            self.send_chunk()
+        }
+    }
+}
+
+impl ChunkSender {
+    fn send_chunk(&mut self) -> std::io::Result<()> {
+        let chunk = std::mem::replace(&mut self.chunk, Vec::with_capacity(self.chunk_size));
+        futures::executor::block_on(self.sender.send(chunk)).map_err(|_| {
+            IoError::new(
+                ErrorKind::BrokenPipe,
+                "the destination stopped accepting chunks",
+            )
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::{DocumentSeparator, SeparatedReader, SeparatedWriter};
+    use crate::{Error, ObjectReader, ObjectWriter};
+    use futures::executor::block_on;
+    use futures::sink::SinkExt;
+    use futures::stream::{self, StreamExt};
+    use std::io::{Read, Write};
+
+    #[derive(Debug, Default)]
+    struct NumberReader;
+
+    impl ObjectReader<u32> for NumberReader {
+        type Error = Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<u32, Self::Error> {
+            let mut s = String::new();
+            r.read_to_string(&mut s)?;
+            s.trim()
+                .parse()
+                .map_err(|e| Error::deserialization("number", e))
+        }
+    }
+
+    /// Returns at most `step` bytes per read, and `Pending` before every other read.
+    struct Trickle {
+        content: &'static [u8],
+        step: usize,
+        ready: bool,
+    }
+
+    impl AsyncRead for Trickle {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            if !self.ready {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.ready = false;
+            let n = self.step.min(buf.len()).min(self.content.len());
+            buf[..n].copy_from_slice(&self.content[..n]);
+            self.content = &self.content[n..];
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    impl ObjectWriter<u32> for NumberReader {
+        type Error = Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &u32) -> Result<(), Self::Error> {
+            Ok(write!(w, "{object}")?)
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct ArrayWriter;
+
+    impl ObjectsWriter<u32> for ArrayWriter {
+        type Error = Error;
+
+        fn write_start<W: Write>(&self, w: &mut W) -> Result<(), Self::Error> {
+            Ok(w.write_all(b"[")?)
+        }
+
+        fn write_next<W: Write>(
+            &self,
+            w: &mut W,
+            object: &u32,
+            index: usize,
+        ) -> Result<(), Self::Error> {
+            Ok(write!(w, "{}{object}", if index > 0 { "," } else { "" })?)
+        }
+
+        fn write_end<W: Write>(&self, w: &mut W, _: usize) -> Result<(), Self::Error> {
+            Ok(w.write_all(b"]")?)
+        }
+    }
+
+    fn reader() -> SeparatedReader<NumberReader> {
+        SeparatedReader::new(NumberReader, DocumentSeparator::Bytes(b";".to_vec()))
+    }
+
+    #[test]
+    fn test_objects_split_across_reads() {
+        let source = Trickle {
+            content: b"12;345;6789;0",
+            step: 2,
+            ready: false,
+        };
+        let stream = ObjectStream::new(reader(), source).with_chunk_size(3);
+        let numbers: Vec<u32> = block_on(stream.map(Result::unwrap).collect());
+        assert_eq!(numbers, vec![12, 345, 6789, 0]);
+    }
+
+    #[test]
+    fn test_error_ends_stream() {
+        let source = futures::io::Cursor::new(b"1;x".to_vec());
+        let mut stream = ObjectStream::new(reader(), source);
+        block_on(async {
+            assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+            assert!(stream.next().await.unwrap().is_err());
+            assert!(stream.next().await.is_none());
+            assert!(stream.is_terminated());
+        });
+    }
+
+    #[test]
+    fn test_max_buffer_size() {
+        let source = futures::io::Cursor::new(b"123456789;1".to_vec());
+        let mut stream = ObjectStream::new(reader(), source)
+            .with_chunk_size(2)
+            .with_max_buffer_size(4);
+        assert!(block_on(stream.next()).unwrap().is_err());
+    }
+
+    /// Records the size of each write, accepting at most `limit` bytes in total.
+    #[derive(Default)]
+    struct Recorder {
+        writes: Vec<usize>,
+        content: Vec<u8>,
+        limit: Option<usize>,
+    }
+
+    impl AsyncWrite for Recorder {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            if self
+                .limit
+                .is_some_and(|limit| self.content.len() + buf.len() > limit)
+            {
+                return Poll::Ready(Err(ErrorKind::StorageFull.into()));
+            }
+            self.writes.push(buf.len());
+            self.content.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RepeatWriter;
+
+    impl ObjectWriter<usize> for RepeatWriter {
+        type Error = Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &usize) -> Result<(), Self::Error> {
+            for i in 0..*object {
+                write!(w, "{}", i % 10)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_chunked_write() {
+        let writer = ChunkedWriter::new(RepeatWriter).with_chunk_size(4);
+        let mut destination = Recorder::default();
+        block_on(writer.write(&mut destination, 10)).unwrap();
+        assert_eq!(destination.writes, vec![4, 4, 2]);
+        assert_eq!(destination.content, b"0123456789");
+    }
+
+    #[test]
+    fn test_chunked_write_destination_error() {
+        let writer = ChunkedWriter::new(RepeatWriter).with_chunk_size(16);
+        let mut destination = Recorder {
+            limit: Some(64),
+            ..Default::default()
+        };
+        let result = block_on(writer.write(&mut destination, Arc::new(1_000_000)));
+        assert!(matches!(result, Err(Error::Io(e)) if e.kind() == ErrorKind::StorageFull));
+        assert_eq!(destination.content.len(), 64);
+    }
+
+    #[test]
+    fn test_sink_round_trip() {
+        let writer = SeparatedWriter::new(NumberReader, DocumentSeparator::Bytes(b";".to_vec()));
+        let mut sink = ObjectSink::new(writer, Vec::new()).with_high_water_mark(4);
+        block_on(stream::iter([12, 345, 6789, 0].map(Ok)).forward(&mut sink)).unwrap();
+        assert_eq!(sink.count(), 4);
+
+        let (_, content) = sink.into_inner();
+        assert_eq!(content, b"12;345;6789;0");
+        let stream = ObjectStream::new(reader(), futures::io::Cursor::new(content));
+        let numbers: Vec<u32> = block_on(stream.map(Result::unwrap).collect());
+        assert_eq!(numbers, vec![12, 345, 6789, 0]);
+    }
+
+    #[test]
+    fn test_sink_start_and_end() {
+        let mut sink = ObjectSink::new(ArrayWriter, Vec::new());
+        block_on(sink.close()).unwrap();
+        assert!(block_on(sink.send(1)).is_err());
+        assert_eq!(sink.into_inner().1, b"[]");
+
+        let mut sink = ObjectSink::new(ArrayWriter, Vec::new());
+        block_on(async {
+            sink.feed(1).await.unwrap();
+            sink.feed(2).await.unwrap();
+            sink.close().await.unwrap();
+        });
+        assert_eq!(sink.into_inner().1, b"[1,2]");
+    }
+}
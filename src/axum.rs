@@ -0,0 +1,269 @@
+/*!
+Provides integration with the [axum](https://docs.rs/axum) web framework, enabled by the `axum`
+feature, so that handlers may accept and return objects using any reader or writer that
+identifies its [media type](crate::media_type::HasMediaType).
+
+[`ObjectRequest`] is an extractor that reads the request body using the reader `R`, rejecting
+requests whose `Content-Type` is not the media type of the reader. [`ObjectResponse`] is a
+responder that writes an object using the writer `W`, setting the `Content-Type` and
+`Content-Length` headers as [`to_response`](crate::http::to_response) does.
+
+# Example
+
+```rust
+use objio::axum::{ObjectRequest, ObjectResponse};
+use objio::raw::{RawReader, RawWriter};
+
+async fn echo(request: ObjectRequest<Vec<u8>, RawReader>) -> ObjectResponse<Vec<u8>, RawWriter> {
+    ObjectResponse::new(request.into_inner())
+}
+
+let router: axum::Router = axum::Router::new().route("/echo", axum::routing::post(echo));
+```
+ */
+
+use crate::media_type::HasMediaType;
+use crate::ObjectReader;
+use crate::ObjectWriter;
+use axum::body::{Body, Bytes};
+use axum::extract::rejection::BytesRejection;
+use axum::extract::{FromRequest, Request};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An extractor that reads an instance of `T` from the request body using the reader `R`.
+///
+pub struct ObjectRequest<T, R> {
+    object: T,
+    reader: PhantomData<R>,
+}
+
+///
+/// A responder that writes an instance of `T` as the response body using the writer `W`.
+///
+#[derive(Debug)]
+pub struct ObjectResponse<T, W> {
+    object: T,
+    writer: W,
+}
+
+///
+/// The rejection returned by [`ObjectRequest`] when a request cannot be read.
+///
+#[derive(Debug)]
+pub enum ObjectRejection {
+    /// The request does not have the media type of the reader; the response is
+    /// `415 Unsupported Media Type`.
+    UnsupportedMediaType { expected: String },
+    /// The request body could not be buffered.
+    Body(BytesRejection),
+    /// The request body could not be read by the reader; the response is
+    /// `422 Unprocessable Entity`.
+    Invalid { message: String },
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<T, R> Debug for ObjectRequest<T, R>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectRequest")
+            .field("object", &self.object)
+            .finish()
+    }
+}
+
+impl<T, R> Deref for ObjectRequest<T, R> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
+
+impl<T, R, S> FromRequest<S> for ObjectRequest<T, R>
+where
+    R: ObjectReader<T> + HasMediaType + Default,
+    R::Error: Display,
+    S: Send + Sync,
+{
+    type Rejection = ObjectRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let expected = R::default().media_type().to_string();
+        let matches = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .is_some_and(|essence| essence.trim().eq_ignore_ascii_case(&expected));
+        if !matches {
+            return Err(ObjectRejection::UnsupportedMediaType { expected });
+        }
+        let body = Bytes::from_request(req, state)
+            .await
+            .map_err(ObjectRejection::Body)?;
+        let object = R::default()
+            .read_from_bytes(body)
+            .map_err(|e| ObjectRejection::Invalid {
+                message: e.to_string(),
+            })?;
+        Ok(Self {
+            object,
+            reader: PhantomData,
+        })
+    }
+}
+
+impl<T, R> ObjectRequest<T, R> {
+    ///
+    /// Returns the object read from the request, consuming this extractor.
+    ///
+    pub fn into_inner(self) -> T {
+        self.object
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T, W> IntoResponse for ObjectResponse<T, W>
+where
+    W: ObjectWriter<T> + HasMediaType,
+    W::Error: Display,
+{
+    fn into_response(self) -> Response {
+        match crate::http::to_response(&self.writer, &self.object) {
+            Ok(response) => response.map(Body::from),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        }
+    }
+}
+
+impl<T, W> ObjectResponse<T, W> {
+    ///
+    /// Construct a new response writing `object` with the default writer.
+    ///
+    pub fn new(object: T) -> Self
+    where
+        W: Default,
+    {
+        Self::with_writer(object, W::default())
+    }
+
+    ///
+    /// Construct a new response writing `object` with `writer`.
+    ///
+    pub fn with_writer(object: T, writer: W) -> Self {
+        Self { object, writer }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for ObjectRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedMediaType { expected } => {
+                write!(f, "Expected request with `Content-Type: {expected}`")
+            }
+            Self::Body(e) => write!(f, "{e}"),
+            Self::Invalid { message } => {
+                write!(f, "Failed to read the request body; {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjectRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Body(e) => Some(e),
+            Self::UnsupportedMediaType { .. } | Self::Invalid { .. } => None,
+        }
+    }
+}
+
+impl IntoResponse for ObjectRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Body(e) => e.into_response(),
+            Self::UnsupportedMediaType { .. } => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string()).into_response()
+            }
+            Self::Invalid { .. } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()).into_response()
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::{RawReader, RawWriter};
+    use futures::executor::block_on;
+
+    fn request(content_type: &str, body: &'static [u8]) -> Request {
+        Request::builder()
+            .header(CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_extract_object() {
+        let extracted: ObjectRequest<String, RawReader> = block_on(ObjectRequest::from_request(
+            request("Application/Octet-Stream; q=1", b"text"),
+            &(),
+        ))
+        .unwrap();
+        assert_eq!(extracted.as_str(), "text");
+
+        let rejection = block_on(ObjectRequest::<String, RawReader>::from_request(
+            request("application/json", b"{}"),
+            &(),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+
+        let rejection = block_on(ObjectRequest::<String, RawReader>::from_request(
+            request("application/octet-stream", b"\xFF"),
+            &(),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[test]
+    fn test_object_response() {
+        let response = ObjectResponse::<_, RawWriter>::new(vec![1u8, 2]).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[CONTENT_TYPE],
+            crate::media_type::APPLICATION_OCTET_STREAM
+        );
+    }
+}
@@ -0,0 +1,313 @@
+/*!
+Provides [`MemoryBudget`], a cooperative handle against which readers charge the memory they
+allocate, so that documents from untrusted sources cannot expand beyond a configured bound.
+
+A budget is shared, by cloning the handle, between all of the code reading a document. Each
+allocation of significant size is charged with [`charge`](MemoryBudget::charge), which fails with
+the typed [`BudgetExceeded`] error if the total would exceed the limit. Memory that is freed while
+reading may be returned with [`release`](MemoryBudget::release), or by charging it with
+[`charge_scoped`](MemoryBudget::charge_scoped) which releases the charge when dropped.
+
+`BudgetExceeded` converts into both [`Error`] and `io::Error`, and so may be returned by any
+reader; the conversion from `io::Error` into `Error` recovers the
+[`Error::BudgetExceeded`] variant.
+
+# Example
+
+```rust
+use objio::budget::MemoryBudget;
+use objio::{Error, ObjectReader};
+use std::io::Read;
+
+#[derive(Debug)]
+struct LinesReader {
+    budget: MemoryBudget,
+}
+
+impl ObjectReader<Vec<String>> for LinesReader {
+    type Error = Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<Vec<String>, Self::Error> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        let mut lines = Vec::new();
+        for line in text.lines() {
+            self.budget.charge(line.len())?;
+            lines.push(line.to_string());
+        }
+        Ok(lines)
+    }
+}
+
+let reader = LinesReader { budget: MemoryBudget::new(8) };
+assert_eq!(reader.read_from_string("one\ntwo").unwrap().len(), 2);
+assert!(matches!(
+    reader.read_from_string("three"),
+    Err(Error::BudgetExceeded(_))
+));
+```
+ */
+
+use crate::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::Error as IoError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A shared handle to a bound on the memory, in bytes, that may be allocated while reading.
+///
+#[derive(Clone, Debug)]
+pub struct MemoryBudget {
+    inner: Arc<BudgetInner>,
+}
+
+///
+/// A charge against a [`MemoryBudget`] that is released when dropped.
+///
+#[derive(Debug)]
+pub struct ScopedCharge {
+    budget: MemoryBudget,
+    bytes: usize,
+}
+
+///
+/// The error returned when a charge would exceed a [`MemoryBudget`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    limit: usize,
+    used: usize,
+    requested: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct BudgetInner {
+    limit: usize,
+    used: AtomicUsize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl MemoryBudget {
+    ///
+    /// Construct a new budget allowing at most `limit` bytes to be charged.
+    ///
+    pub fn new(limit: usize) -> Self {
+        Self {
+            inner: Arc::new(BudgetInner {
+                limit,
+                used: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    ///
+    /// Construct a new budget with no effective limit, which still records the bytes charged.
+    ///
+    pub fn unlimited() -> Self {
+        Self::new(usize::MAX)
+    }
+
+    ///
+    /// Returns the maximum number of bytes that may be charged.
+    ///
+    pub fn limit(&self) -> usize {
+        self.inner.limit
+    }
+
+    ///
+    /// Returns the number of bytes currently charged.
+    ///
+    pub fn used(&self) -> usize {
+        self.inner.used.load(Ordering::Acquire)
+    }
+
+    ///
+    /// Returns the number of bytes that may still be charged.
+    ///
+    pub fn remaining(&self) -> usize {
+        self.limit().saturating_sub(self.used())
+    }
+
+    ///
+    /// Charge `bytes` against this budget, failing, without charging, if the total would exceed
+    /// the limit.
+    ///
+    pub fn charge(&self, bytes: usize) -> Result<(), BudgetExceeded> {
+        self.inner
+            .used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(bytes)
+                    .filter(|total| *total <= self.inner.limit)
+            })
+            .map(|_| ())
+            .map_err(|used| BudgetExceeded {
+                limit: self.inner.limit,
+                used,
+                requested: bytes,
+            })
+    }
+
+    ///
+    /// Charge `bytes` against this budget, as [`charge`](Self::charge), returning a guard that
+    /// releases the charge when dropped.
+    ///
+    pub fn charge_scoped(&self, bytes: usize) -> Result<ScopedCharge, BudgetExceeded> {
+        self.charge(bytes)?;
+        Ok(ScopedCharge {
+            budget: self.clone(),
+            bytes,
+        })
+    }
+
+    ///
+    /// Return `bytes`, previously charged, to this budget.
+    ///
+    pub fn release(&self, bytes: usize) {
+        let _ = self
+            .inner
+            .used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                Some(used.saturating_sub(bytes))
+            });
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Drop for ScopedCharge {
+    fn drop(&mut self) {
+        self.budget.release(self.bytes);
+    }
+}
+
+impl ScopedCharge {
+    ///
+    /// Returns the number of bytes charged by this guard.
+    ///
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    ///
+    /// Keep the charge against the budget after this guard is dropped.
+    ///
+    pub fn keep(self) {
+        let mut self_mut = self;
+        self_mut.bytes = 0;
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for BudgetExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "a request for {} bytes exceeds the memory budget of {} bytes, of which {} are in use",
+            self.requested, self.limit, self.used
+        )
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+impl From<BudgetExceeded> for IoError {
+    fn from(e: BudgetExceeded) -> Self {
+        Error::from(e).into()
+    }
+}
+
+impl BudgetExceeded {
+    ///
+    /// Returns the limit of the budget.
+    ///
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    ///
+    /// Returns the number of bytes charged when the request was made.
+    ///
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    ///
+    /// Returns the number of bytes requested.
+    ///
+    pub fn requested(&self) -> usize {
+        self.requested
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_charge_and_release() {
+        let budget = MemoryBudget::new(10);
+        let shared = budget.clone();
+        budget.charge(6).unwrap();
+        assert_eq!(
+            shared.charge(5).unwrap_err(),
+            BudgetExceeded {
+                limit: 10,
+                used: 6,
+                requested: 5
+            }
+        );
+        assert_eq!(budget.used(), 6);
+        shared.release(2);
+        shared.charge(5).unwrap();
+        assert_eq!(budget.remaining(), 1);
+        assert!(budget.charge(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_scoped_charge() {
+        let budget = MemoryBudget::new(10);
+        {
+            let charge = budget.charge_scoped(8).unwrap();
+            assert_eq!(charge.bytes(), 8);
+            assert!(budget.charge_scoped(8).is_err());
+        }
+        assert_eq!(budget.used(), 0);
+        budget.charge_scoped(4).unwrap().keep();
+        assert_eq!(budget.used(), 4);
+    }
+
+    #[test]
+    fn test_error_conversions() {
+        let exceeded = MemoryBudget::new(1).charge(2).unwrap_err();
+        let io_error = IoError::from(exceeded);
+        assert_eq!(io_error.kind(), ErrorKind::OutOfMemory);
+        assert!(matches!(
+            Error::from(io_error),
+            Error::BudgetExceeded(e) if e == exceeded
+        ));
+    }
+}
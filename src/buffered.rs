@@ -0,0 +1,178 @@
+/*!
+Provides [`BufferedSink`], a destination wrapper that applies the [`Buffering`] mode selected by
+the [`buffering`](crate::options::CommonWriterOptions::buffering) writer option.
+
+Block buffering is the most efficient for files; line buffering ensures that streamed text output,
+such as a line-oriented format piped into another process, appears promptly at the end of each
+line without the cost of writing every fragment separately.
+
+# Example
+
+```rust
+use objio::buffered::BufferedSink;
+use objio::options::{Buffering, CommonWriterOptions};
+use std::io::Write;
+
+let options = CommonWriterOptions::default().with_buffering(Buffering::Line);
+let mut sink = BufferedSink::new(Vec::new(), options.buffering());
+
+write!(sink, "<a> <b> <c> .\n<a> <b>").unwrap();
+assert_eq!(sink.get_ref(), b"<a> <b> <c> .\n");
+
+assert_eq!(sink.into_inner().unwrap(), b"<a> <b> <c> .\n<a> <b>");
+```
+ */
+
+use crate::options::Buffering;
+use std::fmt::{Debug, Formatter};
+use std::io::{BufWriter, Error as IoError, LineWriter, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A destination that buffers output written to an inner destination according to a
+/// [`Buffering`] mode.
+///
+pub struct BufferedSink<W>
+where
+    W: Write,
+{
+    inner: Inner<W>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+enum Inner<W>
+where
+    W: Write,
+{
+    Block(BufWriter<W>),
+    Line(LineWriter<W>),
+    None(W),
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<W> Debug for BufferedSink<W>
+where
+    W: Write + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferedSink")
+            .field("buffering", &self.buffering())
+            .field("inner", self.get_ref())
+            .finish()
+    }
+}
+
+impl<W> Write for BufferedSink<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            Inner::Block(w) => w.write(buf),
+            Inner::Line(w) => w.write(buf),
+            Inner::None(w) => w.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        match &mut self.inner {
+            Inner::Block(w) => w.write_all(buf),
+            Inner::Line(w) => w.write_all(buf),
+            Inner::None(w) => w.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.inner {
+            Inner::Block(w) => w.flush(),
+            Inner::Line(w) => w.flush(),
+            Inner::None(w) => w.flush(),
+        }
+    }
+}
+
+impl<W> BufferedSink<W>
+where
+    W: Write,
+{
+    ///
+    /// Construct a new sink that buffers output written to `inner` according to `buffering`.
+    ///
+    pub fn new(inner: W, buffering: Buffering) -> Self {
+        Self {
+            inner: match buffering {
+                Buffering::Block => Inner::Block(BufWriter::new(inner)),
+                Buffering::Line => Inner::Line(LineWriter::new(inner)),
+                Buffering::None => Inner::None(inner),
+            },
+        }
+    }
+
+    ///
+    /// Returns the buffering mode of this sink.
+    ///
+    pub fn buffering(&self) -> Buffering {
+        match self.inner {
+            Inner::Block(_) => Buffering::Block,
+            Inner::Line(_) => Buffering::Line,
+            Inner::None(_) => Buffering::None,
+        }
+    }
+
+    ///
+    /// Returns a reference to the inner destination; this does not include any buffered output.
+    ///
+    pub fn get_ref(&self) -> &W {
+        match &self.inner {
+            Inner::Block(w) => w.get_ref(),
+            Inner::Line(w) => w.get_ref(),
+            Inner::None(w) => w,
+        }
+    }
+
+    ///
+    /// Flush any buffered output and return the inner destination.
+    ///
+    pub fn into_inner(self) -> Result<W, IoError> {
+        match self.inner {
+            Inner::Block(w) => w.into_inner().map_err(|e| e.into_error()),
+            Inner::Line(w) => w.into_inner().map_err(|e| e.into_error()),
+            Inner::None(w) => Ok(w),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_buffering() {
+        let mut sink = BufferedSink::new(Vec::new(), Buffering::Block);
+        sink.write_all(b"one\ntwo\n").unwrap();
+        assert!(sink.get_ref().is_empty());
+        sink.flush().unwrap();
+        assert_eq!(sink.get_ref(), b"one\ntwo\n");
+    }
+
+    #[test]
+    fn test_no_buffering() {
+        let mut sink = BufferedSink::new(Vec::new(), Buffering::None);
+        sink.write_all(b"one").unwrap();
+        assert_eq!(sink.get_ref(), b"one");
+        assert_eq!(sink.buffering(), Buffering::None);
+    }
+}
@@ -0,0 +1,178 @@
+/*!
+Provides the [`ObjectCanonicalWriter`] trait, implemented by writers for formats with a defined
+canonical form, such that equal objects are always written as identical bytes. Canonical output
+may then be signed, hashed, or compared with a byte-wise diff.
+
+This differs from [`ObjectPrinter::write_canonical`](crate::ObjectPrinter::write_canonical), which
+only asks for a stable, machine-facing, output; a canonical writer follows a published
+specification, named by [`canonical_form`](ObjectCanonicalWriter::canonical_form). With the `json`
+feature the JSON writers implement this trait using the JSON Canonicalization Scheme of
+[RFC 8785](https://www.rfc-editor.org/rfc/rfc8785).
+
+The adapter returned by [`canonical`](ObjectCanonicalWriter::canonical) is itself an
+[`ObjectWriter`], so canonical output may be used anywhere a writer is expected.
+
+# Example
+
+```rust
+use objio::canonical::ObjectCanonicalWriter;
+use objio::ObjectWriter;
+use std::io::Write;
+
+#[derive(Debug, Default)]
+struct SetWriter;
+
+impl ObjectWriter<Vec<u32>> for SetWriter {
+    type Error = std::io::Error;
+
+    fn write<W: Write>(&self, w: &mut W, object: &Vec<u32>) -> Result<(), Self::Error> {
+        write!(w, "{object:?}")
+    }
+}
+
+impl ObjectCanonicalWriter<Vec<u32>> for SetWriter {
+    fn canonical_form(&self) -> &'static str {
+        "sorted, de-duplicated, list"
+    }
+
+    fn write_canonical_form<W: Write>(&self, w: &mut W, object: &Vec<u32>) -> Result<(), Self::Error> {
+        let mut values = object.clone();
+        values.sort_unstable();
+        values.dedup();
+        write!(w, "{values:?}")
+    }
+}
+
+let canonical = SetWriter.canonical();
+assert_eq!(canonical.write_to_string(&vec![3, 1, 3, 2]).unwrap(), "[1, 2, 3]");
+```
+ */
+
+use crate::capabilities::FormatCapabilities;
+use crate::media_type::HasMediaType;
+use crate::options::Buffering;
+use crate::ObjectWriter;
+use std::io::Write;
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by writers for formats with a defined canonical form.
+///
+pub trait ObjectCanonicalWriter<T>: ObjectWriter<T> {
+    ///
+    /// Returns the name of the specification that defines the canonical form.
+    ///
+    fn canonical_form(&self) -> &'static str;
+
+    ///
+    /// Write the canonical form of an instance of `T` to the provided implementation of `Write`.
+    /// Options that would change the output, such as pretty-printing, are ignored.
+    ///
+    fn write_canonical_form<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write;
+
+    ///
+    /// Returns an adapter whose [`write`](ObjectWriter::write) writes the canonical form.
+    ///
+    fn canonical(&self) -> Canonical<'_, Self, T>
+    where
+        Self: Sized,
+    {
+        Canonical {
+            writer: self,
+            object: PhantomData,
+        }
+    }
+}
+
+///
+/// A writer, returned by [`ObjectCanonicalWriter::canonical`], that writes the canonical form of
+/// objects using another writer.
+///
+#[derive(Debug)]
+pub struct Canonical<'a, W, T> {
+    writer: &'a W,
+    object: PhantomData<fn(&T)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<W, T> Clone for Canonical<'_, W, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<W, T> Copy for Canonical<'_, W, T> {}
+
+impl<W, T> ObjectWriter<T> for Canonical<'_, W, T>
+where
+    W: ObjectCanonicalWriter<T>,
+{
+    type Error = W::Error;
+
+    fn write<O>(&self, w: &mut O, object: &T) -> Result<(), Self::Error>
+    where
+        O: Write,
+    {
+        self.writer.write_canonical_form(w, object)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.writer.validate_options()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.writer.buffering()
+    }
+
+    fn write_size_hint(&self, object: &T) -> usize {
+        self.writer.write_size_hint(object)
+    }
+}
+
+impl<W, T> HasMediaType for Canonical<'_, W, T>
+where
+    W: HasMediaType,
+{
+    fn media_type(&self) -> &str {
+        self.writer.media_type()
+    }
+}
+
+impl<W, T> FormatCapabilities for Canonical<'_, W, T>
+where
+    W: FormatCapabilities,
+{
+    fn is_binary(&self) -> bool {
+        self.writer.is_binary()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.writer.supports_streaming()
+    }
+
+    fn is_lossless(&self) -> bool {
+        self.writer.is_lossless()
+    }
+
+    fn preferred_buffer_size(&self) -> usize {
+        self.writer.preferred_buffer_size()
+    }
+}
+
+impl<'a, W, T> Canonical<'a, W, T> {
+    ///
+    /// Returns the writer used to write the canonical form.
+    ///
+    pub fn inner(&self) -> &'a W {
+        self.writer
+    }
+}
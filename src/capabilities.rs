@@ -0,0 +1,144 @@
+/*!
+Provides the [`FormatCapabilities`] trait, implemented by readers and writers to describe the
+format they handle, so that generic tooling can choose how to use them; for example, to refuse to
+write a binary format to a string, or to prefer a streaming path when one is available.
+
+The serde-based readers and writers, with the `serde` feature, take their capabilities from the
+constants of `formats::SerdeFormat`.
+
+# Example
+
+```rust
+use objio::capabilities::{write_to_text, FormatCapabilities};
+use objio::raw::RawWriter;
+
+assert!(RawWriter.is_binary());
+let error = write_to_text(&RawWriter, &vec![0u8, 1, 2]).unwrap_err();
+assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+```
+ */
+
+use crate::raw::{RawReader, RawWriter};
+use crate::ObjectWriter;
+use std::io::{Error as IoError, ErrorKind};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The buffer size preferred by formats that do not specify their own, the same as the default
+/// capacity of `std::io::BufReader` and `std::io::BufWriter`.
+///
+pub const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by readers and writers to describe the capabilities of their format.
+/// The default implementation describes a lossless, textual, format that is not streamed.
+///
+pub trait FormatCapabilities {
+    ///
+    /// Returns `true` if the content is binary, and so cannot be represented as a string.
+    ///
+    fn is_binary(&self) -> bool {
+        false
+    }
+
+    ///
+    /// Returns `true` if content is read or written incrementally, rather than first being
+    /// buffered entirely in memory.
+    ///
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    ///
+    /// Returns `true` if every object can be written and read back without loss.
+    ///
+    fn is_lossless(&self) -> bool {
+        true
+    }
+
+    ///
+    /// Returns the preferred size, in bytes, of buffers used with this reader or writer.
+    ///
+    fn preferred_buffer_size(&self) -> usize {
+        DEFAULT_BUFFER_SIZE
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Write `object` into a string using `writer`, first checking that the format is textual. A
+/// binary writer is an error of kind `InvalidInput`.
+///
+pub fn write_to_text<W, T>(writer: &W, object: &T) -> Result<String, W::Error>
+where
+    W: ObjectWriter<T> + FormatCapabilities + ?Sized,
+{
+    if writer.is_binary() {
+        Err(IoError::new(
+            ErrorKind::InvalidInput,
+            "the writer produces binary content, which cannot be written to a string",
+        )
+        .into())
+    } else {
+        writer.write_to_string(object)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl FormatCapabilities for RawReader {
+    fn is_binary(&self) -> bool {
+        true
+    }
+}
+
+impl FormatCapabilities for RawWriter {
+    fn is_binary(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "json", feature = "cbor", feature = "toml"))]
+mod tests {
+    use super::*;
+    use crate::formats::cbor::Cbor;
+    use crate::formats::json::Json;
+    use crate::formats::toml::Toml;
+    use crate::formats::SerdeWriter;
+
+    #[test]
+    fn test_serde_capabilities() {
+        let json = SerdeWriter::<Json>::default();
+        assert!(!json.is_binary());
+        assert!(json.supports_streaming());
+        assert_eq!(write_to_text(&json, &vec![1]).unwrap(), "[1]");
+
+        let cbor = SerdeWriter::<Cbor>::default();
+        assert!(cbor.is_binary());
+        assert!(write_to_text(&cbor, &vec![1]).is_err());
+
+        let toml = SerdeWriter::<Toml>::default();
+        assert!(!toml.supports_streaming());
+        assert!(!toml.is_lossless());
+    }
+}
@@ -0,0 +1,216 @@
+/*!
+Provides adapters between the streaming traits in [`stream`](crate::stream) and channels, so that
+objects may be read on one thread and processed, or written, on another.
+
+The function [`read_to_sender`] reads each object from a source and sends it to any
+[`ObjectSender`], which is implemented for the standard `mpsc` senders and, with the `crossbeam`
+feature, for `crossbeam_channel::Sender`. The function [`write_from_receiver`] drains any
+receiver, or other iterator, into an [`ObjectsWriter`]. The function [`spawn_reader`] combines a
+bounded channel with a reader thread.
+
+# Example
+
+```rust
+use objio::channel::{spawn_reader, write_from_receiver};
+use objio::stream::{ObjectsReader, ObjectsWriter};
+use std::io::{BufRead, Write};
+
+#[derive(Debug, Default)]
+struct LinesIo;
+
+impl ObjectsReader<String> for LinesIo {
+    type Error = std::io::Error;
+
+    fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<String>, Self::Error> {
+        let mut line = String::new();
+        Ok(match r.read_line(&mut line)? {
+            0 => None,
+            _ => Some(line.trim_end().to_string()),
+        })
+    }
+}
+
+impl ObjectsWriter<String> for LinesIo {
+    type Error = std::io::Error;
+
+    fn write_next<W: Write>(&self, w: &mut W, object: &String, _: usize) -> Result<(), Self::Error> {
+        writeln!(w, "{}", object.to_uppercase())
+    }
+}
+
+let (receiver, reader_thread) = spawn_reader(LinesIo, "one\ntwo\n".as_bytes(), 16);
+
+let mut output: Vec<u8> = Vec::new();
+assert_eq!(write_from_receiver(&LinesIo, &mut output, receiver).unwrap(), 2);
+assert_eq!(reader_thread.join().unwrap().unwrap(), 2);
+assert_eq!(output, b"ONE\nTWO\n");
+```
+ */
+
+use crate::stream::{ObjectsReader, ObjectsWriter};
+use std::io::{BufRead, Error as IoError, ErrorKind, Write};
+use std::sync::mpsc::{Receiver, Sender, SyncSender};
+use std::thread::JoinHandle;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by the sending half of a channel of objects.
+///
+pub trait ObjectSender<T> {
+    ///
+    /// Send `object`, blocking if the channel is bounded and full; returns the object if the
+    /// receiving half of the channel has been dropped.
+    ///
+    fn send_object(&self, object: T) -> Result<(), T>;
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read each object from `r` using `reader` and send it to `sender`, returning the number of
+/// objects sent. Reading stops at the first error; if the receiver is dropped this returns an
+/// error of kind `BrokenPipe`.
+///
+pub fn read_to_sender<O, T, R, S>(reader: &O, r: R, sender: &S) -> Result<usize, O::Error>
+where
+    O: ObjectsReader<T>,
+    R: BufRead,
+    S: ObjectSender<T>,
+{
+    let mut count = 0;
+    for object in reader.objects(r) {
+        if sender.send_object(object?).is_err() {
+            return Err(
+                IoError::new(ErrorKind::BrokenPipe, "the receiver has been dropped").into(),
+            );
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+///
+/// Write each object received from `receiver`, until the channel is closed, to `w` as a complete
+/// stream using `writer`, returning the number of objects written. The destination is flushed
+/// once the stream is complete.
+///
+pub fn write_from_receiver<O, T, W, I>(
+    writer: &O,
+    w: &mut W,
+    receiver: I,
+) -> Result<usize, O::Error>
+where
+    O: ObjectsWriter<T>,
+    W: Write,
+    I: IntoIterator<Item = T>,
+{
+    let count = writer.write_all(w, receiver)?;
+    w.flush()?;
+    Ok(count)
+}
+
+///
+/// Spawn a thread that reads each object from `r` using `reader` and sends it to the returned
+/// receiver, through a channel holding at most `bound` objects. The thread's result is the
+/// result of [`read_to_sender`].
+///
+pub fn spawn_reader<O, T, R>(
+    reader: O,
+    r: R,
+    bound: usize,
+) -> (Receiver<T>, JoinHandle<Result<usize, O::Error>>)
+where
+    O: ObjectsReader<T> + Send + 'static,
+    O::Error: Send + 'static,
+    T: Send + 'static,
+    R: BufRead + Send + 'static,
+{
+    let (sender, receiver) = std::sync::mpsc::sync_channel(bound);
+    let handle = std::thread::spawn(move || read_to_sender(&reader, r, &sender));
+    (receiver, handle)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<T> ObjectSender<T> for Sender<T> {
+    fn send_object(&self, object: T) -> Result<(), T> {
+        self.send(object).map_err(|e| e.0)
+    }
+}
+
+impl<T> ObjectSender<T> for SyncSender<T> {
+    fn send_object(&self, object: T) -> Result<(), T> {
+        self.send(object).map_err(|e| e.0)
+    }
+}
+
+#[cfg(feature = "crossbeam")]
+impl<T> ObjectSender<T> for crossbeam_channel::Sender<T> {
+    fn send_object(&self, object: T) -> Result<(), T> {
+        self.send(object).map_err(|e| e.into_inner())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct NumbersIo;
+
+    impl ObjectsReader<u8> for NumbersIo {
+        type Error = IoError;
+
+        fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<u8>, Self::Error> {
+            let mut line = String::new();
+            if r.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            line.trim()
+                .parse()
+                .map(Some)
+                .map_err(|e| IoError::new(ErrorKind::InvalidData, e))
+        }
+    }
+
+    #[test]
+    fn test_read_error_closes_channel() {
+        let (receiver, handle) = spawn_reader(NumbersIo, "1\n2\nx\n3\n".as_bytes(), 1);
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            handle.join().unwrap().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_dropped_receiver() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        drop(receiver);
+        let error = read_to_sender(&NumbersIo, "1\n".as_bytes(), &sender).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[cfg(feature = "crossbeam")]
+    #[test]
+    fn test_crossbeam_sender() {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        assert_eq!(
+            read_to_sender(&NumbersIo, "1\n2\n".as_bytes(), &sender).unwrap(),
+            2
+        );
+        drop(sender);
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), vec![1, 2]);
+    }
+}
@@ -0,0 +1,207 @@
+/*!
+Provides [`ChunkReader`], an adapter that turns an iterator of byte chunks, such as the body of an
+HTTP response or the frames of a message queue, into a source implementing `Read` and `BufRead`
+so that it may be used with any [`ObjectReader`](crate::ObjectReader).
+
+Each item of the iterator is a `Result` containing any value that can be viewed as a byte slice.
+An error from the iterator is returned by the next read as an I/O error; if the error is itself an
+`io::Error` it is returned unchanged, otherwise it is wrapped in an error of kind `Other`.
+
+# Example
+
+```rust
+use objio::chunks::ChunkReader;
+use objio::ObjectReader;
+use std::io::Read;
+
+#[derive(Debug, Default)]
+struct TextReader;
+
+impl ObjectReader<String> for TextReader {
+    type Error = std::io::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+        let mut s = String::new();
+        r.read_to_string(&mut s)?;
+        Ok(s)
+    }
+}
+
+let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+    vec![Ok(b"Hello, ".to_vec()), Ok(Vec::new()), Ok(b"world".to_vec())];
+let mut source = ChunkReader::new(chunks);
+
+assert_eq!(TextReader.read(&mut source).unwrap(), "Hello, world");
+```
+ */
+
+use crate::error::BoxedError;
+use std::fmt::{Debug, Formatter};
+use std::io::{BufRead, Error as IoError, Read};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A source that reads the bytes of each chunk produced by an iterator in turn.
+///
+pub struct ChunkReader<I, B> {
+    chunks: I,
+    current: Option<B>,
+    position: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<I, B> Debug for ChunkReader<I, B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkReader")
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, B, E> Read for ChunkReader<I, B>
+where
+    I: Iterator<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<BoxedError>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+impl<I, B, E> BufRead for ChunkReader<I, B>
+where
+    I: Iterator<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<BoxedError>,
+{
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        while self
+            .current
+            .as_ref()
+            .is_none_or(|chunk| self.position >= chunk.as_ref().len())
+        {
+            self.position = 0;
+            match self.chunks.next() {
+                Some(Ok(chunk)) => self.current = Some(chunk),
+                Some(Err(e)) => {
+                    self.current = None;
+                    return Err(into_io_error(e.into()));
+                }
+                None => {
+                    self.current = None;
+                    return Ok(&[]);
+                }
+            }
+        }
+        Ok(match &self.current {
+            Some(chunk) => &chunk.as_ref()[self.position..],
+            None => &[],
+        })
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position += amount;
+    }
+}
+
+impl<I, B> ChunkReader<I, B> {
+    ///
+    /// Construct a new source that reads each chunk produced by `chunks`.
+    ///
+    pub fn new<C>(chunks: C) -> Self
+    where
+        C: IntoIterator<IntoIter = I>,
+    {
+        Self {
+            chunks: chunks.into_iter(),
+            current: None,
+            position: 0,
+        }
+    }
+
+    ///
+    /// Returns the remaining, unread, chunks.
+    ///
+    pub fn into_inner(self) -> I {
+        self.chunks
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn into_io_error(e: BoxedError) -> IoError {
+    match e.downcast::<IoError>() {
+        Ok(e) => *e,
+        Err(e) => IoError::other(e),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_small_reads_across_chunks() {
+        let chunks: Vec<Result<&[u8], IoError>> = vec![Ok(b"ab"), Ok(b""), Ok(b"cde"), Ok(b"f")];
+        let mut source = ChunkReader::new(chunks);
+        let mut buffer = [0; 2];
+        let mut output = Vec::new();
+        loop {
+            match source.read(&mut buffer).unwrap() {
+                0 => break,
+                n => output.extend_from_slice(&buffer[..n]),
+            }
+        }
+        assert_eq!(output, b"abcdef");
+    }
+
+    #[test]
+    fn test_lines_across_chunks() {
+        let chunks: Vec<Result<String, IoError>> =
+            vec![Ok("one\nt".to_string()), Ok("wo\n".to_string())];
+        let lines: Vec<String> = ChunkReader::new(chunks)
+            .lines()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(lines, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let chunks: Vec<Result<&[u8], IoError>> = vec![
+            Ok(b"a"),
+            Err(IoError::new(ErrorKind::TimedOut, "timed out")),
+        ];
+        let mut output = Vec::new();
+        let error = ChunkReader::new(chunks)
+            .read_to_end(&mut output)
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::TimedOut);
+        assert_eq!(output, b"a");
+
+        let chunks: Vec<Result<&[u8], String>> = vec![Err("closed".to_string())];
+        let error = ChunkReader::new(chunks)
+            .read_to_end(&mut output)
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert_eq!(error.to_string(), "closed");
+    }
+}
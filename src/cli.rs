@@ -0,0 +1,274 @@
+/*!
+Provides integration with [clap](https://docs.rs/clap) for command-line tools that construct
+readers and writers, enabled by the `clap` feature.
+
+The standard option enumerations, [`LineEnding`], [`Buffering`], and [`ColorChoice`], implement
+[`clap::ValueEnum`] so they may be used directly as argument types. The [`CommonWriterArgs`] type
+may be flattened into a clap command to provide arguments for each of the
+[`CommonWriterOptions`], and the [`OptionStringsArgs`] type provides a repeatable
+`--writer-opt key=value` argument for any options type implementing [`FromOptionStrings`].
+
+# Example
+
+```rust
+use clap::Parser;
+use objio::cli::{CommonWriterArgs, OptionStringsArgs};
+use objio::options::LineEnding;
+
+#[derive(Parser)]
+struct Command {
+    #[command(flatten)]
+    writer: CommonWriterArgs,
+    #[command(flatten)]
+    writer_opts: OptionStringsArgs,
+}
+
+let command = Command::parse_from([
+    "example",
+    "--pretty",
+    "--line-ending",
+    "crlf",
+    "--writer-opt",
+    "indent=4",
+]);
+
+let mut options = command.writer.to_options();
+command.writer_opts.apply_to(&mut options).unwrap();
+
+assert!(options.is_pretty());
+assert_eq!(options.indent(), 4);
+assert_eq!(options.line_ending(), LineEnding::CrLf);
+```
+ */
+
+use crate::error::Error;
+use crate::options::{
+    Buffering, CollectionOrdering, CommonWriterOptions, FormatVersion, FromOptionStrings,
+    LineEnding,
+};
+use crate::style::ColorChoice;
+use clap::builder::PossibleValue;
+use clap::{Args, ValueEnum};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Command-line arguments for each of the [`CommonWriterOptions`]; arguments not provided leave
+/// the corresponding option unchanged.
+///
+#[derive(Args, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommonWriterArgs {
+    /// Produce human-readable, pretty-printed, output.
+    #[arg(long)]
+    pub pretty: bool,
+    /// The number of spaces to use for each level of indentation when pretty-printing.
+    #[arg(long, value_name = "SPACES")]
+    pub indent: Option<usize>,
+    /// The target line width for writers that break long lines when pretty-printing.
+    #[arg(long, value_name = "COLUMNS")]
+    pub line_width: Option<usize>,
+    /// The line ending sequence to use.
+    #[arg(long, value_enum)]
+    pub line_ending: Option<LineEnding>,
+    /// How output is buffered before it is written.
+    #[arg(long, value_enum)]
+    pub buffering: Option<Buffering>,
+    /// Produce byte-identical output for equal objects across runs.
+    #[arg(long)]
+    pub deterministic: bool,
+    /// The order in which map entries are written: insertion or lexicographic.
+    #[arg(long, value_name = "ORDER")]
+    pub ordering: Option<CollectionOrdering>,
+    /// Whether to use ANSI color codes.
+    #[arg(long, value_enum)]
+    pub color: Option<ColorChoice>,
+    /// The version of the format to write, if not the latest.
+    #[arg(long, value_name = "VERSION")]
+    pub target_version: Option<FormatVersion>,
+}
+
+///
+/// A repeatable command-line argument, `--writer-opt key=value`, used to set options by key on
+/// any options type implementing [`FromOptionStrings`].
+///
+#[derive(Args, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OptionStringsArgs {
+    /// Set a format-specific writer option; may be repeated, or take a comma-separated list.
+    #[arg(long = "writer-opt", value_name = "KEY=VALUE")]
+    pub writer_opts: Vec<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl ValueEnum for LineEnding {
+    #[allow(single_use_lifetimes)]
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Lf, Self::CrLf]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Lf => PossibleValue::new("lf").help("A single line feed"),
+            Self::CrLf => PossibleValue::new("crlf").help("A carriage return and line feed pair"),
+        })
+    }
+}
+
+impl ValueEnum for Buffering {
+    #[allow(single_use_lifetimes)]
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Block, Self::Line, Self::None]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Block => PossibleValue::new("block").help("Write output in large blocks"),
+            Self::Line => PossibleValue::new("line").help("Write output at the end of each line"),
+            Self::None => PossibleValue::new("none").help("Write output as it is produced"),
+        })
+    }
+}
+
+impl ValueEnum for ColorChoice {
+    #[allow(single_use_lifetimes)]
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Auto, Self::Always, Self::Never]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Auto => {
+                PossibleValue::new("auto").help("Use color only when writing to a terminal")
+            }
+            Self::Always => PossibleValue::new("always").help("Always use color"),
+            Self::Never => PossibleValue::new("never").help("Never use color"),
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl CommonWriterArgs {
+    ///
+    /// Set each option in `options` for which an argument was provided.
+    ///
+    pub fn apply_to(&self, options: &mut CommonWriterOptions) {
+        if self.pretty {
+            options.set_pretty(true);
+        }
+        if let Some(indent) = self.indent {
+            options.set_indent(indent);
+        }
+        if let Some(line_width) = self.line_width {
+            options.set_line_width(line_width);
+        }
+        if let Some(line_ending) = self.line_ending {
+            options.set_line_ending(line_ending);
+        }
+        if let Some(buffering) = self.buffering {
+            options.set_buffering(buffering);
+        }
+        if self.deterministic {
+            options.set_deterministic(true);
+        }
+        if let Some(ordering) = &self.ordering {
+            options.set_ordering(ordering.clone());
+        }
+        if let Some(color) = self.color {
+            let mut style = options.style().clone();
+            style.set_color(color);
+            options.set_style(style);
+        }
+        if let Some(target_version) = self.target_version {
+            options.set_target_version(Some(target_version));
+        }
+    }
+
+    ///
+    /// Returns the default options with each option for which an argument was provided set.
+    ///
+    pub fn to_options(&self) -> CommonWriterOptions {
+        let mut options = CommonWriterOptions::default();
+        self.apply_to(&mut options);
+        options
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl OptionStringsArgs {
+    ///
+    /// Set each option provided by the `--writer-opt` arguments in `options`, in order.
+    ///
+    pub fn apply_to<T>(&self, options: &mut T) -> Result<(), Error>
+    where
+        T: FromOptionStrings,
+    {
+        for writer_opt in &self.writer_opts {
+            options.set_option_strings(writer_opt)?;
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Debug, Parser)]
+    struct TestCommand {
+        #[command(flatten)]
+        writer: CommonWriterArgs,
+        #[command(flatten)]
+        writer_opts: OptionStringsArgs,
+    }
+
+    #[test]
+    fn test_no_arguments() {
+        let command = TestCommand::parse_from(["test"]);
+        assert_eq!(command.writer.to_options(), CommonWriterOptions::default());
+    }
+
+    #[test]
+    fn test_value_enums() {
+        let command = TestCommand::parse_from([
+            "test",
+            "--color",
+            "never",
+            "--line-width",
+            "100",
+            "--target-version",
+            "1.2",
+            "--buffering",
+            "line",
+            "--ordering",
+            "lexicographic",
+        ]);
+        let options = command.writer.to_options();
+        assert_eq!(options.buffering(), Buffering::Line);
+        assert_eq!(options.ordering(), &CollectionOrdering::Lexicographic);
+        assert_eq!(options.target_version(), Some(FormatVersion::new(1, 2)));
+        assert_eq!(options.style().color(), ColorChoice::Never);
+        assert_eq!(options.line_width(), 100);
+        assert!(TestCommand::try_parse_from(["test", "--line-ending", "cr"]).is_err());
+    }
+
+    #[test]
+    fn test_unknown_writer_opt() {
+        let command = TestCommand::parse_from(["test", "--writer-opt", "indnet=4"]);
+        let mut options = CommonWriterOptions::default();
+        assert!(matches!(
+            command.writer_opts.apply_to(&mut options),
+            Err(Error::UnknownOption { .. })
+        ));
+    }
+}
@@ -0,0 +1,254 @@
+/*!
+Provides a helper for writers that produce tabular text, such as statistics reports or summaries,
+where each column should be aligned.
+
+The [`ColumnWriter`] type buffers rows of cells and, when written, pads each cell to the width of
+the widest cell in its column. Columns are separated by a configurable separator with optional
+padding on either side, and each column may be left- or right-aligned. The final column is never
+padded on the right so that output does not contain trailing whitespace.
+
+# Example
+
+```rust
+use objio::columns::{Alignment, ColumnWriter};
+
+let mut table = ColumnWriter::default()
+    .with_separator("|")
+    .with_padding(1)
+    .with_alignment(1, Alignment::Right);
+table.add_row(["predicate", "count"]);
+table.add_row(["rdf:type", "1024"]);
+table.add_row(["rdfs:label", "7"]);
+
+assert_eq!(
+    table.write_to_string(),
+    "predicate  | count\nrdf:type   |  1024\nrdfs:label |     7\n"
+);
+```
+ */
+
+use crate::options::CommonWriterOptions;
+use crate::HasOptions;
+use std::io::Write;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The alignment of cells within a column.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Alignment {
+    /// Cells are padded on the right.
+    #[default]
+    Left,
+    /// Cells are padded on the left.
+    Right,
+}
+
+///
+/// A buffer of rows that are written with aligned columns.
+///
+#[derive(Clone, Debug)]
+pub struct ColumnWriter {
+    options: CommonWriterOptions,
+    separator: String,
+    padding: usize,
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for ColumnWriter {
+    fn default() -> Self {
+        Self {
+            options: Default::default(),
+            separator: "  ".to_string(),
+            padding: 0,
+            alignments: Default::default(),
+            rows: Default::default(),
+        }
+    }
+}
+
+impl HasOptions<CommonWriterOptions> for ColumnWriter {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl ColumnWriter {
+    ///
+    /// Returns this writer with the string written between columns set to `separator`; the
+    /// default is two spaces.
+    ///
+    pub fn with_separator<S>(self, separator: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.separator = separator.into();
+        self_mut
+    }
+
+    ///
+    /// Returns this writer with the number of spaces written on either side of the separator
+    /// set to `padding`; the default is zero.
+    ///
+    pub fn with_padding(self, padding: usize) -> Self {
+        let mut self_mut = self;
+        self_mut.padding = padding;
+        self_mut
+    }
+
+    ///
+    /// Returns this writer with the column at `index` aligned according to `alignment`; columns
+    /// are left-aligned by default.
+    ///
+    pub fn with_alignment(self, index: usize, alignment: Alignment) -> Self {
+        let mut self_mut = self;
+        if self_mut.alignments.len() <= index {
+            self_mut.alignments.resize(index + 1, Alignment::default());
+        }
+        self_mut.alignments[index] = alignment;
+        self_mut
+    }
+
+    ///
+    /// Add a row of cells to the buffer; rows need not have the same number of cells.
+    ///
+    pub fn add_row<I, S>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+    }
+
+    ///
+    /// Returns the number of buffered rows.
+    ///
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    ///
+    /// Returns `true` if there are no buffered rows.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    ///
+    /// Remove all buffered rows.
+    ///
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    ///
+    /// Write all buffered rows, with aligned columns, to `w`. Each row is terminated with the
+    /// line ending from the writer options.
+    ///
+    pub fn write<W>(&self, w: &mut W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let widths = self.column_widths();
+        let line_ending = self.options.line_ending().as_str();
+        let padding = self.padding;
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                let is_last = i + 1 == row.len();
+                if i > 0 {
+                    write!(w, "{:padding$}{}{:padding$}", "", self.separator, "")?;
+                }
+                let width = widths[i];
+                match self.alignments.get(i).copied().unwrap_or_default() {
+                    Alignment::Left if is_last => write!(w, "{cell}")?,
+                    Alignment::Left => write!(w, "{cell:<width$}")?,
+                    Alignment::Right => write!(w, "{cell:>width$}")?,
+                }
+            }
+            w.write_all(line_ending.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Write all buffered rows, with aligned columns, to a new string.
+    ///
+    pub fn write_to_string(&self) -> String {
+        let mut buffer = Vec::new();
+        self.write(&mut buffer)
+            .expect("writing to a Vec<u8> cannot fail");
+        String::from_utf8(buffer).expect("cells are constructed from valid strings")
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    fn column_widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = Vec::new();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                let width = cell.chars().count();
+                match widths.get_mut(i) {
+                    Some(current) => *current = (*current).max(width),
+                    None => widths.push(width),
+                }
+            }
+        }
+        widths
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::LineEnding;
+
+    #[test]
+    fn test_default_layout_and_ragged_rows() {
+        let mut table = ColumnWriter::default();
+        table.add_row(["a", "bb", "c"]);
+        table.add_row(["aaa", "b"]);
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.write_to_string(), "a    bb  c\naaa  b\n");
+    }
+
+    #[test]
+    fn test_unicode_width_and_line_ending() {
+        let mut table = ColumnWriter::default()
+            .with_separator(",")
+            .with_options(CommonWriterOptions::default().with_line_ending(LineEnding::CrLf));
+        table.add_row(["é", "1"]);
+        table.add_row(["ab", "2"]);
+        assert_eq!(table.write_to_string(), "é ,1\r\nab,2\r\n");
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut table = ColumnWriter::default();
+        table.add_row(["a"]);
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.write_to_string(), "");
+    }
+}
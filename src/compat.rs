@@ -0,0 +1,670 @@
+/*!
+Provides [`Corpus`], a test harness that checks a reader, and optionally a writer, against a
+directory of corpus files organized by format version, as a standard backward-compatibility
+gate for format implementations.
+
+A corpus directory contains one directory for each version of the format, named for the version
+such as `1.0` or `v2.1`, and each of these contains directories named for the [`Expectation`] of
+the files within them:
+
+```text
+corpus/
+├── 1.0/
+│   ├── parse/        files that must be read successfully
+│   ├── fail/         files that must fail to be read
+│   └── roundtrip/    files that must be read, and written back byte-identically
+└── 2.0/
+    └── ...
+```
+
+[`Corpus::check`] reads each file, returning a [`CompatReport`] of the outcome of each case; the
+round-trip cases are skipped, as they require a writer. [`Corpus::check_round_trip`] also writes
+each object read from a round-trip case, in the case's version of the format, using a
+[`VersionedWriter`]. A test will usually finish with [`CompatReport::assert_success`], which
+panics with the summary of the report if any case failed.
+
+# Example
+
+```rust
+use objio::compat::{Corpus, Expectation};
+use objio::ObjectReader;
+use std::io::Read;
+
+#[derive(Debug, Default)]
+struct NumberReader;
+
+impl ObjectReader<u32> for NumberReader {
+    type Error = objio::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<u32, Self::Error> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        text.trim().parse().map_err(|e| objio::Error::deserialization("number", e))
+    }
+}
+
+let root = std::env::temp_dir().join(format!("objio-compat-doc-{}", std::process::id()));
+for (dir, name, content) in [("1.0/parse", "one.txt", "1"), ("1.0/fail", "word.txt", "one")] {
+    std::fs::create_dir_all(root.join(dir)).unwrap();
+    std::fs::write(root.join(dir).join(name), content).unwrap();
+}
+
+let corpus = Corpus::open(&root).unwrap();
+assert_eq!(corpus.len(), 2);
+assert_eq!(corpus.cases()[0].expectation(), Expectation::Parse);
+
+let report = corpus.check(&NumberReader);
+assert_eq!(report.passed(), 2);
+report.assert_success();
+# std::fs::remove_dir_all(&root).unwrap();
+```
+ */
+
+use crate::options::{FormatVersion, ParseOptionValueError};
+use crate::version::VersionedWriter;
+use crate::ObjectReader;
+use std::fmt::{Display, Formatter};
+use std::io::{Error as IoError, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// What is expected of a reader, and writer, for a corpus file.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Expectation {
+    /// The file must be read successfully.
+    Parse,
+    /// The file must fail to be read.
+    Fail,
+    /// The file must be read successfully, and the object read must be written back identically.
+    RoundTrip,
+}
+
+///
+/// A single file of a [`Corpus`].
+///
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CorpusCase {
+    version: FormatVersion,
+    expectation: Expectation,
+    path: PathBuf,
+}
+
+///
+/// A directory of corpus files, organized by format version and expectation.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Corpus {
+    root: PathBuf,
+    cases: Vec<CorpusCase>,
+}
+
+///
+/// The outcome of checking a single [`CorpusCase`].
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// The reader, and writer, met the expectation of the case.
+    Passed,
+    /// The reader, or writer, did not meet the expectation of the case, for the given reason.
+    Failed(String),
+    /// The case was not checked, for the given reason.
+    Skipped(String),
+}
+
+///
+/// The outcome of checking every case of a [`Corpus`].
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    results: Vec<(CorpusCase, Outcome)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Expectation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse => write!(f, "parse"),
+            Self::Fail => write!(f, "fail"),
+            Self::RoundTrip => write!(f, "roundtrip"),
+        }
+    }
+}
+
+impl FromStr for Expectation {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "parse" => Ok(Self::Parse),
+            "fail" => Ok(Self::Fail),
+            "roundtrip" => Ok(Self::RoundTrip),
+            _ => Err(ParseOptionValueError::new(
+                s,
+                &["parse", "fail", "roundtrip"],
+            )),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for CorpusCase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}",
+            self.version,
+            self.expectation,
+            self.path
+                .file_name()
+                .unwrap_or(self.path.as_os_str())
+                .to_string_lossy()
+        )
+    }
+}
+
+impl CorpusCase {
+    ///
+    /// Returns the version of the format of the file.
+    ///
+    pub fn version(&self) -> FormatVersion {
+        self.version
+    }
+
+    ///
+    /// Returns what is expected of the file.
+    ///
+    pub fn expectation(&self) -> Expectation {
+        self.expectation
+    }
+
+    ///
+    /// Returns the path of the file.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Corpus {
+    ///
+    /// Open the corpus in the directory `root`, finding its cases. Every entry of `root` must be a
+    /// directory named for a version, and every entry of those a directory named for an
+    /// expectation; any other entry is an error, so that a misplaced file is not silently
+    /// ignored. Entries whose names start with `.` are ignored.
+    ///
+    pub fn open<P>(root: P) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+    {
+        let root = root.as_ref().to_path_buf();
+        let mut cases = Vec::new();
+        for version_dir in visible_entries(&root)? {
+            let version = parse_dir_name(&version_dir, |name| {
+                name.strip_prefix('v')
+                    .unwrap_or(name)
+                    .parse::<FormatVersion>()
+            })?;
+            for expectation_dir in visible_entries(&version_dir)? {
+                let expectation = parse_dir_name(&expectation_dir, Expectation::from_str)?;
+                for path in visible_entries(&expectation_dir)? {
+                    if path.is_file() {
+                        cases.push(CorpusCase {
+                            version,
+                            expectation,
+                            path,
+                        });
+                    } else {
+                        return Err(IoError::new(
+                            ErrorKind::InvalidData,
+                            format!("the corpus entry `{}` is not a file", path.display()),
+                        ));
+                    }
+                }
+            }
+        }
+        cases.sort();
+        Ok(Self { root, cases })
+    }
+
+    ///
+    /// Returns the root directory of the corpus.
+    ///
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    ///
+    /// Returns the cases of the corpus, ordered by version, expectation, and path.
+    ///
+    pub fn cases(&self) -> &[CorpusCase] {
+        &self.cases
+    }
+
+    ///
+    /// Returns the number of cases in the corpus.
+    ///
+    pub fn len(&self) -> usize {
+        self.cases.len()
+    }
+
+    ///
+    /// Returns `true` if the corpus has no cases.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.cases.is_empty()
+    }
+
+    ///
+    /// Returns the versions of the format in the corpus, in order.
+    ///
+    pub fn versions(&self) -> Vec<FormatVersion> {
+        let mut versions: Vec<FormatVersion> = self.cases.iter().map(|c| c.version).collect();
+        versions.dedup();
+        versions
+    }
+
+    ///
+    /// Check every case using `reader`; round-trip cases are skipped.
+    ///
+    pub fn check<T, R>(&self, reader: &R) -> CompatReport
+    where
+        R: ObjectReader<T>,
+        R::Error: Display,
+    {
+        self.check_with(reader, |_, _| None)
+    }
+
+    ///
+    /// Check every case using `reader`, and round-trip cases using `writer` to write the object
+    /// read in the case's version of the format.
+    ///
+    pub fn check_round_trip<T, R, W>(&self, reader: &R, writer: &W) -> CompatReport
+    where
+        R: ObjectReader<T>,
+        R::Error: Display,
+        W: VersionedWriter<T>,
+        W::Error: Display,
+    {
+        self.check_with(reader, |object, version| {
+            let mut buffer = Vec::new();
+            Some(
+                writer
+                    .write_version(&mut buffer, object, version)
+                    .map(|_| buffer)
+                    .map_err(|e| e.to_string()),
+            )
+        })
+    }
+
+    fn check_with<T, R, F>(&self, reader: &R, write: F) -> CompatReport
+    where
+        R: ObjectReader<T>,
+        R::Error: Display,
+        F: Fn(&T, FormatVersion) -> Option<Result<Vec<u8>, String>>,
+    {
+        let results = self
+            .cases
+            .iter()
+            .map(|case| {
+                let outcome = match std::fs::read(&case.path) {
+                    Ok(content) => {
+                        check_case(case, &content, reader.read(&mut content.as_slice()), &write)
+                    }
+                    Err(e) => Outcome::Failed(format!("could not be opened: {e}")),
+                };
+                (case.clone(), outcome)
+            })
+            .collect();
+        CompatReport { results }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passed => write!(f, "passed"),
+            Self::Failed(reason) => write!(f, "FAILED: {reason}"),
+            Self::Skipped(reason) => write!(f, "skipped: {reason}"),
+        }
+    }
+}
+
+impl Outcome {
+    ///
+    /// Returns `true` if this outcome is [`Failed`](Self::Failed).
+    ///
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for CompatReport {
+    ///
+    /// Writes a line for each version, counting its outcomes, followed by a line for each case
+    /// that failed.
+    ///
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut versions: Vec<FormatVersion> =
+            self.results.iter().map(|(c, _)| c.version).collect();
+        versions.dedup();
+        for version in versions {
+            let outcomes: Vec<&Outcome> = self
+                .results
+                .iter()
+                .filter(|(case, _)| case.version == version)
+                .map(|(_, outcome)| outcome)
+                .collect();
+            let count = |f: fn(&Outcome) -> bool| outcomes.iter().filter(|o| f(o)).count();
+            writeln!(
+                f,
+                "version {version}: {} passed, {} failed, {} skipped",
+                count(|o| *o == Outcome::Passed),
+                count(Outcome::is_failed),
+                count(|o| matches!(o, Outcome::Skipped(_))),
+            )?;
+        }
+        for (case, outcome) in self.failures() {
+            writeln!(f, "{case}: {outcome}")?;
+        }
+        Ok(())
+    }
+}
+
+impl CompatReport {
+    ///
+    /// Returns the outcome of each case, in the order of the corpus.
+    ///
+    pub fn results(&self) -> &[(CorpusCase, Outcome)] {
+        &self.results
+    }
+
+    ///
+    /// Returns the cases that failed, and their outcomes.
+    ///
+    pub fn failures(&self) -> impl Iterator<Item = (&CorpusCase, &Outcome)> {
+        self.results
+            .iter()
+            .filter(|(_, outcome)| outcome.is_failed())
+            .map(|(case, outcome)| (case, outcome))
+    }
+
+    ///
+    /// Returns the number of cases that passed.
+    ///
+    pub fn passed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, outcome)| *outcome == Outcome::Passed)
+            .count()
+    }
+
+    ///
+    /// Returns the number of cases that failed.
+    ///
+    pub fn failed(&self) -> usize {
+        self.failures().count()
+    }
+
+    ///
+    /// Returns the number of cases that were skipped.
+    ///
+    pub fn skipped(&self) -> usize {
+        self.results.len() - self.passed() - self.failed()
+    }
+
+    ///
+    /// Returns `true` if no case failed.
+    ///
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    ///
+    /// Panic, with the summary of this report, if any case failed.
+    ///
+    #[track_caller]
+    pub fn assert_success(&self) {
+        if !self.is_success() {
+            panic!("corpus compatibility check failed:\n{self}");
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn check_case<T, E, F>(case: &CorpusCase, content: &[u8], result: Result<T, E>, write: F) -> Outcome
+where
+    E: Display,
+    F: Fn(&T, FormatVersion) -> Option<Result<Vec<u8>, String>>,
+{
+    match (case.expectation, result) {
+        (Expectation::Fail, Ok(_)) => Outcome::Failed("read, but was expected to fail".into()),
+        (Expectation::Fail, Err(_)) => Outcome::Passed,
+        (_, Err(e)) => Outcome::Failed(format!("could not be read: {e}")),
+        (Expectation::Parse, Ok(_)) => Outcome::Passed,
+        (Expectation::RoundTrip, Ok(object)) => match write(&object, case.version) {
+            None => Outcome::Skipped("no writer was given".into()),
+            Some(Err(e)) => Outcome::Failed(format!("could not be written: {e}")),
+            Some(Ok(written)) if written == content => Outcome::Passed,
+            Some(Ok(written)) => {
+                let offset = written
+                    .iter()
+                    .zip(content)
+                    .position(|(a, b)| a != b)
+                    .unwrap_or(written.len().min(content.len()));
+                Outcome::Failed(format!(
+                    "was written differently, from offset {offset} ({} bytes written, {} read)",
+                    written.len(),
+                    content.len()
+                ))
+            }
+        },
+    }
+}
+
+fn visible_entries(directory: &Path) -> Result<Vec<PathBuf>, IoError> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        if !entry.file_name().to_string_lossy().starts_with('.') {
+            entries.push(entry.path());
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_dir_name<T, E, F>(path: &Path, parse: F) -> Result<T, IoError>
+where
+    F: Fn(&str) -> Result<T, E>,
+{
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    match parse(&name) {
+        Ok(value) if path.is_dir() => Ok(value),
+        _ => Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!(
+                "the corpus entry `{}` is not a valid directory",
+                path.display()
+            ),
+        )),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::VersionedReader;
+    use crate::ObjectWriter;
+    use std::io::{BufRead, Read, Write};
+
+    // Version 1 is a comma-separated list, and version 2 a line-separated list with a header.
+    #[derive(Debug, Default)]
+    struct ListIo;
+
+    impl ObjectReader<Vec<String>> for ListIo {
+        type Error = crate::Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<Vec<String>, Self::Error> {
+            self.read_versioned(r)
+        }
+    }
+
+    impl VersionedReader<Vec<String>> for ListIo {
+        fn current_version(&self) -> FormatVersion {
+            FormatVersion::new(2, 0)
+        }
+
+        fn detect_version<R: BufRead>(
+            &self,
+            r: &mut R,
+        ) -> Result<Option<FormatVersion>, Self::Error> {
+            if r.fill_buf()?.starts_with(b"#2\n") {
+                r.consume(3);
+                Ok(Some(FormatVersion::new(2, 0)))
+            } else {
+                Ok(Some(FormatVersion::new(1, 0)))
+            }
+        }
+
+        fn read_version<R: BufRead>(
+            &self,
+            r: &mut R,
+            version: FormatVersion,
+        ) -> Result<Vec<String>, Self::Error> {
+            let mut text = String::new();
+            r.read_to_string(&mut text)?;
+            let separator = if version.major() == 1 { ',' } else { '\n' };
+            let items: Vec<String> = text
+                .split_terminator(separator)
+                .map(str::to_string)
+                .collect();
+            if items.iter().any(|item| item.is_empty()) {
+                return Err(crate::Error::parse("list", "empty item"));
+            }
+            Ok(items)
+        }
+    }
+
+    impl ObjectWriter<Vec<String>> for ListIo {
+        type Error = crate::Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &Vec<String>) -> Result<(), Self::Error> {
+            self.write_versioned(w, object)
+        }
+    }
+
+    impl VersionedWriter<Vec<String>> for ListIo {
+        fn current_version(&self) -> FormatVersion {
+            FormatVersion::new(2, 0)
+        }
+
+        fn write_version<W: Write>(
+            &self,
+            w: &mut W,
+            object: &Vec<String>,
+            version: FormatVersion,
+        ) -> Result<(), Self::Error> {
+            if version.major() == 1 {
+                write!(w, "{}", object.join(","))?;
+            } else {
+                write!(w, "#2\n{}\n", object.join("\n"))?;
+            }
+            Ok(())
+        }
+    }
+
+    fn corpus(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("objio-compat-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_check_round_trip() {
+        let root = corpus(
+            "round-trip",
+            &[
+                ("v1.0/roundtrip/a.txt", "a,b"),
+                ("v1.0/roundtrip/b.txt", "a,b,"),
+                ("v1.0/fail/empty.txt", "a,,b"),
+                ("2.0/roundtrip/a.txt", "#2\na\nb\n"),
+                ("2.0/fail/a.txt", "#2\na\nb\n"),
+                ("2.0/.notes", "ignored"),
+            ],
+        );
+        let corpus = Corpus::open(&root).unwrap();
+        assert_eq!(
+            corpus.versions(),
+            vec![FormatVersion::new(1, 0), FormatVersion::new(2, 0)]
+        );
+        assert_eq!(corpus.len(), 5);
+
+        let report = corpus.check(&ListIo);
+        assert_eq!(
+            (report.passed(), report.failed(), report.skipped()),
+            (1, 1, 3)
+        );
+
+        let report = corpus.check_round_trip(&ListIo, &ListIo);
+        assert_eq!(
+            (report.passed(), report.failed(), report.skipped()),
+            (3, 2, 0)
+        );
+        let summary = report.to_string();
+        assert_eq!(
+            summary.lines().collect::<Vec<_>>(),
+            vec![
+                "version 1.0: 2 passed, 1 failed, 0 skipped",
+                "version 2.0: 1 passed, 1 failed, 0 skipped",
+                "1.0/roundtrip/b.txt: FAILED: was written differently, from offset 3 \
+                 (3 bytes written, 4 read)",
+                "2.0/fail/a.txt: FAILED: read, but was expected to fail",
+            ]
+        );
+        let panic = std::panic::catch_unwind(|| report.assert_success());
+        assert!(panic.is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_layout() {
+        let root = corpus("invalid", &[("1.0/pass/a.txt", "a")]);
+        let error = Corpus::open(&root).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let root = corpus("file", &[("README", "a")]);
+        assert!(Corpus::open(&root).is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,468 @@
+/*!
+Provides conditional writes, for optimistic concurrency over objects stored by key in an
+[`ObjectStore`], such as a remote object store or a shared directory.
+
+Each stored object has an entity tag, an [`ETag`], which changes whenever its content changes.
+Reading an object with [`read_versioned`] returns its tag alongside it; writing it back with
+[`write_conditional`] and the precondition [`Precondition::IfMatch`] fails, with the typed
+[`PreconditionFailed`] error, if the object has since been changed by someone else, in the same
+way as an HTTP `PUT` with an `If-Match` header. [`Precondition::IfNoneMatch`] similarly creates
+an object only if it does not already exist.
+
+`PreconditionFailed` converts into both [`Error`] and `io::Error`, and the conversion from
+`io::Error` into `Error` recovers the [`Error::PreconditionFailed`] variant.
+
+This module provides [`FileStore`], which stores objects as files in a directory; other backends
+implement [`ObjectStore`], performing the precondition check atomically with the write.
+
+# Example
+
+```rust
+use objio::conditional::{read_versioned, write_conditional, FileStore, Precondition};
+use objio::raw::{RawReader, RawWriter};
+use objio::Error;
+
+let directory = std::env::temp_dir().join(format!("objio-store-doc-{}", std::process::id()));
+let store = FileStore::new(&directory).unwrap();
+write_conditional(&store, "config", &RawWriter, &"v1".to_string(), Precondition::IfNoneMatch)
+    .unwrap();
+
+let versioned = read_versioned::<_, _, String>(&store, "config", &RawReader).unwrap().unwrap();
+let precondition = Precondition::IfMatch(versioned.etag().clone());
+
+// Another writer changes the object after it was read.
+write_conditional(&store, "config", &RawWriter, &"v2".to_string(), Precondition::None)
+    .unwrap();
+
+let error: Error = write_conditional(&store, "config", &RawWriter, &"v3".to_string(), precondition)
+    .map_err(Error::from)
+    .unwrap_err();
+assert!(matches!(error, Error::PreconditionFailed(_)));
+# std::fs::remove_dir_all(&directory).unwrap();
+```
+ */
+
+use crate::error::Error;
+use crate::update::{lock_path, replace_file, LOCK_FILE_EXTENSION};
+use crate::{ObjectReader, ObjectWriter};
+use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An opaque entity tag identifying a version of the content of a stored object.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ETag(String);
+
+///
+/// The condition that must hold, for the current version of an object, for a write to proceed.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Precondition {
+    /// The write is unconditional.
+    #[default]
+    None,
+    /// The object must exist and have the given entity tag.
+    IfMatch(ETag),
+    /// The object must not exist.
+    IfNoneMatch,
+}
+
+///
+/// The error returned when the precondition of a conditional write does not hold.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreconditionFailed {
+    precondition: Precondition,
+    current: Option<ETag>,
+}
+
+///
+/// An object read from a store, with the entity tag of the version read.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Versioned<T> {
+    object: T,
+    etag: ETag,
+}
+
+///
+/// The trait implemented by stores of serialized objects, identified by key, that support
+/// conditional writes.
+///
+pub trait ObjectStore {
+    ///
+    /// Returns the content, and entity tag, of the object stored under `key`, or `None` if there
+    /// is no such object.
+    ///
+    fn get(&self, key: &str) -> Result<Option<(Vec<u8>, ETag)>, IoError>;
+
+    ///
+    /// Store `content` under `key` if `precondition` holds for the current object, returning the
+    /// entity tag of the new content. The check and the write must be atomic with respect to
+    /// other writers, and a failed precondition must be reported as an I/O error carrying
+    /// [`PreconditionFailed`].
+    ///
+    fn put(&self, key: &str, content: &[u8], precondition: &Precondition) -> Result<ETag, IoError>;
+}
+
+///
+/// A store that keeps each object in a file, named by its key, within a directory. Entity tags
+/// are derived from the content, and writes are atomic and serialized by a lock file. Keys may
+/// contain subdirectories, but may not have the lock file extension `.lock`.
+///
+#[derive(Clone, Debug)]
+pub struct FileStore {
+    directory: PathBuf,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read the object stored under `key` using `reader`, with its entity tag, or return `None` if
+/// there is no such object.
+///
+pub fn read_versioned<S, R, T>(
+    store: &S,
+    key: &str,
+    reader: &R,
+) -> Result<Option<Versioned<T>>, R::Error>
+where
+    S: ObjectStore + ?Sized,
+    R: ObjectReader<T> + ?Sized,
+{
+    match store.get(key)? {
+        None => Ok(None),
+        Some((content, etag)) => Ok(Some(Versioned {
+            object: reader.read_from_bytes(content)?,
+            etag,
+        })),
+    }
+}
+
+///
+/// Write `object` using `writer` to the store under `key` if `precondition` holds, returning the
+/// entity tag of the new version.
+///
+pub fn write_conditional<S, W, T>(
+    store: &S,
+    key: &str,
+    writer: &W,
+    object: &T,
+    precondition: Precondition,
+) -> Result<ETag, W::Error>
+where
+    S: ObjectStore + ?Sized,
+    W: ObjectWriter<T> + ?Sized,
+{
+    let content = writer.write_to_bytes(object)?;
+    Ok(store.put(key, &content, &precondition)?)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for ETag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\"", self.0)
+    }
+}
+
+impl From<String> for ETag {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for ETag {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl ETag {
+    ///
+    /// Construct a strong entity tag derived from `content`, using the 64-bit FNV-1a hash and
+    /// the length of the content.
+    ///
+    pub fn of_content(content: &[u8]) -> Self {
+        let hash = content
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+            });
+        Self(format!("{:x}-{hash:016x}", content.len()))
+    }
+
+    ///
+    /// Returns the value of this tag, without quotes.
+    ///
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Precondition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "no precondition"),
+            Self::IfMatch(etag) => write!(f, "If-Match: {etag}"),
+            Self::IfNoneMatch => write!(f, "If-None-Match: *"),
+        }
+    }
+}
+
+impl Precondition {
+    ///
+    /// Check this precondition against the entity tag of the current object, if any.
+    ///
+    pub fn check(&self, current: Option<&ETag>) -> Result<(), PreconditionFailed> {
+        let holds = match self {
+            Self::None => true,
+            Self::IfMatch(etag) => current == Some(etag),
+            Self::IfNoneMatch => current.is_none(),
+        };
+        if holds {
+            Ok(())
+        } else {
+            Err(PreconditionFailed {
+                precondition: self.clone(),
+                current: current.cloned(),
+            })
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PreconditionFailed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.current {
+            Some(etag) => write!(
+                f,
+                "the write required {} but the current object has the entity tag {etag}",
+                self.precondition
+            ),
+            None => write!(
+                f,
+                "the write required {} but the object does not exist",
+                self.precondition
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PreconditionFailed {}
+
+impl From<PreconditionFailed> for IoError {
+    fn from(e: PreconditionFailed) -> Self {
+        Error::from(e).into()
+    }
+}
+
+impl PreconditionFailed {
+    ///
+    /// Returns the precondition that did not hold.
+    ///
+    pub fn precondition(&self) -> &Precondition {
+        &self.precondition
+    }
+
+    ///
+    /// Returns the entity tag of the current object, or `None` if it does not exist.
+    ///
+    pub fn current(&self) -> Option<&ETag> {
+        self.current.as_ref()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Versioned<T> {
+    ///
+    /// Returns the object read.
+    ///
+    pub fn object(&self) -> &T {
+        &self.object
+    }
+
+    ///
+    /// Returns the entity tag of the version read.
+    ///
+    pub fn etag(&self) -> &ETag {
+        &self.etag
+    }
+
+    ///
+    /// Returns the object and its entity tag, consuming this value.
+    ///
+    pub fn into_parts(self) -> (T, ETag) {
+        (self.object, self.etag)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ObjectStore for FileStore {
+    fn get(&self, key: &str) -> Result<Option<(Vec<u8>, ETag)>, IoError> {
+        match std::fs::read(self.path(key)?) {
+            Ok(content) => {
+                let etag = ETag::of_content(&content);
+                Ok(Some((content, etag)))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn put(&self, key: &str, content: &[u8], precondition: &Precondition) -> Result<ETag, IoError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let lock = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(lock_path(&path))?;
+        lock.lock()?;
+        let result = self.get(key).and_then(|current| {
+            precondition.check(current.as_ref().map(|(_, etag)| etag))?;
+            replace_file(&path, content)
+        });
+        lock.unlock()?;
+        result.map(|_| ETag::of_content(content))
+    }
+}
+
+impl FileStore {
+    ///
+    /// Construct a new store in `directory`, creating it if necessary.
+    ///
+    pub fn new<P>(directory: P) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::create_dir_all(directory.as_ref())?;
+        Ok(Self {
+            directory: directory.as_ref().to_path_buf(),
+        })
+    }
+
+    ///
+    /// Returns the directory containing the stored objects.
+    ///
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, IoError> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            Err(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("the key `{key}` is not a relative path within the store"),
+            ))
+        } else if relative.extension() == Some(LOCK_FILE_EXTENSION.as_ref()) {
+            // Such a key would name the lock file of another.
+            Err(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("the key `{key}` has the reserved extension `.{LOCK_FILE_EXTENSION}`"),
+            ))
+        } else {
+            Ok(self.directory.join(relative))
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::{RawReader, RawWriter};
+
+    #[test]
+    fn test_preconditions() {
+        let tag = ETag::of_content(b"content");
+        assert_eq!(tag, ETag::of_content(b"content"));
+        assert_ne!(tag, ETag::of_content(b"Content"));
+
+        assert!(Precondition::None.check(None).is_ok());
+        assert!(Precondition::IfNoneMatch.check(None).is_ok());
+        let error = Precondition::IfNoneMatch.check(Some(&tag)).unwrap_err();
+        assert_eq!(error.current(), Some(&tag));
+        let error = Precondition::IfMatch(tag.clone()).check(None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("the write required If-Match: {tag} but the object does not exist")
+        );
+    }
+
+    #[test]
+    fn test_file_store() {
+        let directory =
+            std::env::temp_dir().join(format!("objio-store-test-{}", std::process::id()));
+        let store = FileStore::new(&directory).unwrap();
+        assert!(store.get("../outside").is_err());
+        assert!(read_versioned::<_, _, String>(&store, "a", &RawReader)
+            .unwrap()
+            .is_none());
+
+        let first = write_conditional(
+            &store,
+            "a",
+            &RawWriter,
+            &vec![1u8],
+            Precondition::IfNoneMatch,
+        )
+        .unwrap();
+        let error = write_conditional(
+            &store,
+            "a",
+            &RawWriter,
+            &vec![2u8],
+            Precondition::IfNoneMatch,
+        )
+        .unwrap_err();
+        assert!(matches!(Error::from(error), Error::PreconditionFailed(_)));
+
+        let second = write_conditional(
+            &store,
+            "a",
+            &RawWriter,
+            &vec![2u8],
+            Precondition::IfMatch(first.clone()),
+        )
+        .unwrap();
+        let (object, etag) = read_versioned::<_, _, Vec<u8>>(&store, "a", &RawReader)
+            .unwrap()
+            .unwrap()
+            .into_parts();
+        assert_eq!(object, vec![2]);
+        assert_eq!(etag, second);
+        assert!(store.put("a", &[3], &Precondition::IfMatch(first)).is_err());
+
+        assert!(store.put("a.lock", &[4], &Precondition::None).is_err());
+        let _ = store.put("nested/b", &[5], &Precondition::None).unwrap();
+        assert_eq!(store.get("nested/b").unwrap().unwrap().0, vec![5]);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+/*!
+Provides integration with [figment](https://docs.rs/figment) so that options may be assembled
+from layered configuration, enabled by the `figment` feature.
+
+A typical application layers defaults, then a configuration file, then environment variables,
+and finally command-line arguments. The [`OptionsProvider`] type adapts any serializable options
+value, or overlay, into a figment provider so that the defaults and command-line layers can be
+expressed with the option types from this crate; as overlays omit values that are not present
+they only replace the values they set. The function [`extract_options`] extracts an options value
+and converts any error, which identifies both the key and the source of a bad value, into an
+[`Error`].
+
+# Example
+
+```rust
+use figment::Figment;
+use figment::providers::Serialized;
+use objio::config::{extract_options, OptionsProvider};
+use objio::options::{CommonWriterOptions, CommonWriterOptionsOverlay};
+
+let command_line = CommonWriterOptionsOverlay {
+    indent: Some(4),
+    ..Default::default()
+};
+
+let figment = Figment::new()
+    .merge(OptionsProvider::new(CommonWriterOptions::default()))
+    .merge(Serialized::defaults(serde_json::json!({ "pretty": true, "indent": 8 })))
+    .merge(OptionsProvider::new(command_line).named("command line"));
+
+let options: CommonWriterOptions = extract_options(&figment).unwrap();
+
+assert!(options.is_pretty());
+assert_eq!(options.indent(), 4);
+```
+ */
+
+use crate::error::Error;
+use ::figment::providers::Serialized;
+use ::figment::value::{Dict, Map};
+use ::figment::{Figment, Metadata, Profile, Provider};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A figment provider for a serializable options value, or overlay.
+///
+#[derive(Clone, Debug)]
+pub struct OptionsProvider<T> {
+    options: T,
+    name: String,
+    key: Option<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Extract an options value from `figment`; any error is returned as [`Error::InvalidOptions`]
+/// with a message identifying the key and the source of the bad value.
+///
+pub fn extract_options<T>(figment: &Figment) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    figment.extract().map_err(Error::from)
+}
+
+///
+/// Extract an options value from the dictionary at `key` in `figment`; any error is returned as
+/// for [`extract_options`].
+///
+pub fn extract_options_at<T>(figment: &Figment, key: &str) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    figment.extract_inner(key).map_err(Error::from)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<T: Serialize> Provider for OptionsProvider<T> {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(self.name.clone())
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, ::figment::Error> {
+        match &self.key {
+            None => Serialized::defaults(&self.options).data(),
+            Some(key) => Serialized::default(key, &self.options).data(),
+        }
+    }
+}
+
+impl<T> OptionsProvider<T> {
+    ///
+    /// Construct a new provider for the values in `options`.
+    ///
+    pub fn new(options: T) -> Self {
+        Self {
+            options,
+            name: "options".to_string(),
+            key: None,
+        }
+    }
+
+    ///
+    /// Returns this provider with the name used to attribute its values in errors set to `name`.
+    ///
+    pub fn named<S>(self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.name = name.into();
+        self_mut
+    }
+
+    ///
+    /// Returns this provider with its values nested in the dictionary at `key`, as in
+    /// `writer.indent`.
+    ///
+    pub fn nested<S>(self, key: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.key = Some(key.into());
+        self_mut
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<::figment::Error> for Error {
+    fn from(e: ::figment::Error) -> Self {
+        Self::invalid_options(e.to_string())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::{CommonWriterOptions, CommonWriterOptionsOverlay, LineEnding};
+
+    #[test]
+    fn test_nested_overlay() {
+        let figment = Figment::new()
+            .merge(OptionsProvider::new(CommonWriterOptions::default()).nested("writer"))
+            .merge(
+                OptionsProvider::new(CommonWriterOptionsOverlay {
+                    line_ending: Some(LineEnding::CrLf),
+                    ..Default::default()
+                })
+                .nested("writer"),
+            );
+        let options: CommonWriterOptions = extract_options_at(&figment, "writer").unwrap();
+        assert_eq!(options.line_ending(), LineEnding::CrLf);
+        assert_eq!(
+            options,
+            CommonWriterOptions::default().with_line_ending(LineEnding::CrLf)
+        );
+    }
+
+    #[test]
+    fn test_error_attribution() {
+        let figment = Figment::new()
+            .merge(OptionsProvider::new(CommonWriterOptions::default()))
+            .merge(OptionsProvider::new(serde_json::json!({ "indent": "wide" })).named("settings"));
+        let error = extract_options::<CommonWriterOptions>(&figment).unwrap_err();
+        let message = error.to_string();
+        assert!(matches!(error, Error::InvalidOptions { .. }));
+        assert!(message.contains("indent"), "{message}");
+        assert!(message.contains("settings"), "{message}");
+    }
+}
@@ -0,0 +1,193 @@
+/*!
+Provides [`convert`], which converts a file from one format to another in a single call, choosing
+the reader from the extension of the source and the writer from the extension of the destination
+using a [`FormatRegistry`].
+
+Either format may instead be named explicitly with [`ConvertOptions`], which is required when
+the path has no extension or an extension that is not registered. The conversion itself is
+performed by [`copy`], so the source is read completely before the destination
+is created.
+
+# Example
+
+```rust
+use objio::convert::{convert, convert_with, ConvertOptions};
+use objio::raw::{RawReader, RawWriter};
+use objio::registry::FormatRegistry;
+
+let mut registry: FormatRegistry<String> = FormatRegistry::default();
+registry.register("text", &["txt"], RawReader, RawWriter);
+
+let directory = std::env::temp_dir();
+let source = directory.join(format!("objio-convert-doc-{}.txt", std::process::id()));
+let target = directory.join(format!("objio-convert-doc-{}.out", std::process::id()));
+std::fs::write(&source, "hello").unwrap();
+
+assert!(convert(&registry, &source, &target).is_err());
+
+let options = ConvertOptions::default().with_writer_format("text");
+let report = convert_with(&registry, &source, &target, &options).unwrap();
+assert_eq!(report.bytes_written(), 5);
+# std::fs::remove_file(&source).unwrap();
+# std::fs::remove_file(&target).unwrap();
+```
+ */
+
+use crate::copy::{copy, CopyError, CopyReport, Sink, Source};
+use crate::pipeline::PipelineStage;
+use crate::registry::FormatRegistry;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Options for [`convert_with`], naming formats to use in place of those chosen by extension.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConvertOptions {
+    reader_format: Option<String>,
+    writer_format: Option<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Convert the file at `from` into the file at `to`, choosing formats by their extensions.
+///
+pub fn convert<T, P, Q>(
+    registry: &FormatRegistry<T>,
+    from: P,
+    to: Q,
+) -> Result<CopyReport, CopyError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    convert_with(registry, from, to, &ConvertOptions::default())
+}
+
+///
+/// Convert the file at `from` into the file at `to`, choosing formats by their extensions unless
+/// named in `options`. A format that cannot be found is an error of kind `Unsupported`, reported
+/// in the read or write stage respectively.
+///
+pub fn convert_with<T, P, Q>(
+    registry: &FormatRegistry<T>,
+    from: P,
+    to: Q,
+    options: &ConvertOptions,
+) -> Result<CopyReport, CopyError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let reader = match &options.reader_format {
+        Some(name) => registry.reader_named(name),
+        None => registry.reader_for_path(from),
+    }
+    .ok_or_else(|| unknown_format(PipelineStage::Read, options.reader_format.as_deref(), from))?;
+    let writer = match &options.writer_format {
+        Some(name) => registry.writer_named(name),
+        None => registry.writer_for_path(to),
+    }
+    .ok_or_else(|| unknown_format(PipelineStage::Write, options.writer_format.as_deref(), to))?;
+    copy(
+        &reader,
+        &writer,
+        Source::Path(from.to_path_buf()),
+        Sink::Path(to.to_path_buf()),
+    )
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl ConvertOptions {
+    ///
+    /// Read the source with the format `name` rather than choosing it by extension.
+    ///
+    pub fn with_reader_format<S>(self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.reader_format = Some(name.into());
+        self_mut
+    }
+
+    ///
+    /// Write the destination with the format `name` rather than choosing it by extension.
+    ///
+    pub fn with_writer_format<S>(self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.writer_format = Some(name.into());
+        self_mut
+    }
+
+    ///
+    /// Returns the name of the format used to read the source, if named explicitly.
+    ///
+    pub fn reader_format(&self) -> Option<&str> {
+        self.reader_format.as_deref()
+    }
+
+    ///
+    /// Returns the name of the format used to write the destination, if named explicitly.
+    ///
+    pub fn writer_format(&self) -> Option<&str> {
+        self.writer_format.as_deref()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn unknown_format(stage: PipelineStage, name: Option<&str>, path: &Path) -> CopyError {
+    let message = match name {
+        Some(name) => format!("no format named `{name}` is registered"),
+        None => format!(
+            "no format is registered for the extension of `{}`",
+            path.display()
+        ),
+    };
+    CopyError::new(stage, IoError::new(ErrorKind::Unsupported, message))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "json", feature = "toml"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_to_toml() {
+        let registry: FormatRegistry<serde_json::Value> = FormatRegistry::with_serde_formats();
+        let directory = std::env::temp_dir();
+        let source = directory.join(format!("objio-convert-{}.json", std::process::id()));
+        let target = directory.join(format!("objio-convert-{}.toml", std::process::id()));
+        std::fs::write(&source, r#"{"name": "objio", "version": 2}"#).unwrap();
+
+        let _ = convert(&registry, &source, &target).unwrap();
+        let toml = std::fs::read_to_string(&target).unwrap();
+        assert!(toml.contains("name = \"objio\""));
+
+        let options = ConvertOptions::default().with_reader_format("XML");
+        let error = convert_with(&registry, &source, &target, &options).unwrap_err();
+        assert_eq!(error.stage(), PipelineStage::Read);
+        std::fs::remove_file(&source).unwrap();
+        std::fs::remove_file(&target).unwrap();
+    }
+}
@@ -0,0 +1,682 @@
+/*!
+Provides [`copy`], which reads an object from a [`Source`] using one reader and writes it to a
+[`Sink`] using a writer, handling the opening, buffering, and closing of each and reporting
+progress and statistics. This is the core of any command that converts between formats.
+
+Sources and sinks may be paths, in-memory strings or bytes, the standard streams, or `file:` URLs;
+both implement `FromStr` so that they may be taken directly from command-line arguments, where
+`-` denotes the standard input or output. The object is read completely before the sink is
+opened, so that a source which fails to parse does not truncate an existing destination, and a
+file may be converted in place. A [`Source`] may also be read directly by any reader using
+[`ObjectReader::read_from`].
+
+# Example
+
+```rust
+use objio::copy::{copy, Sink, Source};
+use objio::raw::{RawReader, RawWriter};
+
+let report = copy::<_, _, String>(
+    &RawReader,
+    &RawWriter,
+    Source::from("Hello, world"),
+    Sink::Buffer,
+)
+.unwrap();
+
+assert_eq!(report.bytes_read(), 12);
+assert_eq!(report.output(), Some("Hello, world".as_bytes()));
+```
+ */
+
+use crate::error::BoxedError;
+use crate::pipeline::PipelineStage;
+use crate::{ObjectReader, ObjectWriter};
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Error as IoError, ErrorKind, Read, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The source from which [`copy`] reads an object.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// The file identified by a path.
+    Path(PathBuf),
+    /// The content of a string.
+    String(String),
+    /// The content of a vector of bytes.
+    Bytes(Vec<u8>),
+    /// The standard input stream.
+    Stdin,
+    /// The resource identified by a URL; only `file:` URLs are currently supported.
+    Url(String),
+}
+
+///
+/// The destination to which [`copy`] writes an object.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sink {
+    /// The file identified by a path, which is created or replaced.
+    Path(PathBuf),
+    /// An in-memory buffer, returned by [`CopyReport::output`].
+    Buffer,
+    /// The standard output stream.
+    Stdout,
+    /// The standard error stream.
+    Stderr,
+    /// The resource identified by a URL; only `file:` URLs are currently supported.
+    Url(String),
+}
+
+///
+/// The phase of a copy reported in a [`CopyProgress`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CopyPhase {
+    /// The object is being read from the source.
+    Reading,
+    /// The object is being written to the sink.
+    Writing,
+    /// The copy has completed.
+    Finished,
+}
+
+///
+/// A progress report, provided to the callback of [`copy_with_progress`] after each read from
+/// the source and each write to the sink.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CopyProgress {
+    phase: CopyPhase,
+    bytes_read: u64,
+    bytes_written: u64,
+}
+
+///
+/// The result of a successful copy.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CopyReport {
+    bytes_read: u64,
+    bytes_written: u64,
+    elapsed: Duration,
+    output: Option<Vec<u8>>,
+}
+
+///
+/// An error reported by [`copy`], identifying the stage in which it occurred.
+///
+#[derive(Debug)]
+pub struct CopyError {
+    stage: PipelineStage,
+    source: BoxedError,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+struct Counting<'a, S> {
+    inner: S,
+    progress: &'a mut dyn FnMut(&CopyProgress),
+    state: CopyProgress,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read an object from `from` using `reader` and write it to `to` using `writer`.
+///
+pub fn copy<R, W, T>(
+    reader: &R,
+    writer: &W,
+    from: Source,
+    to: Sink,
+) -> Result<CopyReport, CopyError>
+where
+    R: ObjectReader<T>,
+    R::Error: Into<BoxedError>,
+    W: ObjectWriter<T>,
+    W::Error: Into<BoxedError>,
+{
+    copy_with_progress(reader, writer, from, to, |_| {})
+}
+
+///
+/// Read an object from `from` using `reader` and write it to `to` using `writer`, calling
+/// `progress` after each read and write and once the copy has finished.
+///
+pub fn copy_with_progress<R, W, T, P>(
+    reader: &R,
+    writer: &W,
+    from: Source,
+    to: Sink,
+    progress: P,
+) -> Result<CopyReport, CopyError>
+where
+    R: ObjectReader<T>,
+    R::Error: Into<BoxedError>,
+    W: ObjectWriter<T>,
+    W::Error: Into<BoxedError>,
+    P: FnMut(&CopyProgress),
+{
+    let mut progress = progress;
+    let start = Instant::now();
+    reader.validate_options().map_err(CopyError::read)?;
+    writer.validate_options().map_err(CopyError::write)?;
+
+    let mut source = Counting {
+        inner: from.open().map_err(CopyError::read)?,
+        progress: &mut progress,
+        state: CopyProgress {
+            phase: CopyPhase::Reading,
+            bytes_read: 0,
+            bytes_written: 0,
+        },
+    };
+    let object = reader.read_buffered(&mut source).map_err(CopyError::read)?;
+    let mut state = source.state;
+    state.phase = CopyPhase::Writing;
+
+    let output = match to {
+        Sink::Buffer => {
+            let mut buffer = Vec::with_capacity(writer.write_size_hint(&object));
+            state = write_to(writer, &object, &mut buffer, &mut progress, state)?;
+            Some(buffer)
+        }
+        Sink::Stdout => {
+            state = write_to(
+                writer,
+                &object,
+                std::io::stdout().lock(),
+                &mut progress,
+                state,
+            )?;
+            None
+        }
+        Sink::Stderr => {
+            state = write_to(
+                writer,
+                &object,
+                std::io::stderr().lock(),
+                &mut progress,
+                state,
+            )?;
+            None
+        }
+        Sink::Path(path) => {
+            let file = create(&path).map_err(CopyError::write)?;
+            state = write_to(writer, &object, BufWriter::new(file), &mut progress, state)?;
+            None
+        }
+        Sink::Url(url) => {
+            let file = file_url_path(&url)
+                .and_then(|path| create(&path))
+                .map_err(CopyError::write)?;
+            state = write_to(writer, &object, BufWriter::new(file), &mut progress, state)?;
+            None
+        }
+    };
+
+    state.phase = CopyPhase::Finished;
+    progress(&state);
+    Ok(CopyReport {
+        bytes_read: state.bytes_read,
+        bytes_written: state.bytes_written,
+        elapsed: start.elapsed(),
+        output,
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl From<&str> for Source {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Source {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Vec<u8>> for Source {
+    fn from(value: Vec<u8>) -> Self {
+        Self::Bytes(value)
+    }
+}
+
+impl From<&[u8]> for Source {
+    fn from(value: &[u8]) -> Self {
+        Self::Bytes(value.to_vec())
+    }
+}
+
+impl From<PathBuf> for Source {
+    fn from(value: PathBuf) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl FromStr for Source {
+    type Err = Infallible;
+
+    ///
+    /// Parse a command-line argument, where `-` denotes the standard input, a value with a URL
+    /// scheme denotes a URL, and any other value a path.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(if s == "-" {
+            Self::Stdin
+        } else if has_url_scheme(s) {
+            Self::Url(s.to_string())
+        } else {
+            Self::Path(s.into())
+        })
+    }
+}
+
+impl Source {
+    fn open(self) -> Result<Box<dyn BufRead>, CopyError> {
+        Ok(match self {
+            Self::Path(path) => Box::new(BufReader::new(open(&path).map_err(CopyError::read)?)),
+            Self::String(s) => Box::new(std::io::Cursor::new(s.into_bytes())),
+            Self::Bytes(bytes) => Box::new(std::io::Cursor::new(bytes)),
+            Self::Stdin => Box::new(std::io::stdin().lock()),
+            Self::Url(url) => Box::new(BufReader::new(
+                file_url_path(&url)
+                    .and_then(|path| open(&path))
+                    .map_err(CopyError::read)?,
+            )),
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<PathBuf> for Sink {
+    fn from(value: PathBuf) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl FromStr for Sink {
+    type Err = Infallible;
+
+    ///
+    /// Parse a command-line argument, where `-` denotes the standard output, a value with a URL
+    /// scheme denotes a URL, and any other value a path.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(if s == "-" {
+            Self::Stdout
+        } else if has_url_scheme(s) {
+            Self::Url(s.to_string())
+        } else {
+            Self::Path(s.into())
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for CopyPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Reading => write!(f, "reading"),
+            Self::Writing => write!(f, "writing"),
+            Self::Finished => write!(f, "finished"),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl CopyProgress {
+    ///
+    /// Returns the current phase of the copy.
+    ///
+    pub fn phase(&self) -> CopyPhase {
+        self.phase
+    }
+
+    ///
+    /// Returns the number of bytes read from the source so far.
+    ///
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    ///
+    /// Returns the number of bytes written to the sink so far.
+    ///
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl CopyReport {
+    ///
+    /// Returns the number of bytes read from the source.
+    ///
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    ///
+    /// Returns the number of bytes written to the sink.
+    ///
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    ///
+    /// Returns the time taken by the copy.
+    ///
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    ///
+    /// Returns the output written to a [`Sink::Buffer`], or `None` for any other sink.
+    ///
+    pub fn output(&self) -> Option<&[u8]> {
+        self.output.as_deref()
+    }
+
+    ///
+    /// Returns the output written to a [`Sink::Buffer`], consuming this report.
+    ///
+    pub fn into_output(self) -> Option<Vec<u8>> {
+        self.output
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for CopyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Copy failed in the {} stage; source: {}",
+            self.stage, self.source
+        )
+    }
+}
+
+impl std::error::Error for CopyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl CopyError {
+    ///
+    /// Returns the stage in which the error occurred, either [`PipelineStage::Read`] or
+    /// [`PipelineStage::Write`].
+    ///
+    pub fn stage(&self) -> PipelineStage {
+        self.stage
+    }
+
+    ///
+    /// Returns the underlying error, consuming this error.
+    ///
+    pub fn into_source(self) -> BoxedError {
+        self.source
+    }
+
+    pub(crate) fn new<E>(stage: PipelineStage, e: E) -> Self
+    where
+        E: Into<BoxedError>,
+    {
+        Self {
+            stage,
+            source: e.into(),
+        }
+    }
+
+    fn read<E>(e: E) -> Self
+    where
+        E: Into<BoxedError>,
+    {
+        Self::new(PipelineStage::Read, e)
+    }
+
+    fn write<E>(e: E) -> Self
+    where
+        E: Into<BoxedError>,
+    {
+        Self::new(PipelineStage::Write, e)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<S> Read for Counting<'_, S>
+where
+    S: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.add_read(count);
+        Ok(count)
+    }
+}
+
+impl<S> BufRead for Counting<'_, S>
+where
+    S: BufRead,
+{
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+        self.add_read(amount);
+    }
+}
+
+impl<S> Write for Counting<'_, S>
+where
+    S: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let count = self.inner.write(buf)?;
+        if count > 0 {
+            self.state.bytes_written += count as u64;
+            (self.progress)(&self.state);
+        }
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<S> Counting<'_, S> {
+    fn add_read(&mut self, count: usize) {
+        if count > 0 {
+            self.state.bytes_read += count as u64;
+            (self.progress)(&self.state);
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn write_to<W, T, S>(
+    writer: &W,
+    object: &T,
+    sink: S,
+    progress: &mut dyn FnMut(&CopyProgress),
+    state: CopyProgress,
+) -> Result<CopyProgress, CopyError>
+where
+    W: ObjectWriter<T>,
+    W::Error: Into<BoxedError>,
+    S: Write,
+{
+    let mut sink = Counting {
+        inner: sink,
+        progress,
+        state,
+    };
+    writer.write(&mut sink, object).map_err(CopyError::write)?;
+    sink.flush().map_err(CopyError::write)?;
+    Ok(sink.state)
+}
+
+fn open(path: &PathBuf) -> Result<File, IoError> {
+    OpenOptions::new().read(true).open(path)
+}
+
+fn create(path: &PathBuf) -> Result<File, IoError> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+}
+
+fn has_url_scheme(s: &str) -> bool {
+    match s.split_once("://") {
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+pub(crate) fn file_url_path(url: &str) -> Result<PathBuf, IoError> {
+    match url.split_once("://") {
+        Some((scheme, path)) if scheme.eq_ignore_ascii_case("file") => Ok(PathBuf::from(path)),
+        _ => Err(IoError::new(
+            ErrorKind::Unsupported,
+            format!("the URL `{url}` is not supported, only `file://` URLs may be copied"),
+        )),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::{RawReader, RawWriter};
+
+    #[test]
+    fn test_read_from() {
+        let path = std::env::temp_dir().join(format!("objio-read-from-{}.txt", std::process::id()));
+        std::fs::write(&path, "in a file").unwrap();
+
+        let read = |source: Source| -> Result<String, IoError> { RawReader.read_from(&source) };
+        assert_eq!(read(Source::from("a string")).unwrap(), "a string");
+        assert_eq!(read(Source::from(&b"a slice"[..])).unwrap(), "a slice");
+        assert_eq!(read(Source::from(path.clone())).unwrap(), "in a file");
+        assert_eq!(
+            read(format!("file://{}", path.display()).parse().unwrap()).unwrap(),
+            "in a file"
+        );
+        assert_eq!(
+            read("http://example.com/a".parse().unwrap())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Unsupported
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        assert_eq!("-".parse::<Source>().unwrap(), Source::Stdin);
+        assert_eq!("-".parse::<Sink>().unwrap(), Sink::Stdout);
+        assert_eq!(
+            "file:///tmp/a.ttl".parse::<Source>().unwrap(),
+            Source::Url("file:///tmp/a.ttl".to_string())
+        );
+        assert_eq!(
+            "c:/data/a.ttl".parse::<Sink>().unwrap(),
+            Sink::Path("c:/data/a.ttl".into())
+        );
+    }
+
+    #[test]
+    fn test_copy_file_with_progress() {
+        let directory = std::env::temp_dir();
+        let path = directory.join(format!("objio-copy-{}", std::process::id()));
+
+        let mut phases = Vec::new();
+        let report = copy_with_progress::<_, _, Vec<u8>, _>(
+            &RawReader,
+            &RawWriter,
+            Source::from(vec![1, 2, 3]),
+            Sink::Url(format!("file://{}", path.display())),
+            |p| phases.push((p.phase(), p.bytes_read(), p.bytes_written())),
+        )
+        .unwrap();
+        assert_eq!(report.bytes_written(), 3);
+        assert_eq!(report.output(), None);
+        assert_eq!(phases.last(), Some(&(CopyPhase::Finished, 3, 3)));
+        assert!(phases.contains(&(CopyPhase::Reading, 3, 0)));
+
+        let report = copy::<_, _, Vec<u8>>(
+            &RawReader,
+            &RawWriter,
+            Source::Path(path.clone()),
+            Sink::Buffer,
+        )
+        .unwrap();
+        assert_eq!(report.into_output().unwrap(), vec![1, 2, 3]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_copy_errors() {
+        let error = copy::<_, _, String>(
+            &RawReader,
+            &RawWriter,
+            Source::Bytes(vec![0xFF]),
+            Sink::Buffer,
+        )
+        .unwrap_err();
+        assert_eq!(error.stage(), PipelineStage::Read);
+
+        let error = copy::<_, _, String>(
+            &RawReader,
+            &RawWriter,
+            Source::from("text"),
+            Sink::Url("https://example.com/a".to_string()),
+        )
+        .unwrap_err();
+        assert_eq!(error.stage(), PipelineStage::Write);
+    }
+}
@@ -0,0 +1,269 @@
+/*!
+Provides helpers for writers that support the
+[`deterministic`](crate::options::CommonWriterOptions::is_deterministic) option, under which equal
+objects must be written as byte-identical output across runs, so that the output may be cached or
+compared by its hash.
+
+The common sources of nondeterminism are the iteration order of hash maps, the current time, and
+generated identifiers. Map entries may be put into a stable order with [`sorted_entries`] or
+[`sorted_map`]. Writers that embed a time or an identifier should take it from a [`Clock`] or an
+[`IdSource`] rather than directly from the system; [`clock_for`] and [`id_source_for`] choose an
+implementation appropriate to the writer's options.
+
+When deterministic, the clock is fixed at the time given by the `SOURCE_DATE_EPOCH` environment
+variable, following the [reproducible builds](https://reproducible-builds.org/specs/source-date-epoch/)
+convention, or at the Unix epoch if it is not set.
+
+# Example
+
+```rust
+use objio::deterministic::{clock_for, id_source_for, sorted_entries};
+use objio::options::CommonWriterOptions;
+use std::collections::HashMap;
+
+let options = CommonWriterOptions::default().with_deterministic(true);
+
+let map: HashMap<&str, u32> = [("b", 2), ("c", 3), ("a", 1)].into_iter().collect();
+let keys: Vec<_> = sorted_entries(&map).into_iter().map(|(k, _)| *k).collect();
+assert_eq!(keys, ["a", "b", "c"]);
+
+let ids = id_source_for(&options);
+assert_eq!((ids.next_id(), ids.next_id()), (0, 1));
+
+let clock = clock_for(&options);
+assert_eq!(clock.now(), clock.now());
+```
+ */
+
+use crate::options::CommonWriterOptions;
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The environment variable holding the time, in seconds since the Unix epoch, used by
+/// [`FixedClock::from_env`].
+///
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A source of the current time for writers that embed timestamps in their output.
+///
+pub trait Clock {
+    ///
+    /// Returns the current time.
+    ///
+    fn now(&self) -> SystemTime;
+}
+
+///
+/// A clock returning the time reported by the system.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+///
+/// A clock that always returns the same time.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedClock(SystemTime);
+
+///
+/// A source of identifiers for writers that generate them, such as for anchors or references.
+///
+pub trait IdSource {
+    ///
+    /// Returns the next identifier.
+    ///
+    fn next_id(&self) -> u64;
+}
+
+///
+/// An identifier source returning `0`, `1`, `2`, and so on.
+///
+#[derive(Debug, Default)]
+pub struct SequentialIds {
+    next: AtomicU64,
+}
+
+///
+/// An identifier source returning identifiers that differ between runs.
+///
+#[derive(Debug, Default)]
+pub struct RandomIds {
+    state: RandomState,
+    next: AtomicU64,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the entries of `map`, or any other collection of pairs, ordered by key.
+///
+pub fn sorted_entries<'a, K, V, I>(map: I) -> Vec<(&'a K, &'a V)>
+where
+    K: Ord + 'a,
+    V: 'a,
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+{
+    let mut entries: Vec<_> = map.into_iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+///
+/// Returns a view of `map`, or any other collection of pairs, that iterates in key order.
+///
+pub fn sorted_map<'a, K, V, I>(map: I) -> BTreeMap<&'a K, &'a V>
+where
+    K: Ord + 'a,
+    V: 'a,
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+{
+    map.into_iter().collect()
+}
+
+///
+/// Returns the clock a writer with `options` should use; a [`FixedClock`] from
+/// [`FixedClock::from_env`] if deterministic, otherwise the [`SystemClock`].
+///
+pub fn clock_for(options: &CommonWriterOptions) -> Box<dyn Clock + Send + Sync> {
+    if options.is_deterministic() {
+        Box::new(FixedClock::from_env())
+    } else {
+        Box::new(SystemClock)
+    }
+}
+
+///
+/// Returns the identifier source a writer with `options` should use; [`SequentialIds`] if
+/// deterministic, otherwise [`RandomIds`].
+///
+pub fn id_source_for(options: &CommonWriterOptions) -> Box<dyn IdSource + Send + Sync> {
+    if options.is_deterministic() {
+        Box::new(SequentialIds::default())
+    } else {
+        Box::new(RandomIds::default())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Default for FixedClock {
+    fn default() -> Self {
+        Self(UNIX_EPOCH)
+    }
+}
+
+impl From<SystemTime> for FixedClock {
+    fn from(value: SystemTime) -> Self {
+        Self(value)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+impl FixedClock {
+    ///
+    /// Construct a new clock fixed at `seconds` after the Unix epoch.
+    ///
+    pub fn from_epoch_seconds(seconds: u64) -> Self {
+        Self(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    ///
+    /// Construct a new clock fixed at the time given by the `SOURCE_DATE_EPOCH` environment
+    /// variable, or at the Unix epoch if it is not set or is not a number of seconds.
+    ///
+    pub fn from_env() -> Self {
+        std::env::var(SOURCE_DATE_EPOCH)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .map(Self::from_epoch_seconds)
+            .unwrap_or_default()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl IdSource for SequentialIds {
+    fn next_id(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+impl SequentialIds {
+    ///
+    /// Construct a new source whose first identifier is `first`.
+    ///
+    pub fn starting_at(first: u64) -> Self {
+        Self {
+            next: AtomicU64::new(first),
+        }
+    }
+}
+
+impl IdSource for RandomIds {
+    fn next_id(&self) -> u64 {
+        let mut hasher = self.state.build_hasher();
+        hasher.write_u64(self.next.fetch_add(1, Ordering::Relaxed));
+        hasher.finish()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_sorted_map() {
+        let map: HashMap<u32, char> = (0..32).map(|i| (31 - i, 'x')).collect();
+        let keys: Vec<u32> = sorted_map(&map).into_keys().copied().collect();
+        assert_eq!(keys, (0..32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_hooks_follow_options() {
+        let options = CommonWriterOptions::default();
+        let ids = id_source_for(&options);
+        assert_ne!(ids.next_id(), ids.next_id());
+
+        let ids = SequentialIds::starting_at(7);
+        assert_eq!(ids.next_id(), 7);
+        assert_eq!(
+            FixedClock::from_epoch_seconds(60).now(),
+            UNIX_EPOCH + Duration::from_secs(60)
+        );
+    }
+}
@@ -0,0 +1,246 @@
+/*!
+Provides a common mechanism for readers and writers to report problems that do not prevent them
+from completing, such as content that was ignored.
+
+A [`Diagnostic`] is reported to a [`DiagnosticSink`], which may discard, collect, or log it. Any
+closure taking a `Diagnostic` is a sink, and the types [`IgnoreDiagnostics`] and
+[`CollectDiagnostics`] provide the two most common behaviors.
+
+# Example
+
+```rust
+use objio::diagnostics::{CollectDiagnostics, Diagnostic, DiagnosticSink, Severity};
+
+let sink = CollectDiagnostics::default();
+sink.report(Diagnostic::warning("ignored unknown element `extra`"));
+
+let diagnostics = sink.into_diagnostics();
+assert_eq!(diagnostics.len(), 1);
+assert_eq!(diagnostics[0].severity(), Severity::Warning);
+assert_eq!(diagnostics[0].to_string(), "warning: ignored unknown element `extra`");
+```
+ */
+
+use std::fmt::{Display, Formatter};
+use std::sync::Mutex;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The severity of a [`Diagnostic`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Information that may be of interest, but requires no action.
+    Info,
+    /// A problem that did not prevent the operation from completing, but may affect its result.
+    Warning,
+    /// A problem that prevented part of the operation from completing.
+    Error,
+}
+
+///
+/// A single problem, or piece of information, reported by a reader or writer.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    severity: Severity,
+    message: String,
+}
+
+///
+/// The trait implemented by receivers of diagnostics.
+///
+pub trait DiagnosticSink {
+    ///
+    /// Report `diagnostic` to this sink.
+    ///
+    fn report(&self, diagnostic: Diagnostic);
+}
+
+///
+/// A sink that discards all diagnostics.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IgnoreDiagnostics;
+
+///
+/// A sink that collects all diagnostics, in the order reported.
+///
+#[derive(Debug, Default)]
+pub struct CollectDiagnostics {
+    diagnostics: Mutex<Vec<Diagnostic>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Info => "info",
+                Self::Warning => "warning",
+                Self::Error => "error",
+            }
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+impl Diagnostic {
+    ///
+    /// Construct a new diagnostic with the given severity.
+    ///
+    pub fn new<S>(severity: Severity, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            severity,
+            message: message.into(),
+        }
+    }
+
+    ///
+    /// Construct a new informational diagnostic.
+    ///
+    pub fn info<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(Severity::Info, message)
+    }
+
+    ///
+    /// Construct a new warning diagnostic.
+    ///
+    pub fn warning<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(Severity::Warning, message)
+    }
+
+    ///
+    /// Construct a new error diagnostic.
+    ///
+    pub fn error<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(Severity::Error, message)
+    }
+
+    ///
+    /// Returns the severity of this diagnostic.
+    ///
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    ///
+    /// Returns the message describing this diagnostic.
+    ///
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<F> DiagnosticSink for F
+where
+    F: Fn(Diagnostic),
+{
+    fn report(&self, diagnostic: Diagnostic) {
+        self(diagnostic)
+    }
+}
+
+impl DiagnosticSink for IgnoreDiagnostics {
+    fn report(&self, _diagnostic: Diagnostic) {}
+}
+
+impl DiagnosticSink for CollectDiagnostics {
+    fn report(&self, diagnostic: Diagnostic) {
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(diagnostic);
+    }
+}
+
+impl CollectDiagnostics {
+    ///
+    /// Returns a copy of the diagnostics collected so far.
+    ///
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    ///
+    /// Returns the highest severity of the diagnostics collected so far, if any.
+    ///
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(Diagnostic::severity)
+            .max()
+    }
+
+    ///
+    /// Returns the collected diagnostics, consuming the sink.
+    ///
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_closure_sink() {
+        let messages = RefCell::new(Vec::new());
+        let sink = |d: Diagnostic| messages.borrow_mut().push(d.to_string());
+        sink.report(Diagnostic::info("one"));
+        sink.report(Diagnostic::error("two"));
+        assert_eq!(*messages.borrow(), vec!["info: one", "error: two"]);
+    }
+
+    #[test]
+    fn test_max_severity() {
+        let sink = CollectDiagnostics::default();
+        assert_eq!(sink.max_severity(), None);
+        sink.report(Diagnostic::warning("one"));
+        sink.report(Diagnostic::info("two"));
+        assert_eq!(sink.max_severity(), Some(Severity::Warning));
+        assert_eq!(sink.diagnostics().len(), 2);
+    }
+}
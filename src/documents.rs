@@ -0,0 +1,343 @@
+/*!
+Provides adapters that read and write streams of multiple documents, separated by a configurable
+[`DocumentSeparator`], using any [`ObjectReader`] or [`ObjectWriter`] for the documents
+themselves.
+
+The [`SeparatedReader`] and [`SeparatedWriter`] types implement the streaming traits
+[`ObjectsReader`] and [`ObjectsWriter`] respectively. Separators are written *between* documents;
+when reading, documents that are empty or contain only whitespace are skipped, so that a leading
+or trailing separator does not produce an additional document.
+
+# Example
+
+```rust
+use objio::documents::{DocumentSeparator, SeparatedReader, SeparatedWriter};
+use objio::stream::{ObjectsReader, ObjectsWriter};
+use objio::{ObjectReader, ObjectWriter};
+use std::io::{Read, Write};
+
+#[derive(Debug, Default)]
+struct TextIo;
+
+impl ObjectWriter<String> for TextIo {
+    type Error = objio::Error;
+
+    fn write<W: Write>(&self, w: &mut W, object: &String) -> Result<(), Self::Error> {
+        Ok(w.write_all(object.as_bytes())?)
+    }
+}
+
+impl ObjectReader<String> for TextIo {
+    type Error = objio::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+        let mut s = String::new();
+        r.read_to_string(&mut s)?;
+        Ok(s)
+    }
+}
+
+let writer = SeparatedWriter::new(TextIo, DocumentSeparator::Yaml);
+let mut buffer: Vec<u8> = Vec::new();
+writer.write_all(&mut buffer, ["a: 1".to_string(), "b: 2\n".to_string()]).unwrap();
+assert_eq!(buffer, b"a: 1\n---\nb: 2\n");
+
+let reader = SeparatedReader::new(TextIo, DocumentSeparator::Yaml);
+let documents = reader.read_all(&mut "---\na: 1\n---\nb: 2\n".as_bytes()).unwrap();
+assert_eq!(documents, vec!["a: 1\n", "b: 2\n"]);
+```
+ */
+
+use crate::stream::{ObjectsReader, ObjectsWriter};
+use crate::{ObjectReader, ObjectWriter};
+use std::io::{BufRead, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The separator between documents in a stream.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DocumentSeparator {
+    /// A line containing only `---`, as used by YAML streams; each document is terminated by a
+    /// line ending before the separator is written.
+    Yaml,
+    /// A single form feed character, `\x0C`.
+    FormFeed,
+    /// An arbitrary, non-empty, sequence of bytes which may appear anywhere in the stream.
+    Bytes(Vec<u8>),
+}
+
+///
+/// A reader for streams of documents separated by a [`DocumentSeparator`], where each document is
+/// read by an inner [`ObjectReader`].
+///
+#[derive(Clone, Debug)]
+pub struct SeparatedReader<O> {
+    inner: O,
+    separator: DocumentSeparator,
+}
+
+///
+/// A writer for streams of documents separated by a [`DocumentSeparator`], where each document is
+/// written by an inner [`ObjectWriter`].
+///
+#[derive(Clone, Debug)]
+pub struct SeparatedWriter<O> {
+    inner: O,
+    separator: DocumentSeparator,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+const YAML_SEPARATOR: &[u8] = b"---";
+const FORM_FEED: &[u8] = b"\x0C";
+
+impl DocumentSeparator {
+    ///
+    /// Returns the bytes written between documents.
+    ///
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Yaml => YAML_SEPARATOR,
+            Self::FormFeed => FORM_FEED,
+            Self::Bytes(bytes) => bytes,
+        }
+    }
+
+    ///
+    /// Read the next document from `r` into `document`, returning `true` if it was terminated by
+    /// a separator or `false` if it was terminated by the end of the stream.
+    ///
+    fn read_document<R>(&self, r: &mut R, document: &mut Vec<u8>) -> std::io::Result<bool>
+    where
+        R: BufRead,
+    {
+        match self {
+            Self::Yaml => {
+                let mut line = Vec::new();
+                loop {
+                    line.clear();
+                    if r.read_until(b'\n', &mut line)? == 0 {
+                        return Ok(false);
+                    }
+                    if line.trim_ascii_end() == YAML_SEPARATOR {
+                        return Ok(true);
+                    }
+                    document.extend_from_slice(&line);
+                }
+            }
+            _ => {
+                let separator = self.as_bytes();
+                let Some(last) = separator.last() else {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "document separator may not be empty",
+                    ));
+                };
+                loop {
+                    if r.read_until(*last, document)? == 0 {
+                        return Ok(false);
+                    }
+                    if document.ends_with(separator) {
+                        document.truncate(document.len() - separator.len());
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O, T> ObjectsReader<T> for SeparatedReader<O>
+where
+    O: ObjectReader<T>,
+{
+    type Error = O::Error;
+
+    fn read_next<R>(&self, r: &mut R) -> Result<Option<T>, Self::Error>
+    where
+        R: BufRead,
+    {
+        loop {
+            let mut document = Vec::new();
+            let separated = self.separator.read_document(r, &mut document)?;
+            if !document.trim_ascii().is_empty() {
+                return self.inner.read_from_bytes(document).map(Some);
+            } else if !separated {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+impl<O> SeparatedReader<O> {
+    ///
+    /// Construct a new reader of documents, read by `inner`, separated by `separator`.
+    ///
+    pub fn new(inner: O, separator: DocumentSeparator) -> Self {
+        Self { inner, separator }
+    }
+
+    ///
+    /// Returns a reference to the inner reader.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Returns the separator between documents.
+    ///
+    pub fn separator(&self) -> &DocumentSeparator {
+        &self.separator
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O, T> ObjectsWriter<T> for SeparatedWriter<O>
+where
+    O: ObjectWriter<T>,
+{
+    type Error = O::Error;
+
+    fn write_start<W>(&self, _w: &mut W) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.inner.validate_options()
+    }
+
+    fn write_next<W>(&self, w: &mut W, object: &T, index: usize) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        if self.separator == DocumentSeparator::Yaml {
+            let document = self.inner.write_to_bytes(object)?;
+            if index > 0 {
+                w.write_all(YAML_SEPARATOR)?;
+                w.write_all(b"\n")?;
+            }
+            w.write_all(&document)?;
+            if !document.ends_with(b"\n") {
+                w.write_all(b"\n")?;
+            }
+        } else {
+            if index > 0 {
+                w.write_all(self.separator.as_bytes())?;
+            }
+            self.inner.write(w, object)?;
+        }
+        Ok(())
+    }
+}
+
+impl<O> SeparatedWriter<O> {
+    ///
+    /// Construct a new writer of documents, written by `inner`, separated by `separator`.
+    ///
+    pub fn new(inner: O, separator: DocumentSeparator) -> Self {
+        Self { inner, separator }
+    }
+
+    ///
+    /// Returns a reference to the inner writer.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Returns the separator between documents.
+    ///
+    pub fn separator(&self) -> &DocumentSeparator {
+        &self.separator
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use std::io::Read;
+
+    #[derive(Debug, Default)]
+    struct TextIo;
+
+    impl ObjectWriter<String> for TextIo {
+        type Error = Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &String) -> Result<(), Self::Error> {
+            Ok(w.write_all(object.as_bytes())?)
+        }
+    }
+
+    impl ObjectReader<String> for TextIo {
+        type Error = Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+            let mut s = String::new();
+            r.read_to_string(&mut s)?;
+            Ok(s)
+        }
+    }
+
+    fn documents() -> Vec<String> {
+        vec!["one".to_string(), "two\n".to_string(), "three".to_string()]
+    }
+
+    #[test]
+    fn test_form_feed_round_trip() {
+        let writer = SeparatedWriter::new(TextIo, DocumentSeparator::FormFeed);
+        let mut buffer: Vec<u8> = Vec::new();
+        writer.write_all(&mut buffer, documents()).unwrap();
+        assert_eq!(buffer, b"one\x0Ctwo\n\x0Cthree");
+
+        let reader = SeparatedReader::new(TextIo, DocumentSeparator::FormFeed);
+        assert_eq!(
+            reader.read_all(&mut buffer.as_slice()).unwrap(),
+            documents()
+        );
+    }
+
+    #[test]
+    fn test_custom_separator_round_trip() {
+        let separator = DocumentSeparator::Bytes(b"<>".to_vec());
+        let writer = SeparatedWriter::new(TextIo, separator.clone());
+        let mut buffer: Vec<u8> = Vec::new();
+        writer.write_all(&mut buffer, documents()).unwrap();
+        assert_eq!(buffer, b"one<>two\n<>three");
+
+        let reader = SeparatedReader::new(TextIo, separator);
+        let mut objects = reader.objects(b"a>b<><><>c<".as_slice());
+        assert_eq!(objects.next().unwrap().unwrap(), "a>b");
+        assert_eq!(objects.next().unwrap().unwrap(), "c<");
+        assert!(objects.next().is_none());
+    }
+
+    #[test]
+    fn test_yaml_separator_lines() {
+        let reader = SeparatedReader::new(TextIo, DocumentSeparator::Yaml);
+        let documents = reader
+            .read_all(&mut "a: ---\n--- \r\n\n---\nb: 2\n---\n".as_bytes())
+            .unwrap();
+        assert_eq!(documents, vec!["a: ---\n", "b: 2\n"]);
+    }
+
+    #[test]
+    fn test_empty_separator() {
+        let reader = SeparatedReader::new(TextIo, DocumentSeparator::Bytes(Vec::new()));
+        assert!(reader.read_all(&mut "a".as_bytes()).is_err());
+    }
+}
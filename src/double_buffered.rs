@@ -0,0 +1,265 @@
+/*!
+Provides [`DoubleBufferedSink`], a destination wrapper that lets a writer serialize into one
+buffer while a background thread writes the previous buffer to an inner destination, so that
+serialization and IO overlap.
+
+Only two buffers are ever allocated, each of a fixed capacity, so memory is bounded; a writer that
+fills its buffer while the other is still being written waits for it. An error from the inner
+destination is returned by the next call that exchanges buffers, by `flush`, or by
+[`finish`](DoubleBufferedSink::finish), which must be called to ensure all content is written and
+to recover the inner destination. Dropping the sink writes any remaining content but ignores
+errors, as `BufWriter` does.
+
+# Example
+
+```rust
+use objio::double_buffered::DoubleBufferedSink;
+use std::io::Write;
+
+let mut sink = DoubleBufferedSink::with_capacity(Vec::new(), 8);
+for i in 0..100 {
+    writeln!(sink, "record {i}").unwrap();
+}
+let output = sink.finish().unwrap();
+
+assert!(output.ends_with(b"record 99\n"));
+```
+ */
+
+use std::fmt::{Debug, Formatter};
+use std::io::{Error as IoError, ErrorKind, Result as IoResult, Write};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread::JoinHandle;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A destination that writes full buffers to an inner destination on a background thread.
+///
+pub struct DoubleBufferedSink<W> {
+    buffer: Vec<u8>,
+    capacity: usize,
+    spare: Option<Vec<u8>>,
+    in_flight: bool,
+    sender: Option<SyncSender<(Vec<u8>, bool)>>,
+    receiver: Receiver<IoResult<Vec<u8>>>,
+    thread: Option<JoinHandle<Option<W>>>,
+}
+
+///
+/// The default capacity, in bytes, of each buffer used by [`DoubleBufferedSink::new`].
+///
+pub const DEFAULT_CAPACITY: usize = 64 * 1024;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<W> Debug for DoubleBufferedSink<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DoubleBufferedSink")
+            .field("buffered", &self.buffer.len())
+            .field("capacity", &self.capacity)
+            .field("in_flight", &self.in_flight)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W> Write for DoubleBufferedSink<W> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let count = buf.len().min(self.capacity - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..count]);
+        if self.buffer.len() == self.capacity {
+            self.exchange(false)?;
+        }
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.exchange(true)?;
+        self.wait()
+    }
+}
+
+impl<W> Drop for DoubleBufferedSink<W> {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+impl<W> DoubleBufferedSink<W>
+where
+    W: Write + Send + 'static,
+{
+    ///
+    /// Construct a new sink writing to `inner` with two buffers of [`DEFAULT_CAPACITY`] bytes.
+    ///
+    pub fn new(inner: W) -> Self {
+        Self::with_capacity(inner, DEFAULT_CAPACITY)
+    }
+
+    ///
+    /// Construct a new sink writing to `inner` with two buffers of `capacity` bytes, which is at
+    /// least one.
+    ///
+    pub fn with_capacity(inner: W, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, buffers) = sync_channel::<(Vec<u8>, bool)>(0);
+        let (returned, receiver) = sync_channel(1);
+        let thread = std::thread::spawn(move || {
+            let mut inner = inner;
+            for (mut buffer, flush) in buffers {
+                let result =
+                    inner
+                        .write_all(&buffer)
+                        .and_then(|_| if flush { inner.flush() } else { Ok(()) });
+                buffer.clear();
+                let failed = result.is_err();
+                if returned.send(result.map(|_| buffer)).is_err() || failed {
+                    return None;
+                }
+            }
+            Some(inner)
+        });
+        Self {
+            buffer: Vec::with_capacity(capacity),
+            capacity,
+            spare: None,
+            in_flight: false,
+            sender: Some(sender),
+            receiver,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl<W> DoubleBufferedSink<W> {
+    ///
+    /// Write any remaining content, flush the inner destination, and return it.
+    ///
+    pub fn finish(mut self) -> IoResult<W> {
+        self.stop()
+    }
+
+    ///
+    /// Returns the capacity, in bytes, of each buffer.
+    ///
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    ///
+    /// Wait for the buffer being written by the background thread, if any, to be returned.
+    ///
+    fn wait(&mut self) -> IoResult<()> {
+        if self.in_flight {
+            self.in_flight = false;
+            match self.receiver.recv() {
+                Ok(Ok(buffer)) => self.spare = Some(buffer),
+                Ok(Err(e)) => return Err(e),
+                Err(_) => return Err(stopped()),
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Hand the current buffer to the background thread, continuing with the spare buffer.
+    ///
+    fn exchange(&mut self, flush: bool) -> IoResult<()> {
+        self.wait()?;
+        let next = self
+            .spare
+            .take()
+            .unwrap_or_else(|| Vec::with_capacity(self.capacity));
+        let full = std::mem::replace(&mut self.buffer, next);
+        match &self.sender {
+            Some(sender) => sender.send((full, flush)).map_err(|_| stopped())?,
+            None => return Err(stopped()),
+        }
+        self.in_flight = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> IoResult<W> {
+        let result = self.flush();
+        drop(self.sender.take());
+        let inner = match self.thread.take() {
+            Some(thread) => thread.join().ok().flatten(),
+            None => None,
+        };
+        result?;
+        inner.ok_or_else(stopped)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn stopped() -> IoError {
+    IoError::new(ErrorKind::BrokenPipe, "the background writer has stopped")
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ObjectWriter;
+
+    struct FailingDestination {
+        remaining: usize,
+    }
+
+    impl Write for FailingDestination {
+        fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+            if self.remaining == 0 {
+                Err(IoError::new(ErrorKind::StorageFull, "no space left"))
+            } else {
+                let count = self.remaining.min(buf.len());
+                self.remaining -= count;
+                Ok(count)
+            }
+        }
+
+        fn flush(&mut self) -> IoResult<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct LinesWriter;
+
+    impl ObjectWriter<Vec<String>> for LinesWriter {
+        type Error = IoError;
+
+        fn write<W: Write>(&self, w: &mut W, object: &Vec<String>) -> Result<(), Self::Error> {
+            object.iter().try_for_each(|line| writeln!(w, "{line}"))
+        }
+    }
+
+    #[test]
+    fn test_write_lines() {
+        let lines: Vec<String> = (0..1000).map(|i| format!("line {i}")).collect();
+        let mut sink = DoubleBufferedSink::with_capacity(Vec::new(), 7);
+        LinesWriter.write(&mut sink, &lines).unwrap();
+        sink.flush().unwrap();
+        LinesWriter.write(&mut sink, &lines).unwrap();
+        let output = String::from_utf8(sink.finish().unwrap()).unwrap();
+        assert_eq!(output.lines().count(), 2000);
+        assert!(output.ends_with("line 999\n"));
+    }
+
+    #[test]
+    fn test_write_error() {
+        let mut sink = DoubleBufferedSink::with_capacity(FailingDestination { remaining: 10 }, 4);
+        let error = sink.write_all(&[0; 64]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::StorageFull);
+        assert!(sink.finish().is_err());
+    }
+}
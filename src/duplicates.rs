@@ -0,0 +1,289 @@
+/*!
+Provides a stream reader, [`DuplicateDetectingReader`], that detects records which duplicate an
+earlier record in the same stream, for deduplication on ingest.
+
+Records are compared by a key, which is either the record itself, for records implementing
+`Eq + Hash + Clone`, or the result of a key extractor. The handling of a duplicate is controlled
+by an [`UnknownHandling`] value: [`Error`](UnknownHandling::Error) fails the stream,
+[`Warn`](UnknownHandling::Warn) skips the duplicate and reports a warning to the diagnostics sink,
+and [`Ignore`](UnknownHandling::Ignore) skips it silently. The keys of the records read are kept
+until the reader is [`reset`](DuplicateDetectingReader::reset).
+
+# Example
+
+```rust
+use objio::duplicates::DuplicateDetectingReader;
+use objio::options::UnknownHandling;
+use objio::stream::ObjectsReader;
+use std::io::BufRead;
+
+#[derive(Debug, Default)]
+struct LinesReader;
+
+impl ObjectsReader<String> for LinesReader {
+    type Error = objio::Error;
+
+    fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<String>, Self::Error> {
+        let mut line = String::new();
+        Ok(match r.read_line(&mut line)? {
+            0 => None,
+            _ => Some(line.trim_end().to_string()),
+        })
+    }
+}
+
+let reader = DuplicateDetectingReader::by_key(LinesReader, "lines", |line: &String| {
+    line.split(',').next().unwrap_or_default().to_string()
+})
+.with_handling(UnknownHandling::Ignore);
+let records = reader.read_all(&mut "1,a\n2,b\n1,c\n".as_bytes()).unwrap();
+
+assert_eq!(records, vec!["1,a", "2,b"]);
+assert_eq!(reader.duplicates(), 1);
+```
+ */
+
+use crate::diagnostics::{DiagnosticSink, IgnoreDiagnostics};
+use crate::error::Error;
+use crate::options::UnknownHandling;
+use crate::stream::ObjectsReader;
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+use std::io::BufRead;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A stream reader that detects records, read by an inner [`ObjectsReader`], whose key duplicates
+/// that of an earlier record.
+///
+pub struct DuplicateDetectingReader<O, K, F> {
+    inner: O,
+    format: &'static str,
+    key: F,
+    handling: UnknownHandling,
+    sink: Option<Arc<dyn DiagnosticSink + Send + Sync>>,
+    state: Mutex<State<K>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct State<K> {
+    keys: HashSet<K>,
+    records: usize,
+    duplicates: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<O, K, F> Debug for DuplicateDetectingReader<O, K, F>
+where
+    O: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DuplicateDetectingReader")
+            .field("inner", &self.inner)
+            .field("format", &self.format)
+            .field("handling", &self.handling)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O, K, F, T> ObjectsReader<T> for DuplicateDetectingReader<O, K, F>
+where
+    O: ObjectsReader<T>,
+    O::Error: From<Error>,
+    F: Fn(&T) -> K,
+    K: Eq + Hash,
+{
+    type Error = O::Error;
+
+    fn read_next<R>(&self, r: &mut R) -> Result<Option<T>, Self::Error>
+    where
+        R: BufRead,
+    {
+        while let Some(object) = self.inner.read_next(r)? {
+            let mut state = self.lock();
+            state.records += 1;
+            if state.keys.insert((self.key)(&object)) {
+                return Ok(Some(object));
+            }
+            state.duplicates += 1;
+            let record = state.records;
+            drop(state);
+            let sink: &dyn DiagnosticSink = match &self.sink {
+                Some(sink) => sink.as_ref(),
+                None => &IgnoreDiagnostics,
+            };
+            self.handling.handle(
+                self.format,
+                format!("record {record} duplicates an earlier record"),
+                sink,
+            )?;
+        }
+        Ok(None)
+    }
+}
+
+impl<O, K> DuplicateDetectingReader<O, K, fn(&K) -> K>
+where
+    K: Clone,
+{
+    ///
+    /// Construct a new reader that detects duplicate records, in the named format, read by
+    /// `inner`, comparing the records themselves.
+    ///
+    pub fn new(inner: O, format: &'static str) -> Self {
+        Self::by_key(inner, format, K::clone)
+    }
+}
+
+impl<O, K, F> DuplicateDetectingReader<O, K, F> {
+    ///
+    /// Construct a new reader that detects duplicate records, in the named format, read by
+    /// `inner`, comparing the keys returned by `key`.
+    ///
+    pub fn by_key(inner: O, format: &'static str, key: F) -> Self {
+        Self {
+            inner,
+            format,
+            key,
+            handling: Default::default(),
+            sink: None,
+            state: Mutex::new(State {
+                keys: HashSet::new(),
+                records: 0,
+                duplicates: 0,
+            }),
+        }
+    }
+
+    ///
+    /// Returns this reader with duplicates handled according to `handling`; by default a
+    /// duplicate is an error.
+    ///
+    pub fn with_handling(self, handling: UnknownHandling) -> Self {
+        let mut self_mut = self;
+        self_mut.handling = handling;
+        self_mut
+    }
+
+    ///
+    /// Returns this reader with warnings about duplicates reported to `sink`; by default they are
+    /// discarded.
+    ///
+    pub fn with_diagnostics(self, sink: Arc<dyn DiagnosticSink + Send + Sync>) -> Self {
+        let mut self_mut = self;
+        self_mut.sink = Some(sink);
+        self_mut
+    }
+
+    ///
+    /// Returns how duplicates are handled by this reader.
+    ///
+    pub fn handling(&self) -> UnknownHandling {
+        self.handling
+    }
+
+    ///
+    /// Returns a reference to the inner reader.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Returns the number of duplicate records found since the reader was constructed or reset.
+    ///
+    pub fn duplicates(&self) -> usize {
+        self.lock().duplicates
+    }
+
+    ///
+    /// Forget the keys of the records read, so that the next record read is treated as the start
+    /// of a new stream.
+    ///
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.keys.clear();
+        state.records = 0;
+        state.duplicates = 0;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<K>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::{CollectDiagnostics, Diagnostic};
+
+    #[derive(Debug, Default)]
+    struct NumbersReader;
+
+    impl ObjectsReader<u32> for NumbersReader {
+        type Error = Error;
+
+        fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<u32>, Self::Error> {
+            let mut line = String::new();
+            if r.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            line.trim()
+                .parse()
+                .map(Some)
+                .map_err(|e| Error::deserialization("numbers", e))
+        }
+    }
+
+    #[test]
+    fn test_duplicate_error() {
+        let reader = DuplicateDetectingReader::new(NumbersReader, "numbers");
+        let mut objects = reader.objects("1\n2\n1\n3\n".as_bytes());
+        assert_eq!(objects.next().unwrap().unwrap(), 1);
+        assert_eq!(objects.next().unwrap().unwrap(), 2);
+        match objects.next() {
+            Some(Err(Error::Deserialization { format, source })) => {
+                assert_eq!(format, "numbers");
+                assert_eq!(source.to_string(), "record 3 duplicates an earlier record");
+            }
+            result => panic!("expected a deserialization error, not {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_warning_and_reset() {
+        let sink = Arc::new(CollectDiagnostics::default());
+        let reader = DuplicateDetectingReader::new(NumbersReader, "numbers")
+            .with_handling(UnknownHandling::Warn)
+            .with_diagnostics(sink.clone());
+        assert_eq!(
+            reader.read_all(&mut "1\n1\n2\n1\n".as_bytes()).unwrap(),
+            vec![1, 2]
+        );
+        assert_eq!(reader.duplicates(), 2);
+        assert_eq!(
+            sink.diagnostics()[1],
+            Diagnostic::warning("record 4 duplicates an earlier record")
+        );
+
+        reader.reset();
+        assert_eq!(reader.read_all(&mut "1\n".as_bytes()).unwrap(), vec![1]);
+        assert_eq!(reader.duplicates(), 0);
+    }
+}
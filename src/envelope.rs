@@ -0,0 +1,479 @@
+/*!
+Provides a self-describing envelope, a small text header followed by content, that wraps the
+output of any [`ObjectWriter`] and is read by wrapping any [`ObjectReader`].
+
+The header identifies the format and version of the content, when it was created, its length and
+digest, and any number of application-defined key/value pairs. The header is a sequence of
+`key: value` lines starting with the line `objio-envelope 1` and ending with an empty line; keys
+for application metadata are prefixed with `meta.`. The digest is a 64-bit FNV-1a hash, which
+detects accidental corruption but is **not** a cryptographic digest.
+
+# Example
+
+```rust
+use objio::envelope::{EnvelopeReader, EnvelopeWriter};
+use objio::{ObjectReader, ObjectWriter};
+use std::io::{Read, Write};
+
+#[derive(Debug, Default)]
+struct TextIo;
+
+impl ObjectWriter<String> for TextIo {
+    type Error = objio::Error;
+
+    fn write<W: Write>(&self, w: &mut W, object: &String) -> Result<(), Self::Error> {
+        Ok(w.write_all(object.as_bytes())?)
+    }
+}
+
+impl ObjectReader<String> for TextIo {
+    type Error = objio::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+        let mut s = String::new();
+        r.read_to_string(&mut s)?;
+        Ok(s)
+    }
+}
+
+let writer = EnvelopeWriter::new(TextIo, "text").with_metadata("author", "me");
+let bytes = writer.write_to_bytes(&"Hello".to_string()).unwrap();
+
+let envelope = EnvelopeReader::new(TextIo).read_from_bytes(&bytes).unwrap();
+
+assert_eq!(envelope.format_id(), "text");
+assert_eq!(envelope.metadata().get("author").map(String::as_str), Some("me"));
+assert_eq!(envelope.content(), "Hello");
+```
+ */
+
+use crate::error::Error;
+use crate::options::{Buffering, FormatVersion};
+use crate::{ObjectReader, ObjectWriter};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The name of the envelope format, as used in errors.
+///
+pub const ENVELOPE_FORMAT_NAME: &str = "envelope";
+
+///
+/// An object along with the metadata read from its envelope header.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Envelope<T> {
+    format_id: String,
+    version: Option<FormatVersion>,
+    created_at: SystemTime,
+    digest: u64,
+    metadata: BTreeMap<String, String>,
+    content: T,
+}
+
+///
+/// A writer that wraps the output of an inner writer in an envelope.
+///
+#[derive(Clone, Debug)]
+pub struct EnvelopeWriter<W> {
+    inner: W,
+    format_id: String,
+    version: Option<FormatVersion>,
+    metadata: BTreeMap<String, String>,
+}
+
+///
+/// A reader that reads an envelope header, and then its content using an inner reader.
+///
+#[derive(Clone, Debug)]
+pub struct EnvelopeReader<R> {
+    inner: R,
+    format_id: Option<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+const HEADER_LINE: &str = "objio-envelope 1";
+const META_PREFIX: &str = "meta.";
+const DIGEST_PREFIX: &str = "fnv1a64:";
+
+impl<T> Envelope<T> {
+    ///
+    /// Returns the identifier of the format of the content.
+    ///
+    pub fn format_id(&self) -> &str {
+        &self.format_id
+    }
+
+    ///
+    /// Returns the version of the format of the content, if recorded.
+    ///
+    pub fn version(&self) -> Option<FormatVersion> {
+        self.version
+    }
+
+    ///
+    /// Returns the time at which the envelope was written, to the nearest second.
+    ///
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    ///
+    /// Returns the digest of the content bytes.
+    ///
+    pub fn digest(&self) -> u64 {
+        self.digest
+    }
+
+    ///
+    /// Returns the application-defined metadata.
+    ///
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    ///
+    /// Returns a reference to the content.
+    ///
+    pub fn content(&self) -> &T {
+        &self.content
+    }
+
+    ///
+    /// Returns the content, discarding the metadata.
+    ///
+    pub fn into_content(self) -> T {
+        self.content
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<W, T> ObjectWriter<T> for EnvelopeWriter<W>
+where
+    W: ObjectWriter<T>,
+{
+    type Error = W::Error;
+
+    fn write<Wr>(&self, w: &mut Wr, object: &T) -> Result<(), Self::Error>
+    where
+        Wr: Write,
+    {
+        let content = self.inner.write_to_bytes(object)?;
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut header = format!("{HEADER_LINE}\nformat: {}\n", self.format_id);
+        if let Some(version) = self.version {
+            header.push_str(&format!("version: {version}\n"));
+        }
+        header.push_str(&format!(
+            "created-at: {created_at}\nlength: {}\ndigest: {DIGEST_PREFIX}{:016x}\n",
+            content.len(),
+            fnv1a64(&content)
+        ));
+        for (key, value) in &self.metadata {
+            header.push_str(&format!("{META_PREFIX}{key}: {value}\n"));
+        }
+        header.push('\n');
+        w.write_all(header.as_bytes())?;
+        w.write_all(&content)?;
+        Ok(())
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.inner.validate_options()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.inner.buffering()
+    }
+}
+
+impl<W> EnvelopeWriter<W> {
+    ///
+    /// Construct a new envelope writer for content written by `inner` in the format identified
+    /// by `format_id`.
+    ///
+    pub fn new<S>(inner: W, format_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            inner,
+            format_id: format_id.into(),
+            version: None,
+            metadata: Default::default(),
+        }
+    }
+
+    ///
+    /// Returns this writer with the version of the content's format set to `version`.
+    ///
+    pub fn with_version(self, version: FormatVersion) -> Self {
+        let mut self_mut = self;
+        self_mut.version = Some(version);
+        self_mut
+    }
+
+    ///
+    /// Returns this writer with the metadata `key` set to `value`; neither may contain a line
+    /// break and `key` may not contain `:`.
+    ///
+    pub fn with_metadata<K, V>(self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut self_mut = self;
+        let key = key.into();
+        let value = value.into();
+        assert!(
+            !key.contains([':', '\n', '\r']) && !value.contains(['\n', '\r']),
+            "metadata may not contain line breaks, or `:` in a key"
+        );
+        self_mut.metadata.insert(key, value);
+        self_mut
+    }
+
+    ///
+    /// Returns a reference to the inner writer.
+    ///
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<R, T> ObjectReader<Envelope<T>> for EnvelopeReader<R>
+where
+    R: ObjectReader<T>,
+{
+    type Error = R::Error;
+
+    fn read<Rd>(&self, r: &mut Rd) -> Result<Envelope<T>, Self::Error>
+    where
+        Rd: Read,
+    {
+        let mut r = BufReader::new(r);
+        let mut line = String::new();
+        r.read_line(&mut line)?;
+        if line.trim_end() != HEADER_LINE {
+            return Err(envelope_error("missing envelope header").into());
+        }
+
+        let mut format_id = None;
+        let mut version = None;
+        let mut created_at = UNIX_EPOCH;
+        let mut length = None;
+        let mut digest = None;
+        let mut metadata = BTreeMap::new();
+        loop {
+            line.clear();
+            if r.read_line(&mut line)? == 0 {
+                return Err(envelope_error("unexpected end of envelope header").into());
+            }
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                break;
+            }
+            let (key, value) = line
+                .split_once(": ")
+                .ok_or_else(|| envelope_error(format!("malformed header line `{line}`")))?;
+            match key {
+                "format" => format_id = Some(value.to_string()),
+                "version" => version = Some(parse_value(key, value)?),
+                "created-at" => {
+                    created_at = UNIX_EPOCH + Duration::from_secs(parse_value(key, value)?)
+                }
+                "length" => length = Some(parse_value::<u64>(key, value)?),
+                "digest" => {
+                    let hex = value
+                        .strip_prefix(DIGEST_PREFIX)
+                        .ok_or_else(|| envelope_error(format!("unknown digest `{value}`")))?;
+                    digest = Some(
+                        u64::from_str_radix(hex, 16)
+                            .map_err(|e| Error::deserialization(ENVELOPE_FORMAT_NAME, e))
+                            .map_err(std::io::Error::from)?,
+                    );
+                }
+                _ => {
+                    if let Some(key) = key.strip_prefix(META_PREFIX) {
+                        metadata.insert(key.to_string(), value.to_string());
+                    }
+                }
+            }
+        }
+
+        let (Some(format_id), Some(length), Some(digest)) = (format_id, length, digest) else {
+            return Err(envelope_error("envelope header is incomplete").into());
+        };
+        if let Some(expected) = &self.format_id {
+            if *expected != format_id {
+                return Err(envelope_error(format!(
+                    "expected format `{expected}`, not `{format_id}`"
+                ))
+                .into());
+            }
+        }
+        let mut content = Vec::new();
+        r.take(length).read_to_end(&mut content)?;
+        if content.len() as u64 != length {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        if fnv1a64(&content) != digest {
+            return Err(envelope_error("content does not match its digest").into());
+        }
+        let content = self.inner.read_from_bytes(&content)?;
+
+        Ok(Envelope {
+            format_id,
+            version,
+            created_at,
+            digest,
+            metadata,
+            content,
+        })
+    }
+}
+
+impl<R> EnvelopeReader<R> {
+    ///
+    /// Construct a new envelope reader for content read by `inner`.
+    ///
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            format_id: None,
+        }
+    }
+
+    ///
+    /// Returns this reader with the format identifier the envelope **must** declare set to
+    /// `format_id`; by default any format is accepted.
+    ///
+    pub fn with_format_id<S>(self, format_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.format_id = Some(format_id.into());
+        self_mut
+    }
+
+    ///
+    /// Returns a reference to the inner reader.
+    ///
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn envelope_error<S>(message: S) -> std::io::Error
+where
+    S: Into<String>,
+{
+    Error::deserialization(ENVELOPE_FORMAT_NAME, message.into()).into()
+}
+
+fn parse_value<T>(key: &str, value: &str) -> Result<T, std::io::Error>
+where
+    T: std::str::FromStr,
+{
+    value
+        .parse()
+        .map_err(|_| envelope_error(format!("invalid value `{value}` for `{key}`")))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct BytesIo;
+
+    impl ObjectWriter<Vec<u8>> for BytesIo {
+        type Error = Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &Vec<u8>) -> Result<(), Self::Error> {
+            Ok(w.write_all(object)?)
+        }
+    }
+
+    impl ObjectReader<Vec<u8>> for BytesIo {
+        type Error = Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<Vec<u8>, Self::Error> {
+            let mut buffer = Vec::new();
+            r.read_to_end(&mut buffer)?;
+            Ok(buffer)
+        }
+    }
+
+    #[test]
+    fn test_fnv1a64() {
+        assert_eq!(fnv1a64(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a64(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_round_trip_binary() {
+        let writer = EnvelopeWriter::new(BytesIo, "bytes").with_version(FormatVersion::new(1, 2));
+        let bytes = writer.write_to_bytes(&vec![0, 10, 13, 255]).unwrap();
+        let envelope = EnvelopeReader::new(BytesIo)
+            .with_format_id("bytes")
+            .read_from_bytes(&bytes)
+            .unwrap();
+        assert_eq!(envelope.version(), Some(FormatVersion::new(1, 2)));
+        assert!(envelope.created_at() > UNIX_EPOCH);
+        assert_eq!(envelope.into_content(), vec![0, 10, 13, 255]);
+    }
+
+    #[test]
+    fn test_corrupt_content() {
+        let writer = EnvelopeWriter::new(BytesIo, "bytes");
+        let mut bytes = writer.write_to_bytes(&b"Hello".to_vec()).unwrap();
+        *bytes.last_mut().unwrap() = b'!';
+        assert!(matches!(
+            EnvelopeReader::new(BytesIo).read_from_bytes(&bytes),
+            Err(Error::Deserialization {
+                format: ENVELOPE_FORMAT_NAME,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_unexpected_format() {
+        let writer = EnvelopeWriter::new(BytesIo, "bytes");
+        let bytes = writer.write_to_bytes(&b"Hello".to_vec()).unwrap();
+        assert!(EnvelopeReader::new(BytesIo)
+            .with_format_id("text")
+            .read_from_bytes(&bytes)
+            .is_err());
+    }
+}
@@ -0,0 +1,533 @@
+/*!
+Provides a common error type for the readers, writers, and utilities provided by this crate.
+
+Implementations of [`ObjectReader`](crate::ObjectReader) and [`ObjectWriter`](crate::ObjectWriter)
+are free to define their own error types, the only constraint being the conversion from
+`std::io::Error`. The [`Error`] type here is used by the implementations within this crate and may
+be used by any implementation that does not need a richer error of its own.
+
+Most variants carry a boxed source or owned strings, and so allocate when they are constructed.
+For callers that must not allocate on an error path, [`Error::parse`] constructs a variant holding
+only a static message and an optional [`Location`], and an `Error::Io` constructed from an
+`std::io::ErrorKind` or a raw OS error does not allocate either. Note that converting any
+variant other than `Error::Io` into an `std::io::Error` allocates.
+ */
+
+use crate::budget::BudgetExceeded;
+use crate::conditional::PreconditionFailed;
+use crate::options::FormatVersion;
+use std::fmt::{Display, Formatter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The type of boxed errors carried as the source of the format-specific variants of [`Error`].
+///
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+///
+/// The common error type for the implementations in this crate.
+///
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An error was returned from the underlying `Read` or `Write` implementation.
+    Io(::std::io::Error),
+    /// An error was reported by the named format while reading, or parsing, an object.
+    Deserialization {
+        format: &'static str,
+        source: BoxedError,
+    },
+    /// An error was reported by the named format while writing, or serializing, an object.
+    Serialization {
+        format: &'static str,
+        source: BoxedError,
+    },
+    /// A set of options failed validation and cannot be used.
+    InvalidOptions { message: String },
+    /// An option was identified by a key that is not known to the options type.
+    UnknownOption { key: String },
+    /// The content, or a request to write it, uses a version of the format that is not supported.
+    UnsupportedVersion { version: FormatVersion },
+    /// The value provided for the option identified by `key` could not be parsed.
+    InvalidOptionValue {
+        key: String,
+        value: String,
+        source: BoxedError,
+    },
+    /// A document did not conform to the schema it was validated against.
+    SchemaValidation { violations: Vec<SchemaViolation> },
+    /// Reading a document would allocate more memory than its budget allows.
+    BudgetExceeded(BudgetExceeded),
+    /// A conditional write was rejected because the target changed since it was read.
+    PreconditionFailed(PreconditionFailed),
+    /// The named format could not parse its content, as described by a static message; this
+    /// variant does not allocate.
+    Parse {
+        format: &'static str,
+        message: &'static str,
+        location: Option<Location>,
+    },
+}
+
+///
+/// A position within the content being read, as a byte offset and, optionally, a line and column;
+/// both line and column numbers start at one.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Location {
+    offset: usize,
+    line_column: Option<(usize, usize)>,
+}
+
+///
+/// A single violation of a schema, identifying the location in the document and the schema.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaViolation {
+    instance_path: String,
+    schema_path: String,
+    message: String,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "An I/O error occurred; source: {e}"),
+            Self::Deserialization { format, source } => {
+                write!(
+                    f,
+                    "An error occurred reading {format} content; source: {source}"
+                )
+            }
+            Self::Serialization { format, source } => {
+                write!(
+                    f,
+                    "An error occurred writing {format} content; source: {source}"
+                )
+            }
+            Self::InvalidOptions { message } => write!(f, "Invalid options; {message}"),
+            Self::UnknownOption { key } => write!(f, "Unknown option `{key}`"),
+            Self::UnsupportedVersion { version } => {
+                write!(f, "Version {version} of the format is not supported")
+            }
+            Self::InvalidOptionValue { key, value, source } => {
+                write!(
+                    f,
+                    "Invalid value `{value}` for option `{key}`; source: {source}"
+                )
+            }
+            Self::SchemaValidation { violations } => {
+                write!(f, "The document does not conform to its schema")?;
+                for (i, violation) in violations.iter().enumerate() {
+                    write!(f, "{} {violation}", if i == 0 { ";" } else { "," })?;
+                }
+                Ok(())
+            }
+            Self::BudgetExceeded(e) => write!(f, "The memory budget was exceeded; {e}"),
+            Self::PreconditionFailed(e) => write!(f, "A conditional write was rejected; {e}"),
+            Self::Parse {
+                format,
+                message,
+                location,
+            } => {
+                write!(f, "An error occurred reading {format} content; {message}")?;
+                match location {
+                    Some(location) => write!(f, " at {location}"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Deserialization { source, .. }
+            | Self::Serialization { source, .. }
+            | Self::InvalidOptionValue { source, .. } => Some(source.as_ref()),
+            Self::BudgetExceeded(e) => Some(e),
+            Self::PreconditionFailed(e) => Some(e),
+            Self::InvalidOptions { .. }
+            | Self::UnknownOption { .. }
+            | Self::UnsupportedVersion { .. }
+            | Self::SchemaValidation { .. }
+            | Self::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<BudgetExceeded> for Error {
+    fn from(e: BudgetExceeded) -> Self {
+        Self::BudgetExceeded(e)
+    }
+}
+
+impl From<PreconditionFailed> for Error {
+    fn from(e: PreconditionFailed) -> Self {
+        Self::PreconditionFailed(e)
+    }
+}
+
+impl From<::std::io::Error> for Error {
+    fn from(e: ::std::io::Error) -> Self {
+        // Unwrap errors from this crate that were carried through an I/O error by `From<Error>`.
+        if e.get_ref().is_some_and(|inner| inner.is::<Error>()) {
+            match e.into_inner().map(|inner| inner.downcast::<Error>()) {
+                Some(Ok(inner)) => *inner,
+                _ => unreachable!("inner error type already checked"),
+            }
+        } else {
+            Self::Io(e)
+        }
+    }
+}
+
+impl From<Error> for ::std::io::Error {
+    fn from(e: Error) -> Self {
+        use ::std::io::ErrorKind;
+        match e {
+            Error::Io(e) => e,
+            Error::InvalidOptions { .. }
+            | Error::UnknownOption { .. }
+            | Error::InvalidOptionValue { .. } => Self::new(ErrorKind::InvalidInput, e),
+            Error::BudgetExceeded(_) => Self::new(ErrorKind::OutOfMemory, e),
+            Error::PreconditionFailed(_) => Self::other(e),
+            _ => Self::new(ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+impl Error {
+    ///
+    /// Construct a new deserialization error for the named format.
+    ///
+    pub fn deserialization<E>(format: &'static str, source: E) -> Self
+    where
+        E: Into<BoxedError>,
+    {
+        Self::Deserialization {
+            format,
+            source: source.into(),
+        }
+    }
+
+    ///
+    /// Construct a new parse error for the named format, described by a static message, which
+    /// does not allocate.
+    ///
+    pub const fn parse(format: &'static str, message: &'static str) -> Self {
+        Self::Parse {
+            format,
+            message,
+            location: None,
+        }
+    }
+
+    ///
+    /// For a parse error, returns the error with its location set to `location`; other errors are
+    /// returned unchanged.
+    ///
+    pub fn at(self, location: Location) -> Self {
+        match self {
+            Self::Parse {
+                format, message, ..
+            } => Self::Parse {
+                format,
+                message,
+                location: Some(location),
+            },
+            _ => self,
+        }
+    }
+
+    ///
+    /// Returns the location of a parse error, if known.
+    ///
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Self::Parse { location, .. } => *location,
+            _ => None,
+        }
+    }
+
+    ///
+    /// Construct a new error describing why a set of options is invalid.
+    ///
+    pub fn invalid_options<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::InvalidOptions {
+            message: message.into(),
+        }
+    }
+
+    ///
+    /// Construct a new error for an option key that is not known to the options type.
+    ///
+    pub fn unknown_option<S>(key: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::UnknownOption { key: key.into() }
+    }
+
+    ///
+    /// Construct a new error for a version of the format that is not supported.
+    ///
+    pub fn unsupported_version(version: FormatVersion) -> Self {
+        Self::UnsupportedVersion { version }
+    }
+
+    ///
+    /// Construct a new error for a value that could not be parsed for the option `key`.
+    ///
+    pub fn invalid_option_value<S1, S2, E>(key: S1, value: S2, source: E) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        E: Into<BoxedError>,
+    {
+        Self::InvalidOptionValue {
+            key: key.into(),
+            value: value.into(),
+            source: source.into(),
+        }
+    }
+
+    ///
+    /// For errors that identify an option by key, returns the error with `prefix` and a `.`
+    /// separator added to the key; this is used to report errors from nested options types.
+    /// Other errors are returned unchanged.
+    ///
+    pub fn with_option_prefix(self, prefix: &str) -> Self {
+        match self {
+            Self::UnknownOption { key } => Self::UnknownOption {
+                key: format!("{prefix}.{key}"),
+            },
+            Self::InvalidOptionValue { key, value, source } => Self::InvalidOptionValue {
+                key: format!("{prefix}.{key}"),
+                value,
+                source,
+            },
+            _ => self,
+        }
+    }
+
+    ///
+    /// Construct a new error for a document with one or more schema violations.
+    ///
+    pub fn schema_validation<I>(violations: I) -> Self
+    where
+        I: IntoIterator<Item = SchemaViolation>,
+    {
+        Self::SchemaValidation {
+            violations: violations.into_iter().collect(),
+        }
+    }
+
+    ///
+    /// Construct a new serialization error for the named format.
+    ///
+    pub fn serialization<E>(format: &'static str, source: E) -> Self
+    where
+        E: Into<BoxedError>,
+    {
+        Self::Serialization {
+            format,
+            source: source.into(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.line_column {
+            Some((line, column)) => write!(
+                f,
+                "line {line}, column {column} (byte offset {})",
+                self.offset
+            ),
+            None => write!(f, "byte offset {}", self.offset),
+        }
+    }
+}
+
+impl Location {
+    ///
+    /// Construct a new location at the byte `offset` from the start of the content.
+    ///
+    pub const fn new(offset: usize) -> Self {
+        Self {
+            offset,
+            line_column: None,
+        }
+    }
+
+    ///
+    /// Returns this location with its line and column set.
+    ///
+    pub const fn with_line_column(self, line: usize, column: usize) -> Self {
+        Self {
+            offset: self.offset,
+            line_column: Some((line, column)),
+        }
+    }
+
+    ///
+    /// Returns the byte offset from the start of the content.
+    ///
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    ///
+    /// Returns the line number, if known.
+    ///
+    pub const fn line(&self) -> Option<usize> {
+        match self.line_column {
+            Some((line, _)) => Some(line),
+            None => None,
+        }
+    }
+
+    ///
+    /// Returns the column number, if known.
+    ///
+    pub const fn column(&self) -> Option<usize> {
+        match self.line_column {
+            Some((_, column)) => Some(column),
+            None => None,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "at `{}` ({}): {}",
+            self.instance_path, self.schema_path, self.message
+        )
+    }
+}
+
+impl SchemaViolation {
+    ///
+    /// Construct a new violation; the paths are JSON pointers into the document and the schema.
+    ///
+    pub fn new<S1, S2, S3>(instance_path: S1, schema_path: S2, message: S3) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<String>,
+    {
+        Self {
+            instance_path: instance_path.into(),
+            schema_path: schema_path.into(),
+            message: message.into(),
+        }
+    }
+
+    ///
+    /// Returns the location of the violating value in the document, as a JSON pointer.
+    ///
+    pub fn instance_path(&self) -> &str {
+        &self.instance_path
+    }
+
+    ///
+    /// Returns the location of the violated keyword in the schema, as a JSON pointer.
+    ///
+    pub fn schema_path(&self) -> &str {
+        &self.schema_path
+    }
+
+    ///
+    /// Returns a description of the violation.
+    ///
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_from_io_error() {
+        let error: Error = ::std::io::Error::new(::std::io::ErrorKind::NotFound, "missing").into();
+        assert!(matches!(error, Error::Io(_)));
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn test_display_deserialization() {
+        let error = Error::deserialization("JSON", "unexpected end of input");
+        assert_eq!(
+            error.to_string(),
+            "An error occurred reading JSON content; source: unexpected end of input"
+        );
+    }
+
+    #[test]
+    fn test_display_parse() {
+        const UNTERMINATED: Error = Error::parse("N-Triples", "unterminated IRI");
+        assert_eq!(
+            UNTERMINATED.to_string(),
+            "An error occurred reading N-Triples content; unterminated IRI"
+        );
+        let error = UNTERMINATED.at(Location::new(42).with_line_column(3, 7));
+        assert_eq!(error.location().and_then(|l| l.line()), Some(3));
+        assert_eq!(
+            error.to_string(),
+            "An error occurred reading N-Triples content; unterminated IRI at line 3, \
+             column 7 (byte offset 42)"
+        );
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_io_error_round_trip() {
+        let io_error: ::std::io::Error = Error::invalid_options("bad").into();
+        assert_eq!(io_error.kind(), ::std::io::ErrorKind::InvalidInput);
+        let error: Error = io_error.into();
+        assert!(matches!(error, Error::InvalidOptions { .. }));
+    }
+
+    #[test]
+    fn test_display_schema_validation() {
+        let error = Error::schema_validation([
+            SchemaViolation::new(
+                "/count",
+                "/properties/count/type",
+                "\"a\" is not an integer",
+            ),
+            SchemaViolation::new("", "/required", "\"name\" is required"),
+        ]);
+        assert_eq!(
+            error.to_string(),
+            "The document does not conform to its schema; at `/count` (/properties/count/type): \
+             \"a\" is not an integer, at `` (/required): \"name\" is required"
+        );
+    }
+}
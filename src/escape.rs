@@ -0,0 +1,519 @@
+/*!
+Provides escaping and unescaping of strings for common conventions: C-style string literals, JSON
+strings, URI percent-encoding, and XML character and entity references.
+
+Each function returns a `Cow`, borrowing the input when it needs no change, so that the common
+case does not allocate. Escaping never fails; unescaping returns an [`UnescapeError`], with the
+byte offset of the offending escape, for malformed input. For any string `s`, unescaping the
+escaped form of `s` returns `s`.
+
+# Example
+
+```rust
+use objio::escape::{
+    escape_c, escape_json, escape_xml_text, percent_decode, percent_encode, unescape_c,
+    unescape_json, unescape_xml,
+};
+
+assert_eq!(escape_c("tab\there"), "tab\\there");
+assert_eq!(unescape_c("\\x41\\102\\u00e9").unwrap(), "ABé");
+
+assert_eq!(escape_json("say \"hi\"\u{1}"), "say \\\"hi\\\"\\u0001");
+assert_eq!(unescape_json("\\ud83d\\ude00").unwrap(), "😀");
+
+assert_eq!(percent_encode("a b/é"), "a%20b%2F%C3%A9");
+assert_eq!(percent_decode("a%20b%2F%C3%A9").unwrap(), "a b/é");
+
+assert_eq!(escape_xml_text("1 < 2 & 3"), "1 &lt; 2 &amp; 3");
+assert_eq!(unescape_xml("&lt;&#233;&#x41;&apos;").unwrap(), "<éA'");
+
+let error = unescape_json("\\q").unwrap_err();
+assert_eq!(error.to_string(), "invalid escape at offset 0: unknown escape");
+```
+ */
+
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An error unescaping a string, identifying the byte offset, in the input, of the malformed
+/// escape.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnescapeError {
+    offset: usize,
+    message: &'static str,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Escape `s` for use within a double-quoted C-style string literal. Backslash, double quote, and
+/// the named control characters use their short escapes, other ASCII control characters use
+/// three-digit octal escapes, and all other characters are unchanged.
+///
+pub fn escape_c(s: &str) -> Cow<'_, str> {
+    escape_with(
+        s,
+        |c| c == '\\' || c == '"' || c.is_ascii_control(),
+        |c, escaped| match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{7}' => escaped.push_str("\\a"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{b}' => escaped.push_str("\\v"),
+            '\u{c}' => escaped.push_str("\\f"),
+            _ => escaped.push_str(&format!("\\{:03o}", c as u32)),
+        },
+    )
+}
+
+///
+/// Unescape the content of a C-style string literal. In addition to the short escapes, this
+/// accepts octal escapes of one to three digits, hexadecimal escapes `\xHH` of one or two
+/// digits, and the Unicode escapes `\uHHHH` and `\UHHHHHHHH`; a numeric escape denotes the
+/// character with that code point.
+///
+pub fn unescape_c(s: &str) -> Result<Cow<'_, str>, UnescapeError> {
+    unescape_with(s, '\\', |offset, chars| {
+        let (_, c) = chars
+            .next()
+            .ok_or(UnescapeError::new(offset, "incomplete escape"))?;
+        let code = match c {
+            'n' => '\n' as u32,
+            'r' => '\r' as u32,
+            't' => '\t' as u32,
+            'a' => 0x7,
+            'b' => 0x8,
+            'v' => 0xB,
+            'f' => 0xC,
+            '\\' | '"' | '\'' | '?' => c as u32,
+            '0'..='7' => {
+                let mut code = c.to_digit(8).unwrap_or_default();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|(_, d)| d.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            let _ = chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                code
+            }
+            'x' => take_digits(chars, 16, 1, 2)
+                .ok_or(UnescapeError::new(offset, "expected hexadecimal digits"))?,
+            'u' => take_digits(chars, 16, 4, 4)
+                .ok_or(UnescapeError::new(offset, "expected 4 hexadecimal digits"))?,
+            'U' => take_digits(chars, 16, 8, 8)
+                .ok_or(UnescapeError::new(offset, "expected 8 hexadecimal digits"))?,
+            _ => return Err(UnescapeError::new(offset, "unknown escape")),
+        };
+        char::from_u32(code).ok_or(UnescapeError::new(offset, "invalid character"))
+    })
+}
+
+///
+/// Escape `s` for use within a JSON string. Backslash, double quote, and the named control
+/// characters use their short escapes, other control characters use `\u00HH`, and all other
+/// characters are unchanged.
+///
+pub fn escape_json(s: &str) -> Cow<'_, str> {
+    escape_with(
+        s,
+        |c| c == '\\' || c == '"' || c < ' ',
+        |c, escaped| match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            _ => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+        },
+    )
+}
+
+///
+/// Unescape the content of a JSON string, where a surrogate pair of `\uHHHH` escapes denotes a
+/// single character.
+///
+pub fn unescape_json(s: &str) -> Result<Cow<'_, str>, UnescapeError> {
+    unescape_with(s, '\\', |offset, chars| {
+        let (_, c) = chars
+            .next()
+            .ok_or(UnescapeError::new(offset, "incomplete escape"))?;
+        match c {
+            '"' | '\\' | '/' => Ok(c),
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            'b' => Ok('\u{8}'),
+            'f' => Ok('\u{c}'),
+            'u' => {
+                let code = take_digits(chars, 16, 4, 4)
+                    .ok_or(UnescapeError::new(offset, "expected 4 hexadecimal digits"))?;
+                let code = match code {
+                    0xD800..=0xDBFF => {
+                        let low = match (chars.next(), chars.next()) {
+                            (Some((_, '\\')), Some((_, 'u'))) => take_digits(chars, 16, 4, 4),
+                            _ => None,
+                        };
+                        match low {
+                            Some(low @ 0xDC00..=0xDFFF) => {
+                                0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                            }
+                            _ => return Err(UnescapeError::new(offset, "unpaired surrogate")),
+                        }
+                    }
+                    _ => code,
+                };
+                char::from_u32(code).ok_or(UnescapeError::new(offset, "unpaired surrogate"))
+            }
+            _ => Err(UnescapeError::new(offset, "unknown escape")),
+        }
+    })
+}
+
+///
+/// Percent-encode the UTF-8 bytes of `s` other than the unreserved characters of
+/// [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-2.3): letters, digits, `-`, `.`, `_`,
+/// and `~`.
+///
+pub fn percent_encode(s: &str) -> Cow<'_, str> {
+    percent_encode_except(s, "")
+}
+
+///
+/// Percent-encode the UTF-8 bytes of `s` other than the unreserved characters and the ASCII
+/// characters in `keep`; for example, `/` may be kept when encoding a path.
+///
+pub fn percent_encode_except<'a>(s: &'a str, keep: &str) -> Cow<'a, str> {
+    let is_kept =
+        |b: u8| b.is_ascii_alphanumeric() || b"-._~".contains(&b) || keep.contains(b as char);
+    if s.bytes().all(is_kept) {
+        return Cow::Borrowed(s);
+    }
+    let mut encoded = String::with_capacity(s.len() * 3);
+    for b in s.bytes() {
+        if is_kept(b) {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{b:02X}"));
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+///
+/// Decode the percent-encoded bytes in `s`, which must form valid UTF-8; `+` is not treated as a
+/// space.
+///
+pub fn percent_decode(s: &str) -> Result<Cow<'_, str>, UnescapeError> {
+    if !s.contains('%') {
+        return Ok(Cow::Borrowed(s));
+    }
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(s.len());
+    let mut origins = Vec::with_capacity(s.len());
+    let mut offset = 0;
+    while offset < bytes.len() {
+        origins.push(offset);
+        if bytes[offset] == b'%' {
+            let byte = s
+                .get(offset + 1..offset + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or(UnescapeError::new(offset, "expected 2 hexadecimal digits"))?;
+            decoded.push(byte);
+            offset += 3;
+        } else {
+            decoded.push(bytes[offset]);
+            offset += 1;
+        }
+    }
+    String::from_utf8(decoded)
+        .map(Cow::Owned)
+        .map_err(|e| UnescapeError::new(origins[e.utf8_error().valid_up_to()], "invalid UTF-8"))
+}
+
+///
+/// Escape the characters in `text` that may not appear literally in XML character data.
+///
+pub fn escape_xml_text(text: &str) -> Cow<'_, str> {
+    escape_xml(text, false)
+}
+
+///
+/// Escape the characters in `value` that may not appear literally within a double-quoted XML
+/// attribute value, including whitespace that attribute-value normalization would replace.
+///
+pub fn escape_xml_attribute(value: &str) -> Cow<'_, str> {
+    escape_xml(value, true)
+}
+
+///
+/// Replace the predefined entity references, `&amp;`, `&lt;`, `&gt;`, `&quot;`, and `&apos;`,
+/// and decimal and hexadecimal character references in `s`.
+///
+pub fn unescape_xml(s: &str) -> Result<Cow<'_, str>, UnescapeError> {
+    unescape_with(s, '&', |offset, chars| {
+        let mut name = String::new();
+        loop {
+            match chars.next() {
+                Some((_, ';')) => break,
+                Some((_, c)) if name.len() < 10 => name.push(c),
+                _ => return Err(UnescapeError::new(offset, "unterminated reference")),
+            }
+        }
+        let code = match name.as_str() {
+            "amp" => '&' as u32,
+            "lt" => '<' as u32,
+            "gt" => '>' as u32,
+            "quot" => '"' as u32,
+            "apos" => '\'' as u32,
+            _ => match name.strip_prefix('#') {
+                Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16)
+                    .map_err(|_| UnescapeError::new(offset, "invalid character reference"))?,
+                Some(decimal) => decimal
+                    .parse()
+                    .map_err(|_| UnescapeError::new(offset, "invalid character reference"))?,
+                None => return Err(UnescapeError::new(offset, "unknown entity")),
+            },
+        };
+        char::from_u32(code).ok_or(UnescapeError::new(offset, "invalid character"))
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for UnescapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid escape at offset {}: {}",
+            self.offset, self.message
+        )
+    }
+}
+
+impl std::error::Error for UnescapeError {}
+
+impl UnescapeError {
+    ///
+    /// Construct a new error for the escape at `offset`, described by `message`.
+    ///
+    pub const fn new(offset: usize, message: &'static str) -> Self {
+        Self { offset, message }
+    }
+
+    ///
+    /// Returns the byte offset, in the input, of the start of the malformed escape.
+    ///
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    ///
+    /// Returns a description of the problem.
+    ///
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn escape_with<P, E>(s: &str, needs_escape: P, escape: E) -> Cow<'_, str>
+where
+    P: Fn(char) -> bool,
+    E: Fn(char, &mut String),
+{
+    if !s.contains(&needs_escape) {
+        return Cow::Borrowed(s);
+    }
+    let mut escaped = String::with_capacity(s.len() + 8);
+    for c in s.chars() {
+        if needs_escape(c) {
+            escape(c, &mut escaped);
+        } else {
+            escaped.push(c);
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+fn unescape_with<F>(s: &str, introducer: char, unescape: F) -> Result<Cow<'_, str>, UnescapeError>
+where
+    F: Fn(usize, &mut Peekable<CharIndices<'_>>) -> Result<char, UnescapeError>,
+{
+    if !s.contains(introducer) {
+        return Ok(Cow::Borrowed(s));
+    }
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        if c == introducer {
+            unescaped.push(unescape(offset, &mut chars)?);
+        } else {
+            unescaped.push(c);
+        }
+    }
+    Ok(Cow::Owned(unescaped))
+}
+
+fn take_digits(
+    chars: &mut Peekable<CharIndices<'_>>,
+    radix: u32,
+    min: usize,
+    max: usize,
+) -> Option<u32> {
+    let mut value = 0;
+    let mut count = 0;
+    while count < max {
+        match chars.peek().and_then(|(_, c)| c.to_digit(radix)) {
+            Some(digit) => {
+                value = value * radix + digit;
+                count += 1;
+                let _ = chars.next();
+            }
+            None => break,
+        }
+    }
+    (count >= min).then_some(value)
+}
+
+fn escape_xml(s: &str, in_attribute: bool) -> Cow<'_, str> {
+    escape_with(
+        s,
+        |c| match c {
+            '&' | '<' | '>' => true,
+            '"' | '\n' | '\r' | '\t' => in_attribute,
+            _ => false,
+        },
+        |c, escaped| match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#xA;"),
+            '\r' => escaped.push_str("&#xD;"),
+            _ => escaped.push_str("&#x9;"),
+        },
+    )
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: &[&str] = &[
+        "",
+        "plain",
+        "quote \" and \\ backslash",
+        "lines\r\n\tand\u{0}\u{1}\u{7f}",
+        "unicode é 😀 \u{2028}",
+        "markup <a href=\"x\">&amp;</a>",
+        "percent %41 + / ? #",
+    ];
+
+    #[test]
+    fn test_round_trips() {
+        for sample in SAMPLES {
+            assert_eq!(unescape_c(&escape_c(sample)).unwrap(), *sample);
+            assert_eq!(unescape_json(&escape_json(sample)).unwrap(), *sample);
+            assert_eq!(percent_decode(&percent_encode(sample)).unwrap(), *sample);
+            assert_eq!(unescape_xml(&escape_xml_text(sample)).unwrap(), *sample);
+            assert_eq!(
+                unescape_xml(&escape_xml_attribute(sample)).unwrap(),
+                *sample
+            );
+        }
+    }
+
+    #[test]
+    fn test_unchanged_input_is_borrowed() {
+        assert!(matches!(escape_c("plain"), Cow::Borrowed(_)));
+        assert!(matches!(escape_json("é"), Cow::Borrowed(_)));
+        assert!(matches!(
+            percent_encode_except("a/b", "/"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(unescape_xml("plain"), Ok(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_c_escapes() {
+        assert_eq!(escape_c("\u{1}1"), "\\0011");
+        assert_eq!(unescape_c("\\0011\\0\\12x").unwrap(), "\u{1}1\u{0}\nx");
+        assert_eq!(unescape_c("\\x4a\\xe9\\U0001F600").unwrap(), "Jé😀");
+        assert_eq!(unescape_c("ab\\").unwrap_err().offset(), 2);
+        assert_eq!(
+            unescape_c("\\xg").unwrap_err().message(),
+            "expected hexadecimal digits"
+        );
+        assert_eq!(
+            unescape_c("\\uD800").unwrap_err().message(),
+            "invalid character"
+        );
+    }
+
+    #[test]
+    fn test_json_escapes() {
+        assert_eq!(escape_json("\u{1f}/"), "\\u001f/");
+        assert_eq!(unescape_json("\\/\\b\\u00E9").unwrap(), "/\u{8}é");
+        assert_eq!(
+            unescape_json("x\\ud83dy").unwrap_err(),
+            UnescapeError::new(1, "unpaired surrogate")
+        );
+        assert!(unescape_json("\\ude00").is_err());
+        assert!(unescape_json("\\u12").is_err());
+    }
+
+    #[test]
+    fn test_percent_encoding() {
+        assert_eq!(percent_encode_except("a b/c", "/"), "a%20b/c");
+        assert_eq!(percent_decode("%e9").unwrap_err().offset(), 0);
+        assert_eq!(percent_decode("ab%4").unwrap_err().offset(), 2);
+        assert_eq!(percent_decode("ab%C3%A9%FF").unwrap_err().offset(), 8);
+        assert_eq!(percent_decode("a+b").unwrap(), "a+b");
+        assert!(percent_decode("%+1").is_err());
+    }
+
+    #[test]
+    fn test_xml_references() {
+        assert_eq!(unescape_xml("&#x1F600;&#65;").unwrap(), "😀A");
+        assert_eq!(
+            unescape_xml("a &nbsp;").unwrap_err().message(),
+            "unknown entity"
+        );
+        assert_eq!(
+            unescape_xml("&amp").unwrap_err().message(),
+            "unterminated reference"
+        );
+        assert_eq!(
+            unescape_xml("&#xD800;").unwrap_err().message(),
+            "invalid character"
+        );
+    }
+}
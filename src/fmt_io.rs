@@ -0,0 +1,112 @@
+/*!
+Provides a bridge from `std::io::Write` to `std::fmt::Formatter`, allowing writers to produce
+output directly into a `Display` implementation.
+ */
+
+use crate::utf8::from_utf8;
+use std::fmt::Formatter;
+use std::io::{Error, ErrorKind, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An implementation of `Write` that forwards all bytes, which must be valid UTF-8, to a
+/// formatter. A multi-byte character may be split across calls to `write`, and so an incomplete
+/// sequence is held until the next call; [`finish`](Self::finish) reports any sequence still
+/// incomplete at the end of output.
+///
+pub(crate) struct FormatterWriter<'a, 'b> {
+    formatter: &'a mut Formatter<'b>,
+    pending: Vec<u8>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Write for FormatterWriter<'_, '_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let joined;
+        let bytes = if self.pending.is_empty() {
+            buf
+        } else {
+            self.pending.extend_from_slice(buf);
+            joined = std::mem::take(&mut self.pending);
+            &joined
+        };
+        let valid = match from_utf8(bytes) {
+            Ok(valid) => valid,
+            Err(e) if e.error_len().is_none() => {
+                self.pending = bytes[e.valid_up_to()..].to_vec();
+                from_utf8(&bytes[..e.valid_up_to()]).expect("prefix is valid UTF-8")
+            }
+            Err(e) => return Err(Error::new(ErrorKind::InvalidData, e)),
+        };
+        self.formatter.write_str(valid).map_err(Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b> FormatterWriter<'a, 'b> {
+    pub(crate) fn new(formatter: &'a mut Formatter<'b>) -> Self {
+        Self {
+            formatter,
+            pending: Vec::new(),
+        }
+    }
+
+    pub(crate) fn finish(self) -> std::io::Result<()> {
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                "output ended with an incomplete UTF-8 sequence",
+            ))
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Display;
+
+    struct Split(&'static [&'static [u8]]);
+
+    impl Display for Split {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            let mut w = FormatterWriter::new(f);
+            for part in self.0 {
+                w.write_all(part).map_err(|_| std::fmt::Error)?;
+            }
+            w.finish().map_err(|_| std::fmt::Error)
+        }
+    }
+
+    #[test]
+    fn test_split_character() {
+        let bytes = "aé€".as_bytes();
+        let parts: &'static [&'static [u8]] = &[b"a\xc3", b"\xa9\xe2", b"\x82", b"\xac"];
+        assert_eq!(parts.concat(), bytes);
+        assert_eq!(Split(parts).to_string(), "aé€");
+    }
+
+    #[test]
+    fn test_incomplete_character() {
+        let mut output = String::new();
+        let result = std::fmt::write(&mut output, format_args!("{}", Split(&[b"a\xc3"])));
+        assert!(result.is_err());
+        assert_eq!(output, "a");
+    }
+}
@@ -0,0 +1,409 @@
+/*!
+Provides support for formats that are read footer-first, where a fixed-size trailer at the end of
+the content locates a footer, commonly an index or schema, which in turn locates the regions of
+the content to read. This is the access pattern of formats such as Parquet and ORC, and is used
+with a source that can seek, as provided to an [`ObjectSeekReader`](crate::seek::ObjectSeekReader).
+
+A [`TrailerLayout`] describes a trailer consisting of the length of the footer followed by a magic
+number; [`TrailerLayout::read_footer`] reads and checks the trailer and returns a [`Footer`] with
+the [`Region`]s of the footer itself and of the data that precedes it. Each region may then be
+read, or be checked to contain the regions named by an index, without each format re-deriving the
+offset arithmetic. Errors in the trailer are reported as I/O errors of kind `InvalidData` carrying
+an [`Error::Parse`], which is recovered by the conversion into [`Error`].
+
+# Example
+
+```rust
+use objio::footer::{ByteOrder, TrailerLayout};
+use std::io::Cursor;
+
+const LAYOUT: TrailerLayout =
+    TrailerLayout::new("example", b"EXM1").with_length(4, ByteOrder::Little);
+
+// data, then a footer holding the 1-byte offset and length of a record, then the trailer.
+let mut content = b"EXM1hello world".to_vec();
+content.extend_from_slice(&[10, 5]);
+content.extend_from_slice(&2u32.to_le_bytes());
+content.extend_from_slice(b"EXM1");
+
+let mut source = Cursor::new(content);
+let footer = LAYOUT.read_footer(&mut source).unwrap();
+let index = footer.body().read(&mut source).unwrap();
+let record = footer
+    .data()
+    .region_at(u64::from(index[0]), u64::from(index[1]))
+    .unwrap();
+
+assert_eq!(record.read(&mut source).unwrap(), b"world");
+```
+ */
+
+use crate::error::{Error, Location};
+use std::io::{Error as IoError, Read, Seek, SeekFrom, Take};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The order of bytes in an integer value stored in a trailer.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// The least significant byte is stored first.
+    #[default]
+    Little,
+    /// The most significant byte is stored first.
+    Big,
+}
+
+///
+/// A contiguous range of bytes in a source, identified by its offset from the start of the source
+/// and its length.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Region {
+    offset: u64,
+    length: u64,
+}
+
+///
+/// The layout of a fixed-size trailer, the footer length followed by a magic number, at the end
+/// of the content of a format.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrailerLayout {
+    format: &'static str,
+    magic: &'static [u8],
+    length_size: usize,
+    byte_order: ByteOrder,
+}
+
+///
+/// The result of reading a trailer, the regions of the footer it describes and of the data that
+/// precedes the footer.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Footer {
+    data: Region,
+    body: Region,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read the last `size` bytes of `r`, returning the offset of the first of them and the bytes.
+/// The source is left positioned at its end.
+///
+/// This function will return an error of kind `UnexpectedEof` if the source is shorter than
+/// `size` bytes.
+///
+pub fn read_trailer<R>(r: &mut R, size: usize) -> Result<(u64, Vec<u8>), IoError>
+where
+    R: Read + Seek,
+{
+    let end = r.seek(SeekFrom::End(0))?;
+    let offset = end
+        .checked_sub(size as u64)
+        .ok_or_else(|| IoError::from(std::io::ErrorKind::UnexpectedEof))?;
+    let _ = r.seek(SeekFrom::Start(offset))?;
+    let mut trailer = vec![0; size];
+    r.read_exact(&mut trailer)?;
+    Ok((offset, trailer))
+}
+
+///
+/// Decode an unsigned integer of up to eight bytes stored in the given byte order.
+///
+/// # Panics
+///
+/// This function panics if `bytes` is longer than eight bytes.
+///
+pub fn decode_uint(bytes: &[u8], byte_order: ByteOrder) -> u64 {
+    assert!(bytes.len() <= 8, "an integer may be at most 8 bytes");
+    let fold = |value: u64, byte: &u8| (value << 8) | u64::from(*byte);
+    match byte_order {
+        ByteOrder::Little => bytes.iter().rev().fold(0, fold),
+        ByteOrder::Big => bytes.iter().fold(0, fold),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Region {
+    ///
+    /// Construct a new region of `length` bytes starting at `offset`.
+    ///
+    pub const fn new(offset: u64, length: u64) -> Self {
+        Self { offset, length }
+    }
+
+    ///
+    /// Returns the offset of the first byte of this region.
+    ///
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    ///
+    /// Returns the number of bytes in this region.
+    ///
+    pub const fn len(&self) -> u64 {
+        self.length
+    }
+
+    ///
+    /// Returns `true` if this region contains no bytes.
+    ///
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    ///
+    /// Returns the offset of the first byte after this region.
+    ///
+    pub const fn end(&self) -> u64 {
+        self.offset + self.length
+    }
+
+    ///
+    /// Returns `true` if `other` lies entirely within this region.
+    ///
+    pub const fn contains(&self, other: &Region) -> bool {
+        other.offset >= self.offset && other.end() <= self.end()
+    }
+
+    ///
+    /// Returns the region of `length` bytes at `offset` relative to the start of this region, or
+    /// `None` if it does not lie entirely within this region.
+    ///
+    pub fn region_at(&self, offset: u64, length: u64) -> Option<Region> {
+        let region = Region::new(self.offset.checked_add(offset)?, length);
+        region
+            .offset
+            .checked_add(length)
+            .filter(|_| self.contains(&region))
+            .map(|_| region)
+    }
+
+    ///
+    /// Position `r` at the start of this region and return a reader limited to its content.
+    ///
+    pub fn reader<'a, R>(&self, r: &'a mut R) -> Result<Take<&'a mut R>, IoError>
+    where
+        R: Read + Seek,
+    {
+        let _ = r.seek(SeekFrom::Start(self.offset))?;
+        Ok(r.take(self.length))
+    }
+
+    ///
+    /// Read the content of this region from `r`.
+    ///
+    /// This function will return an error of kind `UnexpectedEof` if the source ends before the
+    /// end of this region.
+    ///
+    pub fn read<R>(&self, r: &mut R) -> Result<Vec<u8>, IoError>
+    where
+        R: Read + Seek,
+    {
+        let mut content = vec![0; self.length as usize];
+        let _ = r.seek(SeekFrom::Start(self.offset))?;
+        r.read_exact(&mut content)?;
+        Ok(content)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl TrailerLayout {
+    ///
+    /// Construct a new layout for the named format with the given magic number and a 4-byte,
+    /// little-endian, footer length.
+    ///
+    pub const fn new(format: &'static str, magic: &'static [u8]) -> Self {
+        Self {
+            format,
+            magic,
+            length_size: 4,
+            byte_order: ByteOrder::Little,
+        }
+    }
+
+    ///
+    /// Set the size, between one and eight bytes, and byte order of the footer length.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `size` is zero or greater than eight.
+    ///
+    pub const fn with_length(self, size: usize, byte_order: ByteOrder) -> Self {
+        assert!(
+            size > 0 && size <= 8,
+            "a footer length must be 1 to 8 bytes"
+        );
+        let mut self_mut = self;
+        self_mut.length_size = size;
+        self_mut.byte_order = byte_order;
+        self_mut
+    }
+
+    ///
+    /// Returns the name of the format, used in errors.
+    ///
+    pub const fn format(&self) -> &'static str {
+        self.format
+    }
+
+    ///
+    /// Returns the magic number that ends the trailer.
+    ///
+    pub const fn magic(&self) -> &'static [u8] {
+        self.magic
+    }
+
+    ///
+    /// Returns the total size of the trailer in bytes.
+    ///
+    pub const fn size(&self) -> usize {
+        self.length_size + self.magic.len()
+    }
+
+    ///
+    /// Read the trailer at the end of `r`, whose content starts at its current position, check
+    /// its magic number, and return the regions of the footer and of the data that precedes it.
+    ///
+    pub fn read_footer<R>(&self, r: &mut R) -> Result<Footer, IoError>
+    where
+        R: Read + Seek,
+    {
+        let start = r.stream_position()?;
+        let (offset, trailer) = match read_trailer(r, self.size()) {
+            Ok(trailer) if trailer.0 >= start => trailer,
+            Ok(_) => return Err(self.error("the content is too short for its trailer", 0)),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Err(self.error("the content is too short for its trailer", 0))
+            }
+            Err(e) => return Err(e),
+        };
+        let (length, magic) = trailer.split_at(self.length_size);
+        if magic != self.magic {
+            return Err(self.error(
+                "the trailer magic number does not match",
+                offset + self.length_size as u64 - start,
+            ));
+        }
+        let length = decode_uint(length, self.byte_order);
+        let Some(body_offset) = offset.checked_sub(length).filter(|o| *o >= start) else {
+            return Err(self.error("the footer length exceeds the content", offset - start));
+        };
+        Ok(Footer {
+            data: Region::new(start, body_offset - start),
+            body: Region::new(body_offset, length),
+        })
+    }
+
+    fn error(&self, message: &'static str, offset: u64) -> IoError {
+        Error::parse(self.format, message)
+            .at(Location::new(offset as usize))
+            .into()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Footer {
+    ///
+    /// Returns the region of the data that precedes the footer.
+    ///
+    pub const fn data(&self) -> Region {
+        self.data
+    }
+
+    ///
+    /// Returns the region of the footer itself, excluding the trailer.
+    ///
+    pub const fn body(&self) -> Region {
+        self.body
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const LAYOUT: TrailerLayout = TrailerLayout::new("test", b"TST").with_length(2, ByteOrder::Big);
+
+    fn content(prefix: &[u8], data: &[u8], footer: &[u8], magic: &[u8]) -> Cursor<Vec<u8>> {
+        let mut content = prefix.to_vec();
+        content.extend_from_slice(data);
+        content.extend_from_slice(footer);
+        content.extend_from_slice(&(footer.len() as u16).to_be_bytes());
+        content.extend_from_slice(magic);
+        let mut cursor = Cursor::new(content);
+        cursor.set_position(prefix.len() as u64);
+        cursor
+    }
+
+    #[test]
+    fn test_decode_uint() {
+        assert_eq!(decode_uint(&[1, 2], ByteOrder::Little), 0x0201);
+        assert_eq!(decode_uint(&[1, 2], ByteOrder::Big), 0x0102);
+        assert_eq!(decode_uint(&[], ByteOrder::Big), 0);
+        assert_eq!(decode_uint(&[0xFF; 8], ByteOrder::Little), u64::MAX);
+    }
+
+    #[test]
+    fn test_region_at() {
+        let region = Region::new(10, 10);
+        assert_eq!(region.region_at(2, 8), Some(Region::new(12, 8)));
+        assert_eq!(region.region_at(2, 9), None);
+        assert_eq!(region.region_at(u64::MAX, 1), None);
+        assert!(region.region_at(10, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_footer() {
+        let mut source = content(b"skip", b"some data", b"footer", b"TST");
+        let footer = LAYOUT.read_footer(&mut source).unwrap();
+        assert_eq!(footer.data(), Region::new(4, 9));
+        assert_eq!(footer.body().read(&mut source).unwrap(), b"footer");
+
+        let mut data = String::new();
+        footer
+            .data()
+            .reader(&mut source)
+            .unwrap()
+            .read_to_string(&mut data)
+            .unwrap();
+        assert_eq!(data, "some data");
+    }
+
+    #[test]
+    fn test_read_footer_errors() {
+        let error = LAYOUT
+            .read_footer(&mut content(b"", b"data", b"footer", b"BAD"))
+            .unwrap_err();
+        let error = Error::from(error);
+        assert_eq!(error.location(), Some(Location::new(12)));
+        assert!(error.to_string().contains("magic number"));
+
+        let mut source = content(b"", b"", b"footer", b"TST");
+        source.get_mut()[6] = 0xFF;
+        assert!(LAYOUT.read_footer(&mut source).is_err());
+
+        let mut source = Cursor::new(b"TST".to_vec());
+        assert!(Error::from(LAYOUT.read_footer(&mut source).unwrap_err())
+            .to_string()
+            .contains("too short"));
+    }
+}
@@ -0,0 +1,232 @@
+/*!
+Provides a reader and writer for [CBOR](https://cbor.io), using the `ciborium` crate, for any type
+implementing the corresponding serde traits.
+
+As a binary format the settings in [`CommonWriterOptions`] have no effect on the output of these
+types, and the methods `write_to_bytes` and `read_from_bytes` should be used in place of their
+string counterparts.
+
+# Example
+
+```rust
+use objio::{ObjectReader, ObjectWriter};
+use objio::formats::cbor::{CborReader, CborWriter};
+
+let writer = CborWriter::default();
+let bytes = writer.write_to_bytes(&vec![1u8, 2]).unwrap();
+assert_eq!(bytes, vec![0x82, 0x01, 0x02]);
+
+let reader: CborReader<Vec<u8>> = CborReader::default();
+assert_eq!(reader.read_from_bytes(bytes).unwrap(), vec![1, 2]);
+```
+ */
+
+use crate::error::Error;
+use crate::formats::SerdeFormat;
+use crate::options::{Buffering, CommonWriterOptions, ValidateOptions};
+use crate::{HasOptions, ObjectReader, ObjectWriter, ObjectWriterWith};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The CBOR format, usable with the generic [`SerdeReader`](crate::formats::SerdeReader) and
+/// [`SerdeWriter`](crate::formats::SerdeWriter) types.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cbor;
+
+///
+/// A reader for CBOR representations of `T`.
+///
+pub struct CborReader<T: DeserializeOwned> {
+    object: PhantomData<fn() -> T>,
+}
+
+///
+/// A writer producing CBOR representations of `T`.
+///
+pub struct CborWriter<T: Serialize> {
+    options: CommonWriterOptions,
+    object: PhantomData<fn(&T)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl SerdeFormat for Cbor {
+    const NAME: &'static str = "CBOR";
+    const MEDIA_TYPE: &'static str = "application/cbor";
+    const IS_BINARY: bool = true;
+
+    fn from_reader<T, R>(r: &mut R) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        ciborium::de::from_reader(r).map_err(|e| Error::deserialization(Self::NAME, e))
+    }
+
+    fn to_writer<T, W>(w: &mut W, value: &T, _: &CommonWriterOptions) -> Result<(), Error>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        ciborium::ser::into_writer(value, w).map_err(|e| Error::serialization(Self::NAME, e))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: DeserializeOwned> Debug for CborReader<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CborReader").finish()
+    }
+}
+
+impl<T: DeserializeOwned> Default for CborReader<T> {
+    fn default() -> Self {
+        Self {
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> ObjectReader<T> for CborReader<T> {
+    type Error = Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        Cbor::from_reader(r)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: Serialize> Debug for CborWriter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CborWriter")
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<T: Serialize> Default for CborWriter<T> {
+    fn default() -> Self {
+        Self {
+            options: Default::default(),
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> HasOptions<CommonWriterOptions> for CborWriter<T> {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl<T: Serialize> ObjectWriter<T> for CborWriter<T> {
+    type Error = Error;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write_with(w, object, &self.options)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.options.validate()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.options.buffering()
+    }
+}
+
+impl<T: Serialize> ObjectWriterWith<T, CommonWriterOptions> for CborWriter<T> {
+    fn write_with<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        options: &CommonWriterOptions,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Cbor::to_writer(w, object, options)
+    }
+
+    fn validate_options_with(&self, options: &CommonWriterOptions) -> Result<(), Self::Error> {
+        options.validate()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct TestObject {
+        name: String,
+        count: u32,
+    }
+
+    fn test_object() -> TestObject {
+        TestObject {
+            name: "Hello".to_string(),
+            count: 2,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let writer = CborWriter::default();
+        let reader = CborReader::default();
+        let bytes = writer.write_to_bytes(&test_object()).unwrap();
+        let object: TestObject = reader.read_from_bytes(bytes).unwrap();
+        assert_eq!(object, test_object());
+    }
+
+    #[test]
+    fn test_framed_round_trip() {
+        let writer = CborWriter::default();
+        let reader = CborReader::default();
+        let mut stream: Vec<u8> = Vec::new();
+        crate::framing::write_framed(&writer, &mut stream, &test_object()).unwrap();
+        crate::framing::write_framed(&writer, &mut stream, &test_object()).unwrap();
+
+        let mut source = stream.as_slice();
+        let objects: Vec<TestObject> =
+            std::iter::from_fn(|| crate::framing::read_framed(&reader, &mut source).unwrap())
+                .collect();
+        assert_eq!(objects, vec![test_object(), test_object()]);
+    }
+
+    #[test]
+    fn test_read_error() {
+        let reader: CborReader<TestObject> = CborReader::default();
+        assert!(matches!(
+            reader.read_from_bytes([0xFF, 0xFF]),
+            Err(Error::Deserialization { format: "CBOR", .. })
+        ));
+    }
+}
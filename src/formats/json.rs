@@ -0,0 +1,1075 @@
+/*!
+Provides a reader and writer for [JSON](https://www.json.org), using the `serde_json` crate, for
+any type implementing the corresponding serde traits.
+
+The writer honors the pretty-printing and indentation settings in [`CommonWriterOptions`], and
+writes the members of objects in the order given by its
+[`ordering`](crate::options::CommonWriterOptions::ordering). It also implements
+[`ObjectCanonicalWriter`], writing the canonical form defined by the JSON Canonicalization Scheme of
+[RFC 8785](https://www.rfc-editor.org/rfc/rfc8785); object members are sorted and numbers are
+written as IEEE 754 doubles, so integers beyond 2^53 lose precision as the specification requires.
+
+The reader honors the [`projection`](crate::options::CommonReaderOptions::projection) in
+[`CommonReaderOptions`]; the values of members that are not
+selected are skipped rather than parsed, and so the type read must tolerate their absence. Skipped
+array elements are read as `null`, preserving the position of those that are selected.
+
+With the `jsonschema` feature, a reader or writer may be given a `JsonSchema`; documents are then
+validated after they are read and before they are written, and any violations are reported as
+[`Error::SchemaValidation`].
+
+# Example
+
+```rust
+use objio::{HasOptions, ObjectReader, ObjectWriter};
+use objio::formats::json::{JsonReader, JsonWriter};
+use objio::options::Pretty;
+use std::collections::BTreeMap;
+
+let reader: JsonReader<BTreeMap<String, u32>> = JsonReader::default();
+let map = reader.read_from_string(r#"{"a": 1}"#).unwrap();
+
+let writer = JsonWriter::default().with_options(Pretty);
+assert_eq!(writer.write_to_string(&map).unwrap(), "{\n  \"a\": 1\n}");
+```
+ */
+
+use crate::canonical::ObjectCanonicalWriter;
+use crate::error::Error;
+use crate::formats::{SerdeFormat, SerdeWriter};
+use crate::numbers::NumberOptions;
+use crate::options::{
+    Buffering, CollectionOrdering, CommonReaderOptions, CommonWriterOptions, Projection,
+    ValidateOptions,
+};
+use crate::{
+    HasOptions, ObjectPrinter, ObjectReader, ObjectReaderWith, ObjectWriter, ObjectWriterWith,
+};
+use serde::de::{DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use serde_json::ser::{CompactFormatter, PrettyFormatter, Serializer};
+use serde_json::{Map, Value};
+use std::fmt::{Debug, Formatter};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The JSON format, usable with the generic [`SerdeReader`](crate::formats::SerdeReader) and
+/// [`SerdeWriter`] types.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Json;
+
+///
+/// A compiled JSON Schema used to validate documents read by [`JsonReader`] or written by
+/// [`JsonWriter`]; it is cheap to clone.
+///
+#[cfg(feature = "jsonschema")]
+#[derive(Clone)]
+pub struct JsonSchema {
+    validator: std::sync::Arc<jsonschema::Validator>,
+}
+
+///
+/// A reader for JSON representations of `T`.
+///
+pub struct JsonReader<T: DeserializeOwned> {
+    options: CommonReaderOptions,
+    #[cfg(feature = "jsonschema")]
+    schema: Option<JsonSchema>,
+    object: PhantomData<fn() -> T>,
+}
+
+///
+/// A writer producing JSON representations of `T`.
+///
+pub struct JsonWriter<T: Serialize> {
+    options: CommonWriterOptions,
+    #[cfg(feature = "jsonschema")]
+    schema: Option<JsonSchema>,
+    object: PhantomData<fn(&T)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A JSON document that serializes the members of each object in the order given by the options.
+///
+struct Ordered<'a> {
+    value: &'a Value,
+    ordering: &'a CollectionOrdering,
+}
+
+///
+/// A formatter that writes floating-point values as determined by the number options.
+///
+struct Numbers<'a, F> {
+    formatter: F,
+    options: &'a NumberOptions,
+}
+
+///
+/// Deserializes the JSON value at `path`, skipping those parts not required by the projection.
+///
+struct Projected<'a> {
+    projection: &'a Projection,
+    path: String,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl SerdeFormat for Json {
+    const NAME: &'static str = "JSON";
+    const MEDIA_TYPE: &'static str = "application/json";
+
+    fn from_reader<T, R>(r: &mut R) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        serde_json::from_reader(r).map_err(|e| Error::deserialization(Self::NAME, e))
+    }
+
+    fn to_writer<T, W>(w: &mut W, value: &T, options: &CommonWriterOptions) -> Result<(), Error>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        if options.is_deterministic() && options.ordering().is_insertion() {
+            return Self::to_writer(
+                w,
+                value,
+                &options
+                    .clone()
+                    .with_ordering(CollectionOrdering::Lexicographic),
+            );
+        }
+        if !options.ordering().is_insertion() {
+            let document =
+                serde_json::to_value(value).map_err(|e| Error::serialization(Self::NAME, e))?;
+            let ordered = Ordered {
+                value: &document,
+                ordering: options.ordering(),
+            };
+            return Self::to_writer(
+                w,
+                &ordered,
+                &options
+                    .clone()
+                    .with_ordering(Default::default())
+                    .with_deterministic(false),
+            );
+        }
+        if options.is_pretty() {
+            let indent = " ".repeat(options.indent());
+            let mut serializer = Serializer::with_formatter(
+                w,
+                Numbers {
+                    formatter: PrettyFormatter::with_indent(indent.as_bytes()),
+                    options: options.numbers(),
+                },
+            );
+            value.serialize(&mut serializer)
+        } else {
+            let mut serializer = Serializer::with_formatter(
+                w,
+                Numbers {
+                    formatter: CompactFormatter,
+                    options: options.numbers(),
+                },
+            );
+            value.serialize(&mut serializer)
+        }
+        .map_err(|e| Error::serialization(Self::NAME, e))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "jsonschema")]
+impl Debug for JsonSchema {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonSchema").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl JsonSchema {
+    ///
+    /// Compile `schema`, returning [`Error::InvalidOptions`] if it is not a valid schema.
+    ///
+    pub fn new(schema: &serde_json::Value) -> Result<Self, Error> {
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|e| Error::invalid_options(format!("invalid JSON schema; {e}")))?;
+        Ok(Self {
+            validator: std::sync::Arc::new(validator),
+        })
+    }
+
+    ///
+    /// Validate `document`, returning [`Error::SchemaValidation`] with every violation found.
+    ///
+    pub fn validate(&self, document: &serde_json::Value) -> Result<(), Error> {
+        let violations: Vec<_> = self
+            .validator
+            .iter_errors(document)
+            .map(|e| {
+                crate::error::SchemaViolation::new(
+                    e.instance_path.as_str(),
+                    e.schema_path.as_str(),
+                    e.to_string(),
+                )
+            })
+            .collect();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::schema_validation(violations))
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: DeserializeOwned> Debug for JsonReader<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("JsonReader");
+        debug.field("options", &self.options);
+        #[cfg(feature = "jsonschema")]
+        debug.field("schema", &self.schema);
+        debug.finish()
+    }
+}
+
+impl<T: DeserializeOwned> Default for JsonReader<T> {
+    fn default() -> Self {
+        Self {
+            options: Default::default(),
+            #[cfg(feature = "jsonschema")]
+            schema: None,
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> HasOptions<CommonReaderOptions> for JsonReader<T> {
+    fn options(&self) -> &CommonReaderOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonReaderOptions {
+        &mut self.options
+    }
+}
+
+impl<T: DeserializeOwned> ObjectReader<T> for JsonReader<T> {
+    type Error = Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        self.read_with(r, &self.options)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.options.validate()
+    }
+}
+
+impl<T: DeserializeOwned> ObjectReaderWith<T, CommonReaderOptions> for JsonReader<T> {
+    fn read_with<R>(&self, r: &mut R, options: &CommonReaderOptions) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        #[cfg(feature = "jsonschema")]
+        if let Some(schema) = &self.schema {
+            let mut document: serde_json::Value = Json::from_reader(r)?;
+            schema.validate(&document)?;
+            let projection = options.projection();
+            if !projection.is_all() {
+                document = Projected {
+                    projection,
+                    path: String::new(),
+                }
+                .deserialize(document)
+                .map_err(|e| Error::deserialization(Json::NAME, e))?;
+            }
+            return serde_json::from_value(document)
+                .map_err(|e| Error::deserialization(Json::NAME, e));
+        }
+        let projection = options.projection();
+        if !projection.is_all() {
+            let mut deserializer = serde_json::Deserializer::from_reader(r);
+            let document = Projected {
+                projection,
+                path: String::new(),
+            }
+            .deserialize(&mut deserializer)
+            .and_then(|document| deserializer.end().map(|_| document))
+            .map_err(|e| Error::deserialization(Json::NAME, e))?;
+            return serde_json::from_value(document)
+                .map_err(|e| Error::deserialization(Json::NAME, e));
+        }
+        Json::from_reader(r)
+    }
+
+    fn validate_options_with(&self, options: &CommonReaderOptions) -> Result<(), Self::Error> {
+        options.validate()
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl<T: DeserializeOwned> JsonReader<T> {
+    ///
+    /// Returns this reader with every document validated against `schema` after it is read.
+    ///
+    pub fn with_schema(self, schema: JsonSchema) -> Self {
+        let mut self_mut = self;
+        self_mut.schema = Some(schema);
+        self_mut
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: Serialize> Debug for JsonWriter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("JsonWriter");
+        debug.field("options", &self.options);
+        #[cfg(feature = "jsonschema")]
+        debug.field("schema", &self.schema);
+        debug.finish()
+    }
+}
+
+impl<T: Serialize> Default for JsonWriter<T> {
+    fn default() -> Self {
+        Self {
+            options: Default::default(),
+            #[cfg(feature = "jsonschema")]
+            schema: None,
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> HasOptions<CommonWriterOptions> for JsonWriter<T> {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl<T: Serialize> ObjectWriter<T> for JsonWriter<T> {
+    type Error = Error;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write_with(w, object, &self.options)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.options.validate()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.options.buffering()
+    }
+}
+
+impl<T: Serialize> ObjectPrinter<T> for JsonWriter<T> {
+    fn write_pretty<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write_with(w, object, &self.options.clone().pretty())
+    }
+
+    fn write_canonical<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write_with(w, object, &self.options.clone().compact())
+    }
+}
+
+impl<T: Serialize> JsonWriter<T> {
+    ///
+    /// Returns this writer with every document validated against `schema` before it is written.
+    ///
+    #[cfg(feature = "jsonschema")]
+    pub fn with_schema(self, schema: JsonSchema) -> Self {
+        let mut self_mut = self;
+        self_mut.schema = Some(schema);
+        self_mut
+    }
+}
+
+impl<T: Serialize> ObjectWriterWith<T, CommonWriterOptions> for JsonWriter<T> {
+    fn write_with<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        options: &CommonWriterOptions,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        #[cfg(feature = "jsonschema")]
+        if let Some(schema) = &self.schema {
+            let document =
+                serde_json::to_value(object).map_err(|e| Error::serialization(Json::NAME, e))?;
+            schema.validate(&document)?;
+            return Json::to_writer(w, &document, options);
+        }
+        Json::to_writer(w, object, options)
+    }
+
+    fn validate_options_with(&self, options: &CommonWriterOptions) -> Result<(), Self::Error> {
+        options.validate()
+    }
+}
+
+impl<T: Serialize> ObjectCanonicalWriter<T> for JsonWriter<T> {
+    fn canonical_form(&self) -> &'static str {
+        CANONICAL_FORM
+    }
+
+    fn write_canonical_form<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        let document =
+            serde_json::to_value(object).map_err(|e| Error::serialization(Json::NAME, e))?;
+        #[cfg(feature = "jsonschema")]
+        if let Some(schema) = &self.schema {
+            schema.validate(&document)?;
+        }
+        write_canonical_value(w, &document)
+    }
+}
+
+impl<T: Serialize> ObjectCanonicalWriter<T> for SerdeWriter<Json> {
+    fn canonical_form(&self) -> &'static str {
+        CANONICAL_FORM
+    }
+
+    fn write_canonical_form<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        let document =
+            serde_json::to_value(object).map_err(|e| Error::serialization(Json::NAME, e))?;
+        write_canonical_value(w, &document)
+    }
+}
+
+impl Serialize for Ordered<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::{SerializeMap, SerializeSeq};
+        let nested = |value| Ordered {
+            value,
+            ordering: self.ordering,
+        };
+        match self.value {
+            Value::Array(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(&nested(value))?;
+                }
+                seq.end()
+            }
+            Value::Object(members) => {
+                let mut members: Vec<_> = members.iter().collect();
+                self.ordering
+                    .sort_by_key(&mut members, |(name, _)| name.as_str());
+                let mut map = serializer.serialize_map(Some(members.len()))?;
+                for (name, value) in members {
+                    map.serialize_entry(name, &nested(value))?;
+                }
+                map.end()
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+impl<F: serde_json::ser::Formatter> serde_json::ser::Formatter for Numbers<'_, F> {
+    fn write_f32<W>(&mut self, writer: &mut W, value: f32) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        writer.write_all(self.options.format_f32(value).as_bytes())
+    }
+
+    fn write_f64<W>(&mut self, writer: &mut W, value: f64) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        writer.write_all(self.options.format_f64(value).as_bytes())
+    }
+
+    fn begin_array<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.begin_array(writer)
+    }
+
+    fn end_array<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.end_array(writer)
+    }
+
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.end_array_value(writer)
+    }
+
+    fn begin_object<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.begin_object(writer)
+    }
+
+    fn end_object<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.end_object(writer)
+    }
+
+    fn begin_object_key<W>(&mut self, writer: &mut W, first: bool) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.begin_object_key(writer, first)
+    }
+
+    fn end_object_key<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.end_object_key(writer)
+    }
+
+    fn begin_object_value<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.begin_object_value(writer)
+    }
+
+    fn end_object_value<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.formatter.end_object_value(writer)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Projected<'_> {
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if self.projection.includes(&self.path) {
+            Value::deserialize(deserializer)
+        } else {
+            deserializer.deserialize_any(self)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for Projected<'_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        loop {
+            let path = format!("{}/{}", self.path, values.len());
+            let value = if self.projection.requires(&path) {
+                seq.next_element_seed(Projected {
+                    projection: self.projection,
+                    path,
+                })?
+            } else {
+                seq.next_element::<IgnoredAny>()?.map(|_| Value::Null)
+            };
+            match value {
+                Some(value) => values.push(value),
+                None => return Ok(Value::Array(values)),
+            }
+        }
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut members = Map::new();
+        while let Some(name) = map.next_key::<String>()? {
+            // Names are escaped as reference tokens of a JSON Pointer.
+            let path = format!(
+                "{}/{}",
+                self.path,
+                name.replace('~', "~0").replace('/', "~1")
+            );
+            if self.projection.requires(&path) {
+                let value = map.next_value_seed(Projected {
+                    projection: self.projection,
+                    path,
+                })?;
+                let _ = members.insert(name, value);
+            } else {
+                let _ = map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(Value::Object(members))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const CANONICAL_FORM: &str = "RFC 8785 JSON Canonicalization Scheme";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn write_canonical_value<W: Write>(w: &mut W, value: &Value) -> Result<(), Error> {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => {
+            serde_json::to_writer(w, value).map_err(|e| Error::serialization(Json::NAME, e))
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            Ok(w.write_all(canonical_number(number).as_bytes())?)
+        }
+        Value::Array(values) => {
+            w.write_all(b"[")?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    w.write_all(b",")?;
+                }
+                write_canonical_value(w, value)?;
+            }
+            Ok(w.write_all(b"]")?)
+        }
+        Value::Object(members) => {
+            // RFC 8785 orders members by the UTF-16 code units of their names.
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_by(|(lhs, _), (rhs, _)| lhs.encode_utf16().cmp(rhs.encode_utf16()));
+            w.write_all(b"{")?;
+            for (i, (name, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    w.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut *w, name)
+                    .map_err(|e| Error::serialization(Json::NAME, e))?;
+                w.write_all(b":")?;
+                write_canonical_value(w, value)?;
+            }
+            Ok(w.write_all(b"}")?)
+        }
+    }
+}
+
+///
+/// Format `number` as ECMAScript's `Number.prototype.toString` does, as required by RFC 8785.
+///
+fn canonical_number(number: f64) -> String {
+    if number == 0.0 {
+        return "0".to_string();
+    }
+    let sign = if number < 0.0 { "-" } else { "" };
+    // The shortest round-tripping digits, and the exponent, in the form "d.ddde-n".
+    let scientific = format!("{:e}", number.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits = mantissa.replace('.', "");
+    let exponent: i32 = exponent.parse().unwrap_or_default();
+    let length = digits.len() as i32;
+    let point = exponent + 1;
+    let formatted = if length <= point && point <= 21 {
+        format!("{digits}{}", "0".repeat((point - length) as usize))
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point as usize);
+        format!("{integer}.{fraction}")
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{digits}", "0".repeat(-point as usize))
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let fraction = if rest.is_empty() {
+            String::new()
+        } else {
+            format!(".{rest}")
+        };
+        let exponent_sign = if point > 0 { "+" } else { "-" };
+        format!("{first}{fraction}e{exponent_sign}{}", (point - 1).abs())
+    };
+    format!("{sign}{formatted}")
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct TestObject {
+        name: String,
+        count: u32,
+    }
+
+    fn test_object() -> TestObject {
+        TestObject {
+            name: "Hello".to_string(),
+            count: 2,
+        }
+    }
+
+    #[test]
+    fn test_write_compact() {
+        let writer = JsonWriter::default();
+        assert_eq!(
+            writer.write_to_string(&test_object()).unwrap(),
+            r#"{"name":"Hello","count":2}"#
+        );
+    }
+
+    #[test]
+    fn test_write_pretty_indent() {
+        let writer = JsonWriter::default()
+            .with_options(CommonWriterOptions::default().pretty().with_indent(4));
+        assert_eq!(
+            writer.write_to_string(&test_object()).unwrap(),
+            "{\n    \"name\": \"Hello\",\n    \"count\": 2\n}"
+        );
+    }
+
+    #[test]
+    fn test_write_pretty_and_canonical() {
+        let writer = JsonWriter::default().with_options(crate::options::Pretty);
+        let mut buffer = Vec::new();
+        writer.write_canonical(&mut buffer, &test_object()).unwrap();
+        assert_eq!(buffer, br#"{"name":"Hello","count":2}"#);
+
+        let writer = JsonWriter::default();
+        let mut buffer = Vec::new();
+        writer.write_pretty(&mut buffer, &test_object()).unwrap();
+        assert!(buffer.starts_with(b"{\n  \"name\""));
+    }
+
+    #[test]
+    fn test_write_ordering() {
+        let writer = JsonWriter::default().with_options(
+            CommonWriterOptions::default()
+                .with_ordering(CollectionOrdering::custom(|lhs, rhs| rhs.cmp(lhs))),
+        );
+        assert_eq!(
+            writer
+                .write_to_string(&vec![test_object(), test_object()])
+                .unwrap(),
+            r#"[{"name":"Hello","count":2},{"name":"Hello","count":2}]"#
+        );
+
+        let writer = JsonWriter::default().with_options(
+            CommonWriterOptions::default()
+                .pretty()
+                .with_ordering(CollectionOrdering::Lexicographic),
+        );
+        assert_eq!(
+            writer.write_to_string(&test_object()).unwrap(),
+            "{\n  \"count\": 2,\n  \"name\": \"Hello\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_write_deterministic() {
+        let object = |n: u32| (0..n).map(|i| (format!("key-{i}"), i)).collect();
+        let writer: JsonWriter<std::collections::HashMap<String, u32>> = JsonWriter::default()
+            .with_options(CommonWriterOptions::default().with_deterministic(true));
+        let first = writer.write_to_string(&object(16)).unwrap();
+        assert!(first.starts_with(r#"{"key-0":0,"key-1":1,"key-10":10,"#));
+        assert_eq!(writer.write_to_string(&object(16)).unwrap(), first);
+    }
+
+    #[test]
+    fn test_write_numbers() {
+        let writer: JsonWriter<Vec<f64>> = JsonWriter::default();
+        let values = vec![1.0, 0.25, 6.02214076e23, f64::NAN];
+        assert_eq!(
+            writer.write_to_string(&values).unwrap(),
+            "[1.0,0.25,6.02214076e23,null]"
+        );
+
+        let writer = writer.with_options(
+            CommonWriterOptions::default()
+                .with_numbers(NumberOptions::default().with_precision(Some(2))),
+        );
+        assert_eq!(
+            writer.write_to_string(&values).unwrap(),
+            "[1.00,0.25,6.02e23,null]"
+        );
+    }
+
+    #[test]
+    fn test_write_canonical_form() {
+        let value: Value = serde_json::from_str(
+            r#"{
+                "numbers": [1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+                "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+                "literals": [null, true, false],
+                "\u00e9": 1,
+                "\ud83d\ude00": 2,
+                "\ufb33": 3
+            }"#,
+        )
+        .unwrap();
+        let writer = JsonWriter::default().with_options(crate::options::Pretty);
+        assert_eq!(writer.canonical_form(), CANONICAL_FORM);
+        assert_eq!(
+            writer.canonical().write_to_string(&value).unwrap(),
+            concat!(
+                r#"{"literals":[null,true,false],"#,
+                r#""numbers":[1e+30,4.5,0.002,1e-27],"#,
+                r#""string":"€$\u000f\nA'B\"\\\\\"/","#,
+                "\"\u{e9}\":1,\"\u{1f600}\":2,\"\u{fb33}\":3}"
+            )
+        );
+        assert_eq!(canonical_number(333333333.3333333), "333333333.3333333");
+        assert_eq!(canonical_number(1e21), "1e+21");
+        assert_eq!(
+            canonical_number(123456789012345680000.0),
+            "123456789012345680000"
+        );
+        assert_eq!(canonical_number(-1.5e-7), "-1.5e-7");
+        assert_eq!(canonical_number(-0.0), "0");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let writer = JsonWriter::default();
+        let reader = JsonReader::default();
+        let json = writer.write_to_string(&test_object()).unwrap();
+        let object: TestObject = reader.read_from_string(json).unwrap();
+        assert_eq!(object, test_object());
+    }
+
+    #[test]
+    fn test_read_projection() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Narrow {
+            name: String,
+            tags: Vec<Option<String>>,
+        }
+
+        let reader: JsonReader<Vec<Narrow>> = JsonReader::default().with_options(
+            CommonReaderOptions::default().with_projection("/*/name;/*/tags/1".parse().unwrap()),
+        );
+        let objects = reader
+            .read_from_string(
+                r#"[{"name":"a","count":1,"wide":{"x":[1,2]},"tags":["x","y"]},
+                    {"tags":[],"name":"b","count":"not a number"}]"#,
+            )
+            .unwrap();
+        assert_eq!(
+            objects,
+            [
+                Narrow {
+                    name: "a".to_string(),
+                    tags: vec![None, Some("y".to_string())],
+                },
+                Narrow {
+                    name: "b".to_string(),
+                    tags: vec![],
+                },
+            ]
+        );
+        assert!(reader.read_from_string(r#"[{"name":"a"}] 1"#).is_err());
+    }
+
+    #[test]
+    fn test_per_call_options() {
+        let writer: JsonWriter<Vec<u32>> = JsonWriter::default();
+        let pretty = CommonWriterOptions::default().pretty().with_indent(1);
+        assert_eq!(
+            writer.write_to_string_with(&vec![1], &pretty).unwrap(),
+            "[\n 1\n]"
+        );
+        assert_eq!(writer.write_to_string(&vec![1]).unwrap(), "[1]");
+        assert!(matches!(
+            writer.write_to_string_with(&vec![1], &pretty.with_line_width(0)),
+            Err(Error::InvalidOptions { .. })
+        ));
+
+        let reader: JsonReader<Vec<Option<u32>>> = JsonReader::default();
+        let projected = CommonReaderOptions::default().with_projection("/1".parse().unwrap());
+        assert_eq!(
+            reader.read_from_string_with("[1, 2]", &projected).unwrap(),
+            [None, Some(2)]
+        );
+        assert_eq!(
+            reader.read_from_string("[1, 2]").unwrap(),
+            [Some(1), Some(2)]
+        );
+    }
+
+    #[test]
+    fn test_read_error() {
+        let reader: JsonReader<TestObject> = JsonReader::default();
+        assert!(matches!(
+            reader.read_from_string("{"),
+            Err(Error::Deserialization { format: "JSON", .. })
+        ));
+    }
+
+    #[test]
+    fn test_write_invalid_options() {
+        let writer: JsonWriter<TestObject> =
+            JsonWriter::default().with_options(CommonWriterOptions::default().with_line_width(0));
+        assert!(matches!(
+            writer.write_to_string(&test_object()),
+            Err(Error::InvalidOptions { .. })
+        ));
+    }
+
+    #[cfg(feature = "jsonschema")]
+    fn test_schema() -> JsonSchema {
+        JsonSchema::new(&serde_json::json!({
+            "type": "object",
+            "properties": { "count": { "type": "integer", "maximum": 10 } },
+            "required": ["name", "count"]
+        }))
+        .unwrap()
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn test_read_schema_violations() {
+        let reader: JsonReader<TestObject> = JsonReader::default().with_schema(test_schema());
+        assert!(reader
+            .read_from_string(r#"{"name":"Hello","count":2}"#)
+            .is_ok());
+        match reader.read_from_string(r#"{"count":20}"#) {
+            Err(Error::SchemaValidation { violations }) => {
+                assert_eq!(violations.len(), 2);
+                assert!(violations.iter().any(|v| v.instance_path() == "/count"
+                    && v.schema_path() == "/properties/count/maximum"));
+            }
+            result => panic!("expected schema violations, not {result:?}"),
+        }
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn test_read_schema_and_projection() {
+        let reader: JsonReader<Value> = JsonReader::default()
+            .with_schema(test_schema())
+            .with_options(CommonReaderOptions::default().with_projection("/name".parse().unwrap()));
+        assert_eq!(
+            reader
+                .read_from_string(r#"{"name":"Hello","count":2,"extra":true}"#)
+                .unwrap(),
+            serde_json::json!({ "name": "Hello" })
+        );
+        assert!(matches!(
+            reader.read_from_string(r#"{"name":"Hello","count":20}"#),
+            Err(Error::SchemaValidation { .. })
+        ));
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn test_write_schema_violation() {
+        let writer = JsonWriter::default().with_schema(test_schema());
+        let mut object = test_object();
+        assert!(writer.write_to_string(&object).is_ok());
+        object.count = 11;
+        assert!(matches!(
+            writer.write_to_string(&object),
+            Err(Error::SchemaValidation { .. })
+        ));
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn test_invalid_schema() {
+        assert!(matches!(
+            JsonSchema::new(&serde_json::json!({ "type": 1 })),
+            Err(Error::InvalidOptions { .. })
+        ));
+    }
+}
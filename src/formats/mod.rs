@@ -0,0 +1,413 @@
+/*!
+Provides a bridge between the traits in this crate and formats implemented using
+[serde](https://serde.rs).
+
+The trait [`SerdeFormat`] is implemented by a (usually zero-sized) type to describe a serde-based
+format; the generic [`SerdeReader`] then provides an implementation of [`ObjectReader`] for any
+type `T` that implements `DeserializeOwned`, and the generic [`SerdeWriter`] provides an
+implementation of [`ObjectWriter`] for any type `T` that implements `Serialize`. Any errors
+reported by the format's serializer or deserializer are mapped into [`Error`].
+
+# Example
+
+```rust
+use objio::{HasOptions, ObjectReader, ObjectWriter};
+use objio::formats::{SerdeFormat, SerdeReader, SerdeWriter};
+use objio::options::CommonWriterOptions;
+use objio::Error;
+use serde::{de::DeserializeOwned, Serialize};
+use std::io::{Read, Write};
+
+#[derive(Debug, Default)]
+struct MyJson;
+
+impl SerdeFormat for MyJson {
+    const NAME: &'static str = "JSON";
+
+    fn from_reader<T, R>(r: &mut R) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        serde_json::from_reader(r).map_err(|e| Error::deserialization(Self::NAME, e))
+    }
+
+    fn to_writer<T, W>(w: &mut W, value: &T, options: &CommonWriterOptions) -> Result<(), Error>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        if options.is_pretty() {
+            serde_json::to_writer_pretty(w, value)
+        } else {
+            serde_json::to_writer(w, value)
+        }
+        .map_err(|e| Error::serialization(Self::NAME, e))
+    }
+}
+
+let reader: SerdeReader<MyJson> = SerdeReader::default();
+let lines: Vec<String> = reader.read_from_string(r#"["one", "two"]"#).unwrap();
+
+let writer: SerdeWriter<MyJson> = SerdeWriter::default();
+assert_eq!(writer.write_to_string(&lines).unwrap(), r#"["one","two"]"#);
+```
+ */
+
+use crate::capabilities::FormatCapabilities;
+use crate::error::Error;
+use crate::media_type::{HasMediaType, APPLICATION_OCTET_STREAM};
+use crate::options::{Buffering, CommonWriterOptions, ValidateOptions};
+use crate::{HasOptions, ObjectPrinter, ObjectReader, ObjectWriter, ObjectWriterWith};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "cbor")]
+pub mod cbor;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+
+#[cfg(feature = "ron")]
+pub mod ron;
+
+#[cfg(feature = "toml")]
+pub mod toml;
+
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// This trait is implemented by types that describe a serde-based format which can be plugged in
+/// to [`SerdeReader`] and [`SerdeWriter`].
+///
+pub trait SerdeFormat {
+    ///
+    /// A short, human-readable, name for this format; used to identify the format in errors.
+    ///
+    const NAME: &'static str;
+
+    ///
+    /// The IANA media type of content in this format, used for `Content-Type` headers; the
+    /// default is `application/octet-stream`.
+    ///
+    const MEDIA_TYPE: &'static str = APPLICATION_OCTET_STREAM;
+
+    ///
+    /// `true` if content in this format is binary; the default is `false`.
+    ///
+    const IS_BINARY: bool = false;
+
+    ///
+    /// `true` if this format reads and writes content incrementally, rather than buffering it
+    /// entirely in memory; the default is `true`.
+    ///
+    const SUPPORTS_STREAMING: bool = true;
+
+    ///
+    /// `true` if this format can represent every value of the serde data model; the default is
+    /// `true`.
+    ///
+    const IS_LOSSLESS: bool = true;
+
+    ///
+    /// Deserialize an instance of `T` from the provided implementation of `Read`. Any error
+    /// reported by the format's deserializer **should** be returned as
+    /// [`Error::Deserialization`].
+    ///
+    fn from_reader<T, R>(r: &mut R) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        R: Read;
+
+    ///
+    /// Serialize `value` to the provided implementation of `Write`, honoring the common writer
+    /// `options` where the format allows. Any error reported by the format's serializer
+    /// **should** be returned as [`Error::Serialization`].
+    ///
+    fn to_writer<T, W>(w: &mut W, value: &T, options: &CommonWriterOptions) -> Result<(), Error>
+    where
+        T: Serialize,
+        W: Write;
+}
+
+///
+/// A generic implementation of [`ObjectReader`] for any type implementing `DeserializeOwned`,
+/// using the serde format `F`.
+///
+pub struct SerdeReader<F: SerdeFormat> {
+    format: PhantomData<F>,
+}
+
+///
+/// A generic implementation of [`ObjectWriter`] for any type implementing `Serialize`, using the
+/// serde format `F`.
+///
+pub struct SerdeWriter<F: SerdeFormat> {
+    options: CommonWriterOptions,
+    format: PhantomData<F>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<F: SerdeFormat> Debug for SerdeReader<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerdeReader")
+            .field("format", &F::NAME)
+            .finish()
+    }
+}
+
+impl<F: SerdeFormat> Default for SerdeReader<F> {
+    fn default() -> Self {
+        Self {
+            format: PhantomData,
+        }
+    }
+}
+
+impl<F: SerdeFormat, T: DeserializeOwned> ObjectReader<T> for SerdeReader<F> {
+    type Error = Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        F::from_reader(r)
+    }
+}
+
+impl<F: SerdeFormat> SerdeReader<F> {
+    ///
+    /// Returns the name of the format used by this reader.
+    ///
+    pub fn format_name(&self) -> &'static str {
+        F::NAME
+    }
+}
+
+impl<F: SerdeFormat> HasMediaType for SerdeReader<F> {
+    fn media_type(&self) -> &str {
+        F::MEDIA_TYPE
+    }
+}
+
+impl<F: SerdeFormat> FormatCapabilities for SerdeReader<F> {
+    fn is_binary(&self) -> bool {
+        F::IS_BINARY
+    }
+
+    fn supports_streaming(&self) -> bool {
+        F::SUPPORTS_STREAMING
+    }
+
+    fn is_lossless(&self) -> bool {
+        F::IS_LOSSLESS
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<F: SerdeFormat> Debug for SerdeWriter<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerdeWriter")
+            .field("options", &self.options)
+            .field("format", &F::NAME)
+            .finish()
+    }
+}
+
+impl<F: SerdeFormat> Default for SerdeWriter<F> {
+    fn default() -> Self {
+        Self {
+            options: Default::default(),
+            format: PhantomData,
+        }
+    }
+}
+
+impl<F: SerdeFormat> HasOptions<CommonWriterOptions> for SerdeWriter<F> {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl<F: SerdeFormat, T: Serialize> ObjectWriter<T> for SerdeWriter<F> {
+    type Error = Error;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write_with(w, object, &self.options)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.options.validate()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.options.buffering()
+    }
+}
+
+impl<F: SerdeFormat, T: Serialize> ObjectWriterWith<T, CommonWriterOptions> for SerdeWriter<F> {
+    fn write_with<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        options: &CommonWriterOptions,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        F::to_writer(w, object, options)
+    }
+
+    fn validate_options_with(&self, options: &CommonWriterOptions) -> Result<(), Self::Error> {
+        options.validate()
+    }
+}
+
+impl<F: SerdeFormat, T: Serialize> ObjectPrinter<T> for SerdeWriter<F> {
+    fn write_pretty<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        F::to_writer(w, object, &self.options.clone().pretty())
+    }
+
+    fn write_canonical<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        F::to_writer(w, object, &self.options.clone().compact())
+    }
+}
+
+impl<F: SerdeFormat> SerdeWriter<F> {
+    ///
+    /// Returns the name of the format used by this writer.
+    ///
+    pub fn format_name(&self) -> &'static str {
+        F::NAME
+    }
+}
+
+impl<F: SerdeFormat> HasMediaType for SerdeWriter<F> {
+    fn media_type(&self) -> &str {
+        F::MEDIA_TYPE
+    }
+}
+
+impl<F: SerdeFormat> FormatCapabilities for SerdeWriter<F> {
+    fn is_binary(&self) -> bool {
+        F::IS_BINARY
+    }
+
+    fn supports_streaming(&self) -> bool {
+        F::SUPPORTS_STREAMING
+    }
+
+    fn is_lossless(&self) -> bool {
+        F::IS_LOSSLESS
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Default)]
+    struct TestJson;
+
+    impl SerdeFormat for TestJson {
+        const NAME: &'static str = "JSON";
+
+        fn from_reader<T, R>(r: &mut R) -> Result<T, Error>
+        where
+            T: DeserializeOwned,
+            R: Read,
+        {
+            serde_json::from_reader(r).map_err(|e| Error::deserialization(Self::NAME, e))
+        }
+
+        fn to_writer<T, W>(
+            w: &mut W,
+            value: &T,
+            _options: &CommonWriterOptions,
+        ) -> Result<(), Error>
+        where
+            T: Serialize,
+            W: Write,
+        {
+            serde_json::to_writer(w, value).map_err(|e| Error::serialization(Self::NAME, e))
+        }
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct TestObject {
+        value: String,
+    }
+
+    #[test]
+    fn test_serde_reader() {
+        let reader: SerdeReader<TestJson> = SerdeReader::default();
+        let object: TestObject = reader.read_from_string(r#"{"value": "Hello"}"#).unwrap();
+
+        assert_eq!(
+            object,
+            TestObject {
+                value: "Hello".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_serde_reader_error() {
+        let reader: SerdeReader<TestJson> = SerdeReader::default();
+        let result: Result<TestObject, Error> = reader.read_from_string(r#"{"value": "#);
+
+        assert!(matches!(
+            result,
+            Err(Error::Deserialization { format: "JSON", .. })
+        ));
+    }
+
+    #[test]
+    fn test_serde_writer() {
+        let writer: SerdeWriter<TestJson> = SerdeWriter::default();
+        let object = TestObject {
+            value: "Hello".to_string(),
+        };
+
+        assert_eq!(
+            writer.write_to_string(&object).unwrap(),
+            r#"{"value":"Hello"}"#.to_string()
+        );
+    }
+}
@@ -0,0 +1,263 @@
+/*!
+Provides a reader and writer for [RON](https://github.com/ron-rs/ron), the Rusty Object Notation,
+using the `ron` crate, for any type implementing the corresponding serde traits.
+
+The writer honors the pretty-printing and indentation settings in [`CommonWriterOptions`].
+
+# Example
+
+```rust
+use objio::{HasOptions, ObjectReader, ObjectWriter};
+use objio::formats::ron::{RonReader, RonWriter};
+use objio::options::CommonWriterOptions;
+use std::collections::BTreeMap;
+
+let reader: RonReader<BTreeMap<String, u32>> = RonReader::default();
+let map = reader.read_from_string(r#"{"a": 1}"#).unwrap();
+
+let writer = RonWriter::default();
+assert_eq!(writer.write_to_string(&map).unwrap(), "{\"a\":1}");
+
+let writer = RonWriter::default().with_options(CommonWriterOptions::default().pretty());
+assert_eq!(writer.write_to_string(&map).unwrap(), "{\n  \"a\": 1,\n}");
+```
+ */
+
+use crate::error::Error;
+use crate::formats::SerdeFormat;
+use crate::options::{Buffering, CommonWriterOptions, ValidateOptions};
+use crate::{HasOptions, ObjectPrinter, ObjectReader, ObjectWriter, ObjectWriterWith};
+use ::ron::ser::PrettyConfig;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The RON format, usable with the generic [`SerdeReader`](crate::formats::SerdeReader) and
+/// [`SerdeWriter`](crate::formats::SerdeWriter) types.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ron;
+
+///
+/// A reader for RON representations of `T`.
+///
+pub struct RonReader<T: DeserializeOwned> {
+    object: PhantomData<fn() -> T>,
+}
+
+///
+/// A writer producing RON representations of `T`.
+///
+pub struct RonWriter<T: Serialize> {
+    options: CommonWriterOptions,
+    object: PhantomData<fn(&T)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl SerdeFormat for Ron {
+    const NAME: &'static str = "RON";
+    const MEDIA_TYPE: &'static str = "application/ron";
+    const SUPPORTS_STREAMING: bool = false;
+
+    fn from_reader<T, R>(r: &mut R) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        ::ron::de::from_reader(r).map_err(|e| Error::deserialization(Self::NAME, e))
+    }
+
+    fn to_writer<T, W>(w: &mut W, value: &T, options: &CommonWriterOptions) -> Result<(), Error>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        let serialized = if options.is_pretty() {
+            let config = PrettyConfig::new().indentor(" ".repeat(options.indent()));
+            ::ron::ser::to_string_pretty(value, config)
+        } else {
+            ::ron::ser::to_string(value)
+        }
+        .map_err(|e| Error::serialization(Self::NAME, e))?;
+        w.write_all(serialized.as_bytes())?;
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: DeserializeOwned> Debug for RonReader<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RonReader").finish()
+    }
+}
+
+impl<T: DeserializeOwned> Default for RonReader<T> {
+    fn default() -> Self {
+        Self {
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> ObjectReader<T> for RonReader<T> {
+    type Error = Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        Ron::from_reader(r)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: Serialize> Debug for RonWriter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RonWriter")
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<T: Serialize> Default for RonWriter<T> {
+    fn default() -> Self {
+        Self {
+            options: Default::default(),
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> HasOptions<CommonWriterOptions> for RonWriter<T> {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl<T: Serialize> ObjectWriter<T> for RonWriter<T> {
+    type Error = Error;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write_with(w, object, &self.options)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.options.validate()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.options.buffering()
+    }
+}
+
+impl<T: Serialize> ObjectWriterWith<T, CommonWriterOptions> for RonWriter<T> {
+    fn write_with<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        options: &CommonWriterOptions,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Ron::to_writer(w, object, options)
+    }
+
+    fn validate_options_with(&self, options: &CommonWriterOptions) -> Result<(), Self::Error> {
+        options.validate()
+    }
+}
+
+impl<T: Serialize> ObjectPrinter<T> for RonWriter<T> {
+    fn write_pretty<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Ron::to_writer(w, object, &self.options.clone().pretty())
+    }
+
+    fn write_canonical<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Ron::to_writer(w, object, &self.options.clone().compact())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct TestObject {
+        name: String,
+        count: u32,
+    }
+
+    fn test_object() -> TestObject {
+        TestObject {
+            name: "Hello".to_string(),
+            count: 2,
+        }
+    }
+
+    #[test]
+    fn test_write_compact() {
+        let writer = RonWriter::default();
+        assert_eq!(
+            writer.write_to_string(&test_object()).unwrap(),
+            r#"(name:"Hello",count:2)"#
+        );
+    }
+
+    #[test]
+    fn test_write_pretty_indent() {
+        let writer = RonWriter::default()
+            .with_options(CommonWriterOptions::default().pretty().with_indent(4));
+        assert_eq!(
+            writer.write_to_string(&test_object()).unwrap(),
+            "(\n    name: \"Hello\",\n    count: 2,\n)"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let writer = RonWriter::default();
+        let reader = RonReader::default();
+        let json = writer.write_to_string(&test_object()).unwrap();
+        let object: TestObject = reader.read_from_string(json).unwrap();
+        assert_eq!(object, test_object());
+    }
+
+    #[test]
+    fn test_read_error() {
+        let reader: RonReader<TestObject> = RonReader::default();
+        assert!(matches!(
+            reader.read_from_string("(name:"),
+            Err(Error::Deserialization { format: "RON", .. })
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+/*!
+Provides a reader and writer for [TOML](https://toml.io), using the `toml` crate, for any type
+implementing the corresponding serde traits.
+
+The writer honors the pretty-printing setting in [`CommonWriterOptions`]; note that the `toml`
+crate does not support a configurable indentation width.
+
+# Example
+
+```rust
+use objio::{HasOptions, ObjectReader, ObjectWriter};
+use objio::formats::toml::{TomlReader, TomlWriter};
+use objio::options::CommonWriterOptions;
+use std::collections::BTreeMap;
+
+let reader: TomlReader<BTreeMap<String, Vec<u32>>> = TomlReader::default();
+let map = reader.read_from_string("a = [1, 2]").unwrap();
+
+let writer = TomlWriter::default();
+assert_eq!(writer.write_to_string(&map).unwrap(), "a = [1, 2]\n");
+
+let writer = TomlWriter::default().with_options(CommonWriterOptions::default().pretty());
+assert_eq!(writer.write_to_string(&map).unwrap(), "a = [\n    1,\n    2,\n]\n");
+```
+ */
+
+use crate::error::Error;
+use crate::formats::SerdeFormat;
+use crate::options::{Buffering, CommonWriterOptions, ValidateOptions};
+use crate::{HasOptions, ObjectPrinter, ObjectReader, ObjectWriter, ObjectWriterWith};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The TOML format, usable with the generic [`SerdeReader`](crate::formats::SerdeReader) and
+/// [`SerdeWriter`](crate::formats::SerdeWriter) types.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Toml;
+
+///
+/// A reader for TOML representations of `T`.
+///
+pub struct TomlReader<T: DeserializeOwned> {
+    object: PhantomData<fn() -> T>,
+}
+
+///
+/// A writer producing TOML representations of `T`.
+///
+pub struct TomlWriter<T: Serialize> {
+    options: CommonWriterOptions,
+    object: PhantomData<fn(&T)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl SerdeFormat for Toml {
+    const NAME: &'static str = "TOML";
+    const MEDIA_TYPE: &'static str = "application/toml";
+    const SUPPORTS_STREAMING: bool = false;
+    const IS_LOSSLESS: bool = false;
+
+    fn from_reader<T, R>(r: &mut R) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        let mut buffer = String::new();
+        r.read_to_string(&mut buffer)?;
+        ::toml::from_str(&buffer).map_err(|e| Error::deserialization(Self::NAME, e))
+    }
+
+    fn to_writer<T, W>(w: &mut W, value: &T, options: &CommonWriterOptions) -> Result<(), Error>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        let serialized = if options.is_pretty() {
+            ::toml::to_string_pretty(value)
+        } else {
+            ::toml::to_string(value)
+        }
+        .map_err(|e| Error::serialization(Self::NAME, e))?;
+        w.write_all(serialized.as_bytes())?;
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: DeserializeOwned> Debug for TomlReader<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TomlReader").finish()
+    }
+}
+
+impl<T: DeserializeOwned> Default for TomlReader<T> {
+    fn default() -> Self {
+        Self {
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> ObjectReader<T> for TomlReader<T> {
+    type Error = Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        Toml::from_reader(r)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: Serialize> Debug for TomlWriter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TomlWriter")
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<T: Serialize> Default for TomlWriter<T> {
+    fn default() -> Self {
+        Self {
+            options: Default::default(),
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> HasOptions<CommonWriterOptions> for TomlWriter<T> {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl<T: Serialize> ObjectWriter<T> for TomlWriter<T> {
+    type Error = Error;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write_with(w, object, &self.options)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.options.validate()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.options.buffering()
+    }
+}
+
+impl<T: Serialize> ObjectWriterWith<T, CommonWriterOptions> for TomlWriter<T> {
+    fn write_with<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        options: &CommonWriterOptions,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Toml::to_writer(w, object, options)
+    }
+
+    fn validate_options_with(&self, options: &CommonWriterOptions) -> Result<(), Self::Error> {
+        options.validate()
+    }
+}
+
+impl<T: Serialize> ObjectPrinter<T> for TomlWriter<T> {
+    fn write_pretty<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Toml::to_writer(w, object, &self.options.clone().pretty())
+    }
+
+    fn write_canonical<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Toml::to_writer(w, object, &self.options.clone().compact())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct TestObject {
+        name: String,
+        count: u32,
+    }
+
+    fn test_object() -> TestObject {
+        TestObject {
+            name: "Hello".to_string(),
+            count: 2,
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let writer = TomlWriter::default();
+        assert_eq!(
+            writer.write_to_string(&test_object()).unwrap(),
+            "name = \"Hello\"\ncount = 2\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let writer = TomlWriter::default();
+        let reader = TomlReader::default();
+        let toml = writer.write_to_string(&test_object()).unwrap();
+        let object: TestObject = reader.read_from_string(toml).unwrap();
+        assert_eq!(object, test_object());
+    }
+
+    #[test]
+    fn test_read_error() {
+        let reader: TomlReader<TestObject> = TomlReader::default();
+        assert!(matches!(
+            reader.read_from_string("name = "),
+            Err(Error::Deserialization { format: "TOML", .. })
+        ));
+    }
+}
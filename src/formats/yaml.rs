@@ -0,0 +1,279 @@
+/*!
+Provides a reader and writer for [YAML](https://yaml.org), using the `serde_yaml` crate, for any
+type implementing the corresponding serde traits.
+
+YAML output is always in block style, so the settings in [`CommonWriterOptions`] have no effect
+on the output of these types.
+
+A single YAML stream may contain multiple documents separated by the `---` marker; the methods
+[`YamlReader::read_documents`] and [`YamlWriter::write_documents`] provide support for such
+streams in addition to the single document methods of the object traits.
+
+# Example
+
+```rust
+use objio::{ObjectReader, ObjectWriter};
+use objio::formats::yaml::{YamlReader, YamlWriter};
+use std::collections::BTreeMap;
+
+let reader: YamlReader<BTreeMap<String, u32>> = YamlReader::default();
+let maps = reader.read_documents(&mut "a: 1\n---\nb: 2\n".as_bytes()).unwrap();
+assert_eq!(maps.len(), 2);
+
+let writer = YamlWriter::default();
+assert_eq!(writer.write_to_string(&maps[0]).unwrap(), "a: 1\n");
+
+let mut buffer: Vec<u8> = Vec::new();
+writer.write_documents(&mut buffer, &maps).unwrap();
+assert_eq!(String::from_utf8(buffer).unwrap(), "a: 1\n---\nb: 2\n");
+```
+ */
+
+use crate::error::Error;
+use crate::formats::SerdeFormat;
+use crate::options::{Buffering, CommonWriterOptions, ValidateOptions};
+use crate::{HasOptions, ObjectReader, ObjectWriter, ObjectWriterWith};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The YAML format, usable with the generic [`SerdeReader`](crate::formats::SerdeReader) and
+/// [`SerdeWriter`](crate::formats::SerdeWriter) types.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Yaml;
+
+///
+/// A reader for YAML representations of `T`.
+///
+pub struct YamlReader<T: DeserializeOwned> {
+    object: PhantomData<fn() -> T>,
+}
+
+///
+/// A writer producing YAML representations of `T`.
+///
+pub struct YamlWriter<T: Serialize> {
+    options: CommonWriterOptions,
+    object: PhantomData<fn(&T)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl SerdeFormat for Yaml {
+    const NAME: &'static str = "YAML";
+    const MEDIA_TYPE: &'static str = "application/yaml";
+
+    fn from_reader<T, R>(r: &mut R) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        serde_yaml::from_reader(r).map_err(|e| Error::deserialization(Self::NAME, e))
+    }
+
+    fn to_writer<T, W>(w: &mut W, value: &T, _: &CommonWriterOptions) -> Result<(), Error>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        serde_yaml::to_writer(w, value).map_err(|e| Error::serialization(Self::NAME, e))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: DeserializeOwned> Debug for YamlReader<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("YamlReader").finish()
+    }
+}
+
+impl<T: DeserializeOwned> Default for YamlReader<T> {
+    fn default() -> Self {
+        Self {
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> ObjectReader<T> for YamlReader<T> {
+    type Error = Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        Yaml::from_reader(r)
+    }
+}
+
+impl<T: DeserializeOwned> YamlReader<T> {
+    ///
+    /// Read all of the documents in the provided YAML stream, returning one instance of `T` for
+    /// each document.
+    ///
+    pub fn read_documents<R>(&self, r: &mut R) -> Result<Vec<T>, Error>
+    where
+        R: Read,
+    {
+        serde_yaml::Deserializer::from_reader(r)
+            .map(|document| {
+                T::deserialize(document).map_err(|e| Error::deserialization(Yaml::NAME, e))
+            })
+            .collect()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T: Serialize> Debug for YamlWriter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("YamlWriter")
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<T: Serialize> Default for YamlWriter<T> {
+    fn default() -> Self {
+        Self {
+            options: Default::default(),
+            object: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> HasOptions<CommonWriterOptions> for YamlWriter<T> {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl<T: Serialize> ObjectWriter<T> for YamlWriter<T> {
+    type Error = Error;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write_with(w, object, &self.options)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.options.validate()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.options.buffering()
+    }
+}
+
+impl<T: Serialize> ObjectWriterWith<T, CommonWriterOptions> for YamlWriter<T> {
+    fn write_with<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        options: &CommonWriterOptions,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Yaml::to_writer(w, object, options)
+    }
+
+    fn validate_options_with(&self, options: &CommonWriterOptions) -> Result<(), Self::Error> {
+        options.validate()
+    }
+}
+
+impl<T: Serialize> YamlWriter<T> {
+    ///
+    /// Write each object in `objects` as a separate document in a single YAML stream, with each
+    /// document after the first preceded by the `---` document marker.
+    ///
+    pub fn write_documents<W>(&self, w: &mut W, objects: &[T]) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.validate_options()?;
+        for (i, object) in objects.iter().enumerate() {
+            if i > 0 {
+                w.write_all(b"---\n")?;
+            }
+            self.write(w, object)?;
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct TestObject {
+        name: String,
+        count: u32,
+    }
+
+    fn test_object() -> TestObject {
+        TestObject {
+            name: "Hello".to_string(),
+            count: 2,
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let writer = YamlWriter::default();
+        assert_eq!(
+            writer.write_to_string(&test_object()).unwrap(),
+            "name: Hello\ncount: 2\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let writer = YamlWriter::default();
+        let reader = YamlReader::default();
+        let yaml = writer.write_to_string(&test_object()).unwrap();
+        let object: TestObject = reader.read_from_string(yaml).unwrap();
+        assert_eq!(object, test_object());
+    }
+
+    #[test]
+    fn test_read_documents() {
+        let reader: YamlReader<TestObject> = YamlReader::default();
+        let objects = reader
+            .read_documents(&mut "name: Hello\ncount: 2\n---\nname: Hello\ncount: 2\n".as_bytes())
+            .unwrap();
+        assert_eq!(objects, vec![test_object(), test_object()]);
+    }
+
+    #[test]
+    fn test_read_error() {
+        let reader: YamlReader<TestObject> = YamlReader::default();
+        assert!(matches!(
+            reader.read_from_string("name: [Hello"),
+            Err(Error::Deserialization { format: "YAML", .. })
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+/*!
+Provides simple length-prefixed framing so that multiple serialized objects can be written to, and
+read from, a single stream.
+
+Each frame consists of a 4-byte, big-endian, length followed by that many bytes of content. This
+is most useful with binary formats, which commonly have no document separator of their own.
+
+# Example
+
+```rust
+use objio::{framing, ObjectReader, ObjectWriter};
+# use std::io::{Read, Write};
+# #[derive(Debug, Default)]
+# struct BytesIo;
+# impl ObjectWriter<Vec<u8>> for BytesIo {
+#     type Error = std::io::Error;
+#     fn write<W: Write>(&self, w: &mut W, object: &Vec<u8>) -> Result<(), Self::Error> {
+#         w.write_all(object)
+#     }
+# }
+# impl ObjectReader<Vec<u8>> for BytesIo {
+#     type Error = std::io::Error;
+#     fn read<R: Read>(&self, r: &mut R) -> Result<Vec<u8>, Self::Error> {
+#         let mut buffer = Vec::new();
+#         r.read_to_end(&mut buffer)?;
+#         Ok(buffer)
+#     }
+# }
+
+let mut stream: Vec<u8> = Vec::new();
+framing::write_framed(&BytesIo, &mut stream, &vec![1, 2, 3]).unwrap();
+framing::write_framed(&BytesIo, &mut stream, &vec![4]).unwrap();
+
+let mut source = stream.as_slice();
+assert_eq!(framing::read_framed(&BytesIo, &mut source).unwrap(), Some(vec![1, 2, 3]));
+assert_eq!(framing::read_framed(&BytesIo, &mut source).unwrap(), Some(vec![4]));
+assert_eq!(framing::read_framed(&BytesIo, &mut source).unwrap(), None);
+```
+ */
+
+use crate::{ObjectReader, ObjectWriter};
+use std::io::{Error as IoError, ErrorKind, Read, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The number of bytes used for the length prefix of each frame.
+///
+pub const FRAME_HEADER_LEN: usize = 4;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Write `frame` to `w`, preceded by its length.
+///
+/// This function will return an error of kind `InvalidInput` if the frame is larger than
+/// `u32::MAX` bytes.
+///
+pub fn write_frame<W>(w: &mut W, frame: &[u8]) -> Result<(), IoError>
+where
+    W: Write,
+{
+    let length = u32::try_from(frame.len()).map_err(|_| {
+        IoError::new(
+            ErrorKind::InvalidInput,
+            "frame is too large for a 32-bit length",
+        )
+    })?;
+    w.write_all(&length.to_be_bytes())?;
+    w.write_all(frame)
+}
+
+///
+/// Read the next frame from `r`, returning `None` if the stream ends cleanly at a frame boundary.
+///
+/// This function will return an error of kind `UnexpectedEof` if the stream ends part-way through
+/// a frame.
+///
+pub fn read_frame<R>(r: &mut R) -> Result<Option<Vec<u8>>, IoError>
+where
+    R: Read,
+{
+    let mut header = [0u8; FRAME_HEADER_LEN];
+    let mut filled = 0;
+    while filled < FRAME_HEADER_LEN {
+        match r.read(&mut header[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    // The length is untrusted, so the frame grows as its content is read.
+    let length = u32::from_be_bytes(header) as u64;
+    let mut frame = Vec::new();
+    let _ = r.take(length).read_to_end(&mut frame)?;
+    if (frame.len() as u64) < length {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    Ok(Some(frame))
+}
+
+///
+/// Serialize `object` using `writer` and write the result to `w` as a single frame.
+///
+pub fn write_framed<O, T, W>(writer: &O, w: &mut W, object: &T) -> Result<(), O::Error>
+where
+    O: ObjectWriter<T>,
+    W: Write,
+{
+    let frame = writer.write_to_bytes(object)?;
+    Ok(write_frame(w, &frame)?)
+}
+
+///
+/// Read the next frame from `r` and deserialize its content using `reader`, returning `None` if
+/// the stream ends cleanly at a frame boundary.
+///
+pub fn read_framed<O, T, R>(reader: &O, r: &mut R) -> Result<Option<T>, O::Error>
+where
+    O: ObjectReader<T>,
+    R: Read,
+{
+    match read_frame(r)? {
+        None => Ok(None),
+        Some(frame) => reader.read_from_bytes(frame).map(Some),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let mut stream: Vec<u8> = Vec::new();
+        write_frame(&mut stream, b"Hello").unwrap();
+        write_frame(&mut stream, b"").unwrap();
+        assert_eq!(stream.len(), (FRAME_HEADER_LEN * 2) + 5);
+
+        let mut source = stream.as_slice();
+        assert_eq!(read_frame(&mut source).unwrap(), Some(b"Hello".to_vec()));
+        assert_eq!(read_frame(&mut source).unwrap(), Some(Vec::new()));
+        assert_eq!(read_frame(&mut source).unwrap(), None);
+    }
+
+    #[test]
+    fn test_truncated_frame() {
+        let mut stream: Vec<u8> = Vec::new();
+        write_frame(&mut stream, b"Hello").unwrap();
+        stream.truncate(stream.len() - 1);
+
+        let mut source = stream.as_slice();
+        let error = read_frame(&mut source).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+        let mut source: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF, 1, 2, 3];
+        let error = read_frame(&mut source).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+        let mut source: &[u8] = &[0, 0];
+        let error = read_frame(&mut source).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,147 @@
+/*!
+Provides helpers, enabled by the `http` feature, that serialize an object into an
+[`http::Response`], setting the `Content-Type` header from the writer's
+[`media type`](crate::media_type::HasMediaType) and the `Content-Length` header from the length
+of the serialized content.
+
+[`to_response`] serializes the object into the response body, pre-allocated using the writer's
+size hint. Size hints are estimates and so are never used for `Content-Length` directly; when the
+body is to be streamed, [`response_builder`] measures the length with a dry run that serializes
+into a counting sink, and returns a builder with both headers set.
+
+# Example
+
+```rust
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use objio::http::to_response;
+use objio::raw::RawWriter;
+
+let response = to_response(&RawWriter, &b"\x01\x02\x03".to_vec()).unwrap();
+assert_eq!(response.headers()[CONTENT_TYPE], "application/octet-stream");
+assert_eq!(response.headers()[CONTENT_LENGTH], "3");
+assert_eq!(response.body(), &vec![1, 2, 3]);
+```
+ */
+
+use crate::media_type::HasMediaType;
+use crate::ObjectWriter;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use http::response::Builder;
+use http::Response;
+use std::io::{Error as IoError, ErrorKind, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+struct CountingSink {
+    count: u64,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Serialize `object` using `writer` into the body of a new response with the status `200 OK`.
+///
+pub fn to_response<W, T>(writer: &W, object: &T) -> Result<Response<Vec<u8>>, W::Error>
+where
+    W: ObjectWriter<T> + HasMediaType + ?Sized,
+{
+    writer.validate_options()?;
+    let mut body = Vec::with_capacity(writer.write_size_hint(object));
+    writer.write(&mut body, object)?;
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, writer.media_type())
+        .header(CONTENT_LENGTH, body.len())
+        .body(body)
+        .map_err(invalid_response)?)
+}
+
+///
+/// Returns a response builder with the `Content-Type` and `Content-Length` headers set for the
+/// serialization of `object` by `writer`, measuring the length by serializing `object` without
+/// retaining the content. The body must then be written by the same writer, with the same
+/// options, for the length to be correct.
+///
+pub fn response_builder<W, T>(writer: &W, object: &T) -> Result<Builder, W::Error>
+where
+    W: ObjectWriter<T> + HasMediaType + ?Sized,
+{
+    writer.validate_options()?;
+    let mut sink = CountingSink::default();
+    writer.write(&mut sink, object)?;
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, writer.media_type())
+        .header(CONTENT_LENGTH, sink.count))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn invalid_response(e: http::Error) -> IoError {
+    IoError::new(ErrorKind::InvalidData, e)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::RawWriter;
+
+    #[derive(Debug)]
+    struct BadMediaType;
+
+    impl ObjectWriter<String> for BadMediaType {
+        type Error = IoError;
+
+        fn write<W: Write>(&self, w: &mut W, object: &String) -> Result<(), Self::Error> {
+            w.write_all(object.as_bytes())
+        }
+    }
+
+    impl HasMediaType for BadMediaType {
+        fn media_type(&self) -> &str {
+            "text/plain\n"
+        }
+    }
+
+    #[test]
+    fn test_response_builder() {
+        let text = "streamed".to_string();
+        let response = response_builder(&RawWriter, &text)
+            .unwrap()
+            .status(http::StatusCode::CREATED)
+            .body(())
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::CREATED);
+        assert_eq!(response.headers()[CONTENT_LENGTH], "8");
+    }
+
+    #[test]
+    fn test_invalid_media_type() {
+        let error = to_response(&BadMediaType, &"text".to_string()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,208 @@
+/*!
+Provides a wrapper around an implementation of `Write` that manages indentation for writers that
+produce line-oriented, pretty-printed, output.
+
+The [`IndentWriter`] type tracks a current indentation level, changed with
+[`IndentWriter::indent`] and [`IndentWriter::dedent`], and writes the corresponding prefix at the
+start of every line. Blank lines are never indented so that output does not contain trailing
+whitespace, and each line feed written is replaced with the line ending from the
+[`CommonWriterOptions`].
+
+# Example
+
+```rust
+use objio::indent::IndentWriter;
+use std::io::Write;
+
+let mut w = IndentWriter::new(Vec::new());
+writeln!(w, "{{").unwrap();
+w.indent();
+writeln!(w, "\"a\": 1,\n\n\"b\": 2").unwrap();
+w.dedent();
+write!(w, "}}").unwrap();
+
+assert_eq!(
+    String::from_utf8(w.into_inner()).unwrap(),
+    "{\n  \"a\": 1,\n\n  \"b\": 2\n}"
+);
+```
+ */
+
+use crate::options::CommonWriterOptions;
+use crate::HasOptions;
+use std::io::Write;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A wrapper around an implementation of `Write` that indents each non-blank line.
+///
+#[derive(Debug)]
+pub struct IndentWriter<W: Write> {
+    inner: W,
+    options: CommonWriterOptions,
+    level: usize,
+    at_line_start: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<W: Write> HasOptions<CommonWriterOptions> for IndentWriter<W> {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl<W: Write> Write for IndentWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut remaining = buf;
+        while !remaining.is_empty() {
+            match remaining.iter().position(|b| *b == b'\n') {
+                Some(0) => {
+                    self.inner
+                        .write_all(self.options.line_ending().as_str().as_bytes())?;
+                    self.at_line_start = true;
+                    remaining = &remaining[1..];
+                }
+                Some(end) => {
+                    // a carriage return preceding the line feed is replaced with the line ending.
+                    let line = match remaining[end - 1] {
+                        b'\r' => &remaining[..end - 1],
+                        _ => &remaining[..end],
+                    };
+                    self.write_line_content(line)?;
+                    remaining = &remaining[end..];
+                }
+                None => {
+                    self.write_line_content(remaining)?;
+                    remaining = &[];
+                }
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> IndentWriter<W> {
+    ///
+    /// Construct a new indenting writer wrapping `inner`, with an indentation level of zero.
+    ///
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            options: Default::default(),
+            level: 0,
+            at_line_start: true,
+        }
+    }
+
+    ///
+    /// Increase the indentation level by one; this takes effect from the start of the next line.
+    ///
+    pub fn indent(&mut self) {
+        self.level += 1;
+    }
+
+    ///
+    /// Decrease the indentation level by one, if it is greater than zero; this takes effect from
+    /// the start of the next line.
+    ///
+    pub fn dedent(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    ///
+    /// Returns the current indentation level.
+    ///
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    ///
+    /// Set the current indentation level.
+    ///
+    pub fn set_level(&mut self, level: usize) {
+        self.level = level;
+    }
+
+    ///
+    /// Returns a reference to the wrapped implementation of `Write`.
+    ///
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    ///
+    /// Consume this writer, returning the wrapped implementation of `Write`.
+    ///
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    fn write_line_content(&mut self, content: &[u8]) -> std::io::Result<()> {
+        if content.is_empty() {
+            return Ok(());
+        }
+        if self.at_line_start {
+            let width = self.level * self.options.indent();
+            write!(self.inner, "{:width$}", "")?;
+            self.at_line_start = false;
+        }
+        self.inner.write_all(content)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::LineEnding;
+
+    #[test]
+    fn test_split_writes() {
+        let mut w = IndentWriter::new(Vec::new());
+        w.indent();
+        w.write_all(b"a").unwrap();
+        w.write_all(b"b\nc").unwrap();
+        w.indent();
+        w.write_all(b"\n").unwrap();
+        w.write_all(b"d").unwrap();
+        assert_eq!(w.into_inner(), b"  ab\n  c\n    d".to_vec());
+    }
+
+    #[test]
+    fn test_dedent_saturates() {
+        let mut w = IndentWriter::new(Vec::new());
+        w.dedent();
+        assert_eq!(w.level(), 0);
+    }
+
+    #[test]
+    fn test_line_ending_and_width() {
+        let mut w = IndentWriter::new(Vec::new()).with_options(
+            CommonWriterOptions::default()
+                .with_indent(4)
+                .with_line_ending(LineEnding::CrLf),
+        );
+        w.set_level(1);
+        w.write_all(b"a\r\n\nb\n").unwrap();
+        assert_eq!(w.into_inner(), b"    a\r\n\r\n    b\r\n".to_vec());
+    }
+}
@@ -0,0 +1,214 @@
+/*!
+Provides the [`Interner`] trait, for readers that share a single copy of strings that are repeated
+within and across documents, such as IRIs and datatype names, rather than allocating each
+occurrence.
+
+An interned string is an `Arc<str>`, so that it is cheap to clone and may outlive the interner.
+[`SharedInterner`] is a thread-safe interner whose clones share the same table, so that it may be
+held in the options of any number of readers, and [`NoInterner`] allocates a new string for every
+call, for readers that take an interner but where one is not wanted.
+
+# Example
+
+```rust
+use objio::interner::{Interner, SharedInterner};
+use objio::{impl_has_options, HasOptions, ObjectReader};
+use std::io::Read;
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+struct WordsOptions {
+    interner: SharedInterner,
+}
+
+#[derive(Debug, Default)]
+struct WordsReader {
+    options: WordsOptions,
+}
+
+impl_has_options!(WordsReader, WordsOptions);
+
+impl ObjectReader<Vec<Arc<str>>> for WordsReader {
+    type Error = std::io::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<Vec<Arc<str>>, Self::Error> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        Ok(text
+            .split_whitespace()
+            .map(|word| self.options.interner.intern(word))
+            .collect())
+    }
+}
+
+let interner = SharedInterner::default();
+let reader = WordsReader::default().with_options(WordsOptions { interner: interner.clone() });
+let first = reader.read_from_string("a b a").unwrap();
+let second = reader.read_from_string("b c").unwrap();
+
+assert!(Arc::ptr_eq(&first[0], &first[2]));
+assert!(Arc::ptr_eq(&first[1], &second[0]));
+assert_eq!(interner.len(), 3);
+```
+ */
+
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by types which intern strings.
+///
+pub trait Interner {
+    ///
+    /// Returns the shared copy of `s`, adding it if this is its first occurrence.
+    ///
+    fn intern(&self, s: &str) -> Arc<str>;
+}
+
+///
+/// A thread-safe interner; clones of an interner share the same table of strings.
+///
+/// Two interners are equal if they share the same table, which allows an interner to be held in
+/// an options type deriving `PartialEq`.
+///
+#[derive(Clone, Default)]
+pub struct SharedInterner {
+    strings: Arc<Mutex<HashSet<Arc<str>>>>,
+}
+
+///
+/// An interner that does not intern, allocating a new string for each call.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoInterner;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<I> Interner for &I
+where
+    I: Interner + ?Sized,
+{
+    fn intern(&self, s: &str) -> Arc<str> {
+        (**self).intern(s)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for SharedInterner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedInterner")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl PartialEq for SharedInterner {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.strings, &other.strings)
+    }
+}
+
+impl Eq for SharedInterner {}
+
+impl Interner for SharedInterner {
+    fn intern(&self, s: &str) -> Arc<str> {
+        let mut strings = self.lock();
+        match strings.get(s) {
+            Some(interned) => interned.clone(),
+            None => {
+                let interned: Arc<str> = Arc::from(s);
+                let _ = strings.insert(interned.clone());
+                interned
+            }
+        }
+    }
+}
+
+impl SharedInterner {
+    ///
+    /// Returns the number of distinct strings interned.
+    ///
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    ///
+    /// Returns `true` if no strings have been interned.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    ///
+    /// Returns `true` if `s` has been interned.
+    ///
+    pub fn contains(&self, s: &str) -> bool {
+        self.lock().contains(s)
+    }
+
+    ///
+    /// Remove all strings from the table; strings already returned remain valid, but are no
+    /// longer shared with those interned later.
+    ///
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashSet<Arc<str>>> {
+        self.strings.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Interner for NoInterner {
+    fn intern(&self, s: &str) -> Arc<str> {
+        Arc::from(s)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intern_all(interner: &dyn Interner, strings: &[&str]) -> Vec<Arc<str>> {
+        strings.iter().map(|s| interner.intern(s)).collect()
+    }
+
+    #[test]
+    fn test_shared_interner() {
+        let interner = SharedInterner::default();
+        let copy = interner.clone();
+        assert_eq!(interner, copy);
+        assert_ne!(interner, SharedInterner::default());
+
+        let strings = intern_all(&interner, &["x", "y"]);
+        assert!(Arc::ptr_eq(&strings[0], &copy.intern("x")));
+        assert!(copy.contains("y"));
+        assert_eq!(copy.len(), 2);
+
+        interner.clear();
+        assert!(copy.is_empty());
+        assert!(!Arc::ptr_eq(&strings[0], &copy.intern("x")));
+        assert_eq!(&*strings[0], "x");
+    }
+
+    #[test]
+    fn test_no_interner() {
+        let strings = intern_all(&NoInterner, &["x", "x"]);
+        assert_eq!(strings[0], strings[1]);
+        assert!(!Arc::ptr_eq(&strings[0], &strings[1]));
+    }
+}
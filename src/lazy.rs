@@ -0,0 +1,253 @@
+/*!
+Provides [`LazyObject`], a handle to an object whose content has been read but which is only
+parsed when it is first accessed.
+
+This is useful when loading many objects of which only some will be inspected; reading a file
+into a lazy object costs only the IO, and the parsing cost is paid by [`get`](LazyObject::get) for
+those objects actually used. The parsed object is kept, so later calls return it directly, but a
+parsing error is not, and so a later call will parse the content again.
+
+# Example
+
+```rust
+use objio::lazy::LazyObject;
+use objio::ObjectReader;
+use std::io::Read;
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+struct NumberReader;
+
+impl ObjectReader<u32> for NumberReader {
+    type Error = objio::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<u32, Self::Error> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        text.trim()
+            .parse()
+            .map_err(|e| objio::Error::deserialization("number", e))
+    }
+}
+
+let reader = Arc::new(NumberReader);
+let objects: Vec<_> = ["1", "two", "3"]
+    .into_iter()
+    .map(|text| LazyObject::from_bytes(reader.clone(), text))
+    .collect();
+
+assert!(!objects[0].is_parsed());
+assert_eq!(objects[2].get().unwrap(), &3);
+assert!(objects[1].get().is_err());
+assert!(objects[2].is_parsed());
+```
+ */
+
+use crate::ObjectReader;
+use std::fmt::{Debug, Formatter};
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The content of an instance of `T`, which is parsed by the reader `O` when first accessed.
+///
+pub struct LazyObject<T, O> {
+    reader: Arc<O>,
+    bytes: Vec<u8>,
+    object: OnceLock<T>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<T, O> Debug for LazyObject<T, O>
+where
+    T: Debug,
+    O: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyObject")
+            .field("reader", &self.reader)
+            .field("bytes", &self.bytes.len())
+            .field("object", &self.object.get())
+            .finish()
+    }
+}
+
+impl<T, O> LazyObject<T, O>
+where
+    O: ObjectReader<T>,
+{
+    ///
+    /// Construct a new lazy object from `bytes`, which will be parsed by `reader`.
+    ///
+    pub fn from_bytes<B>(reader: Arc<O>, bytes: B) -> Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        Self {
+            reader,
+            bytes: bytes.into(),
+            object: OnceLock::new(),
+        }
+    }
+
+    ///
+    /// Read the content of an object from the provided implementation of `Read`, without parsing
+    /// it; only IO errors are returned.
+    ///
+    pub fn read<R>(reader: Arc<O>, r: &mut R) -> Result<Self, std::io::Error>
+    where
+        R: Read,
+    {
+        let mut bytes = Vec::new();
+        let _ = r.read_to_end(&mut bytes)?;
+        Ok(Self::from_bytes(reader, bytes))
+    }
+
+    ///
+    /// Read the content of an object from the file identified by `path`, without parsing it; only
+    /// IO errors are returned.
+    ///
+    pub fn read_from_file<P>(reader: Arc<O>, path: P) -> Result<Self, std::io::Error>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::from_bytes(reader, std::fs::read(path)?))
+    }
+
+    ///
+    /// Returns the object, parsing its content if this is the first successful access.
+    ///
+    pub fn get(&self) -> Result<&T, O::Error> {
+        if let Some(object) = self.object.get() {
+            return Ok(object);
+        }
+        let object = parse(self.reader.as_ref(), &self.bytes)?;
+        Ok(self.object.get_or_init(|| object))
+    }
+
+    ///
+    /// Returns a mutable reference to the object, parsing its content if this is the first
+    /// successful access. Note that changes to the object are not reflected in its content.
+    ///
+    pub fn get_mut(&mut self) -> Result<&mut T, O::Error> {
+        if self.object.get().is_none() {
+            let object = parse(self.reader.as_ref(), &self.bytes)?;
+            let _ = self.object.set(object);
+        }
+        Ok(self.object.get_mut().expect("the object was parsed above"))
+    }
+
+    ///
+    /// Returns the object, parsing its content if it has not already been parsed.
+    ///
+    pub fn into_object(self) -> Result<T, O::Error> {
+        match self.object.into_inner() {
+            Some(object) => Ok(object),
+            None => parse(self.reader.as_ref(), &self.bytes),
+        }
+    }
+
+    ///
+    /// Returns `true` if the content has been successfully parsed.
+    ///
+    pub fn is_parsed(&self) -> bool {
+        self.object.get().is_some()
+    }
+
+    ///
+    /// Returns the unparsed content of the object.
+    ///
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    ///
+    /// Returns the unparsed content of the object, discarding any parsed object.
+    ///
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    ///
+    /// Returns the reader used to parse the content.
+    ///
+    pub fn reader(&self) -> &Arc<O> {
+        &self.reader
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn parse<T, O>(reader: &O, bytes: &[u8]) -> Result<T, O::Error>
+where
+    O: ObjectReader<T>,
+{
+    reader.validate_options()?;
+    let mut data = bytes;
+    reader.read_buffered(&mut data)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CountingReader {
+        reads: AtomicUsize,
+    }
+
+    impl ObjectReader<String> for CountingReader {
+        type Error = crate::Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+            let _ = self.reads.fetch_add(1, Ordering::SeqCst);
+            let mut text = String::new();
+            let _ = r.read_to_string(&mut text)?;
+            if text.is_empty() {
+                Err(crate::Error::deserialization("text", "empty"))
+            } else {
+                Ok(text)
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_once() {
+        let reader = Arc::new(CountingReader::default());
+        let mut object = LazyObject::read(reader.clone(), &mut "hello".as_bytes()).unwrap();
+        assert_eq!(reader.reads.load(Ordering::SeqCst), 0);
+        assert_eq!(object.get().unwrap(), "hello");
+        assert_eq!(object.get().unwrap(), "hello");
+        object.get_mut().unwrap().push('!');
+        assert_eq!(reader.reads.load(Ordering::SeqCst), 1);
+        assert_eq!(object.bytes(), b"hello");
+        assert_eq!(object.into_object().unwrap(), "hello!");
+        assert_eq!(reader.reads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_error_not_cached() {
+        let reader = Arc::new(CountingReader::default());
+        let object = LazyObject::from_bytes(reader.clone(), "");
+        assert!(object.get().is_err());
+        assert!(object.get().is_err());
+        assert!(!object.is_parsed());
+        assert_eq!(reader.reads.load(Ordering::SeqCst), 2);
+        assert!(object.into_object().is_err());
+    }
+}
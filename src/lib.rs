@@ -123,10 +123,177 @@ assert_eq!(
     dyn_drop,
 )]
 
+// Allows the derive macros, which generate paths relative to `::objio`, to be used in this crate.
+#[cfg(feature = "derive")]
+#[allow(unused_extern_crates)]
+extern crate self as objio;
+
+use std::fmt::{Debug, Display, Formatter};
 use std::fs::OpenOptions;
-use std::io::{Cursor, Read, Write};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::marker::PhantomData;
 use std::path::Path;
 
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+// Allows the `register_format!` macro to refer to the `inventory` crate used by this crate.
+#[cfg(feature = "inventory")]
+#[doc(hidden)]
+pub use inventory;
+
+mod error;
+pub use error::{BoxedError, Error, Location, SchemaViolation};
+
+#[cfg(feature = "derive")]
+pub use objio_derive::ObjectBuilder;
+
+mod fmt_io;
+use fmt_io::FormatterWriter;
+
+pub mod append_log;
+
+#[cfg(feature = "zip")]
+pub mod archive;
+
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+
+#[cfg(feature = "futures")]
+pub mod async_io;
+
+#[cfg(feature = "axum")]
+pub mod axum;
+
+pub mod budget;
+
+pub mod buffered;
+
+pub mod canonical;
+
+pub mod capabilities;
+
+pub mod channel;
+
+pub mod chunks;
+
+#[cfg(feature = "clap")]
+pub mod cli;
+
+pub mod columns;
+
+pub mod compat;
+
+pub mod conditional;
+
+#[cfg(feature = "figment")]
+pub mod config;
+
+pub mod convert;
+
+pub mod copy;
+
+pub mod deterministic;
+
+pub mod diagnostics;
+
+pub mod documents;
+
+pub mod double_buffered;
+
+pub mod duplicates;
+
+pub mod envelope;
+
+pub mod escape;
+
+pub mod footer;
+
+pub mod framing;
+
+#[cfg(feature = "http")]
+pub mod http;
+
+pub mod indent;
+
+pub mod interner;
+
+pub mod lazy;
+
+#[cfg(feature = "icu")]
+pub mod locale;
+
+pub mod media_type;
+
+#[cfg(feature = "mmap")]
+pub mod mmap;
+
+pub mod normalize;
+
+pub mod numbers;
+
+pub mod options;
+
+pub mod patch;
+
+pub mod pipeline;
+
+#[cfg(feature = "plugins")]
+pub mod plugins;
+
+pub mod prefetch;
+
+pub mod prefixes;
+
+#[cfg(feature = "pretty")]
+pub mod pretty;
+
+pub mod raw;
+
+pub mod recovery;
+
+pub mod registry;
+
+pub mod scoped;
+
+pub mod seek;
+
+pub mod session;
+
+pub mod shared;
+
+pub mod split;
+
+pub mod stream;
+
+pub mod style;
+
+pub mod text;
+
+#[cfg(feature = "chrono")]
+pub mod timestamps;
+
+pub mod trailing;
+
+pub mod transform;
+
+pub mod trivia;
+
+pub mod update;
+
+#[cfg(all(feature = "uring", target_os = "linux"))]
+pub mod uring;
+
+pub mod utf8;
+
+pub mod version;
+
+pub mod xml;
+
+#[cfg(feature = "serde")]
+pub mod formats;
+
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
@@ -163,24 +330,127 @@ pub trait HasOptions<T: Default> {
     ///     .with_options(get_options_from_config());
     /// ```
     ///
-    fn with_options(self, options: T) -> Self
+    /// As the argument may be any type that can be converted into the options type, lightweight
+    /// presets may be used in place of a complete options value.
+    ///
+    /// ```rust
+    /// # use objio::{impl_has_options, HasOptions};
+    /// # #[derive(Debug, Default)]
+    /// # struct TestOptions {
+    /// #     indent: usize,
+    /// # }
+    /// # #[derive(Debug, Default)]
+    /// # struct TestWriter {
+    /// #     options: TestOptions,
+    /// # }
+    /// # impl_has_options!(TestWriter, TestOptions);
+    /// struct Pretty;
+    ///
+    /// impl From<Pretty> for TestOptions {
+    ///     fn from(_: Pretty) -> Self {
+    ///         Self { indent: 2 }
+    ///     }
+    /// }
+    ///
+    /// let writer = TestWriter::default().with_options(Pretty);
+    ///
+    /// assert_eq!(writer.options().indent, 2);
+    /// ```
+    ///
+    fn with_options<O>(self, options: O) -> Self
     where
         Self: Sized,
+        O: Into<T>,
     {
         let mut self_mut = self;
-        self_mut.set_options(options);
+        self_mut.set_options(options.into());
         self_mut
     }
 
     ///
     /// Set the current options value to `options`.
     ///
-    fn set_options(&mut self, options: T);
+    fn set_options(&mut self, options: T) {
+        *self.options_mut() = options;
+    }
+
+    ///
+    /// Set the current options value to a value convertible into the options type, such as a
+    /// preset.
+    ///
+    fn set_options_from<O>(&mut self, options: O)
+    where
+        Self: Sized,
+        O: Into<T>,
+    {
+        self.set_options(options.into());
+    }
 
     ///
     /// Returns a reference to the current options.
     ///
     fn options(&self) -> &T;
+
+    ///
+    /// Returns a mutable reference to the current options, allowing individual options to be
+    /// changed after construction.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use objio::{impl_has_options, HasOptions};
+    /// #[derive(Debug, Default)]
+    /// struct TestOptions {
+    ///     indent: usize,
+    /// }
+    ///
+    /// #[derive(Debug, Default)]
+    /// struct TestWriter {
+    ///     options: TestOptions,
+    /// }
+    ///
+    /// impl_has_options!(TestWriter, TestOptions);
+    ///
+    /// let mut writer = TestWriter::default();
+    /// writer.options_mut().indent += 2;
+    ///
+    /// assert_eq!(writer.options().indent, 2);
+    /// ```
+    ///
+    fn options_mut(&mut self) -> &mut T;
+
+    ///
+    /// A builder-like function that replaces the inheritable options with those of `parent`, the
+    /// options of a writer, or reader, delegating to this one; see
+    /// [`InheritOptions`](options::InheritOptions).
+    ///
+    fn with_inherited_options<P>(self, parent: &P) -> Self
+    where
+        Self: Sized,
+        T: options::InheritOptions<P>,
+        P: ?Sized,
+    {
+        let mut self_mut = self;
+        self_mut.options_mut().inherit(parent);
+        self_mut
+    }
+
+    ///
+    /// A builder-like function that replaces the current options with the default set for the
+    /// current thread by [`scoped::with_default_options`], if any; otherwise the options are
+    /// unchanged. This allows code without access to the caller's options, such as an
+    /// implementation of `Display`, to honor them.
+    ///
+    fn with_scoped_options(self) -> Self
+    where
+        Self: Sized,
+        T: Clone + 'static,
+    {
+        match scoped::default_options::<T>() {
+            Some(options) => self.with_options(options),
+            None => self,
+        }
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -203,6 +473,55 @@ pub trait ObjectReader<T> {
     where
         R: Read;
 
+    ///
+    /// Read an instance of `T` from the provided implementation of `BufRead`. The default
+    /// implementation calls `read`.
+    ///
+    /// Readers that need `read_line` or `fill_buf` should override this method, and implement
+    /// `read` by wrapping the source in a `BufReader` and calling this method; a caller that
+    /// already has a buffered source, and the convenience methods below, then avoid buffering
+    /// the content twice.
+    ///
+    fn read_buffered<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: BufRead,
+    {
+        self.read(r)
+    }
+
+    ///
+    /// Check that the options for this reader, if any, are consistent. This is called by the
+    /// convenience methods below before any work begins; the default implementation does nothing.
+    ///
+    /// Implementations whose options implement [`ValidateOptions`](options::ValidateOptions)
+    /// can return any violation via the conversion from `Error` into `io::Error`, which is
+    /// reversed by the conversion from `io::Error` into `Error`.
+    ///
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    ///
+    /// Read an instance of `T` from the provided implementation of `Read`, returning `None` if
+    /// the source is empty or contains only whitespace.
+    ///
+    /// This is intended for formats where an empty source is a legal absent value; the source
+    /// is read into memory before it is checked.
+    ///
+    fn try_read<R>(&self, r: &mut R) -> Result<Option<T>, Self::Error>
+    where
+        R: Read,
+    {
+        self.validate_options()?;
+        let mut buffer = Vec::new();
+        let _ = r.read_to_end(&mut buffer)?;
+        if buffer.iter().all(u8::is_ascii_whitespace) {
+            Ok(None)
+        } else {
+            self.read_buffered(&mut buffer.as_slice()).map(Some)
+        }
+    }
+
     ///
     /// Read an instance of `T` from the provided string.
     ///
@@ -210,70 +529,854 @@ pub trait ObjectReader<T> {
     where
         S: AsRef<str>,
     {
+        self.validate_options()?;
         let mut data = string.as_ref().as_bytes();
-        self.read(&mut data)
+        self.read_buffered(&mut data)
+    }
+
+    ///
+    /// Read an instance of `T` from the provided bytes; this is most useful for binary formats.
+    ///
+    fn read_from_bytes<B>(&self, bytes: B) -> Result<T, Self::Error>
+    where
+        B: AsRef<[u8]>,
+    {
+        self.validate_options()?;
+        let mut data = bytes.as_ref();
+        self.read_buffered(&mut data)
+    }
+
+    ///
+    /// Read an instance of `T` from the file identified by `path`.
+    ///
+    /// This method will return an IO error if the path is invalid, or file does not exist. With the
+    /// `zip` feature enabled the path may also name an entry within a ZIP archive, as described
+    /// by `archive::ArchivePath`.
+    ///
+    fn read_from_file<P>(&self, path: P) -> Result<T, Self::Error>
+    where
+        P: AsRef<Path>,
+    {
+        #[cfg(feature = "zip")]
+        if let Some(archive_path) = archive::ArchivePath::detect(path.as_ref()) {
+            return archive_path.read_with(self);
+        }
+        self.validate_options()?;
+        let file = OpenOptions::new().read(true).open(path.as_ref())?;
+        self.read_buffered(&mut BufReader::new(file))
+    }
+
+    ///
+    /// Read an instance of `T` from `source`, which may be a path, string, bytes, the standard
+    /// input, or a URL; this allows an application to accept any of these, for example from a
+    /// command-line argument parsed as a [`Source`](copy::Source), without dispatching on each.
+    ///
+    /// Paths, and `file:` URLs, are read as for `read_from_file`. This method will return an IO
+    /// error of kind `Unsupported` for any other URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use objio::copy::Source;
+    /// use objio::raw::RawReader;
+    /// use objio::ObjectReader;
+    ///
+    /// let source = Source::from("Hello, world");
+    /// let text: String = RawReader.read_from(&source).unwrap();
+    /// assert_eq!(text, "Hello, world");
+    ///
+    /// let source: Source = "https://example.com/data.txt".parse().unwrap();
+    /// assert!(ObjectReader::<String>::read_from(&RawReader, &source).is_err());
+    /// ```
+    ///
+    fn read_from(&self, source: &copy::Source) -> Result<T, Self::Error> {
+        match source {
+            copy::Source::Path(path) => self.read_from_file(path),
+            copy::Source::String(s) => self.read_from_string(s),
+            copy::Source::Bytes(bytes) => self.read_from_bytes(bytes),
+            copy::Source::Stdin => {
+                self.validate_options()?;
+                self.read_buffered(&mut std::io::stdin().lock())
+            }
+            copy::Source::Url(url) => self.read_from_file(copy::file_url_path(url)?),
+        }
+    }
+
+    ///
+    /// Read an instance of `T` from the file identified by `path`, or return `T::default()` if
+    /// the file does not exist.
+    ///
+    /// Any other error, including an error opening a file that does exist, is returned. As for
+    /// `read_from_file` the path may name an entry within a ZIP archive, which is read if it
+    /// exists.
+    ///
+    fn read_from_file_or_default<P>(&self, path: P) -> Result<T, Self::Error>
+    where
+        P: AsRef<Path>,
+        T: Default,
+    {
+        #[cfg(feature = "zip")]
+        if let Some(archive_path) = archive::ArchivePath::detect(path.as_ref()) {
+            return archive_path.read_with_or_default(self);
+        }
+        self.validate_options()?;
+        match OpenOptions::new().read(true).open(path.as_ref()) {
+            Ok(file) => self.read_buffered(&mut BufReader::new(file)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(T::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by types which write instances of `T`.
+///
+pub trait ObjectWriter<T> {
+    ///
+    /// The type indicating errors, this **must** implement the conversion from `io::Error` as this
+    /// error is intrinsic to the methods on `Write`. This constraint allows the error type to also
+    /// signal serialization errors related to the content itself.
+    ///
+    type Error: From<::std::io::Error>;
+
+    ///
+    /// Write an instance of `T` to the provided implementation of `Write`.
+    ///
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write;
+
+    ///
+    /// Write an instance of `T`, taking ownership of it, to the provided implementation of
+    /// `Write`. The default implementation simply calls `write` with a reference to `object`;
+    /// writers for very large objects may override this method to move buffers out of the
+    /// object rather than copying from them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use objio::ObjectWriter;
+    /// use std::io::Write;
+    ///
+    /// #[derive(Debug, Default)]
+    /// struct LinesWriter;
+    ///
+    /// impl ObjectWriter<Vec<String>> for LinesWriter {
+    ///     type Error = std::io::Error;
+    ///
+    ///     fn write<W>(&self, w: &mut W, object: &Vec<String>) -> Result<(), Self::Error>
+    ///     where
+    ///         W: Write,
+    ///     {
+    ///         w.write_all(object.join("\n").as_bytes())
+    ///     }
+    ///
+    ///     fn write_owned<W>(&self, w: &mut W, object: Vec<String>) -> Result<(), Self::Error>
+    ///     where
+    ///         W: Write,
+    ///     {
+    ///         // reuse the first line's buffer rather than allocating a new one.
+    ///         let mut lines = object.into_iter();
+    ///         let mut buffer = lines.next().unwrap_or_default();
+    ///         for line in lines {
+    ///             buffer.push('\n');
+    ///             buffer.push_str(&line);
+    ///         }
+    ///         w.write_all(buffer.as_bytes())
+    ///     }
+    /// }
+    ///
+    /// let mut buffer = Vec::new();
+    /// LinesWriter
+    ///     .write_owned(&mut buffer, vec!["a".to_string(), "b".to_string()])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(buffer, b"a\nb");
+    /// ```
+    ///
+    fn write_owned<W>(&self, w: &mut W, object: T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write(w, &object)
+    }
+
+    ///
+    /// Check that the options for this writer, if any, are consistent. This is called by the
+    /// convenience methods below before any work begins; the default implementation does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use objio::{impl_has_options, HasOptions, ObjectWriter};
+    /// # use objio::options::{CommonWriterOptions, ValidateOptions};
+    /// # use std::io::Write;
+    /// #[derive(Debug, Default)]
+    /// struct TestWriter {
+    ///     options: CommonWriterOptions,
+    /// }
+    ///
+    /// impl_has_options!(TestWriter, CommonWriterOptions);
+    ///
+    /// impl ObjectWriter<String> for TestWriter {
+    ///     type Error = std::io::Error;
+    ///
+    ///     fn write<W>(&self, w: &mut W, object: &String) -> Result<(), Self::Error>
+    ///     where
+    ///         W: Write,
+    ///     {
+    ///         w.write_all(object.as_bytes())
+    ///     }
+    ///
+    ///     fn validate_options(&self) -> Result<(), Self::Error> {
+    ///         Ok(self.options.validate()?)
+    ///     }
+    /// }
+    ///
+    /// let writer = TestWriter::default()
+    ///     .with_options(CommonWriterOptions::default().with_line_width(0));
+    ///
+    /// assert!(writer.write_to_string(&"Hello".to_string()).is_err());
+    /// ```
+    ///
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    ///
+    /// Returns an estimate of the number of bytes that writing `object` will produce, used to
+    /// size the buffers allocated by the in-memory convenience methods below. The default
+    /// implementation returns `0`, leaving the buffer to grow as required.
+    ///
+    fn write_size_hint(&self, _object: &T) -> usize {
+        0
+    }
+
+    ///
+    /// Returns the buffering applied to files written by the convenience methods below; writers
+    /// with [`CommonWriterOptions`](options::CommonWriterOptions) return their
+    /// [`buffering`](options::CommonWriterOptions::buffering) option. The default is block
+    /// buffering.
+    ///
+    fn buffering(&self) -> options::Buffering {
+        options::Buffering::Block
+    }
+
+    ///
+    /// Write an instance of `T` to, and return, a string.
+    ///
+    /// The output is validated as it is written, and this method will return an IO error of kind
+    /// `InvalidData`, carrying a [`utf8::InvalidUtf8`] error, at the first invalid UTF-8 sequence.
+    ///
+    fn write_to_string(&self, object: &T) -> Result<String, Self::Error> {
+        self.validate_options()?;
+        let mut sink = utf8::Utf8Sink::with_capacity(self.write_size_hint(object));
+        self.write(&mut sink, object)?;
+        Ok(sink.finish()?)
+    }
+
+    ///
+    /// Write an instance of `T` to, and return, a vector of bytes. Unlike `write_to_string` this
+    /// makes no assumption that the output is valid UTF-8, and so is the preferred method for
+    /// binary formats.
+    ///
+    fn write_to_bytes(&self, object: &T) -> Result<Vec<u8>, Self::Error> {
+        self.validate_options()?;
+        let mut buffer = Vec::with_capacity(self.write_size_hint(object));
+        self.write(&mut buffer, object)?;
+        Ok(buffer)
+    }
+
+    ///
+    /// Write an instance of `T` into the caller-provided `buffer`, allowing the buffer's
+    /// allocation to be reused across many calls. If `append` is `true` the output is added to
+    /// the end of the existing content, otherwise the buffer is cleared first. If an error occurs
+    /// the buffer is truncated to remove any partial output.
+    ///
+    fn write_to_buffer(
+        &self,
+        object: &T,
+        buffer: &mut Vec<u8>,
+        append: bool,
+    ) -> Result<(), Self::Error> {
+        self.validate_options()?;
+        if !append {
+            buffer.clear();
+        }
+        let start = buffer.len();
+        self.write(buffer, object)
+            .inspect_err(|_| buffer.truncate(start))
+    }
+
+    ///
+    /// Write an instance of `T` directly into the formatter `f`, allowing an implementation of
+    /// `Display` to delegate to this writer without an intermediate string. The output of the
+    /// writer **must** be valid UTF-8, and any error is reported as `fmt::Error`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use objio::ObjectWriter;
+    /// use std::fmt::{Display, Formatter};
+    /// use std::io::Write;
+    ///
+    /// struct Greeting(String);
+    ///
+    /// #[derive(Debug, Default)]
+    /// struct GreetingWriter;
+    ///
+    /// impl ObjectWriter<Greeting> for GreetingWriter {
+    ///     type Error = std::io::Error;
+    ///
+    ///     fn write<W>(&self, w: &mut W, object: &Greeting) -> Result<(), Self::Error>
+    ///     where
+    ///         W: Write,
+    ///     {
+    ///         write!(w, "Hello, {}!", object.0)
+    ///     }
+    /// }
+    ///
+    /// impl Display for Greeting {
+    ///     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    ///         GreetingWriter.write_to_formatter(f, self)
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Greeting("world".into()).to_string(), "Hello, world!");
+    /// ```
+    ///
+    fn write_to_formatter(&self, f: &mut Formatter<'_>, object: &T) -> std::fmt::Result {
+        self.validate_options().map_err(|_| std::fmt::Error)?;
+        let mut bridge = FormatterWriter::new(f);
+        self.write(&mut bridge, object)
+            .map_err(|_| std::fmt::Error)?;
+        bridge.finish().map_err(|_| std::fmt::Error)
+    }
+
+    ///
+    /// Returns a value that implements `Display`, and `Debug`, by writing `object` with this
+    /// writer; this allows objects to be used in `format!` or `println!` with a chosen
+    /// serialization.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use objio::ObjectWriter;
+    /// use std::io::Write;
+    ///
+    /// #[derive(Debug, Default)]
+    /// struct ListWriter;
+    ///
+    /// impl ObjectWriter<Vec<u32>> for ListWriter {
+    ///     type Error = std::io::Error;
+    ///
+    ///     fn write<W>(&self, w: &mut W, object: &Vec<u32>) -> Result<(), Self::Error>
+    ///     where
+    ///         W: Write,
+    ///     {
+    ///         let values: Vec<String> = object.iter().map(u32::to_string).collect();
+    ///         write!(w, "({})", values.join(" "))
+    ///     }
+    /// }
+    ///
+    /// let list = vec![1, 2, 3];
+    ///
+    /// assert_eq!(format!("list: {}", ListWriter.display(&list)), "list: (1 2 3)");
+    /// ```
+    ///
+    fn display<'a>(&'a self, object: &'a T) -> Displayed<'a, Self, T>
+    where
+        Self: Sized,
+    {
+        Displayed {
+            writer: self,
+            object,
+            _marker: PhantomData,
+        }
+    }
+
+    ///
+    /// Write an instance of `T` into the file identified by `path`.
+    ///
+    /// This method will return an IO error if the path is invalid, or the file is not writeable.
+    /// If the file exists it will be replaced.
+    ///
+    fn write_to_file<P>(&self, object: &T, path: P) -> Result<(), Self::Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.validate_options()?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path.as_ref())?;
+        let mut sink = buffered::BufferedSink::new(file, self.buffering());
+        self.write(&mut sink, object)?;
+        let _ = sink.into_inner()?;
+        Ok(())
+    }
+
+    ///
+    /// Write an instance of `T` into a new temporary file, in the default temporary directory,
+    /// returning its handle.
+    ///
+    /// The file is created securely and is removed when the handle is dropped unless it is first
+    /// persisted with `NamedTempFile::persist`.
+    ///
+    #[cfg(feature = "tempfile")]
+    fn write_to_temp(&self, object: &T) -> Result<tempfile::NamedTempFile, Self::Error> {
+        self.write_to_temp_in(object, std::env::temp_dir())
+    }
+
+    ///
+    /// Write an instance of `T` into a new temporary file in the directory `dir`, returning its
+    /// handle, which is positioned at the start of the file. Creating the file in the same
+    /// directory as its final destination allows it to be persisted by a rename.
+    ///
+    #[cfg(feature = "tempfile")]
+    fn write_to_temp_in<P>(
+        &self,
+        object: &T,
+        dir: P,
+    ) -> Result<tempfile::NamedTempFile, Self::Error>
+    where
+        P: AsRef<Path>,
+    {
+        use std::io::{Seek, SeekFrom};
+        self.validate_options()?;
+        let mut sink =
+            buffered::BufferedSink::new(tempfile::NamedTempFile::new_in(dir)?, self.buffering());
+        self.write(&mut sink, object)?;
+        let mut file = sink.into_inner()?;
+        let _ = file.as_file_mut().seek(SeekFrom::Start(0))?;
+        Ok(file)
+    }
+
+    ///
+    /// Write an instance of `T` into the file identified by `path` through a memory map, which
+    /// is pre-sized using [`write_size_hint`](Self::write_size_hint); this avoids copying very
+    /// large outputs through the page cache, as described for
+    /// [`MappedFileSink`](crate::mmap::MappedFileSink). If the writer provides no size hint this
+    /// calls [`write_to_file`](Self::write_to_file).
+    ///
+    #[cfg(feature = "mmap")]
+    fn write_to_file_mapped<P>(&self, object: &T, path: P) -> Result<(), Self::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let size_hint = self.write_size_hint(object);
+        if size_hint == 0 {
+            return self.write_to_file(object, path);
+        }
+        self.validate_options()?;
+        let mut sink = mmap::MappedFileSink::create(path, size_hint)?;
+        self.write(&mut sink, object)?;
+        let _ = sink.finish()?;
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by writers that distinguish human-facing output, which may be colored,
+/// indented, or elided, from machine-facing output, which must be stable. Command-line tools can
+/// use this trait to choose a mode directly rather than through options flags.
+///
+/// Both methods have default implementations that delegate to [`ObjectWriter::write`], so a
+/// writer with a single output mode may implement this trait with an empty body.
+///
+/// # Example
+///
+/// ```rust
+/// use objio::{ObjectPrinter, ObjectWriter};
+/// use std::io::Write;
+///
+/// #[derive(Debug, Default)]
+/// struct ListWriter;
+///
+/// impl ObjectWriter<Vec<u32>> for ListWriter {
+///     type Error = std::io::Error;
+///
+///     fn write<W>(&self, w: &mut W, object: &Vec<u32>) -> Result<(), Self::Error>
+///     where
+///         W: Write,
+///     {
+///         let values: Vec<String> = object.iter().map(u32::to_string).collect();
+///         write!(w, "{}", values.join(","))
+///     }
+/// }
+///
+/// impl ObjectPrinter<Vec<u32>> for ListWriter {
+///     fn write_pretty<W>(&self, w: &mut W, object: &Vec<u32>) -> Result<(), Self::Error>
+///     where
+///         W: Write,
+///     {
+///         for (i, value) in object.iter().enumerate() {
+///             writeln!(w, "{i:>3}: {value}")?;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let mut output = Vec::new();
+/// ListWriter.write_canonical(&mut output, &vec![1, 2]).unwrap();
+///
+/// assert_eq!(output, b"1,2");
+///
+/// let mut output = Vec::new();
+/// ListWriter.write_pretty(&mut output, &vec![1, 2]).unwrap();
+///
+/// assert_eq!(output, b"  0: 1\n  1: 2\n");
+/// ```
+///
+pub trait ObjectPrinter<T>: ObjectWriter<T> {
+    ///
+    /// Write an instance of `T`, for a human reader, to the provided implementation of `Write`.
+    ///
+    fn write_pretty<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write(w, object)
+    }
+
+    ///
+    /// Write an instance of `T`, in a stable form for another program, to the provided
+    /// implementation of `Write`.
+    ///
+    fn write_canonical<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.write(w, object)
+    }
+
+    ///
+    /// Write an instance of `T`, for a human reader, to standard output.
+    ///
+    fn print(&self, object: &T) -> Result<(), Self::Error> {
+        self.validate_options()?;
+        let mut stdout = std::io::stdout().lock();
+        self.write_pretty(&mut stdout, object)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+///
+/// This trait is implemented by writers that can write with options other than those configured,
+/// so that a single call may deviate from the configured options without mutating, or cloning,
+/// the writer; for example, a writer shared between threads may use a different indent on each.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "json")]
+/// # {
+/// use objio::formats::json::JsonWriter;
+/// use objio::options::{CommonWriterOptions, CommonWriterOptionsOverlay};
+/// use objio::{ObjectWriter, ObjectWriterWith};
+///
+/// let writer = JsonWriter::default();
+/// let value = vec![1, 2];
+///
+/// let mut output = Vec::new();
+/// writer
+///     .write_with(&mut output, &value, &CommonWriterOptions::default().pretty())
+///     .unwrap();
+/// assert_eq!(output, b"[\n  1,\n  2\n]");
+///
+/// let mut output = Vec::new();
+/// writer
+///     .write_with_overrides(
+///         &mut output,
+///         &value,
+///         CommonWriterOptionsOverlay {
+///             indent: Some(4),
+///             pretty: Some(true),
+///             ..Default::default()
+///         },
+///     )
+///     .unwrap();
+/// assert_eq!(output, b"[\n    1,\n    2\n]");
+/// assert_eq!(writer.write_to_string(&value).unwrap(), "[1,2]");
+/// # }
+/// ```
+///
+pub trait ObjectWriterWith<T, O: Default>: ObjectWriter<T> + HasOptions<O> {
+    ///
+    /// Write an instance of `T` to the provided implementation of `Write` using `options` in
+    /// place of the configured options. As for `write`, the options are not validated.
+    ///
+    fn write_with<W>(&self, w: &mut W, object: &T, options: &O) -> Result<(), Self::Error>
+    where
+        W: Write;
+
+    ///
+    /// Check that `options` are consistent, as [`ObjectWriter::validate_options`] does for the
+    /// configured options; this is called by the convenience methods below before any work
+    /// begins. The default implementation always succeeds.
+    ///
+    fn validate_options_with(&self, _options: &O) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    ///
+    /// Write an instance of `T` to the provided implementation of `Write` using the configured
+    /// options with `overrides` merged into them.
+    ///
+    fn write_with_overrides<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        overrides: O::Overlay,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write,
+        O: options::MergeOptions + Clone,
+    {
+        let options = self.options().clone().merged(overrides);
+        self.write_with(w, object, &options)
+    }
+
+    ///
+    /// Write an instance of `T` to, and return, a string using `options` in place of the
+    /// configured options.
+    ///
+    fn write_to_string_with(&self, object: &T, options: &O) -> Result<String, Self::Error> {
+        self.validate_options_with(options)?;
+        let mut sink = utf8::Utf8Sink::with_capacity(self.write_size_hint(object));
+        self.write_with(&mut sink, object, options)?;
+        Ok(sink.finish()?)
+    }
+}
+
+///
+/// This trait is implemented by readers that can read with options other than those configured,
+/// so that a single call may deviate from the configured options without mutating, or cloning,
+/// the reader.
+///
+pub trait ObjectReaderWith<T, O: Default>: ObjectReader<T> + HasOptions<O> {
+    ///
+    /// Read an instance of `T` from the provided implementation of `Read` using `options` in
+    /// place of the configured options. As for `read`, the options are not validated.
+    ///
+    fn read_with<R>(&self, r: &mut R, options: &O) -> Result<T, Self::Error>
+    where
+        R: Read;
+
+    ///
+    /// Check that `options` are consistent, as [`ObjectReader::validate_options`] does for the
+    /// configured options; this is called by the convenience methods below before any work
+    /// begins. The default implementation always succeeds.
+    ///
+    fn validate_options_with(&self, _options: &O) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    ///
+    /// Read an instance of `T` from the provided implementation of `Read` using the configured
+    /// options with `overrides` merged into them.
+    ///
+    fn read_with_overrides<R>(&self, r: &mut R, overrides: O::Overlay) -> Result<T, Self::Error>
+    where
+        R: Read,
+        O: options::MergeOptions + Clone,
+    {
+        let options = self.options().clone().merged(overrides);
+        self.read_with(r, &options)
+    }
+
+    ///
+    /// Read an instance of `T` from the provided string using `options` in place of the
+    /// configured options.
+    ///
+    fn read_from_string_with<S>(&self, string: S, options: &O) -> Result<T, Self::Error>
+    where
+        S: AsRef<str>,
+    {
+        self.validate_options_with(options)?;
+        let mut data = string.as_ref().as_bytes();
+        self.read_with(&mut data, options)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A writer and an object reference bundled together, as returned by
+/// [`ObjectWriter::display`], that implements `Display` and `Debug` by writing the object.
+///
+pub struct Displayed<'a, W, T> {
+    writer: &'a W,
+    object: &'a T,
+    _marker: PhantomData<fn(&T)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<W: ObjectWriter<T>, T> Display for Displayed<'_, W, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.writer.write_to_formatter(f, self.object)
+    }
+}
+
+impl<W: ObjectWriter<T>, T> Debug for Displayed<'_, W, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.writer.write_to_formatter(f, self.object)
     }
+}
 
-    ///
-    /// Read an instance of `T` from the file identified by `path`.
-    ///
-    /// This method will return an IO error if the path is invalid, or file does not exist.
-    ///
-    fn read_from_file<P>(&self, path: P) -> Result<T, Self::Error>
-    where
-        P: AsRef<Path>,
-    {
-        let mut file = OpenOptions::new().read(true).open(path.as_ref())?;
-        self.read(&mut file)
+impl<W, T> Clone for Displayed<'_, W, T> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
 
+impl<W, T> Copy for Displayed<'_, W, T> {}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
 // ------------------------------------------------------------------------------------------------
 
 ///
-/// The trait implemented by types which write instances of `T`.
+/// Read an instance of `T` from `r` using `reader`; equivalent to [`ObjectReader::read`].
 ///
-pub trait ObjectWriter<T> {
-    ///
-    /// The type indicating errors, this **must** implement the conversion from `io::Error` as this
-    /// error is intrinsic to the methods on `Write`. This constraint allows the error type to also
-    /// signal serialization errors related to the content itself.
-    ///
-    type Error: From<::std::io::Error>;
+/// # Example
+///
+/// ```rust
+/// # use objio::ObjectReader;
+/// # use std::io::Read;
+/// # #[derive(Debug, Default)]
+/// # struct LineReader;
+/// # impl ObjectReader<String> for LineReader {
+/// #     type Error = std::io::Error;
+/// #     fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+/// #         let mut s = String::new();
+/// #         r.read_to_string(&mut s)?;
+/// #         Ok(s)
+/// #     }
+/// # }
+/// let mut input = "Hello".as_bytes();
+/// let value: String = objio::from_reader(&LineReader, &mut input).unwrap();
+///
+/// assert_eq!(value, "Hello");
+/// assert_eq!(objio::from_str(&LineReader, "World").unwrap(), "World");
+/// ```
+///
+pub fn from_reader<O, T, R>(reader: &O, r: &mut R) -> Result<T, O::Error>
+where
+    O: ObjectReader<T>,
+    R: Read,
+{
+    reader.read(r)
+}
 
-    ///
-    /// Write an instance of `T` to the provided implementation of `Write`.
-    ///
-    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
-    where
-        W: Write;
+///
+/// Read an instance of `T` from the string `s` using `reader`; equivalent to
+/// [`ObjectReader::read_from_string`].
+///
+pub fn from_str<O, T, S>(reader: &O, s: S) -> Result<T, O::Error>
+where
+    O: ObjectReader<T>,
+    S: AsRef<str>,
+{
+    reader.read_from_string(s)
+}
 
-    ///
-    /// Write an instance of `T` to, and return, a string.
-    ///
-    fn write_to_string(&self, object: &T) -> Result<String, Self::Error> {
-        let mut buffer = Cursor::new(Vec::new());
-        self.write(&mut buffer, object)?;
-        Ok(String::from_utf8(buffer.into_inner()).unwrap())
-    }
+///
+/// Read an instance of `T` from the bytes `v` using `reader`; equivalent to
+/// [`ObjectReader::read_from_bytes`].
+///
+pub fn from_slice<O, T, B>(reader: &O, v: B) -> Result<T, O::Error>
+where
+    O: ObjectReader<T>,
+    B: AsRef<[u8]>,
+{
+    reader.read_from_bytes(v)
+}
 
-    ///
-    /// Write an instance of `T` into the file identified by `path`.
-    ///
-    /// This method will return an IO error if the path is invalid, or the file is not writeable.
-    /// If the file exists it will be replaced.
-    ///
-    fn write_to_file<P>(&self, object: &T, path: P) -> Result<(), Self::Error>
-    where
-        P: AsRef<Path>,
-    {
-        let mut file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(path.as_ref())?;
-        self.write(&mut file, object)
-    }
+///
+/// Read an instance of `T` from the file identified by `path` using `reader`; equivalent to
+/// [`ObjectReader::read_from_file`].
+///
+pub fn from_file<O, T, P>(reader: &O, path: P) -> Result<T, O::Error>
+where
+    O: ObjectReader<T>,
+    P: AsRef<Path>,
+{
+    reader.read_from_file(path)
+}
+
+///
+/// Write `value` to `w` using `writer`; equivalent to [`ObjectWriter::write`].
+///
+/// # Example
+///
+/// ```rust
+/// # use objio::ObjectWriter;
+/// # use std::io::Write;
+/// # #[derive(Debug, Default)]
+/// # struct LineWriter;
+/// # impl ObjectWriter<String> for LineWriter {
+/// #     type Error = std::io::Error;
+/// #     fn write<W: Write>(&self, w: &mut W, object: &String) -> Result<(), Self::Error> {
+/// #         writeln!(w, "{object}")
+/// #     }
+/// # }
+/// let mut output = Vec::new();
+/// objio::to_writer(&LineWriter, &mut output, &"Hello".to_string()).unwrap();
+///
+/// assert_eq!(output, b"Hello\n");
+/// assert_eq!(objio::to_string(&LineWriter, &"World".to_string()).unwrap(), "World\n");
+/// ```
+///
+pub fn to_writer<O, T, W>(writer: &O, w: &mut W, value: &T) -> Result<(), O::Error>
+where
+    O: ObjectWriter<T>,
+    W: Write,
+{
+    writer.write(w, value)
+}
+
+///
+/// Write `value` to, and return, a string using `writer`; equivalent to
+/// [`ObjectWriter::write_to_string`].
+///
+pub fn to_string<O, T>(writer: &O, value: &T) -> Result<String, O::Error>
+where
+    O: ObjectWriter<T>,
+{
+    writer.write_to_string(value)
+}
+
+///
+/// Write `value` to, and return, a vector of bytes using `writer`; equivalent to
+/// [`ObjectWriter::write_to_bytes`].
+///
+pub fn to_vec<O, T>(writer: &O, value: &T) -> Result<Vec<u8>, O::Error>
+where
+    O: ObjectWriter<T>,
+{
+    writer.write_to_bytes(value)
+}
+
+///
+/// Write `value` into the file identified by `path` using `writer`; equivalent to
+/// [`ObjectWriter::write_to_file`].
+///
+pub fn to_file<O, T, P>(writer: &O, value: &T, path: P) -> Result<(), O::Error>
+where
+    O: ObjectWriter<T>,
+    P: AsRef<Path>,
+{
+    writer.write_to_file(value, path)
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -283,20 +1386,129 @@ pub trait ObjectWriter<T> {
 ///
 /// Provides a boiler-place implementation of [`HasOptions`].
 ///
+/// The implementing type may be generic, in which case the lifetime and type parameters, each
+/// type parameter with an optional single bound, are listed after `impl` and an optional
+/// where-clause, for any other bounds, may follow the option type or field name.
+///
+/// # Example
+///
+/// ```rust
+/// use objio::{impl_has_options, HasOptions};
+/// use std::fmt::Debug;
+/// use std::marker::PhantomData;
+///
+/// #[derive(Debug, Default)]
+/// struct TestOptions {
+///     indent: usize,
+/// }
+///
+/// #[derive(Debug, Default)]
+/// struct TestWriter<T, U> {
+///     config: TestOptions,
+///     _marker: PhantomData<(T, U)>,
+/// }
+///
+/// impl_has_options!(impl<T: Debug, U> TestWriter<T, U>, TestOptions, config where U: Clone);
+///
+/// let writer: TestWriter<u8, String> =
+///     TestWriter::default().with_options(TestOptions { indent: 2 });
+///
+/// assert_eq!(writer.options().indent, 2);
+/// ```
+///
+/// Implementing types that borrow shared state, and so carry a lifetime, are supported in the
+/// same manner.
+///
+/// ```rust
+/// # use objio::{impl_has_options, HasOptions};
+/// # #[derive(Debug, Default)]
+/// # struct TestOptions {
+/// #     indent: usize,
+/// # }
+/// #[derive(Debug)]
+/// struct TestWriter<'a> {
+///     options: TestOptions,
+///     prefixes: &'a [(String, String)],
+/// }
+///
+/// impl_has_options!(impl<'a> TestWriter<'a>, TestOptions);
+///
+/// let prefixes = Vec::new();
+/// let mut writer = TestWriter {
+///     options: Default::default(),
+///     prefixes: &prefixes,
+/// };
+/// writer.set_options(TestOptions { indent: 4 });
+///
+/// assert_eq!(writer.options().indent, 4);
+/// ```
+///
+/// A reader or writer that has no options may implement `HasOptions<()>`, so that generic code
+/// may bound on `HasOptions<O>` for any reader or writer, by naming only the implementing type.
+///
+/// ```rust
+/// # use objio::{impl_has_options, HasOptions};
+/// #[derive(Debug, Default)]
+/// struct PlainWriter;
+///
+/// impl_has_options!(PlainWriter);
+///
+/// fn configure<W: HasOptions<O>, O: Default>(writer: W, options: O) -> W {
+///     writer.with_options(options)
+/// }
+///
+/// let writer = configure(PlainWriter, ());
+/// assert_eq!(writer.options(), &());
+/// ```
+///
 #[macro_export]
 macro_rules! impl_has_options {
+    (impl<$($param: tt $(: $bound: path)?),+> $impl_type: ty, $option_type: ty
+     $(where $($where_clause: tt)+)?) => {
+        impl_has_options!(
+            impl<$($param $(: $bound)?),+> $impl_type, $option_type, options
+            $(where $($where_clause)+)?
+        );
+    };
+    (impl<$($param: tt $(: $bound: path)?),+> $impl_type: ty, $option_type: ty,
+     $field_name: ident $(where $($where_clause: tt)+)?) => {
+        impl<$($param $(: $bound)?),+> $crate::HasOptions<$option_type> for $impl_type
+        $(where $($where_clause)+)?
+        {
+            fn options(&self) -> &$option_type {
+                &self.$field_name
+            }
+
+            fn options_mut(&mut self) -> &mut $option_type {
+                &mut self.$field_name
+            }
+        }
+    };
     ($impl_type: ty, $option_type: ty) => {
         impl_has_options!($impl_type, $option_type, options);
     };
     ($impl_type: ty, $option_type: ty, $field_name: ident) => {
         impl $crate::HasOptions<$option_type> for $impl_type {
-            fn set_options(&mut self, options: $option_type) {
-                self.$field_name = options;
-            }
-
             fn options(&self) -> &$option_type {
                 &self.$field_name
             }
+
+            fn options_mut(&mut self) -> &mut $option_type {
+                &mut self.$field_name
+            }
+        }
+    };
+    ($impl_type: ty) => {
+        impl $crate::HasOptions<()> for $impl_type {
+            fn options(&self) -> &() {
+                &()
+            }
+
+            #[allow(unsafe_code)]
+            fn options_mut(&mut self) -> &mut () {
+                // SAFETY: a dangling, well-aligned, pointer is valid for a zero-sized type.
+                unsafe { ::std::ptr::NonNull::<()>::dangling().as_mut() }
+            }
         }
     };
 }
@@ -380,6 +1592,10 @@ mod tests {
             fn options(&self) -> &TestOptions {
                 &self.options
             }
+
+            fn options_mut(&mut self) -> &mut TestOptions {
+                &mut self.options
+            }
         }
 
         let obj = TestObject::default().with_options(TestOptions { count: 2 });
@@ -401,9 +1617,271 @@ mod tests {
 
         impl_has_options!(TestObject, TestOptions);
 
-        let obj = TestObject::default().with_options(TestOptions { count: 2 });
+        let mut obj = TestObject::default().with_options(TestOptions { count: 2 });
+
+        assert_eq!(obj.options().count, 2);
+
+        obj.options_mut().count += 1;
+
+        assert_eq!(obj.options().count, 3);
+    }
+
+    #[test]
+    fn test_macro_generic_options() {
+        #[derive(Debug, Default)]
+        struct TestOptions {
+            count: u32,
+        }
+
+        #[derive(Debug, Default)]
+        struct TestObject<T: Default> {
+            options: TestOptions,
+            value: T,
+        }
+
+        impl_has_options!(impl<T: Default> TestObject<T>, TestOptions where T: Clone);
+
+        let obj: TestObject<String> = TestObject::default().with_options(TestOptions { count: 2 });
 
         assert_eq!(obj.options().count, 2);
+        assert_eq!(obj.value, "");
+    }
+
+    #[test]
+    fn test_macro_lifetime_options() {
+        #[derive(Debug, Default)]
+        struct TestOptions {
+            count: u32,
+        }
+
+        #[derive(Debug)]
+        struct TestObject<'a, 'b, T> {
+            options: TestOptions,
+            names: &'a [&'b str],
+            value: T,
+        }
+
+        impl_has_options!(impl<'a, 'b, T: Clone> TestObject<'a, 'b, T>, TestOptions where 'b: 'a);
+
+        let names = ["a", "b"];
+        let mut obj = TestObject {
+            options: Default::default(),
+            names: &names,
+            value: 1_u8,
+        };
+        obj.options_mut().count += 1;
+
+        assert_eq!(obj.options().count, 1);
+        assert_eq!(obj.names.len(), 2);
+        assert_eq!(obj.value, 1);
+    }
+
+    #[test]
+    fn test_read_from_file_or_default() {
+        #[derive(Debug, Default)]
+        struct TestReader {}
+
+        impl ObjectReader<String> for TestReader {
+            type Error = std::io::Error;
+
+            fn read<R>(&self, r: &mut R) -> Result<String, Self::Error>
+            where
+                R: Read,
+            {
+                let mut s = String::new();
+                r.read_to_string(&mut s)?;
+                if s.is_empty() {
+                    Err(ErrorKind::InvalidData.into())
+                } else {
+                    Ok(s)
+                }
+            }
+        }
+
+        let reader = TestReader::default();
+        let directory = std::env::temp_dir();
+        let path = directory.join(format!("objio-or-default-{}", std::process::id()));
+
+        assert_eq!(reader.read_from_file_or_default(&path).unwrap(), "");
+
+        std::fs::write(&path, "state").unwrap();
+        assert_eq!(reader.read_from_file_or_default(&path).unwrap(), "state");
+
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(
+            reader.read_from_file_or_default(&path).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(reader.read_from_file_or_default(&directory).is_err());
+    }
+
+    #[test]
+    fn test_write_to_file_buffering() {
+        #[derive(Debug)]
+        struct TestWriter {
+            path: std::path::PathBuf,
+            buffering: options::Buffering,
+            written: std::cell::RefCell<Vec<String>>,
+        }
+
+        impl ObjectWriter<Vec<&str>> for TestWriter {
+            type Error = std::io::Error;
+
+            fn write<W>(&self, w: &mut W, object: &Vec<&str>) -> Result<(), Self::Error>
+            where
+                W: Write,
+            {
+                for line in object {
+                    writeln!(w, "{line}")?;
+                    let written = std::fs::read_to_string(&self.path)?;
+                    self.written.borrow_mut().push(written);
+                }
+                Ok(())
+            }
+
+            fn buffering(&self) -> options::Buffering {
+                self.buffering
+            }
+        }
+
+        let path = std::env::temp_dir().join(format!("objio-buffering-{}", std::process::id()));
+        let writer = TestWriter {
+            path: path.clone(),
+            buffering: options::Buffering::Line,
+            written: Default::default(),
+        };
+        writer.write_to_file(&vec!["one", "two"], &path).unwrap();
+        assert_eq!(*writer.written.borrow(), ["one\n", "one\ntwo\n"]);
+
+        let writer = TestWriter {
+            buffering: options::Buffering::Block,
+            ..writer
+        };
+        writer.written.borrow_mut().clear();
+        writer.write_to_file(&vec!["one", "two"], &path).unwrap();
+        assert_eq!(*writer.written.borrow(), ["", ""]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_try_read() {
+        #[derive(Debug, Default)]
+        struct TestReader {}
+
+        impl ObjectReader<u32> for TestReader {
+            type Error = std::io::Error;
+
+            fn read<R>(&self, r: &mut R) -> Result<u32, Self::Error>
+            where
+                R: Read,
+            {
+                let mut s = String::new();
+                r.read_to_string(&mut s)?;
+                s.trim()
+                    .parse()
+                    .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+            }
+        }
+
+        let reader = TestReader::default();
+
+        assert_eq!(reader.try_read(&mut "".as_bytes()).unwrap(), None);
+        assert_eq!(reader.try_read(&mut " \n\t".as_bytes()).unwrap(), None);
+        assert_eq!(reader.try_read(&mut " 12\n".as_bytes()).unwrap(), Some(12));
+        assert!(reader.try_read(&mut "twelve".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_read_buffered() {
+        #[derive(Debug, Default)]
+        struct FirstLineReader {}
+
+        impl ObjectReader<String> for FirstLineReader {
+            type Error = std::io::Error;
+
+            fn read<R>(&self, r: &mut R) -> Result<String, Self::Error>
+            where
+                R: Read,
+            {
+                self.read_buffered(&mut BufReader::new(r))
+            }
+
+            fn read_buffered<R>(&self, r: &mut R) -> Result<String, Self::Error>
+            where
+                R: BufRead,
+            {
+                let mut line = String::new();
+                let _ = r.read_line(&mut line)?;
+                Ok(line.trim_end().to_string())
+            }
+        }
+
+        let reader = FirstLineReader::default();
+        let mut source = "one\ntwo\n".as_bytes();
+
+        assert_eq!(reader.read_buffered(&mut source).unwrap(), "one");
+        assert_eq!(source, b"two\n");
+        assert_eq!(reader.read_from_string("three\nfour").unwrap(), "three");
+        assert_eq!(reader.read(&mut "five".as_bytes()).unwrap(), "five");
+    }
+
+    #[test]
+    fn test_write_to_buffer_error() {
+        #[derive(Debug, Default)]
+        struct TestWriter {}
+
+        impl ObjectWriter<u8> for TestWriter {
+            type Error = std::io::Error;
+
+            fn write<W>(&self, w: &mut W, object: &u8) -> Result<(), Self::Error>
+            where
+                W: Write,
+            {
+                w.write_all(b"partial")?;
+                if *object == 0 {
+                    Err(ErrorKind::InvalidInput.into())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        let mut buffer = b"kept".to_vec();
+        assert!(TestWriter::default()
+            .write_to_buffer(&0, &mut buffer, true)
+            .is_err());
+        assert_eq!(buffer, b"kept".to_vec());
+    }
+
+    #[test]
+    fn test_write_to_string_invalid_utf8() {
+        #[derive(Debug, Default)]
+        struct TestWriter {}
+
+        impl ObjectWriter<u8> for TestWriter {
+            type Error = std::io::Error;
+
+            fn write<W>(&self, w: &mut W, object: &u8) -> Result<(), Self::Error>
+            where
+                W: Write,
+            {
+                w.write_all(&[*object])
+            }
+
+            fn write_size_hint(&self, _object: &u8) -> usize {
+                1
+            }
+        }
+
+        let writer = TestWriter::default();
+
+        assert_eq!(writer.write_to_string(&b'a').unwrap(), "a");
+        let error = writer.write_to_string(&0xFF).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "invalid UTF-8 sequence at byte offset 0");
     }
 
     #[test]
@@ -432,5 +1910,81 @@ mod tests {
             writer.write_to_string(&TestObject::default()).unwrap(),
             "Hello".to_string()
         );
+        assert_eq!(
+            writer.write_to_bytes(&TestObject::default()).unwrap(),
+            b"Hello".to_vec()
+        );
+
+        let mut buffer = Vec::new();
+        writer
+            .write_owned(&mut buffer, TestObject::default())
+            .unwrap();
+        assert_eq!(buffer, b"Hello".to_vec());
+
+        assert_eq!(to_vec(&writer, &TestObject::default()).unwrap(), b"Hello");
+
+        #[cfg(feature = "tempfile")]
+        {
+            let mut file = writer.write_to_temp(&TestObject::default()).unwrap();
+            let mut content = String::new();
+            let _ = file.read_to_string(&mut content).unwrap();
+            assert_eq!(content, "Hello");
+            let path = file.path().to_path_buf();
+            drop(file);
+            assert!(!path.exists());
+        }
+
+        let mut buffer = b"Hi".to_vec();
+        writer
+            .write_to_buffer(&TestObject::default(), &mut buffer, true)
+            .unwrap();
+        assert_eq!(buffer, b"HiHello".to_vec());
+        writer
+            .write_to_buffer(&TestObject::default(), &mut buffer, false)
+            .unwrap();
+        assert_eq!(buffer, b"Hello".to_vec());
+
+        impl ObjectPrinter<TestObject> for TestWriter {}
+
+        let mut buffer = Vec::new();
+        writer
+            .write_canonical(&mut buffer, &TestObject::default())
+            .unwrap();
+        assert_eq!(buffer, b"Hello".to_vec());
+
+        let object = TestObject::default();
+        let displayed = writer.display(&object);
+        assert_eq!(format!("{displayed} {displayed:?}"), "Hello Hello");
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_object_builder() {
+        use crate::options::{CommonWriterOptions, ValidateOptions};
+
+        fn check_options<F>(writer: &TestWriter<F>) -> Result<(), crate::Error> {
+            writer.options.validate()
+        }
+
+        #[derive(Debug, Default, ObjectBuilder)]
+        #[builder(validate = check_options)]
+        struct TestWriter<F> {
+            options: CommonWriterOptions,
+            pretty: bool,
+            format: PhantomData<F>,
+        }
+
+        let writer = TestWriter::<()>::builder()
+            .options(CommonWriterOptions::default().with_line_width(40))
+            .pretty(true)
+            .build()
+            .unwrap();
+        assert!(writer.pretty);
+        assert_eq!(writer.options.line_width(), 40);
+
+        assert!(TestWriter::<u8>::builder()
+            .options(CommonWriterOptions::default().with_line_width(0))
+            .build()
+            .is_err());
     }
 }
@@ -0,0 +1,620 @@
+/*!
+Provides locale-sensitive formatting of numbers and dates, using the ICU4X crates, for writers of
+human-readable output such as reports.
+
+Locale-sensitive output is intended to be read by people, and is not generally readable by
+programs; writers of machine formats should use [`NumberOptions`](crate::numbers::NumberOptions)
+and, with the `chrono` feature, `TimestampOptions`, which are independent of any locale. For this
+reason [`LocaleOptions`] is not part of [`CommonWriterOptions`](crate::options::CommonWriterOptions)
+and should only be held in the options of writers whose output is meant to be localized.
+
+A [`LocaleFormatter`] is created from the options, loading the locale's data once, and formats
+integers and floating-point values with the locale's digit grouping and decimal separator, and
+dates, with or without a time, in the locale's Gregorian calendar patterns. Data for all locales
+is compiled into the crate; a locale without data of its own uses that of its nearest parent.
+
+This module is only available with the `icu` feature.
+
+# Example
+
+```rust
+use icu_calendar::DateTime;
+use objio::locale::{DateLength, LocaleOptions, TimeLength};
+
+let options = LocaleOptions::new("de-DE").with_fraction_digits(Some(2));
+let formatter = options.formatter().unwrap();
+assert_eq!(formatter.format_integer(1234567), "1.234.567");
+assert_eq!(formatter.format_f64(1234.5), "1.234,50");
+
+let datetime = DateTime::try_new_iso_datetime(2024, 3, 1, 14, 5, 9).unwrap();
+assert_eq!(formatter.format_datetime(&datetime), "01.03.2024, 14:05");
+
+let formatter = LocaleOptions::new("en-US")
+    .with_date_length(DateLength::Long)
+    .with_time_length(TimeLength::None)
+    .formatter()
+    .unwrap();
+assert_eq!(formatter.format_datetime(&datetime), "March 1, 2024");
+```
+ */
+
+use crate::error::Error;
+use crate::options::{
+    DescribeOptions, FromOptionStrings, MergeOptions, OptionDescriptor, OptionsOverlay,
+    ParseOptionValueError, ValidateOptions,
+};
+use fixed_decimal::{FixedDecimal, FloatPrecision};
+use icu_calendar::{Date, DateTime, Gregorian, Iso};
+use icu_datetime::options::length;
+use icu_datetime::{TypedDateFormatter, TypedDateTimeFormatter};
+use icu_decimal::options::{FixedDecimalFormatterOptions, GroupingStrategy};
+use icu_decimal::FixedDecimalFormatter;
+use icu_locid::Locale;
+use std::fmt::{Debug, Formatter};
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The locale used if none is set.
+///
+pub const DEFAULT_LOCALE: &str = "en";
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The length of the date in formatted dates and date-times.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum DateLength {
+    /// A numeric date, such as `3/1/24` in English.
+    Short,
+    /// A date with an abbreviated month, such as `Mar 1, 2024` in English.
+    #[default]
+    Medium,
+    /// A date with the full month, such as `March 1, 2024` in English.
+    Long,
+    /// A date with the weekday and full month, such as `Friday, March 1, 2024` in English.
+    Full,
+}
+
+///
+/// The length of the time in formatted date-times.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum TimeLength {
+    /// No time is written, only the date.
+    None,
+    /// Hours and minutes, such as `2:05 PM` in English.
+    #[default]
+    Short,
+    /// Hours, minutes, and seconds, such as `2:05:09 PM` in English.
+    Medium,
+}
+
+///
+/// Options determining how numbers and dates are formatted for a locale.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct LocaleOptions {
+    locale: String,
+    grouping: bool,
+    fraction_digits: Option<u16>,
+    date_length: DateLength,
+    time_length: TimeLength,
+}
+
+///
+/// A partial set of values to be merged into an instance of [`LocaleOptions`].
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct LocaleOptionsOverlay {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub locale: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub grouping: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub fraction_digits: Option<Option<u16>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub date_length: Option<DateLength>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub time_length: Option<TimeLength>,
+}
+
+///
+/// Formats numbers and dates according to a set of [`LocaleOptions`], with the locale's data
+/// loaded when it is created.
+///
+pub struct LocaleFormatter {
+    options: LocaleOptions,
+    decimals: FixedDecimalFormatter,
+    dates: TypedDateFormatter<Gregorian>,
+    date_times: Option<TypedDateTimeFormatter<Gregorian>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for DateLength {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "short" => Ok(Self::Short),
+            "medium" => Ok(Self::Medium),
+            "long" => Ok(Self::Long),
+            "full" => Ok(Self::Full),
+            _ => Err(ParseOptionValueError::new(
+                s,
+                &["short", "medium", "long", "full"],
+            )),
+        }
+    }
+}
+
+impl From<DateLength> for length::Date {
+    fn from(value: DateLength) -> Self {
+        match value {
+            DateLength::Short => Self::Short,
+            DateLength::Medium => Self::Medium,
+            DateLength::Long => Self::Long,
+            DateLength::Full => Self::Full,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for TimeLength {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "short" => Ok(Self::Short),
+            "medium" => Ok(Self::Medium),
+            _ => Err(ParseOptionValueError::new(s, &["none", "short", "medium"])),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Default for LocaleOptions {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOCALE)
+    }
+}
+
+impl MergeOptions for LocaleOptions {
+    type Overlay = LocaleOptionsOverlay;
+
+    fn merge(&mut self, overlay: Self::Overlay) {
+        if let Some(locale) = overlay.locale {
+            self.locale = locale;
+        }
+        if let Some(grouping) = overlay.grouping {
+            self.grouping = grouping;
+        }
+        if let Some(fraction_digits) = overlay.fraction_digits {
+            self.fraction_digits = fraction_digits;
+        }
+        if let Some(date_length) = overlay.date_length {
+            self.date_length = date_length;
+        }
+        if let Some(time_length) = overlay.time_length {
+            self.time_length = time_length;
+        }
+    }
+}
+
+impl OptionsOverlay for LocaleOptionsOverlay {
+    fn overlay(self, other: Self) -> Self {
+        Self {
+            locale: other.locale.or(self.locale),
+            grouping: other.grouping.or(self.grouping),
+            fraction_digits: other.fraction_digits.or(self.fraction_digits),
+            date_length: other.date_length.or(self.date_length),
+            time_length: other.time_length.or(self.time_length),
+        }
+    }
+}
+
+impl ValidateOptions for LocaleOptions {
+    fn validate(&self) -> Result<(), Error> {
+        self.parse_locale().map(|_| ())
+    }
+}
+
+impl DescribeOptions for LocaleOptions {
+    fn describe_options() -> Vec<OptionDescriptor> {
+        let defaults = Self::default();
+        vec![
+            OptionDescriptor::new(
+                "locale",
+                "String",
+                defaults.locale.clone(),
+                "The BCP 47 language tag of the locale, such as `en-US` or `de-DE`.",
+            ),
+            OptionDescriptor::new(
+                "grouping",
+                "bool",
+                defaults.grouping.to_string(),
+                "Whether the digits of numbers are grouped, such as into thousands.",
+            ),
+            OptionDescriptor::new(
+                "fraction_digits",
+                "Option<u16>",
+                format!("{:?}", defaults.fraction_digits),
+                "The number of digits after the decimal separator, if not the shortest needed.",
+            ),
+            OptionDescriptor::new(
+                "date_length",
+                "DateLength",
+                format!("{:?}", defaults.date_length),
+                "The length of the date in formatted dates and date-times.",
+            ),
+            OptionDescriptor::new(
+                "time_length",
+                "TimeLength",
+                format!("{:?}", defaults.time_length),
+                "The length of the time in formatted date-times, or `none` for only the date.",
+            ),
+        ]
+    }
+}
+
+impl FromOptionStrings for LocaleOptions {
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        fn parse<T>(key: &str, value: &str) -> Result<T, Error>
+        where
+            T: FromStr,
+            T::Err: Into<crate::BoxedError>,
+        {
+            value
+                .parse()
+                .map_err(|e| Error::invalid_option_value(key, value, e))
+        }
+        match key.replace('-', "_").as_str() {
+            "locale" => self.locale = value.to_string(),
+            "grouping" => self.grouping = parse(key, value)?,
+            "fraction_digits" if value == "shortest" => self.fraction_digits = None,
+            "fraction_digits" => self.fraction_digits = Some(parse(key, value)?),
+            "date_length" => self.date_length = parse(key, value)?,
+            "time_length" => self.time_length = parse(key, value)?,
+            _ => return Err(Error::unknown_option(key)),
+        }
+        Ok(())
+    }
+}
+
+impl LocaleOptions {
+    ///
+    /// Construct new options for the locale identified by the BCP 47 language tag `locale`, with
+    /// all other options set to their defaults.
+    ///
+    pub fn new<S>(locale: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            locale: locale.into(),
+            grouping: true,
+            fraction_digits: None,
+            date_length: Default::default(),
+            time_length: Default::default(),
+        }
+    }
+
+    ///
+    /// Returns a copy of these options with the locale set to the BCP 47 language tag `locale`.
+    ///
+    pub fn with_locale<S>(self, locale: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        self_mut.locale = locale.into();
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with digit grouping set to `grouping`.
+    ///
+    pub fn with_grouping(self, grouping: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.grouping = grouping;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the number of digits after the decimal separator set
+    /// to `fraction_digits`, where `None` indicates the shortest representation that round-trips.
+    ///
+    pub fn with_fraction_digits(self, fraction_digits: Option<u16>) -> Self {
+        let mut self_mut = self;
+        self_mut.fraction_digits = fraction_digits;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the date length set to `date_length`.
+    ///
+    pub fn with_date_length(self, date_length: DateLength) -> Self {
+        let mut self_mut = self;
+        self_mut.date_length = date_length;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the time length set to `time_length`.
+    ///
+    pub fn with_time_length(self, time_length: TimeLength) -> Self {
+        let mut self_mut = self;
+        self_mut.time_length = time_length;
+        self_mut
+    }
+
+    ///
+    /// Returns the BCP 47 language tag of the locale.
+    ///
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    ///
+    /// Returns `true` if the digits of numbers are grouped.
+    ///
+    pub fn is_grouping(&self) -> bool {
+        self.grouping
+    }
+
+    ///
+    /// Returns the number of digits written after the decimal separator, or `None` if the
+    /// shortest representation that round-trips is written.
+    ///
+    pub fn fraction_digits(&self) -> Option<u16> {
+        self.fraction_digits
+    }
+
+    ///
+    /// Returns the length of the date in formatted dates and date-times.
+    ///
+    pub fn date_length(&self) -> DateLength {
+        self.date_length
+    }
+
+    ///
+    /// Returns the length of the time in formatted date-times.
+    ///
+    pub fn time_length(&self) -> TimeLength {
+        self.time_length
+    }
+
+    ///
+    /// Create a formatter for these options, loading the data for the locale.
+    ///
+    pub fn formatter(&self) -> Result<LocaleFormatter, Error> {
+        LocaleFormatter::new(self.clone())
+    }
+
+    fn parse_locale(&self) -> Result<Locale, Error> {
+        self.locale.parse().map_err(|e| {
+            Error::invalid_options(format!("locale `{}` is not valid: {e}", self.locale))
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for LocaleFormatter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocaleFormatter")
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LocaleFormatter {
+    ///
+    /// Construct a new formatter for `options`, loading the data for the locale.
+    ///
+    pub fn new(options: LocaleOptions) -> Result<Self, Error> {
+        let locale = options.parse_locale()?.into();
+        let data_error = |e: &dyn std::fmt::Display| {
+            Error::invalid_options(format!(
+                "data for locale `{}` could not be loaded: {e}",
+                options.locale
+            ))
+        };
+
+        let mut decimal_options = FixedDecimalFormatterOptions::default();
+        decimal_options.grouping_strategy = if options.grouping {
+            GroupingStrategy::Auto
+        } else {
+            GroupingStrategy::Never
+        };
+        let decimals =
+            FixedDecimalFormatter::try_new(&locale, decimal_options).map_err(|e| data_error(&e))?;
+        let dates = TypedDateFormatter::try_new_with_length(&locale, options.date_length.into())
+            .map_err(|e| data_error(&e))?;
+        let time_length = match options.time_length {
+            TimeLength::None => None,
+            TimeLength::Short => Some(length::Time::Short),
+            TimeLength::Medium => Some(length::Time::Medium),
+        };
+        let date_times = match time_length {
+            Some(time_length) => Some(
+                TypedDateTimeFormatter::try_new(
+                    &locale,
+                    length::Bag::from_date_time_style(options.date_length.into(), time_length)
+                        .into(),
+                )
+                .map_err(|e| data_error(&e))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            options,
+            decimals,
+            dates,
+            date_times,
+        })
+    }
+
+    ///
+    /// Returns the options this formatter was created with.
+    ///
+    pub fn options(&self) -> &LocaleOptions {
+        &self.options
+    }
+
+    ///
+    /// Format the integer `value`.
+    ///
+    pub fn format_integer(&self, value: i64) -> String {
+        self.decimals.format_to_string(&FixedDecimal::from(value))
+    }
+
+    ///
+    /// Format the floating-point `value`; non-finite values are written as `NaN`, `inf`, and
+    /// `-inf`, as by [`NumberOptions`](crate::numbers::NumberOptions).
+    ///
+    pub fn format_f64(&self, value: f64) -> String {
+        match FixedDecimal::try_from_f64(value, FloatPrecision::Floating) {
+            Ok(mut decimal) => {
+                if let Some(fraction_digits) = self.options.fraction_digits {
+                    let position = -i16::try_from(fraction_digits).unwrap_or(i16::MAX);
+                    decimal.half_even(position);
+                    decimal.pad_end(position);
+                }
+                self.decimals.format_to_string(&decimal)
+            }
+            Err(_) => value.to_string(),
+        }
+    }
+
+    ///
+    /// Format `date`, in the Gregorian calendar, with the date length of the options.
+    ///
+    pub fn format_date(&self, date: &Date<Iso>) -> String {
+        self.dates.format_to_string(&date.to_calendar(Gregorian))
+    }
+
+    ///
+    /// Format `datetime`, in the Gregorian calendar, with the date and time lengths of the
+    /// options; if the time length is [`TimeLength::None`] only the date is written.
+    ///
+    pub fn format_datetime(&self, datetime: &DateTime<Iso>) -> String {
+        let datetime = datetime.to_calendar(Gregorian);
+        match &self.date_times {
+            Some(date_times) => date_times.format_to_string(&datetime),
+            None => self.dates.format_to_string(&datetime.date),
+        }
+    }
+
+    ///
+    /// Format the `chrono` date-time `datetime`, as by [`format_datetime`](Self::format_datetime).
+    ///
+    #[cfg(feature = "chrono")]
+    pub fn format_naive_datetime(&self, datetime: &chrono::NaiveDateTime) -> Result<String, Error> {
+        use chrono::{Datelike, Timelike};
+        let to_u8 = |value: u32| u8::try_from(value).unwrap_or(u8::MAX);
+        let datetime = DateTime::try_new_iso_datetime(
+            datetime.year(),
+            to_u8(datetime.month()),
+            to_u8(datetime.day()),
+            to_u8(datetime.hour()),
+            to_u8(datetime.minute()),
+            to_u8(datetime.second()),
+        )
+        .map_err(|e| Error::invalid_options(format!("date-time is out of range: {e}")))?;
+        Ok(self.format_datetime(&datetime))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_numbers() {
+        let formatter = LocaleOptions::new("fr").formatter().unwrap();
+        assert_eq!(
+            formatter.format_integer(-1234567),
+            "-1\u{202f}234\u{202f}567"
+        );
+        assert_eq!(formatter.format_f64(0.1 + 0.2), "0,30000000000000004");
+        assert_eq!(formatter.format_f64(f64::NAN), "NaN");
+
+        let formatter = LocaleOptions::new("en-IN")
+            .with_fraction_digits(Some(1))
+            .formatter()
+            .unwrap();
+        assert_eq!(formatter.format_f64(12345678.25), "1,23,45,678.2");
+
+        let formatter = LocaleOptions::default()
+            .with_grouping(false)
+            .with_fraction_digits(Some(0))
+            .formatter()
+            .unwrap();
+        assert_eq!(formatter.format_f64(12345.5), "12346");
+    }
+
+    #[test]
+    fn test_format_dates() {
+        let date = Date::try_new_iso_date(2024, 12, 31).unwrap();
+        let formatter =
+            LocaleOptions::from_option_strings("locale=en-GB,date_length=full,time_length=medium")
+                .unwrap()
+                .formatter()
+                .unwrap();
+        assert_eq!(formatter.format_date(&date), "Tuesday, 31 December 2024");
+
+        let datetime = DateTime::try_new_iso_datetime(2024, 12, 31, 23, 59, 1).unwrap();
+        assert_eq!(
+            formatter.format_datetime(&datetime),
+            "Tuesday, 31 December 2024, 23:59:01"
+        );
+    }
+
+    #[test]
+    fn test_invalid_locale() {
+        let options = LocaleOptions::new("not a locale");
+        assert!(options.validate().is_err());
+        assert!(matches!(
+            options.formatter(),
+            Err(Error::InvalidOptions { .. })
+        ));
+    }
+}
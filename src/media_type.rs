@@ -0,0 +1,59 @@
+/*!
+Provides the [`HasMediaType`] trait, implemented by readers and writers that can identify the
+IANA media type of the content they handle, for use in `Content-Type` headers and content
+negotiation.
+
+The serde-based readers and writers, with the `serde` feature, take their media type from
+`formats::SerdeFormat::MEDIA_TYPE`.
+
+# Example
+
+```rust
+use objio::media_type::{HasMediaType, APPLICATION_OCTET_STREAM};
+use objio::raw::RawWriter;
+
+assert_eq!(RawWriter.media_type(), APPLICATION_OCTET_STREAM);
+```
+ */
+
+use crate::raw::{RawReader, RawWriter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The media type of arbitrary binary content, used when no more specific type is known.
+///
+pub const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by readers and writers that identify the media type of their content.
+///
+pub trait HasMediaType {
+    ///
+    /// Returns the media type, such as `application/json`, optionally with parameters such as
+    /// `charset`.
+    ///
+    fn media_type(&self) -> &str;
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl HasMediaType for RawReader {
+    fn media_type(&self) -> &str {
+        APPLICATION_OCTET_STREAM
+    }
+}
+
+impl HasMediaType for RawWriter {
+    fn media_type(&self) -> &str {
+        APPLICATION_OCTET_STREAM
+    }
+}
@@ -0,0 +1,226 @@
+/*!
+Provides [`MappedFileSink`], enabled by the `mmap` feature, a destination that writes a file
+through a memory map, avoiding the copy from a user buffer into the page cache made by each
+`write` system call; this benefits very large outputs.
+
+The file is pre-sized to an expected length, commonly the
+[`write_size_hint`](crate::ObjectWriter::write_size_hint) of a writer, and mapped. If the output
+exceeds this length the file is extended and mapped again, and when the sink is finished the file
+is truncated to the length actually written. The writer method
+[`write_to_file_mapped`](crate::ObjectWriter::write_to_file_mapped) uses this sink when a writer
+provides a size hint, and otherwise falls back to
+[`write_to_file`](crate::ObjectWriter::write_to_file).
+
+As with any memory map, the file must not be modified or truncated by another process while it is
+being written.
+
+# Example
+
+```rust,no_run
+use objio::mmap::MappedFileSink;
+use std::io::Write;
+
+let mut sink = MappedFileSink::create("output.txt", 1024).unwrap();
+for i in 0..1000 {
+    writeln!(sink, "record {i}").unwrap();
+}
+let file = sink.finish().unwrap();
+
+assert_eq!(file.metadata().unwrap().len(), 10_890);
+```
+ */
+
+use memmap2::MmapMut;
+use std::fmt::{Debug, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{Error as IoError, Write};
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A destination that writes a file through a memory map, extending it as required.
+///
+pub struct MappedFileSink {
+    file: Option<File>,
+    map: Option<MmapMut>,
+    position: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for MappedFileSink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedFileSink")
+            .field("file", &self.file)
+            .field("capacity", &self.capacity())
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl Write for MappedFileSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let end = self.position + buf.len();
+        if end > self.capacity() {
+            self.remap(end.max(self.capacity() * 2))?;
+        }
+        if let Some(map) = self.map.as_mut() {
+            map[self.position..end].copy_from_slice(buf);
+        }
+        self.position = end;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &self.map {
+            Some(map) => map.flush_async(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for MappedFileSink {
+    fn drop(&mut self) {
+        let _ = self.truncate();
+    }
+}
+
+impl MappedFileSink {
+    ///
+    /// Create, or truncate, the file identified by `path`, pre-sized to `capacity` bytes.
+    ///
+    pub fn create<P>(path: P, capacity: usize) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+    {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path.as_ref())?;
+        let mut new_self = Self {
+            file: Some(file),
+            map: None,
+            position: 0,
+        };
+        new_self.remap(capacity)?;
+        Ok(new_self)
+    }
+
+    ///
+    /// Returns the number of bytes written.
+    ///
+    pub fn len(&self) -> usize {
+        self.position
+    }
+
+    ///
+    /// Returns `true` if no bytes have been written.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.position == 0
+    }
+
+    ///
+    /// Returns the number of bytes that may be written before the file is extended.
+    ///
+    pub fn capacity(&self) -> usize {
+        self.map.as_ref().map(|map| map.len()).unwrap_or_default()
+    }
+
+    ///
+    /// Unmap the file, truncate it to the number of bytes written, and return it. Dropping the
+    /// sink without calling this method truncates the file but ignores any error.
+    ///
+    pub fn finish(mut self) -> Result<File, IoError> {
+        self.truncate()?;
+        Ok(self.file.take().expect("the file is only taken by finish"))
+    }
+
+    fn remap(&mut self, capacity: usize) -> Result<(), IoError> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        // The content of a shared map is already in the page cache, so it need not be flushed.
+        drop(self.map.take());
+        file.set_len(capacity as u64)?;
+        if capacity > 0 {
+            // SAFETY: the file was created, or truncated, by this sink and is not modified by
+            // it other than through the map; modification by another process is documented as
+            // unsupported.
+            #[allow(unsafe_code)]
+            let map = unsafe { MmapMut::map_mut(file)? };
+            self.map = Some(map);
+        }
+        Ok(())
+    }
+
+    fn truncate(&mut self) -> Result<(), IoError> {
+        drop(self.map.take());
+        match &self.file {
+            Some(file) => file.set_len(self.position as u64),
+            None => Ok(()),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ObjectWriter;
+
+    #[derive(Debug)]
+    struct LinesWriter {
+        size_hint: usize,
+    }
+
+    impl ObjectWriter<Vec<String>> for LinesWriter {
+        type Error = IoError;
+
+        fn write<W: Write>(&self, w: &mut W, object: &Vec<String>) -> Result<(), Self::Error> {
+            object.iter().try_for_each(|line| writeln!(w, "{line}"))
+        }
+
+        fn write_size_hint(&self, _object: &Vec<String>) -> usize {
+            self.size_hint
+        }
+    }
+
+    #[test]
+    fn test_write_mapped() {
+        let path = std::env::temp_dir().join(format!("objio-mmap-{}.txt", std::process::id()));
+        let lines: Vec<String> = (0..1000).map(|i| format!("line {i}")).collect();
+        let expected = LinesWriter { size_hint: 0 }
+            .write_to_string(&lines)
+            .unwrap();
+
+        for size_hint in [0, 10, expected.len(), 100_000] {
+            LinesWriter { size_hint }
+                .write_to_file_mapped(&lines, &path)
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_drop_truncates() {
+        let path = std::env::temp_dir().join(format!("objio-mmap-{}.bin", std::process::id()));
+        let mut sink = MappedFileSink::create(&path, 4096).unwrap();
+        sink.write_all(b"partial").unwrap();
+        assert_eq!(sink.capacity(), 4096);
+        drop(sink);
+        assert_eq!(std::fs::read(&path).unwrap(), b"partial");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,447 @@
+/*!
+Provides the [`Normalize`] trait, a hook that fixes up an object before it is written, such as by
+trimming whitespace, sorting, or removing duplicates, and records each change made in a
+[`NormalizeReport`], so that cleaning on write leaves an audit trail.
+
+Any closure `Fn(&mut T, &mut NormalizeReport)` is a normalizer, and the types [`Trim`], [`Sort`],
+and [`Dedup`] provide common fix-ups. The adapter [`NormalizingWriter`] normalizes a copy of each
+object before it is written by an inner writer; its report is returned by
+[`NormalizingWriter::write_normalized`], or reported as diagnostics by the `ObjectWriter`
+methods.
+
+# Example
+
+```rust
+use objio::normalize::{Dedup, Normalize, NormalizeReport, NormalizingWriter, Trim};
+use objio::ObjectWriter;
+use std::io::Write;
+
+#[derive(Clone, Debug)]
+struct Contact {
+    name: String,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct ContactWriter;
+
+impl ObjectWriter<Contact> for ContactWriter {
+    type Error = std::io::Error;
+
+    fn write<W: Write>(&self, w: &mut W, object: &Contact) -> Result<(), Self::Error> {
+        write!(w, "{} [{}]", object.name, object.tags.join(","))
+    }
+}
+
+let clean = |contact: &mut Contact, report: &mut NormalizeReport| {
+    report.within("/name", |report| Trim.normalize(&mut contact.name, report));
+    report.within("/tags", |report| Dedup.normalize(&mut contact.tags, report));
+};
+
+let contact = Contact {
+    name: " Ada ".to_string(),
+    tags: vec!["math".to_string(), "math".to_string()],
+};
+let writer = NormalizingWriter::new(ContactWriter, clean);
+let mut output = Vec::new();
+let report = writer.write_normalized(&mut output, &contact).unwrap();
+
+assert_eq!(output, b"Ada [math]");
+assert_eq!(report.len(), 2);
+assert_eq!(report.changes()[0].to_string(), "/name: trimmed whitespace");
+```
+ */
+
+use crate::diagnostics::{Diagnostic, DiagnosticSink};
+use crate::options::Buffering;
+use crate::ObjectWriter;
+use std::collections::HashSet;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+use std::io::Write;
+use std::sync::Arc;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by types which fix up instances of `T` before they are written.
+///
+pub trait Normalize<T: ?Sized> {
+    ///
+    /// Normalize `object` in place, recording each change made in `report`.
+    ///
+    fn normalize(&self, object: &mut T, report: &mut NormalizeReport);
+
+    ///
+    /// Returns a normalizer that applies this normalizer and then `next`.
+    ///
+    fn and_then<N>(self, next: N) -> Then<Self, N>
+    where
+        Self: Sized,
+        N: Normalize<T>,
+    {
+        Then {
+            first: self,
+            second: next,
+        }
+    }
+}
+
+///
+/// A single change made by a normalizer.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    path: String,
+    description: String,
+}
+
+///
+/// The changes made while normalizing an object, in the order they were made.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NormalizeReport {
+    prefix: String,
+    changes: Vec<Change>,
+}
+
+///
+/// A normalizer that removes leading and trailing whitespace from a `String`.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Trim;
+
+///
+/// A normalizer that sorts the elements of a `Vec`.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sort;
+
+///
+/// A normalizer that removes duplicate elements from a `Vec`, keeping the first occurrence of
+/// each.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Dedup;
+
+///
+/// A normalizer that applies two normalizers in sequence, returned by [`Normalize::and_then`].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+///
+/// A writer that normalizes a copy of each object before it is written by an inner writer.
+///
+pub struct NormalizingWriter<O, N> {
+    inner: O,
+    normalizer: N,
+    sink: Option<Arc<dyn DiagnosticSink + Send + Sync>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<T, F> Normalize<T> for F
+where
+    T: ?Sized,
+    F: Fn(&mut T, &mut NormalizeReport),
+{
+    fn normalize(&self, object: &mut T, report: &mut NormalizeReport) {
+        self(object, report)
+    }
+}
+
+impl<T, A, B> Normalize<T> for Then<A, B>
+where
+    T: ?Sized,
+    A: Normalize<T>,
+    B: Normalize<T>,
+{
+    fn normalize(&self, object: &mut T, report: &mut NormalizeReport) {
+        self.first.normalize(object, report);
+        self.second.normalize(object, report);
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Normalize<String> for Trim {
+    fn normalize(&self, object: &mut String, report: &mut NormalizeReport) {
+        let trimmed = object.trim();
+        if trimmed.len() != object.len() {
+            *object = trimmed.to_string();
+            report.record("", "trimmed whitespace");
+        }
+    }
+}
+
+impl<T> Normalize<Vec<T>> for Sort
+where
+    T: Ord,
+{
+    fn normalize(&self, object: &mut Vec<T>, report: &mut NormalizeReport) {
+        if !object.is_sorted() {
+            object.sort();
+            report.record("", "sorted elements");
+        }
+    }
+}
+
+impl<T> Normalize<Vec<T>> for Dedup
+where
+    T: Eq + Hash,
+{
+    fn normalize(&self, object: &mut Vec<T>, report: &mut NormalizeReport) {
+        let mut seen = HashSet::with_capacity(object.len());
+        let keep: Vec<bool> = object.iter().map(|element| seen.insert(element)).collect();
+        let removed = keep.iter().filter(|kept| !**kept).count();
+        if removed > 0 {
+            let mut keep = keep.into_iter();
+            object.retain(|_| keep.next().unwrap_or(true));
+            report.record("", format!("removed {removed} duplicate elements"));
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.description)
+        } else {
+            write!(f, "{}: {}", self.path, self.description)
+        }
+    }
+}
+
+impl Change {
+    ///
+    /// Returns the path, in the syntax of a JSON Pointer, of the changed value within the object;
+    /// the empty path identifies the object itself.
+    ///
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    ///
+    /// Returns a description of the change.
+    ///
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl NormalizeReport {
+    ///
+    /// Record a change to the value at `path`, relative to the current path, with the given
+    /// description.
+    ///
+    pub fn record<S>(&mut self, path: &str, description: S)
+    where
+        S: Into<String>,
+    {
+        self.changes.push(Change {
+            path: format!("{}{path}", self.prefix),
+            description: description.into(),
+        });
+    }
+
+    ///
+    /// Call `f` with `path` appended to the current path, so that changes recorded by a
+    /// normalizer of a part of an object are identified within the whole.
+    ///
+    pub fn within<F, R>(&mut self, path: &str, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let length = self.prefix.len();
+        self.prefix.push_str(path);
+        let result = f(self);
+        self.prefix.truncate(length);
+        result
+    }
+
+    ///
+    /// Returns `true` if no changes were made.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    ///
+    /// Returns the number of changes made.
+    ///
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    ///
+    /// Returns the changes made.
+    ///
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    ///
+    /// Consume this report, returning the changes made.
+    ///
+    pub fn into_changes(self) -> Vec<Change> {
+        self.changes
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O, N> Debug for NormalizingWriter<O, N>
+where
+    O: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NormalizingWriter")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O, N, T> ObjectWriter<T> for NormalizingWriter<O, N>
+where
+    O: ObjectWriter<T>,
+    N: Normalize<T>,
+    T: Clone,
+{
+    type Error = O::Error;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        let report = self.write_normalized(w, object)?;
+        if let Some(sink) = &self.sink {
+            for change in report.into_changes() {
+                sink.report(Diagnostic::info(format!("normalized {change}")));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.inner.validate_options()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.inner.buffering()
+    }
+}
+
+impl<O, N> NormalizingWriter<O, N> {
+    ///
+    /// Construct a new writer that applies `normalizer` to a copy of each object before it is
+    /// written by `inner`.
+    ///
+    pub fn new(inner: O, normalizer: N) -> Self {
+        Self {
+            inner,
+            normalizer,
+            sink: None,
+        }
+    }
+
+    ///
+    /// Returns this writer with each change made by the `ObjectWriter` methods reported to `sink`
+    /// as an informational diagnostic; by default they are discarded.
+    ///
+    pub fn with_diagnostics(self, sink: Arc<dyn DiagnosticSink + Send + Sync>) -> Self {
+        let mut self_mut = self;
+        self_mut.sink = Some(sink);
+        self_mut
+    }
+
+    ///
+    /// Returns a reference to the inner writer.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Normalize a copy of `object` and write it to `w`, returning the report of the changes made.
+    ///
+    pub fn write_normalized<W, T>(&self, w: &mut W, object: &T) -> Result<NormalizeReport, O::Error>
+    where
+        W: Write,
+        O: ObjectWriter<T>,
+        N: Normalize<T>,
+        T: Clone,
+    {
+        let mut object = object.clone();
+        let mut report = NormalizeReport::default();
+        self.normalizer.normalize(&mut object, &mut report);
+        self.inner.write(w, &object)?;
+        Ok(report)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::CollectDiagnostics;
+
+    #[derive(Debug, Default)]
+    struct ListWriter;
+
+    impl ObjectWriter<Vec<u32>> for ListWriter {
+        type Error = std::io::Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &Vec<u32>) -> Result<(), Self::Error> {
+            write!(w, "{object:?}")
+        }
+    }
+
+    #[test]
+    fn test_builtin_normalizers() {
+        let mut report = NormalizeReport::default();
+        let mut list: Vec<u32> = vec![3, 1, 3, 2, 1];
+        let normalizer = Normalize::<Vec<u32>>::and_then(Dedup, Sort);
+        normalizer.normalize(&mut list, &mut report);
+        assert_eq!(list, vec![1, 2, 3]);
+        normalizer.normalize(&mut list, &mut report);
+
+        let mut name = "unchanged".to_string();
+        report.within("/name", |report| Trim.normalize(&mut name, report));
+        assert_eq!(
+            report
+                .changes()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["removed 2 duplicate elements", "sorted elements"]
+        );
+    }
+
+    #[test]
+    fn test_normalizing_writer_diagnostics() {
+        let sink = Arc::new(CollectDiagnostics::default());
+        let writer = NormalizingWriter::new(ListWriter, Sort).with_diagnostics(sink.clone());
+        let list = vec![2, 1];
+        assert_eq!(writer.write_to_string(&list).unwrap(), "[1, 2]");
+        assert_eq!(list, vec![2, 1]);
+        assert_eq!(
+            sink.diagnostics(),
+            vec![Diagnostic::info("normalized sorted elements")]
+        );
+    }
+}
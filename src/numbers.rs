@@ -0,0 +1,306 @@
+/*!
+Provides number formatting options and helpers, so that writers of text formats produce
+consistent numeric output.
+
+The [`NumberOptions`] type, part of [`CommonWriterOptions`](crate::options::CommonWriterOptions),
+determines how floating-point values are written. By default a value is written with the fewest
+digits that read back as the same value, and always with a decimal point or exponent so that it
+reads back as a floating-point, rather than integer, value; scientific notation is used for values
+whose decimal exponent is outside the range set by the scientific threshold. A fixed precision
+may be set instead, which is not guaranteed to round-trip. The canonical mode writes the shortest
+representation in the form used by ECMAScript, and so by the JSON Canonicalization Scheme of
+[RFC 8785](https://www.rfc-editor.org/rfc/rfc8785), ignoring the other options.
+
+Non-finite values are written as `NaN`, `inf`, and `-inf`; writers for formats that represent
+these differently, or not at all, should check for them before calling the helpers.
+
+# Example
+
+```rust
+use objio::numbers::NumberOptions;
+
+let options = NumberOptions::default();
+assert_eq!(options.format_f64(1.0), "1.0");
+assert_eq!(options.format_f64(0.1 + 0.2), "0.30000000000000004");
+assert_eq!(options.format_f64(6.02214076e23), "6.02214076e23");
+
+let options = options.with_precision(Some(2));
+assert_eq!(options.format_f64(3.14159), "3.14");
+
+let options = NumberOptions::default().with_canonical(true);
+assert_eq!(options.format_f64(1.0), "1");
+assert_eq!(options.format_f64(1e21), "1e+21");
+assert_eq!(options.format_f32(0.1), "0.1");
+```
+ */
+
+use std::fmt::{Display, LowerExp};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The default decimal exponent at, or beyond, which values are written in scientific notation.
+///
+pub const DEFAULT_SCIENTIFIC_THRESHOLD: u16 = 16;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Options determining how floating-point numbers are written.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct NumberOptions {
+    precision: Option<u16>,
+    scientific_threshold: u16,
+    canonical: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for NumberOptions {
+    fn default() -> Self {
+        Self {
+            precision: None,
+            scientific_threshold: DEFAULT_SCIENTIFIC_THRESHOLD,
+            canonical: false,
+        }
+    }
+}
+
+impl NumberOptions {
+    ///
+    /// Returns a copy of these options with the number of digits after the decimal point set to
+    /// `precision`, where `None` indicates the shortest representation that round-trips.
+    ///
+    pub fn with_precision(self, precision: Option<u16>) -> Self {
+        let mut self_mut = self;
+        self_mut.precision = precision;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the scientific notation threshold set to
+    /// `scientific_threshold`.
+    ///
+    pub fn with_scientific_threshold(self, scientific_threshold: u16) -> Self {
+        let mut self_mut = self;
+        self_mut.scientific_threshold = scientific_threshold;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with canonical formatting set to `canonical`.
+    ///
+    pub fn with_canonical(self, canonical: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.canonical = canonical;
+        self_mut
+    }
+
+    ///
+    /// Returns the number of digits written after the decimal point, or `None` if the shortest
+    /// representation that round-trips is written.
+    ///
+    pub fn precision(&self) -> Option<u16> {
+        self.precision
+    }
+
+    ///
+    /// Set the number of digits written after the decimal point, where `None` indicates the
+    /// shortest representation that round-trips.
+    ///
+    pub fn set_precision(&mut self, precision: Option<u16>) {
+        self.precision = precision;
+    }
+
+    ///
+    /// Returns the scientific notation threshold; a non-zero value is written in scientific
+    /// notation if its magnitude is at least 10 to this power, or less than 10 to its negation.
+    ///
+    pub fn scientific_threshold(&self) -> u16 {
+        self.scientific_threshold
+    }
+
+    ///
+    /// Set the scientific notation threshold.
+    ///
+    pub fn set_scientific_threshold(&mut self, scientific_threshold: u16) {
+        self.scientific_threshold = scientific_threshold;
+    }
+
+    ///
+    /// Returns `true` if values are written in the canonical form used by ECMAScript, ignoring the
+    /// precision and threshold.
+    ///
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    ///
+    /// Set whether values are written in the canonical form used by ECMAScript.
+    ///
+    pub fn set_canonical(&mut self, canonical: bool) {
+        self.canonical = canonical;
+    }
+
+    ///
+    /// Format `value` according to these options.
+    ///
+    pub fn format_f64(&self, value: f64) -> String {
+        self.format(value)
+    }
+
+    ///
+    /// Format `value` according to these options; the shortest representation is that which
+    /// reads back as the same `f32`.
+    ///
+    pub fn format_f32(&self, value: f32) -> String {
+        self.format(value)
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    fn format<F>(&self, value: F) -> String
+    where
+        F: Copy + Display + LowerExp + Into<f64>,
+    {
+        let float: f64 = value.into();
+        if !float.is_finite() {
+            return value.to_string();
+        }
+        // The shortest round-trip digits, and decimal exponent, of the value.
+        let scientific = format!("{value:e}");
+        let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+        let exponent: i32 = exponent.parse().unwrap_or_default();
+        if self.canonical {
+            return canonical(float, mantissa, exponent);
+        }
+        let threshold = i32::from(self.scientific_threshold);
+        let use_scientific = float != 0.0 && (exponent >= threshold || exponent < -threshold);
+        match (self.precision, use_scientific) {
+            (Some(precision), true) => {
+                format!("{value:.precision$e}", precision = usize::from(precision))
+            }
+            (Some(precision), false) => {
+                format!("{value:.precision$}", precision = usize::from(precision))
+            }
+            (None, true) => scientific,
+            (None, false) => {
+                let fixed = value.to_string();
+                if fixed.contains('.') {
+                    fixed
+                } else {
+                    format!("{fixed}.0")
+                }
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The algorithm of ECMAScript `Number::toString`, given the shortest round-trip digits.
+///
+fn canonical(value: f64, mantissa: &str, exponent: i32) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+    let body = if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat((-n) as usize))
+    } else {
+        let fraction = match k {
+            1 => String::new(),
+            _ => format!(".{}", &digits[1..]),
+        };
+        format!(
+            "{}{fraction}e{}{}",
+            &digits[..1],
+            if n > 0 { "+" } else { "-" },
+            (n - 1).abs()
+        )
+    };
+    format!("{sign}{body}")
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortest_round_trips() {
+        let options = NumberOptions::default();
+        for value in [0.1, -2.5, 1e15, 1e16, 123456.789, 5e-324, f64::MAX, -0.0] {
+            let text = options.format_f64(value);
+            assert!(text.contains(['.', 'e']), "{text}");
+            assert_eq!(text.parse::<f64>().unwrap().to_bits(), value.to_bits());
+        }
+        assert_eq!(options.format_f64(1e16), "1e16");
+        assert_eq!(options.format_f64(1e-17), "1e-17");
+        assert_eq!(options.format_f64(-0.0), "-0.0");
+        assert_eq!(options.format_f32(1.1), "1.1");
+        assert_eq!(options.format_f64(f64::NEG_INFINITY), "-inf");
+    }
+
+    #[test]
+    fn test_precision_and_threshold() {
+        let options = NumberOptions::default()
+            .with_precision(Some(3))
+            .with_scientific_threshold(3);
+        assert_eq!(options.format_f64(2.0), "2.000");
+        assert_eq!(options.format_f64(-1234.4), "-1.234e3");
+        assert_eq!(options.format_f64(0.0001234), "1.234e-4");
+        assert_eq!(options.format_f64(0.0), "0.000");
+    }
+
+    #[test]
+    fn test_canonical() {
+        let options = NumberOptions::default()
+            .with_canonical(true)
+            .with_precision(Some(1));
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (123e18, "123000000000000000000"),
+            (1e21, "1e+21"),
+            (1.5e-7, "1.5e-7"),
+            (0.000001, "0.000001"),
+            (333333333.3333333, "333333333.3333333"),
+            (5e-324, "5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(options.format_f64(value), expected);
+        }
+    }
+}
@@ -0,0 +1,2372 @@
+/*!
+Provides standard option types that may be used, directly or as a field within a larger options
+structure, by reader and writer implementations.
+
+Using these types allows common configuration, such as whether output should be pretty-printed,
+to be expressed in the same manner across different formats.
+
+# Example
+
+```rust
+use objio::options::{CommonWriterOptions, Pretty};
+
+let options = CommonWriterOptions::default().pretty().with_indent(4);
+
+assert!(options.is_pretty());
+assert_eq!(options.indent(), 4);
+
+let options: CommonWriterOptions = Pretty.into();
+
+assert!(options.is_pretty());
+```
+ */
+
+use crate::columns::ColumnWriter;
+use crate::diagnostics::{Diagnostic, DiagnosticSink};
+use crate::error::Error;
+use crate::numbers::NumberOptions;
+use crate::style::StyleOptions;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+#[cfg(feature = "derive")]
+pub use objio_derive::{
+    DescribeOptions, FromOptionStrings, MergeOptions, OptionsBuilder, OptionsFromEnv,
+};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// This trait is implemented by option types that can be updated by merging in a partial set of
+/// values, allowing options to be built up in layers; for example, defaults, then a profile, then
+/// per-call overrides.
+///
+/// With the `derive` feature enabled this trait may be derived for structs with named fields; the
+/// derive generates a corresponding overlay struct named `{Name}Overlay`.
+///
+/// # Example
+///
+/// ```rust
+/// use objio::options::{
+///     CommonWriterOptions, CommonWriterOptionsOverlay, MergeOptions, OptionsOverlay,
+/// };
+///
+/// let profile = CommonWriterOptionsOverlay {
+///     pretty: Some(true),
+///     indent: Some(4),
+///     ..Default::default()
+/// };
+/// let overrides = CommonWriterOptionsOverlay {
+///     indent: Some(8),
+///     ..Default::default()
+/// };
+///
+/// let options = CommonWriterOptions::default().merged(profile.overlay(overrides));
+///
+/// assert!(options.is_pretty());
+/// assert_eq!(options.indent(), 8);
+/// ```
+///
+pub trait MergeOptions {
+    ///
+    /// The type holding a partial set of values for this options type.
+    ///
+    type Overlay: OptionsOverlay;
+
+    ///
+    /// Replace each value in this options instance with the corresponding value in `overlay`,
+    /// if that value is present.
+    ///
+    fn merge(&mut self, overlay: Self::Overlay);
+
+    ///
+    /// Returns this options instance with `overlay` merged into it.
+    ///
+    fn merged(self, overlay: Self::Overlay) -> Self
+    where
+        Self: Sized,
+    {
+        let mut self_mut = self;
+        self_mut.merge(overlay);
+        self_mut
+    }
+}
+
+///
+/// This trait is implemented by the overlay type associated with an implementation of
+/// [`MergeOptions`].
+///
+pub trait OptionsOverlay: Default {
+    ///
+    /// Combine two overlays where the values present in `other` take precedence over those in
+    /// `self`.
+    ///
+    fn overlay(self, other: Self) -> Self;
+}
+
+///
+/// This trait is implemented by option types that can be derived from the options of a parent, so
+/// that a composite writer, or reader, delegating part of its work to a child applies the same
+/// propagation rules as any other. Only those options that describe the shared output, such as
+/// pretty-printing, widths, and strictness, are inherited; options that describe the child's own
+/// format are kept.
+///
+/// The parent options type `P` defaults to the child's own type; an options type that embeds
+/// [`CommonWriterOptions`] may also implement `InheritOptions<CommonWriterOptions>` by delegating
+/// to it.
+///
+/// # Example
+///
+/// ```rust
+/// use objio::options::{CommonWriterOptions, FormatVersion, InheritOptions};
+///
+/// let parent = CommonWriterOptions::default().pretty().with_indent(4);
+/// let child = CommonWriterOptions::default()
+///     .with_target_version(Some(FormatVersion::new(1, 1)))
+///     .inherited_from(&parent);
+///
+/// assert!(child.is_pretty());
+/// assert_eq!(child.indent(), 4);
+/// assert_eq!(child.target_version(), Some(FormatVersion::new(1, 1)));
+/// ```
+///
+pub trait InheritOptions<P: ?Sized = Self> {
+    ///
+    /// Replace the inheritable options in this instance with those of `parent`.
+    ///
+    fn inherit(&mut self, parent: &P);
+
+    ///
+    /// Returns this options instance with the inheritable options of `parent`.
+    ///
+    fn inherited_from(self, parent: &P) -> Self
+    where
+        Self: Sized,
+    {
+        let mut self_mut = self;
+        self_mut.inherit(parent);
+        self_mut
+    }
+}
+
+///
+/// This trait is implemented by option types that can check their own consistency, for example
+/// that a line width exceeds the indentation width. The convenience methods on
+/// [`ObjectReader`](crate::ObjectReader) and [`ObjectWriter`](crate::ObjectWriter) call
+/// `validate_options` before any work begins, and the implementations in this crate use this
+/// trait to do so.
+///
+/// # Example
+///
+/// ```rust
+/// use objio::options::{CommonWriterOptions, ValidateOptions};
+/// use objio::Error;
+///
+/// let options = CommonWriterOptions::default().with_indent(8).with_line_width(4);
+///
+/// assert!(matches!(options.validate(), Err(Error::InvalidOptions { .. })));
+/// ```
+///
+pub trait ValidateOptions {
+    ///
+    /// Returns `Ok(())` if these options are consistent, else an
+    /// [`Error::InvalidOptions`] describing the first violation found.
+    ///
+    fn validate(&self) -> Result<(), Error>;
+}
+
+///
+/// This trait is implemented by option types that can describe each of their options at runtime,
+/// allowing generic tools to enumerate the options of any reader or writer, for example to render
+/// `--help` text or a settings form.
+///
+/// With the `derive` feature enabled this trait may be derived for structs with named fields that
+/// implement `Default`; each option is documented by the field's doc comment.
+///
+/// # Example
+///
+/// ```rust
+/// use objio::options::{CommonWriterOptions, DescribeOptions};
+///
+/// let descriptors = CommonWriterOptions::describe_options();
+///
+/// assert_eq!(descriptors[1].name(), "indent");
+/// assert_eq!(descriptors[1].type_name(), "usize");
+/// assert_eq!(descriptors[1].default_value(), "2");
+/// ```
+///
+pub trait DescribeOptions {
+    ///
+    /// Returns a descriptor for each option, in declaration order.
+    ///
+    fn describe_options() -> Vec<OptionDescriptor>;
+
+    ///
+    /// Returns a table, one option per line, of the name, type, default value, and
+    /// documentation of each option.
+    ///
+    fn options_help() -> String {
+        let mut table = ColumnWriter::default();
+        for descriptor in Self::describe_options() {
+            table.add_row([
+                descriptor.name().to_string(),
+                descriptor.type_name().to_string(),
+                format!("[default: {}]", descriptor.default_value()),
+                descriptor.documentation().to_string(),
+            ]);
+        }
+        table.write_to_string()
+    }
+}
+
+///
+/// This trait is implemented by option types that can be populated from `key=value` strings,
+/// allowing command-line tools to pass format-specific options through a single generic flag.
+///
+/// Keys are field names, where `-` may be used in place of `_`, and the keys of nested options
+/// types are prefixed with the field name and a `.`, as in `style.color=never`.
+///
+/// With the `derive` feature enabled this trait may be derived for structs with named fields
+/// where each field type implements `FromStr`.
+///
+/// # Example
+///
+/// ```rust
+/// use objio::options::{CommonWriterOptions, FromOptionStrings, LineEnding};
+/// use objio::Error;
+///
+/// let options = CommonWriterOptions::from_option_strings("pretty,indent=4,line-ending=crlf")
+///     .unwrap();
+///
+/// assert!(options.is_pretty());
+/// assert_eq!(options.indent(), 4);
+/// assert_eq!(options.line_ending(), LineEnding::CrLf);
+///
+/// assert!(matches!(
+///     CommonWriterOptions::from_option_strings("indnet=4"),
+///     Err(Error::UnknownOption { .. })
+/// ));
+/// ```
+///
+pub trait FromOptionStrings {
+    ///
+    /// Set the option identified by `key` to the result of parsing `value`.
+    ///
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error>;
+
+    ///
+    /// Set each option in the comma-separated list of `key=value` pairs in `options`. A key
+    /// without a value, such as `pretty`, is equivalent to `pretty=true`.
+    ///
+    fn set_option_strings(&mut self, options: &str) -> Result<(), Error> {
+        for option in options.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            match option.split_once('=') {
+                Some((key, value)) => self.set_option(key.trim(), value.trim())?,
+                None => self.set_option(option, "true")?,
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the default options with each option in `options` set as described for
+    /// [`set_option_strings`](Self::set_option_strings).
+    ///
+    fn from_option_strings(options: &str) -> Result<Self, Error>
+    where
+        Self: Default + Sized,
+    {
+        let mut new_self = Self::default();
+        new_self.set_option_strings(options)?;
+        Ok(new_self)
+    }
+}
+
+///
+/// This trait is implemented by option types that can be populated from environment variables,
+/// allowing long-running services to reconfigure readers and writers without code changes.
+///
+/// Each variable named with the prefix from [`env_prefix`](Self::env_prefix) followed by `_`
+/// identifies an option; the remainder of the name is lower-cased, with `__` identifying a
+/// nested option, and passed to [`FromOptionStrings::set_option`]. For example, with the prefix
+/// `MYAPP_WRITER` the variable `MYAPP_WRITER_LINE_WIDTH=100` sets the option `line_width` and
+/// `MYAPP_WRITER_STYLE__COLOR=never` sets the option `style.color`. Errors identify the variable
+/// by name rather than the option key.
+///
+/// As environment variables are usually one layer among several, [`with_env`](Self::with_env)
+/// may be chained after [`MergeOptions::merged`] to apply them after defaults and profiles.
+///
+/// With the `derive` feature enabled this trait may be derived, along with
+/// [`FromOptionStrings`], and the prefix set using the attribute
+/// `#[options_env(prefix = "...")]`.
+///
+/// # Example
+///
+/// ```rust
+/// use objio::options::{CommonWriterOptions, OptionsFromEnv};
+///
+/// let mut options = CommonWriterOptions::default();
+/// options
+///     .set_options_from_vars([("OBJIO_WRITER_INDENT", "4"), ("HOME", "/home/me")])
+///     .unwrap();
+///
+/// assert_eq!(options.indent(), 4);
+/// ```
+///
+pub trait OptionsFromEnv: FromOptionStrings {
+    ///
+    /// Returns the prefix, without a trailing `_`, of the environment variables that identify
+    /// options of this type.
+    ///
+    fn env_prefix() -> &'static str;
+
+    ///
+    /// Set each option identified by a variable in the current process environment.
+    ///
+    fn set_options_from_env(&mut self) -> Result<(), Error> {
+        self.set_options_from_vars(std::env::vars())
+    }
+
+    ///
+    /// Set each option identified by a variable in `vars`; variables without the prefix are
+    /// ignored.
+    ///
+    fn set_options_from_vars<I, K, V>(&mut self, vars: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let prefix = format!("{}_", Self::env_prefix());
+        for (name, value) in vars {
+            let name = name.as_ref();
+            if let Some(key) = name.strip_prefix(&prefix) {
+                let key = key.to_lowercase().replace("__", ".");
+                self.set_option(&key, value.as_ref()).map_err(|e| match e {
+                    Error::UnknownOption { .. } => Error::unknown_option(name),
+                    Error::InvalidOptionValue { value, source, .. } => {
+                        Error::invalid_option_value(name, value, source)
+                    }
+                    e => e,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns these options with each option identified by a variable in the current process
+    /// environment set.
+    ///
+    fn with_env(self) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let mut self_mut = self;
+        self_mut.set_options_from_env()?;
+        Ok(self_mut)
+    }
+}
+
+///
+/// The error returned when parsing the standard option enumerations, such as [`LineEnding`],
+/// from strings.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptionValueError {
+    value: String,
+    expected: &'static [&'static str],
+}
+
+///
+/// A description of a single option, as returned by [`DescribeOptions::describe_options`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OptionDescriptor {
+    name: String,
+    type_name: &'static str,
+    default_value: String,
+    documentation: String,
+}
+
+///
+/// The default number of spaces used for each level of indentation when pretty-printing.
+///
+pub const DEFAULT_INDENT: usize = 2;
+
+///
+/// The default target line width for writers that break long lines when pretty-printing.
+///
+pub const DEFAULT_LINE_WIDTH: usize = 80;
+
+///
+/// The line ending sequence to use when writing text.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum LineEnding {
+    /// A single line feed, `\n`, as used on Unix-like platforms.
+    #[default]
+    Lf,
+    /// A carriage return and line feed pair, `\r\n`, as used on Windows.
+    CrLf,
+}
+
+///
+/// How output should be buffered before it is written to the destination.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Buffering {
+    /// Output is written in large blocks, as the buffer fills.
+    #[default]
+    Block,
+    /// Output is written at the end of each line, so that it appears promptly when streamed to
+    /// another process.
+    Line,
+    /// Output is written as it is produced.
+    None,
+}
+
+///
+/// The order in which writers should emit the entries of maps, and other keyed collections, whose
+/// order is not otherwise significant.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum CollectionOrdering {
+    /// Entries are written in the order in which they were inserted, or in the order of the
+    /// collection's own iterator.
+    #[default]
+    Insertion,
+    /// Entries are written in the lexicographic order of their keys.
+    Lexicographic,
+    /// Entries are written in the order of their keys as determined by a comparator; this
+    /// ordering can only be set programmatically.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(KeyComparator),
+}
+
+///
+/// A shared function comparing the keys of collection entries, used by
+/// [`CollectionOrdering::Custom`]. Two comparators are equal only if they share the same function.
+///
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+pub struct KeyComparator(std::sync::Arc<dyn Fn(&str, &str) -> Ordering + Send + Sync>);
+
+///
+/// The version of a format, as a major and minor number; versions are ordered so that readers and
+/// writers may compare a version against those they support. The textual form, used by
+/// `Display` and `FromStr`, is `major.minor` where `.minor` may be omitted when parsing.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct FormatVersion {
+    major: u16,
+    minor: u16,
+}
+
+///
+/// A preset that converts into [`CommonWriterOptions`] with pretty-printing enabled.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pretty;
+
+///
+/// A preset that converts into [`CommonWriterOptions`] with pretty-printing disabled.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Compact;
+
+///
+/// Options common to most writer implementations.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct CommonWriterOptions {
+    pretty: bool,
+    indent: usize,
+    line_width: usize,
+    line_ending: LineEnding,
+    buffering: Buffering,
+    deterministic: bool,
+    ordering: CollectionOrdering,
+    style: StyleOptions,
+    numbers: NumberOptions,
+    target_version: Option<FormatVersion>,
+}
+
+///
+/// A partial set of values to be merged into an instance of [`CommonWriterOptions`].
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct CommonWriterOptionsOverlay {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub pretty: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub indent: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub line_width: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub line_ending: Option<LineEnding>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub buffering: Option<Buffering>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub deterministic: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub ordering: Option<CollectionOrdering>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub style: Option<StyleOptions>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub numbers: Option<NumberOptions>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub target_version: Option<Option<FormatVersion>>,
+}
+
+///
+/// How a reader should treat content it does not recognize, such as unknown elements or
+/// properties, which is commonly the result of reading a document written by a newer version of a
+/// format.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum UnknownHandling {
+    /// Fail with an error.
+    #[default]
+    Error,
+    /// Skip the content, reporting a warning to the diagnostics sink.
+    Warn,
+    /// Skip the content silently.
+    Ignore,
+}
+
+///
+/// Whether a reader should fail on content it cannot decode or represent, or substitute a
+/// replacement value and continue, for best-effort ingest of damaged or unusual documents.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum ReadMode {
+    /// Fail with an error.
+    #[default]
+    Lossless,
+    /// Substitute a replacement value, reporting a warning to the diagnostics sink.
+    Lossy,
+}
+
+///
+/// The parts of an object a caller needs, so that a reader may skip parsing the rest. Each path is
+/// in the syntax of a JSON Pointer ([RFC 6901](https://www.rfc-editor.org/rfc/rfc6901)), where a
+/// segment `*` matches any member or element; selecting a path selects everything beneath it. The
+/// default projection, with no paths, selects the whole object.
+///
+/// The textual form, used by `Display` and `FromStr`, is the list of paths separated by `;`.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(transparent)
+)]
+pub struct Projection {
+    paths: Vec<String>,
+}
+
+///
+/// Options common to most reader implementations.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct CommonReaderOptions {
+    unknown_handling: UnknownHandling,
+    trailing_data: UnknownHandling,
+    read_mode: ReadMode,
+    projection: Projection,
+    preserve_comments: bool,
+    preserve_formatting: bool,
+}
+
+///
+/// A partial set of values to be merged into an instance of [`CommonReaderOptions`].
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct CommonReaderOptionsOverlay {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub unknown_handling: Option<UnknownHandling>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub trailing_data: Option<UnknownHandling>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub read_mode: Option<ReadMode>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub projection: Option<Projection>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub preserve_comments: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub preserve_formatting: Option<bool>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for CommonWriterOptions {
+    fn default() -> Self {
+        Self {
+            pretty: false,
+            indent: DEFAULT_INDENT,
+            line_width: DEFAULT_LINE_WIDTH,
+            line_ending: Default::default(),
+            buffering: Default::default(),
+            deterministic: false,
+            ordering: Default::default(),
+            style: Default::default(),
+            numbers: Default::default(),
+            target_version: None,
+        }
+    }
+}
+
+impl From<Pretty> for CommonWriterOptions {
+    fn from(_: Pretty) -> Self {
+        Self::default().pretty()
+    }
+}
+
+impl From<Compact> for CommonWriterOptions {
+    fn from(_: Compact) -> Self {
+        Self::default().compact()
+    }
+}
+
+impl MergeOptions for CommonWriterOptions {
+    type Overlay = CommonWriterOptionsOverlay;
+
+    fn merge(&mut self, overlay: Self::Overlay) {
+        if let Some(pretty) = overlay.pretty {
+            self.pretty = pretty;
+        }
+        if let Some(indent) = overlay.indent {
+            self.indent = indent;
+        }
+        if let Some(line_width) = overlay.line_width {
+            self.line_width = line_width;
+        }
+        if let Some(line_ending) = overlay.line_ending {
+            self.line_ending = line_ending;
+        }
+        if let Some(buffering) = overlay.buffering {
+            self.buffering = buffering;
+        }
+        if let Some(deterministic) = overlay.deterministic {
+            self.deterministic = deterministic;
+        }
+        if let Some(ordering) = overlay.ordering {
+            self.ordering = ordering;
+        }
+        if let Some(style) = overlay.style {
+            self.style = style;
+        }
+        if let Some(numbers) = overlay.numbers {
+            self.numbers = numbers;
+        }
+        if let Some(target_version) = overlay.target_version {
+            self.target_version = target_version;
+        }
+    }
+}
+
+impl OptionsOverlay for CommonWriterOptionsOverlay {
+    fn overlay(self, other: Self) -> Self {
+        Self {
+            pretty: other.pretty.or(self.pretty),
+            indent: other.indent.or(self.indent),
+            line_width: other.line_width.or(self.line_width),
+            line_ending: other.line_ending.or(self.line_ending),
+            buffering: other.buffering.or(self.buffering),
+            deterministic: other.deterministic.or(self.deterministic),
+            ordering: other.ordering.or(self.ordering),
+            style: other.style.or(self.style),
+            numbers: other.numbers.or(self.numbers),
+            target_version: other.target_version.or(self.target_version),
+        }
+    }
+}
+
+impl ValidateOptions for CommonWriterOptions {
+    fn validate(&self) -> Result<(), Error> {
+        if self.line_width <= self.indent {
+            Err(Error::invalid_options(format!(
+                "line width ({}) must exceed the indent width ({})",
+                self.line_width, self.indent
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl DescribeOptions for CommonWriterOptions {
+    fn describe_options() -> Vec<OptionDescriptor> {
+        let defaults = Self::default();
+        vec![
+            OptionDescriptor::new(
+                "pretty",
+                "bool",
+                defaults.pretty.to_string(),
+                "Whether the writer should produce human-readable, pretty-printed, output.",
+            ),
+            OptionDescriptor::new(
+                "indent",
+                "usize",
+                defaults.indent.to_string(),
+                "The number of spaces to use for each level of indentation when pretty-printing.",
+            ),
+            OptionDescriptor::new(
+                "line_width",
+                "usize",
+                defaults.line_width.to_string(),
+                "The target line width for writers that break long lines when pretty-printing.",
+            ),
+            OptionDescriptor::new(
+                "line_ending",
+                "LineEnding",
+                format!("{:?}", defaults.line_ending),
+                "The line ending sequence the writer should use.",
+            ),
+            OptionDescriptor::new(
+                "buffering",
+                "Buffering",
+                format!("{:?}", defaults.buffering),
+                "How output should be buffered before it is written to the destination.",
+            ),
+            OptionDescriptor::new(
+                "deterministic",
+                "bool",
+                defaults.deterministic.to_string(),
+                "Whether the writer must produce byte-identical output for equal objects across runs.",
+            ),
+            OptionDescriptor::new(
+                "ordering",
+                "CollectionOrdering",
+                format!("{:?}", defaults.ordering),
+                "The order in which the entries of maps and other keyed collections are written.",
+            ),
+            OptionDescriptor::new(
+                "style.color",
+                "ColorChoice",
+                format!("{:?}", defaults.style.color()),
+                "Whether the writer should use ANSI color codes.",
+            ),
+            OptionDescriptor::new(
+                "numbers.precision",
+                "Option<u16>",
+                format!("{:?}", defaults.numbers.precision()),
+                "The number of digits after the decimal point, if not the shortest that round-trips.",
+            ),
+            OptionDescriptor::new(
+                "numbers.scientific_threshold",
+                "u16",
+                defaults.numbers.scientific_threshold().to_string(),
+                "The decimal exponent at, or beyond, which numbers use scientific notation.",
+            ),
+            OptionDescriptor::new(
+                "numbers.canonical",
+                "bool",
+                defaults.numbers.is_canonical().to_string(),
+                "Whether numbers are written in the canonical form used by ECMAScript.",
+            ),
+            OptionDescriptor::new(
+                "target_version",
+                "Option<FormatVersion>",
+                format!("{:?}", defaults.target_version),
+                "The version of the format to write, if not the latest.",
+            ),
+        ]
+    }
+}
+
+impl FromOptionStrings for CommonWriterOptions {
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        fn parse<T>(key: &str, value: &str) -> Result<T, Error>
+        where
+            T: FromStr,
+            T::Err: Into<crate::BoxedError>,
+        {
+            value
+                .parse()
+                .map_err(|e| Error::invalid_option_value(key, value, e))
+        }
+        match key.replace('-', "_").as_str() {
+            "pretty" => self.pretty = parse(key, value)?,
+            "indent" => self.indent = parse(key, value)?,
+            "line_width" => self.line_width = parse(key, value)?,
+            "line_ending" => self.line_ending = parse(key, value)?,
+            "buffering" => self.buffering = parse(key, value)?,
+            "deterministic" => self.deterministic = parse(key, value)?,
+            "ordering" => self.ordering = parse(key, value)?,
+            "style.color" => self.style.set_color(parse(key, value)?),
+            "numbers.precision" if value == "shortest" => self.numbers.set_precision(None),
+            "numbers.precision" => self.numbers.set_precision(Some(parse(key, value)?)),
+            "numbers.scientific_threshold" => {
+                self.numbers.set_scientific_threshold(parse(key, value)?)
+            }
+            "numbers.canonical" => self.numbers.set_canonical(parse(key, value)?),
+            "target_version" if value == "latest" => self.target_version = None,
+            "target_version" => self.target_version = Some(parse(key, value)?),
+            _ => return Err(Error::unknown_option(key)),
+        }
+        Ok(())
+    }
+}
+
+impl OptionsFromEnv for CommonWriterOptions {
+    fn env_prefix() -> &'static str {
+        "OBJIO_WRITER"
+    }
+}
+
+impl InheritOptions for CommonWriterOptions {
+    ///
+    /// Inherits all options except buffering, as the child writes to the parent's destination,
+    /// and the target version, which is specific to the child's format.
+    ///
+    fn inherit(&mut self, parent: &Self) {
+        self.pretty = parent.pretty;
+        self.indent = parent.indent;
+        self.line_width = parent.line_width;
+        self.line_ending = parent.line_ending;
+        self.deterministic = parent.deterministic;
+        self.ordering = parent.ordering.clone();
+        self.style = parent.style.clone();
+        self.numbers = parent.numbers;
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl MergeOptions for CommonReaderOptions {
+    type Overlay = CommonReaderOptionsOverlay;
+
+    fn merge(&mut self, overlay: Self::Overlay) {
+        if let Some(unknown_handling) = overlay.unknown_handling {
+            self.unknown_handling = unknown_handling;
+        }
+        if let Some(trailing_data) = overlay.trailing_data {
+            self.trailing_data = trailing_data;
+        }
+        if let Some(read_mode) = overlay.read_mode {
+            self.read_mode = read_mode;
+        }
+        if let Some(projection) = overlay.projection {
+            self.projection = projection;
+        }
+        if let Some(preserve_comments) = overlay.preserve_comments {
+            self.preserve_comments = preserve_comments;
+        }
+        if let Some(preserve_formatting) = overlay.preserve_formatting {
+            self.preserve_formatting = preserve_formatting;
+        }
+    }
+}
+
+impl OptionsOverlay for CommonReaderOptionsOverlay {
+    fn overlay(self, other: Self) -> Self {
+        Self {
+            unknown_handling: other.unknown_handling.or(self.unknown_handling),
+            trailing_data: other.trailing_data.or(self.trailing_data),
+            read_mode: other.read_mode.or(self.read_mode),
+            projection: other.projection.or(self.projection),
+            preserve_comments: other.preserve_comments.or(self.preserve_comments),
+            preserve_formatting: other.preserve_formatting.or(self.preserve_formatting),
+        }
+    }
+}
+
+impl ValidateOptions for CommonReaderOptions {
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl DescribeOptions for CommonReaderOptions {
+    fn describe_options() -> Vec<OptionDescriptor> {
+        let defaults = Self::default();
+        vec![
+            OptionDescriptor::new(
+                "unknown_handling",
+                "UnknownHandling",
+                format!("{:?}", defaults.unknown_handling),
+                "How the reader should treat content it does not recognize.",
+            ),
+            OptionDescriptor::new(
+                "trailing_data",
+                "UnknownHandling",
+                format!("{:?}", defaults.trailing_data),
+                "How the reader should treat content following the object.",
+            ),
+            OptionDescriptor::new(
+                "read_mode",
+                "ReadMode",
+                format!("{:?}", defaults.read_mode),
+                "Whether the reader should substitute replacement values for undecodable content.",
+            ),
+            OptionDescriptor::new(
+                "projection",
+                "Projection",
+                defaults.projection.to_string(),
+                "The paths, separated by `;`, of the parts of the object the reader should parse.",
+            ),
+            OptionDescriptor::new(
+                "preserve_comments",
+                "bool",
+                defaults.preserve_comments.to_string(),
+                "Whether the reader should capture comments as trivia.",
+            ),
+            OptionDescriptor::new(
+                "preserve_formatting",
+                "bool",
+                defaults.preserve_formatting.to_string(),
+                "Whether the reader should capture whitespace and other formatting as trivia.",
+            ),
+        ]
+    }
+}
+
+impl FromOptionStrings for CommonReaderOptions {
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        match key.replace('-', "_").as_str() {
+            "unknown_handling" => {
+                self.unknown_handling = value
+                    .parse()
+                    .map_err(|e| Error::invalid_option_value(key, value, e))?
+            }
+            "trailing_data" => {
+                self.trailing_data = value
+                    .parse()
+                    .map_err(|e| Error::invalid_option_value(key, value, e))?
+            }
+            "read_mode" => {
+                self.read_mode = value
+                    .parse()
+                    .map_err(|e| Error::invalid_option_value(key, value, e))?
+            }
+            "projection" => {
+                self.projection = value
+                    .parse()
+                    .map_err(|e| Error::invalid_option_value(key, value, e))?
+            }
+            "preserve_comments" => {
+                self.preserve_comments = value
+                    .parse()
+                    .map_err(|e| Error::invalid_option_value(key, value, e))?
+            }
+            "preserve_formatting" => {
+                self.preserve_formatting = value
+                    .parse()
+                    .map_err(|e| Error::invalid_option_value(key, value, e))?
+            }
+            _ => return Err(Error::unknown_option(key)),
+        }
+        Ok(())
+    }
+}
+
+impl OptionsFromEnv for CommonReaderOptions {
+    fn env_prefix() -> &'static str {
+        "OBJIO_READER"
+    }
+}
+
+impl InheritOptions for CommonReaderOptions {
+    ///
+    /// Inherits the handling of unknown content, the read mode, and the preservation options; the
+    /// projection, whose paths are relative to the parent's object, and the handling of trailing
+    /// data, which the parent checks, are kept.
+    ///
+    fn inherit(&mut self, parent: &Self) {
+        self.unknown_handling = parent.unknown_handling;
+        self.read_mode = parent.read_mode;
+        self.preserve_comments = parent.preserve_comments;
+        self.preserve_formatting = parent.preserve_formatting;
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for ParseOptionValueError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid value `{}`, expected one of: {}",
+            self.value,
+            self.expected.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ParseOptionValueError {}
+
+impl ParseOptionValueError {
+    ///
+    /// Construct a new error for `value`, which is not one of the `expected` values.
+    ///
+    pub fn new<S>(value: S, expected: &'static [&'static str]) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            value: value.into(),
+            expected,
+        }
+    }
+
+    ///
+    /// Returns the value that could not be parsed.
+    ///
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    ///
+    /// Returns the list of accepted values.
+    ///
+    pub fn expected(&self) -> &'static [&'static str] {
+        self.expected
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl OptionDescriptor {
+    ///
+    /// Construct a new descriptor; `default_value` is the textual form of the option's default.
+    ///
+    pub fn new<S1, S2, S3>(
+        name: S1,
+        type_name: &'static str,
+        default_value: S2,
+        documentation: S3,
+    ) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            type_name,
+            default_value: default_value.into(),
+            documentation: documentation.into(),
+        }
+    }
+
+    ///
+    /// Returns this descriptor with its name prefixed by `prefix` and a `.` separator; this is
+    /// used to describe the options of a nested options type.
+    ///
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        let mut self_mut = self;
+        self_mut.name = format!("{prefix}.{}", self_mut.name);
+        self_mut
+    }
+
+    ///
+    /// Returns the name of the option.
+    ///
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    ///
+    /// Returns the name of the option's type, as written in its declaration.
+    ///
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    ///
+    /// Returns the textual form of the option's default value.
+    ///
+    pub fn default_value(&self) -> &str {
+        &self.default_value
+    }
+
+    ///
+    /// Returns the documentation for the option, which may be empty.
+    ///
+    pub fn documentation(&self) -> &str {
+        &self.documentation
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for FormatVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for FormatVersion {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseOptionValueError::new(s, &["MAJOR", "MAJOR.MINOR"]);
+        let (major, minor) = s.split_once('.').unwrap_or((s, "0"));
+        Ok(Self::new(
+            major.parse().map_err(|_| error())?,
+            minor.parse().map_err(|_| error())?,
+        ))
+    }
+}
+
+impl From<FormatVersion> for String {
+    fn from(version: FormatVersion) -> Self {
+        version.to_string()
+    }
+}
+
+impl TryFrom<String> for FormatVersion {
+    type Error = ParseOptionValueError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl FormatVersion {
+    ///
+    /// Construct a new version from its major and minor numbers.
+    ///
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+
+    ///
+    /// Returns the major version number.
+    ///
+    pub const fn major(&self) -> u16 {
+        self.major
+    }
+
+    ///
+    /// Returns the minor version number.
+    ///
+    pub const fn minor(&self) -> u16 {
+        self.minor
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for LineEnding {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lf" => Ok(Self::Lf),
+            "crlf" => Ok(Self::CrLf),
+            "native" => Ok(Self::native()),
+            _ => Err(ParseOptionValueError::new(s, &["lf", "crlf", "native"])),
+        }
+    }
+}
+
+impl FromStr for CollectionOrdering {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "insertion" => Ok(Self::Insertion),
+            "lexicographic" => Ok(Self::Lexicographic),
+            _ => Err(ParseOptionValueError::new(
+                s,
+                &["insertion", "lexicographic"],
+            )),
+        }
+    }
+}
+
+impl FromStr for Buffering {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "block" => Ok(Self::Block),
+            "line" => Ok(Self::Line),
+            "none" => Ok(Self::None),
+            _ => Err(ParseOptionValueError::new(s, &["block", "line", "none"])),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl CollectionOrdering {
+    ///
+    /// Construct a custom ordering using `comparator` to compare keys.
+    ///
+    pub fn custom<F>(comparator: F) -> Self
+    where
+        F: Fn(&str, &str) -> Ordering + Send + Sync + 'static,
+    {
+        Self::Custom(KeyComparator::new(comparator))
+    }
+
+    ///
+    /// Returns `true` if entries are written in insertion order, and so need not be sorted.
+    ///
+    pub fn is_insertion(&self) -> bool {
+        matches!(self, Self::Insertion)
+    }
+
+    ///
+    /// Compare the keys `lhs` and `rhs`; for insertion ordering all keys compare as equal.
+    ///
+    pub fn compare(&self, lhs: &str, rhs: &str) -> Ordering {
+        match self {
+            Self::Insertion => Ordering::Equal,
+            Self::Lexicographic => lhs.cmp(rhs),
+            Self::Custom(comparator) => comparator.compare(lhs, rhs),
+        }
+    }
+
+    ///
+    /// Sort `entries` by the key returned by `key` for each; the sort is stable, so insertion
+    /// ordering leaves the entries unchanged.
+    ///
+    pub fn sort_by_key<T, F>(&self, entries: &mut [T], key: F)
+    where
+        F: Fn(&T) -> &str,
+    {
+        if !self.is_insertion() {
+            entries.sort_by(|lhs, rhs| self.compare(key(lhs), key(rhs)));
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for KeyComparator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("KeyComparator").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for KeyComparator {
+    fn eq(&self, other: &Self) -> bool {
+        std::sync::Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for KeyComparator {}
+
+impl KeyComparator {
+    ///
+    /// Construct a new comparator from the function `comparator`.
+    ///
+    pub fn new<F>(comparator: F) -> Self
+    where
+        F: Fn(&str, &str) -> Ordering + Send + Sync + 'static,
+    {
+        Self(std::sync::Arc::new(comparator))
+    }
+
+    ///
+    /// Compare the keys `lhs` and `rhs`.
+    ///
+    pub fn compare(&self, lhs: &str, rhs: &str) -> Ordering {
+        (self.0)(lhs, rhs)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl LineEnding {
+    ///
+    /// Returns the character sequence for this line ending.
+    ///
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+
+    ///
+    /// Returns the line ending native to the current platform.
+    ///
+    pub fn native() -> Self {
+        if cfg!(windows) {
+            Self::CrLf
+        } else {
+            Self::Lf
+        }
+    }
+}
+
+impl FromStr for UnknownHandling {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            "ignore" => Ok(Self::Ignore),
+            _ => Err(ParseOptionValueError::new(s, &["error", "warn", "ignore"])),
+        }
+    }
+}
+
+impl FromStr for ReadMode {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lossless" => Ok(Self::Lossless),
+            "lossy" => Ok(Self::Lossy),
+            _ => Err(ParseOptionValueError::new(s, &["lossless", "lossy"])),
+        }
+    }
+}
+
+impl UnknownHandling {
+    ///
+    /// Apply this policy to unrecognized content in the named format, described by `message`.
+    /// Returns a deserialization error for [`Error`](Self::Error), reports a warning to `sink` for
+    /// [`Warn`](Self::Warn), and otherwise does nothing; a reader continues past the content if
+    /// this returns `Ok`.
+    ///
+    pub fn handle<S>(
+        &self,
+        format: &'static str,
+        message: S,
+        sink: &dyn DiagnosticSink,
+    ) -> Result<(), Error>
+    where
+        S: Into<String>,
+    {
+        match self {
+            Self::Error => Err(Error::deserialization(format, message.into())),
+            Self::Warn => {
+                sink.report(Diagnostic::warning(message));
+                Ok(())
+            }
+            Self::Ignore => Ok(()),
+        }
+    }
+}
+
+impl ReadMode {
+    ///
+    /// Returns `true` if the reader should substitute replacement values rather than fail.
+    ///
+    pub fn is_lossy(&self) -> bool {
+        matches!(self, Self::Lossy)
+    }
+
+    ///
+    /// Apply this mode to content in the named format that cannot be decoded, described by
+    /// `message`. Returns a deserialization error for [`Lossless`](Self::Lossless); for
+    /// [`Lossy`](Self::Lossy) reports a warning to `sink` and returns `replacement`, which the
+    /// reader uses in place of the content.
+    ///
+    pub fn substitute<T, S>(
+        &self,
+        format: &'static str,
+        message: S,
+        replacement: T,
+        sink: &dyn DiagnosticSink,
+    ) -> Result<T, Error>
+    where
+        S: Into<String>,
+    {
+        match self {
+            Self::Lossless => Err(Error::deserialization(format, message.into())),
+            Self::Lossy => {
+                sink.report(Diagnostic::warning(format!(
+                    "{}; a replacement value was substituted",
+                    message.into()
+                )));
+                Ok(replacement)
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Projection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.paths.join(";"))
+    }
+}
+
+impl FromStr for Projection {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let paths: Vec<&str> = s
+            .split(';')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect();
+        if paths.iter().all(|path| path.starts_with('/')) {
+            Ok(Self::new(paths))
+        } else {
+            Err(ParseOptionValueError::new(s, &["/POINTER;/POINTER..."]))
+        }
+    }
+}
+
+impl Projection {
+    ///
+    /// Construct a projection selecting each of `paths`; an empty list selects the whole object.
+    ///
+    pub fn new<I, S>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            paths: paths.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    ///
+    /// Returns `true` if this projection selects the whole object.
+    ///
+    pub fn is_all(&self) -> bool {
+        self.paths.is_empty() || self.paths.iter().any(String::is_empty)
+    }
+
+    ///
+    /// Returns the selected paths.
+    ///
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.paths.iter().map(String::as_str)
+    }
+
+    ///
+    /// Returns `true` if the element at `path` is selected, either directly or because an
+    /// ancestor is selected; a reader must then parse it in full.
+    ///
+    pub fn includes(&self, path: &str) -> bool {
+        let path = segments(path);
+        self.is_all()
+            || self.paths.iter().any(|selected| {
+                let selected = segments(selected);
+                selected.len() <= path.len() && matches_prefix(&selected, &path)
+            })
+    }
+
+    ///
+    /// Returns `true` if a selected path lies beneath the element at `path`; a reader must then
+    /// parse the element, but may skip any of its children that are neither included nor lead to
+    /// a selected path.
+    ///
+    pub fn leads_to(&self, path: &str) -> bool {
+        let path = segments(path);
+        self.paths.iter().any(|selected| {
+            let selected = segments(selected);
+            selected.len() > path.len() && matches_prefix(&selected, &path)
+        })
+    }
+
+    ///
+    /// Returns `true` if a reader must parse the element at `path`, as it is either included or
+    /// leads to a selected path.
+    ///
+    pub fn requires(&self, path: &str) -> bool {
+        self.includes(path) || self.leads_to(path)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl CommonReaderOptions {
+    ///
+    /// Returns a copy of these options with the handling of unrecognized content set to
+    /// `unknown_handling`.
+    ///
+    pub fn with_unknown_handling(self, unknown_handling: UnknownHandling) -> Self {
+        let mut self_mut = self;
+        self_mut.unknown_handling = unknown_handling;
+        self_mut
+    }
+
+    ///
+    /// Returns how the reader should treat content it does not recognize.
+    ///
+    pub fn unknown_handling(&self) -> UnknownHandling {
+        self.unknown_handling
+    }
+
+    ///
+    /// Set how the reader should treat content it does not recognize.
+    ///
+    pub fn set_unknown_handling(&mut self, unknown_handling: UnknownHandling) {
+        self.unknown_handling = unknown_handling;
+    }
+
+    ///
+    /// Returns a copy of these options with the handling of content following the object set to
+    /// `trailing_data`.
+    ///
+    pub fn with_trailing_data(self, trailing_data: UnknownHandling) -> Self {
+        let mut self_mut = self;
+        self_mut.trailing_data = trailing_data;
+        self_mut
+    }
+
+    ///
+    /// Returns how the reader should treat non-whitespace content following the object.
+    ///
+    pub fn trailing_data(&self) -> UnknownHandling {
+        self.trailing_data
+    }
+
+    ///
+    /// Set how the reader should treat non-whitespace content following the object.
+    ///
+    pub fn set_trailing_data(&mut self, trailing_data: UnknownHandling) {
+        self.trailing_data = trailing_data;
+    }
+
+    ///
+    /// Returns a copy of these options with the read mode set to `read_mode`.
+    ///
+    pub fn with_read_mode(self, read_mode: ReadMode) -> Self {
+        let mut self_mut = self;
+        self_mut.read_mode = read_mode;
+        self_mut
+    }
+
+    ///
+    /// Returns whether the reader should substitute replacement values for undecodable content.
+    ///
+    pub fn read_mode(&self) -> ReadMode {
+        self.read_mode
+    }
+
+    ///
+    /// Set whether the reader should substitute replacement values for undecodable content.
+    ///
+    pub fn set_read_mode(&mut self, read_mode: ReadMode) {
+        self.read_mode = read_mode;
+    }
+
+    ///
+    /// Returns a copy of these options with the projection set to `projection`.
+    ///
+    pub fn with_projection(self, projection: Projection) -> Self {
+        let mut self_mut = self;
+        self_mut.projection = projection;
+        self_mut
+    }
+
+    ///
+    /// Returns the parts of the object the reader should parse.
+    ///
+    pub fn projection(&self) -> &Projection {
+        &self.projection
+    }
+
+    ///
+    /// Set the parts of the object the reader should parse.
+    ///
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    ///
+    /// Returns a copy of these options with the capture of comments set to `preserve_comments`.
+    ///
+    pub fn with_preserve_comments(self, preserve_comments: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.preserve_comments = preserve_comments;
+        self_mut
+    }
+
+    ///
+    /// Returns `true` if the reader should capture comments as [trivia](crate::trivia).
+    ///
+    pub fn preserve_comments(&self) -> bool {
+        self.preserve_comments
+    }
+
+    ///
+    /// Set whether the reader should capture comments as trivia.
+    ///
+    pub fn set_preserve_comments(&mut self, preserve_comments: bool) {
+        self.preserve_comments = preserve_comments;
+    }
+
+    ///
+    /// Returns a copy of these options with the capture of formatting set to
+    /// `preserve_formatting`.
+    ///
+    pub fn with_preserve_formatting(self, preserve_formatting: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.preserve_formatting = preserve_formatting;
+        self_mut
+    }
+
+    ///
+    /// Returns `true` if the reader should capture whitespace and other formatting as
+    /// [trivia](crate::trivia).
+    ///
+    pub fn preserve_formatting(&self) -> bool {
+        self.preserve_formatting
+    }
+
+    ///
+    /// Set whether the reader should capture whitespace and other formatting as trivia.
+    ///
+    pub fn set_preserve_formatting(&mut self, preserve_formatting: bool) {
+        self.preserve_formatting = preserve_formatting;
+    }
+}
+
+impl CommonWriterOptions {
+    ///
+    /// Returns a copy of these options with pretty-printing enabled.
+    ///
+    pub fn pretty(self) -> Self {
+        self.with_pretty(true)
+    }
+
+    ///
+    /// Returns a copy of these options with pretty-printing disabled.
+    ///
+    pub fn compact(self) -> Self {
+        self.with_pretty(false)
+    }
+
+    ///
+    /// Returns a copy of these options with pretty-printing set to `pretty`.
+    ///
+    pub fn with_pretty(self, pretty: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.pretty = pretty;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options for content written `level` levels of indentation deep,
+    /// such as by a child writer; the line width is reduced by the width of the indentation, but
+    /// always exceeds the indentation width of a single level.
+    ///
+    pub fn nested(self, level: usize) -> Self {
+        let mut self_mut = self;
+        self_mut.line_width = self_mut
+            .line_width
+            .saturating_sub(level.saturating_mul(self_mut.indent))
+            .max(self_mut.indent.saturating_add(1));
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the indentation width set to `indent` spaces.
+    ///
+    pub fn with_indent(self, indent: usize) -> Self {
+        let mut self_mut = self;
+        self_mut.indent = indent;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the target line width set to `line_width`.
+    ///
+    pub fn with_line_width(self, line_width: usize) -> Self {
+        let mut self_mut = self;
+        self_mut.line_width = line_width;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the line ending set to `line_ending`.
+    ///
+    pub fn with_line_ending(self, line_ending: LineEnding) -> Self {
+        let mut self_mut = self;
+        self_mut.line_ending = line_ending;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with output buffering set to `buffering`.
+    ///
+    pub fn with_buffering(self, buffering: Buffering) -> Self {
+        let mut self_mut = self;
+        self_mut.buffering = buffering;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with deterministic output set to `deterministic`.
+    ///
+    pub fn with_deterministic(self, deterministic: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.deterministic = deterministic;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the order of collection entries set to `ordering`.
+    ///
+    pub fn with_ordering(self, ordering: CollectionOrdering) -> Self {
+        let mut self_mut = self;
+        self_mut.ordering = ordering;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the version of the format to write set to
+    /// `target_version`, where `None` indicates the latest version.
+    ///
+    pub fn with_target_version(self, target_version: Option<FormatVersion>) -> Self {
+        let mut self_mut = self;
+        self_mut.target_version = target_version;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the styling options set to `style`.
+    ///
+    pub fn with_style(self, style: StyleOptions) -> Self {
+        let mut self_mut = self;
+        self_mut.style = style;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the number formatting options set to `numbers`.
+    ///
+    pub fn with_numbers(self, numbers: NumberOptions) -> Self {
+        let mut self_mut = self;
+        self_mut.numbers = numbers;
+        self_mut
+    }
+
+    ///
+    /// Returns `true` if the writer should produce human-readable, pretty-printed, output.
+    ///
+    pub fn is_pretty(&self) -> bool {
+        self.pretty
+    }
+
+    ///
+    /// Set whether the writer should produce human-readable, pretty-printed, output.
+    ///
+    pub fn set_pretty(&mut self, pretty: bool) {
+        self.pretty = pretty;
+    }
+
+    ///
+    /// Returns the number of spaces to use for each level of indentation when pretty-printing.
+    ///
+    pub fn indent(&self) -> usize {
+        self.indent
+    }
+
+    ///
+    /// Set the number of spaces to use for each level of indentation when pretty-printing.
+    ///
+    pub fn set_indent(&mut self, indent: usize) {
+        self.indent = indent;
+    }
+
+    ///
+    /// Returns the target line width for writers that break long lines when pretty-printing.
+    ///
+    pub fn line_width(&self) -> usize {
+        self.line_width
+    }
+
+    ///
+    /// Set the target line width for writers that break long lines when pretty-printing.
+    ///
+    pub fn set_line_width(&mut self, line_width: usize) {
+        self.line_width = line_width;
+    }
+
+    ///
+    /// Returns the line ending sequence the writer should use.
+    ///
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    ///
+    /// Set the line ending sequence the writer should use.
+    ///
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    ///
+    /// Returns how output should be buffered before it is written to the destination.
+    ///
+    pub fn buffering(&self) -> Buffering {
+        self.buffering
+    }
+
+    ///
+    /// Set how output should be buffered before it is written to the destination.
+    ///
+    pub fn set_buffering(&mut self, buffering: Buffering) {
+        self.buffering = buffering;
+    }
+
+    ///
+    /// Returns `true` if the writer must produce byte-identical output for equal objects across
+    /// runs; for example, by ordering map entries and not embedding timestamps or random
+    /// identifiers. See [`deterministic`](crate::deterministic) for helpers.
+    ///
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    ///
+    /// Set whether the writer must produce byte-identical output for equal objects across runs.
+    ///
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    ///
+    /// Returns the order in which the entries of maps and other keyed collections are written.
+    ///
+    pub fn ordering(&self) -> &CollectionOrdering {
+        &self.ordering
+    }
+
+    ///
+    /// Set the order in which the entries of maps and other keyed collections are written.
+    ///
+    pub fn set_ordering(&mut self, ordering: CollectionOrdering) {
+        self.ordering = ordering;
+    }
+
+    ///
+    /// Returns the styling options for writers whose output may be shown in a terminal.
+    ///
+    pub fn style(&self) -> &StyleOptions {
+        &self.style
+    }
+
+    ///
+    /// Set the styling options for writers whose output may be shown in a terminal.
+    ///
+    pub fn set_style(&mut self, style: StyleOptions) {
+        self.style = style;
+    }
+
+    ///
+    /// Returns the options determining how numbers are written.
+    ///
+    pub fn numbers(&self) -> &NumberOptions {
+        &self.numbers
+    }
+
+    ///
+    /// Set the options determining how numbers are written.
+    ///
+    pub fn set_numbers(&mut self, numbers: NumberOptions) {
+        self.numbers = numbers;
+    }
+
+    ///
+    /// Returns the version of the format to write, or `None` for the latest version.
+    ///
+    pub fn target_version(&self) -> Option<FormatVersion> {
+        self.target_version
+    }
+
+    ///
+    /// Set the version of the format to write, where `None` indicates the latest version.
+    ///
+    pub fn set_target_version(&mut self, target_version: Option<FormatVersion>) {
+        self.target_version = target_version;
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/').skip(1).collect()
+}
+
+///
+/// Returns `true` if the leading segments of `selected` match those of `path`, where a selected
+/// segment `*` matches any segment.
+///
+fn matches_prefix(selected: &[&str], path: &[&str]) -> bool {
+    selected
+        .iter()
+        .zip(path)
+        .all(|(selected, segment)| *selected == "*" || selected == segment)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writer_defaults() {
+        let options = CommonWriterOptions::default();
+        assert!(!options.is_pretty());
+        assert_eq!(options.indent(), DEFAULT_INDENT);
+        assert_eq!(options.line_width(), DEFAULT_LINE_WIDTH);
+        assert_eq!(options.line_ending(), LineEnding::Lf);
+    }
+
+    #[test]
+    fn test_writer_builder() {
+        let options = CommonWriterOptions::default()
+            .pretty()
+            .with_indent(4)
+            .compact();
+        assert!(!options.is_pretty());
+        assert_eq!(options.indent(), 4);
+    }
+
+    #[test]
+    fn test_reader_option_strings() {
+        let options =
+            CommonReaderOptions::from_option_strings("unknown-handling=warn,trailing-data=ignore")
+                .unwrap();
+        assert_eq!(options.unknown_handling(), UnknownHandling::Warn);
+        assert_eq!(options.trailing_data(), UnknownHandling::Ignore);
+        assert!(matches!(
+            CommonReaderOptions::from_option_strings("unknown_handling=skip"),
+            Err(Error::InvalidOptionValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unknown_handling() {
+        use crate::diagnostics::CollectDiagnostics;
+
+        let sink = CollectDiagnostics::default();
+        assert!(UnknownHandling::Error
+            .handle("test", "unknown element `x`", &sink)
+            .is_err());
+        assert!(UnknownHandling::Ignore
+            .handle("test", "unknown element `y`", &sink)
+            .is_ok());
+        assert!(UnknownHandling::Warn
+            .handle("test", "unknown element `z`", &sink)
+            .is_ok());
+        assert_eq!(
+            sink.into_diagnostics(),
+            vec![Diagnostic::warning("unknown element `z`")]
+        );
+    }
+
+    #[test]
+    fn test_merge_layers() {
+        let profile = CommonWriterOptionsOverlay {
+            pretty: Some(true),
+            line_ending: Some(LineEnding::CrLf),
+            ..Default::default()
+        };
+        let overrides = CommonWriterOptionsOverlay {
+            pretty: Some(false),
+            ..Default::default()
+        };
+        let options = CommonWriterOptions::default().merged(profile.overlay(overrides));
+        assert!(!options.is_pretty());
+        assert_eq!(options.line_ending(), LineEnding::CrLf);
+        assert_eq!(options.indent(), DEFAULT_INDENT);
+    }
+
+    #[test]
+    fn test_validate_line_width() {
+        assert!(CommonWriterOptions::default().validate().is_ok());
+        let error = CommonWriterOptions::default()
+            .with_indent(4)
+            .with_line_width(4)
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid options; line width (4) must exceed the indent width (4)"
+        );
+    }
+
+    #[test]
+    fn test_inherit_options() {
+        let parent = CommonWriterOptions::default()
+            .pretty()
+            .with_line_width(40)
+            .with_buffering(Buffering::Line);
+        let child = CommonWriterOptions::default()
+            .with_target_version(Some(FormatVersion::new(2, 0)))
+            .inherited_from(&parent)
+            .nested(3);
+        assert!(child.is_pretty());
+        assert_eq!(child.line_width(), 34);
+        assert_eq!(child.buffering(), Buffering::Block);
+        assert_eq!(child.target_version(), Some(FormatVersion::new(2, 0)));
+        assert_eq!(child.clone().nested(100).line_width(), 3);
+        assert!(child.clone().nested(100).validate().is_ok());
+        assert_eq!(child.nested(usize::MAX).line_width(), 3);
+
+        let parent = CommonReaderOptions::default()
+            .with_read_mode(ReadMode::Lossy)
+            .with_trailing_data(UnknownHandling::Ignore);
+        let child = CommonReaderOptions::default().inherited_from(&parent);
+        assert_eq!(child.read_mode(), ReadMode::Lossy);
+        assert_eq!(child.trailing_data(), UnknownHandling::Error);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_options() {
+        let options: CommonWriterOptions =
+            serde_json::from_str(r#"{ "pretty": true, "line_ending": "crlf" }"#).unwrap();
+        assert!(options.is_pretty());
+        assert_eq!(options.indent(), DEFAULT_INDENT);
+        assert_eq!(options.line_ending(), LineEnding::CrLf);
+
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CommonWriterOptions>(&json).unwrap(),
+            options
+        );
+
+        let overlay: CommonWriterOptionsOverlay =
+            serde_json::from_str(r#"{ "indent": 4 }"#).unwrap();
+        assert_eq!(overlay.indent, Some(4));
+        assert_eq!(overlay.pretty, None);
+    }
+
+    #[test]
+    fn test_format_version() {
+        assert_eq!(
+            "2".parse::<FormatVersion>().unwrap(),
+            FormatVersion::new(2, 0)
+        );
+        assert_eq!(FormatVersion::new(1, 10).to_string(), "1.10");
+        assert!(FormatVersion::new(1, 10) > FormatVersion::new(1, 9));
+        assert!("1.x".parse::<FormatVersion>().is_err());
+
+        let options = CommonWriterOptions::from_option_strings("target_version=1.2").unwrap();
+        assert_eq!(options.target_version(), Some(FormatVersion::new(1, 2)));
+        let options = options.merged(CommonWriterOptionsOverlay {
+            target_version: Some(None),
+            ..Default::default()
+        });
+        assert_eq!(options.target_version(), None);
+    }
+
+    #[test]
+    fn test_collection_ordering() {
+        let options = CommonWriterOptions::from_option_strings("ordering=lexicographic").unwrap();
+        assert_eq!(options.ordering(), &CollectionOrdering::Lexicographic);
+        assert!("sorted".parse::<CollectionOrdering>().is_err());
+
+        let mut entries = vec![("b", 1), ("c", 2), ("a", 3)];
+        CollectionOrdering::Insertion.sort_by_key(&mut entries, |(key, _)| key);
+        assert_eq!(entries, [("b", 1), ("c", 2), ("a", 3)]);
+        let reverse = CollectionOrdering::custom(|lhs, rhs| rhs.cmp(lhs));
+        reverse.sort_by_key(&mut entries, |(key, _)| key);
+        assert_eq!(entries, [("c", 2), ("b", 1), ("a", 3)]);
+        assert_eq!(reverse, reverse.clone());
+        assert_ne!(reverse, CollectionOrdering::custom(|lhs, rhs| rhs.cmp(lhs)));
+    }
+
+    #[test]
+    fn test_projection() {
+        let options =
+            CommonReaderOptions::from_option_strings("projection=/name; /items/*/id").unwrap();
+        let projection = options.projection();
+        assert_eq!(projection.to_string(), "/name;/items/*/id");
+        assert!(!projection.is_all());
+        assert!(projection.includes("/name"));
+        assert!(projection.includes("/name/first"));
+        assert!(!projection.includes("/items/0"));
+        assert!(projection.leads_to("/items/0"));
+        assert!(projection.includes("/items/0/id"));
+        assert!(!projection.requires("/items/0/name"));
+        assert!(!projection.requires("/count"));
+        assert!(projection.requires(""));
+
+        assert!(Projection::default().includes("/anything"));
+        assert!("name".parse::<Projection>().is_err());
+    }
+
+    #[test]
+    fn test_options_help() {
+        let help = CommonWriterOptions::options_help();
+        let first = help.lines().next().unwrap();
+        assert!(first.starts_with(
+            "pretty                        bool                   [default: false]  "
+        ));
+        assert_eq!(help.lines().count(), 12);
+    }
+
+    #[test]
+    fn test_option_strings() {
+        let mut options = CommonWriterOptions::default();
+        options
+            .set_option_strings(" line_width = 100, style.color=NEVER,")
+            .unwrap();
+        assert_eq!(options.line_width(), 100);
+        assert_eq!(options.style().color(), crate::style::ColorChoice::Never);
+
+        let error = options.set_option_strings("line_ending=cr").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value `cr` for option `line_ending`; source: invalid value `cr`, expected \
+             one of: lf, crlf, native"
+        );
+        assert!(matches!(
+            options.set_option("indent", "-1"),
+            Err(Error::InvalidOptionValue { .. })
+        ));
+
+        options
+            .set_option_strings("numbers.precision=3,numbers.scientific-threshold=4")
+            .unwrap();
+        assert_eq!(options.numbers().format_f64(12345.0), "1.234e4");
+        options.set_option("numbers.precision", "shortest").unwrap();
+        assert_eq!(options.numbers().precision(), None);
+    }
+
+    #[test]
+    fn test_options_from_vars() {
+        let mut options = CommonWriterOptions::default();
+        options
+            .set_options_from_vars([
+                ("OBJIO_WRITER_LINE_WIDTH", "100"),
+                ("OBJIO_WRITER_STYLE__COLOR", "always"),
+                ("OBJIO_READER_INDENT", "8"),
+            ])
+            .unwrap();
+        assert_eq!(options.line_width(), 100);
+        assert_eq!(options.indent(), DEFAULT_INDENT);
+        assert_eq!(options.style().color(), crate::style::ColorChoice::Always);
+
+        let error = options
+            .set_options_from_vars([("OBJIO_WRITER_INDENT", "x")])
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::InvalidOptionValue { key, .. } if key == "OBJIO_WRITER_INDENT")
+        );
+    }
+
+    #[test]
+    fn test_options_with_env() {
+        std::env::set_var("OBJIO_WRITER_PRETTY", "true");
+        let options = CommonWriterOptions::default().with_env();
+        std::env::remove_var("OBJIO_WRITER_PRETTY");
+        assert!(options.unwrap().is_pretty());
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_options_from_env() {
+        #[derive(Debug, Default, FromOptionStrings, OptionsFromEnv)]
+        #[options_env(prefix = "TEST")]
+        struct TestOptions {
+            count: u32,
+        }
+
+        #[derive(Debug, Default, FromOptionStrings, OptionsFromEnv)]
+        struct OtherTestOptions {}
+
+        let mut options = TestOptions::default();
+        options
+            .set_options_from_vars([("TEST_COUNT", "2")])
+            .unwrap();
+        assert_eq!(options.count, 2);
+        assert!(matches!(
+            options.set_options_from_vars([("TEST_OTHER", "2")]),
+            Err(Error::UnknownOption { key }) if key == "TEST_OTHER"
+        ));
+        assert_eq!(OtherTestOptions::env_prefix(), "OTHER_TEST_OPTIONS");
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_from_option_strings() {
+        #[derive(Debug, Default, FromOptionStrings)]
+        struct TestOptions {
+            count: u32,
+            name: String,
+            #[option_strings(nested)]
+            common: CommonWriterOptions,
+            #[option_strings(skip)]
+            internal: bool,
+        }
+
+        let options =
+            TestOptions::from_option_strings("count=2,name=test,common.pretty=true").unwrap();
+        assert_eq!(options.count, 2);
+        assert_eq!(options.name, "test");
+        assert!(options.common.is_pretty());
+        assert!(!options.internal);
+
+        let error = TestOptions::from_option_strings("internal=true").unwrap_err();
+        assert!(matches!(error, Error::UnknownOption { key } if key == "internal"));
+        let error = TestOptions::from_option_strings("common.indent=x").unwrap_err();
+        assert!(matches!(error, Error::InvalidOptionValue { key, .. } if key == "common.indent"));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_describe_options() {
+        #[derive(Debug, Default, DescribeOptions)]
+        struct TestOptions {
+            ///
+            /// The number of items.
+            ///
+            count: u32,
+            name: Option<String>,
+            #[describe(nested)]
+            #[allow(dead_code)]
+            common: CommonWriterOptions,
+            #[describe(skip)]
+            #[allow(dead_code)]
+            internal: bool,
+        }
+
+        let descriptors = TestOptions::describe_options();
+        assert_eq!(descriptors.len(), 14);
+        assert_eq!(
+            descriptors[0],
+            OptionDescriptor::new("count", "u32", "0", "The number of items.")
+        );
+        assert_eq!(descriptors[1].type_name(), "Option<String>");
+        assert_eq!(descriptors[1].default_value(), "None");
+        assert_eq!(descriptors[3].name(), "common.indent");
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_merge_options() {
+        #[derive(Debug, Default, MergeOptions)]
+        struct TestOptions {
+            count: u32,
+            name: String,
+            #[merge(nested)]
+            common: CommonWriterOptions,
+            #[merge(skip)]
+            #[allow(dead_code)]
+            internal: bool,
+        }
+
+        let mut options = TestOptions::default();
+        options.merge(TestOptionsOverlay {
+            count: Some(2),
+            common: CommonWriterOptionsOverlay {
+                indent: Some(4),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        assert_eq!(options.count, 2);
+        assert_eq!(options.name, "");
+        assert_eq!(options.common.indent(), 4);
+
+        let overlay = TestOptionsOverlay {
+            name: Some("a".to_string()),
+            ..Default::default()
+        }
+        .overlay(TestOptionsOverlay {
+            name: Some("b".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(overlay.name, Some("b".to_string()));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_options_builder() {
+        fn check_width(options: &TestOptions) -> Result<(), crate::Error> {
+            if options.width > options.indent {
+                Ok(())
+            } else {
+                Err(crate::Error::invalid_options(
+                    "width must exceed the indent",
+                ))
+            }
+        }
+
+        #[derive(Debug, Default, OptionsBuilder)]
+        #[builder(validate = check_width)]
+        struct TestOptions {
+            indent: usize,
+            width: usize,
+            name: String,
+        }
+
+        let options = TestOptions::builder()
+            .indent(2_usize)
+            .width(80_usize)
+            .name("test")
+            .build()
+            .unwrap();
+        assert_eq!(options.indent, 2);
+        assert_eq!(options.name, "test");
+
+        assert!(matches!(
+            TestOptions::builder().indent(2_usize).build(),
+            Err(crate::Error::InvalidOptions { .. })
+        ));
+    }
+}
@@ -0,0 +1,418 @@
+/*!
+Provides the [`ObjectPatchWriter`] trait for writers that can update existing output in place,
+given the object it was written from and the object it should now represent, rewriting only the
+parts of the output that differ rather than regenerating all of it.
+
+The output is given as a destination that can be read, written, sought, and [`Truncate`]d, such
+as a file. The adapter [`RecordPatchWriter`] implements [`ObjectPatchWriter`] for a sequence of
+records written by any [`ObjectsWriter`]: a changed record whose serialized length is unchanged
+is overwritten where it is, and from the first record whose length changes, or that was added or
+removed, the remainder of the output is rewritten. The regions rewritten are returned as a
+[`PatchSummary`].
+
+Patching is not atomic; if it fails, or the process exits while patching, the output may be
+left partially updated, and should be regenerated.
+
+# Example
+
+```rust
+use objio::patch::{ObjectPatchWriter, RecordPatchWriter};
+use objio::stream::ObjectsWriter;
+use std::io::Write;
+
+#[derive(Debug, Default)]
+struct LinesWriter;
+
+impl ObjectsWriter<String> for LinesWriter {
+    type Error = std::io::Error;
+
+    fn write_next<W: Write>(&self, w: &mut W, object: &String, _: usize) -> Result<(), Self::Error> {
+        writeln!(w, "{object}")
+    }
+}
+
+let previous: Vec<String> = vec!["alpha".into(), "beta".into(), "gamma".into()];
+let mut current = previous.clone();
+current[1] = "BETA".into();
+
+let writer = RecordPatchWriter::new(LinesWriter);
+let mut output = b"alpha\nbeta\ngamma\n".to_vec();
+let summary = writer.patch_bytes(&mut output, &previous, &current).unwrap();
+
+assert_eq!(output, b"alpha\nBETA\ngamma\n");
+assert_eq!(summary.regions().len(), 1);
+assert_eq!(summary.regions()[0], 6..11);
+```
+ */
+
+use crate::stream::ObjectsWriter;
+use crate::ObjectWriter;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by destinations whose length can be set, so that output that shrinks
+/// when patched can be truncated.
+///
+pub trait Truncate {
+    ///
+    /// Set the length of the destination to `len` bytes.
+    ///
+    fn truncate(&mut self, len: u64) -> std::io::Result<()>;
+}
+
+///
+/// The trait implemented by types which update the output written for an instance of `T` in
+/// place.
+///
+pub trait ObjectPatchWriter<T> {
+    ///
+    /// The type indicating errors, this **must** implement the conversion from `io::Error` as for
+    /// [`ObjectWriter::Error`].
+    ///
+    type Error: From<::std::io::Error>;
+
+    ///
+    /// Update `f`, which contains the output written for `previous`, so that it contains the
+    /// output for `current`, returning the regions of `f` that were rewritten.
+    ///
+    fn write_patch<F>(
+        &self,
+        f: &mut F,
+        previous: &T,
+        current: &T,
+    ) -> Result<PatchSummary, Self::Error>
+    where
+        F: Read + Write + Seek + Truncate;
+
+    ///
+    /// Check that the options for this writer, if any, are consistent; the default
+    /// implementation does nothing.
+    ///
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    ///
+    /// Update the provided bytes, which contain the output written for `previous`, so that they
+    /// contain the output for `current`.
+    ///
+    fn patch_bytes(
+        &self,
+        bytes: &mut Vec<u8>,
+        previous: &T,
+        current: &T,
+    ) -> Result<PatchSummary, Self::Error> {
+        self.validate_options()?;
+        self.write_patch(&mut Cursor::new(bytes), previous, current)
+    }
+
+    ///
+    /// Update the file identified by `path`, which contains the output written for `previous`, so
+    /// that it contains the output for `current`, and sync it to durable storage.
+    ///
+    fn patch_file<P>(&self, path: P, previous: &T, current: &T) -> Result<PatchSummary, Self::Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.validate_options()?;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path.as_ref())?;
+        let summary = self.write_patch(&mut file, previous, current)?;
+        file.sync_all()?;
+        Ok(summary)
+    }
+}
+
+///
+/// The regions of the output rewritten by [`ObjectPatchWriter::write_patch`].
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PatchSummary {
+    regions: Vec<Range<u64>>,
+    len: u64,
+}
+
+///
+/// A patch writer for a sequence of records, written by an inner [`ObjectsWriter`].
+///
+/// The output must have been written by the inner writer, as it is by this type's implementation
+/// of [`ObjectWriter`], and the inner writer must write each record, and the end of the stream,
+/// identically given the same record, index, and count.
+///
+#[derive(Clone, Debug, Default)]
+pub struct RecordPatchWriter<O> {
+    inner: O,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Truncate for File {
+    fn truncate(&mut self, len: u64) -> std::io::Result<()> {
+        self.set_len(len)
+    }
+}
+
+impl Truncate for Cursor<Vec<u8>> {
+    fn truncate(&mut self, len: u64) -> std::io::Result<()> {
+        self.get_mut().truncate(to_usize(len)?);
+        Ok(())
+    }
+}
+
+impl Truncate for Cursor<&mut Vec<u8>> {
+    fn truncate(&mut self, len: u64) -> std::io::Result<()> {
+        self.get_mut().truncate(to_usize(len)?);
+        Ok(())
+    }
+}
+
+impl<F> Truncate for &mut F
+where
+    F: Truncate + ?Sized,
+{
+    fn truncate(&mut self, len: u64) -> std::io::Result<()> {
+        (**self).truncate(len)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl PatchSummary {
+    ///
+    /// Returns the byte ranges of the output that were rewritten, in order.
+    ///
+    pub fn regions(&self) -> &[Range<u64>] {
+        &self.regions
+    }
+
+    ///
+    /// Returns the total number of bytes rewritten.
+    ///
+    pub fn bytes_written(&self) -> u64 {
+        self.regions
+            .iter()
+            .map(|region| region.end - region.start)
+            .sum()
+    }
+
+    ///
+    /// Returns the length of the output after it was patched.
+    ///
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    ///
+    /// Returns `true` if nothing was rewritten.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O, T> ObjectWriter<Vec<T>> for RecordPatchWriter<O>
+where
+    O: ObjectsWriter<T>,
+{
+    type Error = O::Error;
+
+    fn write<W>(&self, w: &mut W, object: &Vec<T>) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.inner.write_all(w, object.iter()).map(|_| ())
+    }
+}
+
+impl<O, T> ObjectPatchWriter<Vec<T>> for RecordPatchWriter<O>
+where
+    O: ObjectsWriter<T>,
+    T: PartialEq,
+{
+    type Error = O::Error;
+
+    fn write_patch<F>(
+        &self,
+        f: &mut F,
+        previous: &Vec<T>,
+        current: &Vec<T>,
+    ) -> Result<PatchSummary, Self::Error>
+    where
+        F: Read + Write + Seek + Truncate,
+    {
+        let mut summary = PatchSummary::default();
+        let mut scratch = Vec::new();
+        self.inner.write_start(&mut scratch)?;
+        let mut offset = scratch.len() as u64;
+
+        // Overwrite changed records in place, until one whose length differs.
+        let common = previous.len().min(current.len());
+        let mut index = 0;
+        while index < common {
+            scratch.clear();
+            self.inner
+                .write_next(&mut scratch, &previous[index], index)?;
+            let len = scratch.len();
+            if previous[index] != current[index] {
+                scratch.clear();
+                self.inner
+                    .write_next(&mut scratch, &current[index], index)?;
+                if scratch.len() != len {
+                    break;
+                }
+                let _ = f.seek(SeekFrom::Start(offset))?;
+                f.write_all(&scratch)?;
+                summary.regions.push(offset..offset + len as u64);
+            }
+            offset += len as u64;
+            index += 1;
+        }
+
+        if index == common && previous.len() == current.len() {
+            scratch.clear();
+            self.inner.write_end(&mut scratch, current.len())?;
+            summary.len = offset + scratch.len() as u64;
+        } else {
+            // Rewrite the remaining records, and the end of the stream.
+            let start = offset;
+            let _ = f.seek(SeekFrom::Start(start))?;
+            let mut w = BufWriter::new(&mut *f);
+            for (index, record) in current.iter().enumerate().skip(index) {
+                scratch.clear();
+                self.inner.write_next(&mut scratch, record, index)?;
+                w.write_all(&scratch)?;
+                offset += scratch.len() as u64;
+            }
+            scratch.clear();
+            self.inner.write_end(&mut scratch, current.len())?;
+            w.write_all(&scratch)?;
+            offset += scratch.len() as u64;
+            w.flush()?;
+            drop(w);
+            f.truncate(offset)?;
+            summary.regions.push(start..offset);
+            summary.len = offset;
+        }
+        f.flush()?;
+        Ok(summary)
+    }
+}
+
+impl<O> RecordPatchWriter<O> {
+    ///
+    /// Construct a new patch writer for records written by `inner`.
+    ///
+    pub fn new(inner: O) -> Self {
+        Self { inner }
+    }
+
+    ///
+    /// Returns a reference to the inner writer.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Returns the inner writer.
+    ///
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn to_usize(len: u64) -> std::io::Result<usize> {
+    usize::try_from(len).map_err(std::io::Error::other)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Error;
+
+    #[derive(Debug, Default)]
+    struct ArrayWriter;
+
+    impl ObjectsWriter<u32> for ArrayWriter {
+        type Error = Error;
+
+        fn write_start<W: Write>(&self, w: &mut W) -> Result<(), Self::Error> {
+            w.write_all(b"[")
+        }
+
+        fn write_next<W: Write>(
+            &self,
+            w: &mut W,
+            object: &u32,
+            index: usize,
+        ) -> Result<(), Self::Error> {
+            if index > 0 {
+                w.write_all(b",")?;
+            }
+            write!(w, "{object}")
+        }
+
+        fn write_end<W: Write>(&self, w: &mut W, count: usize) -> Result<(), Self::Error> {
+            write!(w, "]#{count}")
+        }
+    }
+
+    fn patch(previous: &[u32], current: &[u32]) -> (String, PatchSummary) {
+        let writer = RecordPatchWriter::new(ArrayWriter);
+        let (previous, current) = (previous.to_vec(), current.to_vec());
+        let mut output = writer.write_to_bytes(&previous).unwrap();
+        let summary = writer
+            .patch_bytes(&mut output, &previous, &current)
+            .unwrap();
+        assert_eq!(output, writer.write_to_bytes(&current).unwrap());
+        assert_eq!(summary.len(), output.len() as u64);
+        (String::from_utf8(output).unwrap(), summary)
+    }
+
+    #[test]
+    fn test_patch_in_place() {
+        let (output, summary) = patch(&[10, 20, 30, 40], &[10, 21, 30, 49]);
+        assert_eq!(output, "[10,21,30,49]#4");
+        assert_eq!(summary.regions(), &[3..6, 9..12]);
+        assert_eq!(summary.bytes_written(), 6);
+
+        let (_, summary) = patch(&[1, 2], &[1, 2]);
+        assert!(summary.is_empty());
+    }
+
+    #[test]
+    fn test_patch_rewrites_tail() {
+        let (output, summary) = patch(&[1, 2, 3, 4], &[5, 200, 3, 4]);
+        assert_eq!(output, "[5,200,3,4]#4");
+        assert_eq!(summary.regions(), &[1..2, 2..13]);
+
+        let (output, summary) = patch(&[1, 2, 3, 4], &[1, 2]);
+        assert_eq!(output, "[1,2]#2");
+        assert_eq!(summary.regions().len(), 1);
+        assert_eq!(summary.regions()[0], 4..7);
+
+        let (output, _) = patch(&[], &[7, 8]);
+        assert_eq!(output, "[7,8]#2");
+    }
+}
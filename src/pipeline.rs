@@ -0,0 +1,487 @@
+/*!
+Provides a builder that connects an [`ObjectsReader`], a sequence of transformation stages, and an
+[`ObjectsWriter`] into a single pipeline.
+
+A [`Pipeline`] is constructed from a reader, extended with [`map`](Pipeline::map),
+[`try_map`](Pipeline::try_map), [`transform`](Pipeline::transform), and
+[`filter`](Pipeline::filter) stages, and completed with
+[`write_with`](Pipeline::write_with) to produce a [`PipelineRunner`]. Running the pipeline returns
+[`PipelineStats`] describing the records processed; any failure is reported as a
+[`PipelineError`] identifying the stage and record that failed, along with the statistics up to
+that point.
+
+# Example
+
+```rust
+use objio::pipeline::Pipeline;
+use objio::stream::{ObjectsReader, ObjectsWriter};
+use std::io::{BufRead, Write};
+
+#[derive(Debug, Default)]
+struct LinesIo;
+
+impl ObjectsReader<String> for LinesIo {
+    type Error = std::io::Error;
+
+    fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<String>, Self::Error> {
+        let mut line = String::new();
+        Ok(match r.read_line(&mut line)? {
+            0 => None,
+            _ => Some(line.trim_end().to_string()),
+        })
+    }
+}
+
+impl ObjectsWriter<String> for LinesIo {
+    type Error = std::io::Error;
+
+    fn write_next<W: Write>(&self, w: &mut W, object: &String, _: usize) -> Result<(), Self::Error> {
+        writeln!(w, "{object}")
+    }
+}
+
+let mut pipeline = Pipeline::new(LinesIo)
+    .filter(|line: &String| !line.starts_with('#'))
+    .map(|line| line.to_uppercase())
+    .write_with(LinesIo);
+
+let mut output: Vec<u8> = Vec::new();
+let stats = pipeline.run("# header\none\ntwo\n".as_bytes(), &mut output).unwrap();
+
+assert_eq!(output, b"ONE\nTWO\n");
+assert_eq!(stats.read(), 3);
+assert_eq!(stats.filtered(), 1);
+assert_eq!(stats.written(), 2);
+```
+ */
+
+use crate::error::BoxedError;
+use crate::stream::{ObjectsReader, ObjectsWriter};
+use crate::transform::Transform;
+use std::fmt::{Debug, Display, Formatter};
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A pipeline of transformation stages applied to the objects, of type `I`, read by an
+/// [`ObjectsReader`], producing objects of type `T`.
+///
+pub struct Pipeline<R, I, T> {
+    reader: R,
+    stages: Stages<I, T>,
+}
+
+///
+/// A complete pipeline, with a writer for the transformed objects, ready to run.
+///
+pub struct PipelineRunner<R, I, T, W> {
+    reader: R,
+    stages: Stages<I, T>,
+    writer: W,
+    object: PhantomData<fn(I)>,
+}
+
+///
+/// Statistics describing a run of a pipeline.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PipelineStats {
+    read: usize,
+    filtered: usize,
+    written: usize,
+    elapsed: Duration,
+}
+
+///
+/// The part of a pipeline in which an error occurred.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PipelineStage {
+    /// Reading an object from the source.
+    Read,
+    /// Applying a transformation stage to an object.
+    Transform,
+    /// Writing an object, or the start or end of the stream, to the destination.
+    Write,
+}
+
+///
+/// An error reported by a pipeline, identifying the stage and the record in which it occurred.
+///
+#[derive(Debug)]
+pub struct PipelineError {
+    stage: PipelineStage,
+    record: usize,
+    stats: PipelineStats,
+    source: BoxedError,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+type Stages<I, T> = Box<dyn FnMut(I) -> Result<Option<T>, BoxedError>>;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<R, I, T> Debug for Pipeline<R, I, T>
+where
+    R: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("reader", &self.reader)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R, I> Pipeline<R, I, I>
+where
+    R: ObjectsReader<I>,
+    I: 'static,
+{
+    ///
+    /// Construct a new pipeline of the objects read by `reader`, with no transformation stages.
+    ///
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            stages: Box::new(|object| Ok(Some(object))),
+        }
+    }
+}
+
+impl<R, I, T> Pipeline<R, I, T>
+where
+    R: ObjectsReader<I>,
+    I: 'static,
+    T: 'static,
+{
+    ///
+    /// Add a stage that transforms each object using `f`.
+    ///
+    pub fn map<U, F>(self, mut f: F) -> Pipeline<R, I, U>
+    where
+        F: FnMut(T) -> U + 'static,
+    {
+        self.then(move |object| Ok(Some(f(object))))
+    }
+
+    ///
+    /// Add a stage that transforms each object using the fallible `f`; an error stops the
+    /// pipeline.
+    ///
+    pub fn try_map<U, E, F>(self, mut f: F) -> Pipeline<R, I, U>
+    where
+        E: Into<BoxedError>,
+        F: FnMut(T) -> Result<U, E> + 'static,
+    {
+        self.then(move |object| f(object).map(Some).map_err(Into::into))
+    }
+
+    ///
+    /// Add a stage that converts each object using `transform`; an error stops the pipeline.
+    ///
+    pub fn transform<U, X>(self, transform: X) -> Pipeline<R, I, U>
+    where
+        X: Transform<T, U> + 'static,
+    {
+        self.try_map(move |object| transform.transform(object))
+    }
+
+    ///
+    /// Add a stage that discards each object for which `predicate` returns `false`.
+    ///
+    pub fn filter<P>(self, mut predicate: P) -> Self
+    where
+        P: FnMut(&T) -> bool + 'static,
+    {
+        self.then(move |object| Ok(predicate(&object).then_some(object)))
+    }
+
+    ///
+    /// Complete the pipeline, writing the transformed objects using `writer`.
+    ///
+    pub fn write_with<W>(self, writer: W) -> PipelineRunner<R, I, T, W>
+    where
+        W: ObjectsWriter<T>,
+    {
+        PipelineRunner {
+            reader: self.reader,
+            stages: self.stages,
+            writer,
+            object: PhantomData,
+        }
+    }
+
+    fn then<U, F>(self, mut stage: F) -> Pipeline<R, I, U>
+    where
+        F: FnMut(T) -> Result<Option<U>, BoxedError> + 'static,
+    {
+        let mut stages = self.stages;
+        Pipeline {
+            reader: self.reader,
+            stages: Box::new(move |object| match stages(object)? {
+                Some(object) => stage(object),
+                None => Ok(None),
+            }),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<R, I, T, W> Debug for PipelineRunner<R, I, T, W>
+where
+    R: Debug,
+    W: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PipelineRunner")
+            .field("reader", &self.reader)
+            .field("writer", &self.writer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R, I, T, W> PipelineRunner<R, I, T, W>
+where
+    R: ObjectsReader<I>,
+    R::Error: Into<BoxedError>,
+    W: ObjectsWriter<T>,
+    W::Error: Into<BoxedError>,
+{
+    ///
+    /// Run the pipeline, reading from `r` and writing to `w`, which is flushed on completion.
+    ///
+    pub fn run<Rd, Wr>(&mut self, r: Rd, w: &mut Wr) -> Result<PipelineStats, PipelineError>
+    where
+        Rd: BufRead,
+        Wr: Write,
+    {
+        let started = Instant::now();
+        let mut stats = PipelineStats::default();
+        let fail = |stage, record, stats: PipelineStats, source: BoxedError| PipelineError {
+            stage,
+            record,
+            stats: stats.with_elapsed(started.elapsed()),
+            source,
+        };
+
+        self.writer
+            .write_start(w)
+            .map_err(|e| fail(PipelineStage::Write, 0, stats, e.into()))?;
+        for object in self.reader.objects(r) {
+            let record = stats.read;
+            let object = object.map_err(|e| fail(PipelineStage::Read, record, stats, e.into()))?;
+            stats.read += 1;
+            match (self.stages)(object) {
+                Ok(Some(object)) => {
+                    self.writer
+                        .write_next(w, &object, stats.written)
+                        .map_err(|e| fail(PipelineStage::Write, record, stats, e.into()))?;
+                    stats.written += 1;
+                }
+                Ok(None) => stats.filtered += 1,
+                Err(e) => return Err(fail(PipelineStage::Transform, record, stats, e)),
+            }
+        }
+        self.writer
+            .write_end(w, stats.written)
+            .map_err(|e| fail(PipelineStage::Write, stats.read, stats, e.into()))?;
+        w.flush()
+            .map_err(|e| fail(PipelineStage::Write, stats.read, stats, e.into()))?;
+
+        Ok(stats.with_elapsed(started.elapsed()))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl PipelineStats {
+    ///
+    /// Returns the number of objects read from the source.
+    ///
+    pub fn read(&self) -> usize {
+        self.read
+    }
+
+    ///
+    /// Returns the number of objects discarded by filter stages.
+    ///
+    pub fn filtered(&self) -> usize {
+        self.filtered
+    }
+
+    ///
+    /// Returns the number of objects written to the destination.
+    ///
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    ///
+    /// Returns the time taken by the run.
+    ///
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn with_elapsed(self, elapsed: Duration) -> Self {
+        let mut self_mut = self;
+        self_mut.elapsed = elapsed;
+        self_mut
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PipelineStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Read => "read",
+                Self::Transform => "transform",
+                Self::Write => "write",
+            }
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PipelineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Pipeline failed in the {} stage at record {}; source: {}",
+            self.stage, self.record, self.source
+        )
+    }
+}
+
+impl std::error::Error for PipelineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl PipelineError {
+    ///
+    /// Returns the stage in which the error occurred.
+    ///
+    pub fn stage(&self) -> PipelineStage {
+        self.stage
+    }
+
+    ///
+    /// Returns the zero-based index of the record being processed when the error occurred.
+    ///
+    pub fn record(&self) -> usize {
+        self.record
+    }
+
+    ///
+    /// Returns the statistics of the run up to the point the error occurred.
+    ///
+    pub fn stats(&self) -> &PipelineStats {
+        &self.stats
+    }
+
+    ///
+    /// Returns the underlying error, consuming this error.
+    ///
+    pub fn into_source(self) -> BoxedError {
+        self.source
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Error as IoError, ErrorKind};
+
+    #[derive(Debug, Default)]
+    struct NumbersIo;
+
+    impl ObjectsReader<i32> for NumbersIo {
+        type Error = IoError;
+
+        fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<i32>, Self::Error> {
+            let mut line = String::new();
+            if r.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            line.trim()
+                .parse()
+                .map(Some)
+                .map_err(|e| IoError::new(ErrorKind::InvalidData, e))
+        }
+    }
+
+    impl ObjectsWriter<i32> for NumbersIo {
+        type Error = IoError;
+
+        fn write_next<W: Write>(
+            &self,
+            w: &mut W,
+            object: &i32,
+            _: usize,
+        ) -> Result<(), Self::Error> {
+            writeln!(w, "{object}")
+        }
+    }
+
+    #[test]
+    fn test_stages_in_order() {
+        let mut pipeline = Pipeline::new(NumbersIo)
+            .map(|n| n * 10)
+            .filter(|n| *n != 20)
+            .try_map(|n| u8::try_from(n + 1))
+            .map(i32::from)
+            .write_with(NumbersIo);
+        let mut output: Vec<u8> = Vec::new();
+        let stats = pipeline.run("1\n2\n3\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(output, b"11\n31\n");
+        assert_eq!((stats.read(), stats.filtered(), stats.written()), (3, 1, 2));
+    }
+
+    #[test]
+    fn test_read_error() {
+        let mut pipeline = Pipeline::new(NumbersIo).write_with(NumbersIo);
+        let mut output: Vec<u8> = Vec::new();
+        let error = pipeline
+            .run("1\nx\n3\n".as_bytes(), &mut output)
+            .unwrap_err();
+        assert_eq!(error.stage(), PipelineStage::Read);
+        assert_eq!(error.record(), 1);
+        assert_eq!(error.stats().written(), 1);
+    }
+
+    #[test]
+    fn test_transform_error() {
+        let mut pipeline = Pipeline::new(NumbersIo)
+            .try_map(|n| if n < 0 { Err("negative") } else { Ok(n) })
+            .write_with(NumbersIo);
+        let mut output: Vec<u8> = Vec::new();
+        let error = pipeline.run("1\n-2\n".as_bytes(), &mut output).unwrap_err();
+        assert_eq!(error.stage(), PipelineStage::Transform);
+        assert_eq!(
+            error.to_string(),
+            "Pipeline failed in the transform stage at record 1; source: negative"
+        );
+    }
+}
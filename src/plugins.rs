@@ -0,0 +1,321 @@
+/*!
+Provides dynamic format plugins, enabled by the `plugins` feature, so that readers and writers
+built as separate shared libraries may be added to a [`FormatRegistry`] at runtime.
+
+A plugin is a library of crate type `cdylib` that declares itself with the
+[`export_plugin!`](crate::export_plugin!) macro, naming a function that is given a
+[`PluginRegistrar`] and registers its formats with each registry of an object type it supports.
+An application loads the library with [`load_plugin`] and then calls
+[`Plugin::register_formats`] for each registry.
+
+Rust has no stable ABI, so a plugin **must** be built with the same compiler and the same version
+of this crate, and of any crate defining a shared object type, as the application; the
+[`PLUGIN_API_VERSION`] and crate version recorded by the macro are checked when loading but the
+compiler is not. A loaded library is never unloaded, as the readers and writers it registered
+refer to its code.
+
+# Example
+
+The plugin library:
+
+```rust
+use objio::plugins::PluginRegistrar;
+use objio::raw::{RawReader, RawWriter};
+
+fn register(registrar: &mut PluginRegistrar<'_>) {
+    if let Some(registry) = registrar.registry::<String>() {
+        registry.register("text", &["txt"], RawReader, RawWriter);
+    }
+}
+
+objio::export_plugin!("text-formats", register);
+```
+
+The application:
+
+```rust,no_run
+use objio::plugins::load_plugin;
+use objio::registry::FormatRegistry;
+
+let mut registry: FormatRegistry<String> = FormatRegistry::default();
+// SAFETY: the plugin is trusted and was built as required above.
+let plugin = unsafe { load_plugin("plugins/libtext_formats.so") }.unwrap();
+plugin.register_formats(&mut registry);
+
+assert_eq!(registry.format_for_path("notes.txt"), Some("text"));
+```
+ */
+
+use crate::registry::FormatRegistry;
+use libloading::Library;
+use std::any::Any;
+use std::fmt::{Debug, Formatter};
+use std::io::{Error as IoError, ErrorKind};
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The version of the plugin entry point, incremented whenever [`PluginDeclaration`] or
+/// [`PluginRegistrar`] change.
+///
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+///
+/// The version of this crate, recorded by [`export_plugin!`](crate::export_plugin!) and checked
+/// when loading.
+///
+pub const OBJIO_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+///
+/// The name of the static exported by [`export_plugin!`](crate::export_plugin!).
+///
+pub const PLUGIN_DECLARATION_SYMBOL: &str = "OBJIO_PLUGIN_DECLARATION";
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The declaration exported by a plugin library, created by
+/// [`export_plugin!`](crate::export_plugin!).
+///
+#[derive(Debug)]
+pub struct PluginDeclaration {
+    /// The plugin entry point version the library was built with.
+    pub api_version: u32,
+    /// The version of this crate the library was built with.
+    pub objio_version: &'static str,
+    /// The name of the plugin.
+    pub name: &'static str,
+    /// The function that registers the plugin's formats.
+    pub register: fn(&mut PluginRegistrar<'_>),
+}
+
+///
+/// Passed to a plugin's registration function for each registry, of any object type, to which
+/// the plugin's formats are added.
+///
+pub struct PluginRegistrar<'a> {
+    registry: &'a mut dyn Any,
+}
+
+///
+/// A loaded plugin library.
+///
+pub struct Plugin {
+    path: PathBuf,
+    declaration: &'static PluginDeclaration,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Macros
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Declare the library being built as a plugin with the given name and registration function,
+/// a `fn(&mut PluginRegistrar<'_>)`. This must be used once, at the root of the library.
+///
+#[macro_export]
+macro_rules! export_plugin {
+    ($name:expr, $register:expr $(,)?) => {
+        #[doc(hidden)]
+        #[allow(unreachable_pub, unsafe_code)]
+        #[no_mangle]
+        pub static OBJIO_PLUGIN_DECLARATION: $crate::plugins::PluginDeclaration =
+            $crate::plugins::PluginDeclaration {
+                api_version: $crate::plugins::PLUGIN_API_VERSION,
+                objio_version: $crate::plugins::OBJIO_VERSION,
+                name: $name,
+                register: $register,
+            };
+    };
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Load the plugin library at `path` and check that it was built for this version of the plugin
+/// entry point and of this crate.
+///
+/// # Safety
+///
+/// Loading a library runs its initialization code, and calling into it is only sound if it was
+/// built with the same compiler, and the same versions of any shared crates, as the application.
+/// The caller must trust the library to meet these requirements.
+///
+#[allow(unsafe_code)]
+pub unsafe fn load_plugin<P>(path: P) -> Result<Plugin, IoError>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    // SAFETY: the caller trusts the library's initialization code.
+    let library = unsafe { Library::new(path) }.map_err(IoError::other)?;
+    // SAFETY: the symbol is exported by `export_plugin!` with this type.
+    let declaration: &'static PluginDeclaration = unsafe {
+        let symbol = library
+            .get::<*const PluginDeclaration>(PLUGIN_DECLARATION_SYMBOL.as_bytes())
+            .map_err(IoError::other)?;
+        &**symbol
+    };
+    check_declaration(declaration)?;
+    // The library is never unloaded, so the declaration, and registered readers and writers,
+    // remain valid.
+    std::mem::forget(library);
+    Ok(Plugin {
+        path: path.to_path_buf(),
+        declaration,
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for PluginRegistrar<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginRegistrar").finish_non_exhaustive()
+    }
+}
+
+impl<'a> PluginRegistrar<'a> {
+    ///
+    /// Construct a new registrar for `registry`; this is called by
+    /// [`Plugin::register_formats`] and need not be called directly.
+    ///
+    pub fn new<T>(registry: &'a mut FormatRegistry<T>) -> Self
+    where
+        T: 'static,
+    {
+        Self { registry }
+    }
+
+    ///
+    /// Returns the registry being populated if its object type is `T`.
+    ///
+    pub fn registry<T>(&mut self) -> Option<&mut FormatRegistry<T>>
+    where
+        T: 'static,
+    {
+        self.registry.downcast_mut()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for Plugin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Plugin")
+            .field("path", &self.path)
+            .field("name", &self.declaration.name)
+            .finish()
+    }
+}
+
+impl Plugin {
+    ///
+    /// Returns the name declared by the plugin.
+    ///
+    pub fn name(&self) -> &'static str {
+        self.declaration.name
+    }
+
+    ///
+    /// Returns the path the plugin was loaded from.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    ///
+    /// Register the plugin's formats for the object type `T` with `registry`.
+    ///
+    pub fn register_formats<T>(&self, registry: &mut FormatRegistry<T>)
+    where
+        T: 'static,
+    {
+        (self.declaration.register)(&mut PluginRegistrar::new(registry));
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn check_declaration(declaration: &PluginDeclaration) -> Result<(), IoError> {
+    if declaration.api_version != PLUGIN_API_VERSION {
+        Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!(
+                "plugin {:?} uses entry point version {}, expected {}",
+                declaration.name, declaration.api_version, PLUGIN_API_VERSION
+            ),
+        ))
+    } else if declaration.objio_version != OBJIO_VERSION {
+        Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!(
+                "plugin {:?} was built with objio {}, expected {}",
+                declaration.name, declaration.objio_version, OBJIO_VERSION
+            ),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::{RawReader, RawWriter};
+
+    fn register(registrar: &mut PluginRegistrar<'_>) {
+        if let Some(registry) = registrar.registry::<Vec<u8>>() {
+            registry.register("raw", &["bin"], RawReader, RawWriter);
+        }
+    }
+
+    crate::export_plugin!("test", register);
+
+    #[test]
+    fn test_register_formats() {
+        check_declaration(&OBJIO_PLUGIN_DECLARATION).unwrap();
+        let plugin = Plugin {
+            path: PathBuf::new(),
+            declaration: &OBJIO_PLUGIN_DECLARATION,
+        };
+
+        let mut registry: FormatRegistry<Vec<u8>> = FormatRegistry::default();
+        plugin.register_formats(&mut registry);
+        assert_eq!(registry.format_for_path("x.bin"), Some("raw"));
+
+        let mut registry: FormatRegistry<String> = FormatRegistry::default();
+        plugin.register_formats(&mut registry);
+        assert_eq!(registry.format_names().count(), 0);
+    }
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn test_check_declaration() {
+        let declaration = PluginDeclaration {
+            api_version: PLUGIN_API_VERSION + 1,
+            objio_version: OBJIO_VERSION,
+            name: "future",
+            register,
+        };
+        assert_eq!(
+            check_declaration(&declaration).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert!(unsafe { load_plugin("no/such/plugin.so") }.is_err());
+    }
+}
@@ -0,0 +1,222 @@
+/*!
+Provides [`PrefetchReader`], a source wrapper that reads ahead from an inner source on a
+background thread, so that a parser reading from it does not wait on the latency of a disk or
+network.
+
+The background thread fills a fixed ring of buffers, and so the memory used is bounded by their
+number and size; once every buffer is full it waits for the parser to consume one. An error from
+the inner source is returned once the content read before it has been consumed. If the reader is
+dropped early the background thread stops at its next read.
+
+# Example
+
+```rust
+use objio::prefetch::PrefetchReader;
+use std::io::Read;
+
+let source: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+let mut reader = PrefetchReader::with_buffers(std::io::Cursor::new(source.clone()), 3, 4096);
+
+let mut content = Vec::new();
+reader.read_to_end(&mut content).unwrap();
+assert_eq!(content, source);
+```
+ */
+
+use std::fmt::{Debug, Formatter};
+use std::io::{BufRead, ErrorKind, Read, Result as IoResult};
+use std::sync::mpsc::{channel, sync_channel, Receiver, Sender};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A source that reads ahead from an inner source on a background thread into a ring of buffers.
+///
+pub struct PrefetchReader {
+    filled: Receiver<IoResult<Vec<u8>>>,
+    recycled: Sender<Vec<u8>>,
+    current: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+///
+/// The default number of buffers used by [`PrefetchReader::new`].
+///
+pub const DEFAULT_BUFFER_COUNT: usize = 4;
+
+///
+/// The default size, in bytes, of each buffer used by [`PrefetchReader::new`].
+///
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for PrefetchReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrefetchReader")
+            .field("buffered", &(self.current.len() - self.position))
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Read for PrefetchReader {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl BufRead for PrefetchReader {
+    fn fill_buf(&mut self) -> IoResult<&[u8]> {
+        if self.position >= self.current.len() && !self.finished {
+            let used = std::mem::take(&mut self.current);
+            // The background thread may have stopped, in which case the buffer is not needed.
+            let _ = self.recycled.send(used);
+            self.position = 0;
+            match self.filled.recv() {
+                Ok(Ok(buffer)) => self.current = buffer,
+                Ok(Err(e)) => {
+                    self.finished = true;
+                    return Err(e);
+                }
+                Err(_) => self.finished = true,
+            }
+        }
+        Ok(&self.current[self.position..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position = (self.position + amt).min(self.current.len());
+    }
+}
+
+impl PrefetchReader {
+    ///
+    /// Start reading ahead from `inner` into [`DEFAULT_BUFFER_COUNT`] buffers, each of
+    /// [`DEFAULT_BUFFER_SIZE`] bytes.
+    ///
+    pub fn new<R>(inner: R) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        Self::with_buffers(inner, DEFAULT_BUFFER_COUNT, DEFAULT_BUFFER_SIZE)
+    }
+
+    ///
+    /// Start reading ahead from `inner` into `count` buffers, each of `size` bytes; both are
+    /// at least one.
+    ///
+    pub fn with_buffers<R>(inner: R, count: usize, size: usize) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        let size = size.max(1);
+        // One buffer is always being filled, so the channel holds the remainder.
+        let (filled_sender, filled) = sync_channel(count.max(1) - 1);
+        let (recycled, recycled_receiver) = channel::<Vec<u8>>();
+        let _ = std::thread::spawn(move || {
+            let mut inner = inner;
+            loop {
+                let mut buffer = recycled_receiver.try_recv().unwrap_or_default();
+                buffer.resize(size, 0);
+                let result = loop {
+                    match inner.read(&mut buffer) {
+                        Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                        result => break result,
+                    }
+                };
+                let message = match result {
+                    Ok(0) => break,
+                    Ok(length) => {
+                        buffer.truncate(length);
+                        Ok(buffer)
+                    }
+                    Err(e) => Err(e),
+                };
+                let failed = message.is_err();
+                if filled_sender.send(message).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Self {
+            filled,
+            recycled,
+            current: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ObjectReader;
+    use std::io::Error as IoError;
+
+    struct FailingSource {
+        remaining: usize,
+    }
+
+    impl Read for FailingSource {
+        fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+            if self.remaining == 0 {
+                Err(IoError::new(ErrorKind::ConnectionReset, "connection reset"))
+            } else {
+                let count = self.remaining.min(buf.len()).min(3);
+                buf[..count].fill(b'x');
+                self.remaining -= count;
+                Ok(count)
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct LinesReader;
+
+    impl ObjectReader<Vec<String>> for LinesReader {
+        type Error = IoError;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<Vec<String>, Self::Error> {
+            self.read_buffered(&mut std::io::BufReader::new(r))
+        }
+
+        fn read_buffered<R: BufRead>(&self, r: &mut R) -> Result<Vec<String>, Self::Error> {
+            r.lines().collect()
+        }
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let text: String = (0..1000).map(|i| format!("line {i}\n")).collect();
+        let mut reader = PrefetchReader::with_buffers(std::io::Cursor::new(text), 2, 7);
+        let lines = LinesReader.read_buffered(&mut reader).unwrap();
+        assert_eq!(lines.len(), 1000);
+        assert_eq!(lines[999], "line 999");
+        assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_error_after_content() {
+        let mut reader = PrefetchReader::with_buffers(FailingSource { remaining: 10 }, 1, 0);
+        let mut content = Vec::new();
+        let error = reader.read_to_end(&mut content).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ConnectionReset);
+        assert_eq!(content, b"xxxxxxxxxx");
+        assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+    }
+}
@@ -0,0 +1,378 @@
+/*!
+Provides namespace prefix management for writers of RDF, and similar, formats that abbreviate
+IRIs as prefixed names, such as `rdf:type`.
+
+A [`PrefixMap`] maps prefixes to namespace IRIs, in the order they were added. It compacts an IRI
+using the longest matching namespace, provided the remainder is a valid local name; expands a
+prefixed name back to an IRI; generates a prefix for a new namespace; and writes the prefix
+declarations in the style of Turtle or SPARQL.
+
+# Example
+
+```rust
+use objio::prefixes::{PrefixMap, PrefixStyle};
+
+let mut prefixes = PrefixMap::default();
+prefixes.insert("ex", "http://example.org/");
+prefixes.insert("exv", "http://example.org/vocab#");
+
+let name = prefixes.compact("http://example.org/vocab#Thing").unwrap();
+assert_eq!(name.to_string(), "exv:Thing");
+assert!(prefixes.compact("http://example.org/a/b").is_none());
+
+assert_eq!(prefixes.generate("http://xmlns.com/foaf/0.1/"), "ns");
+assert_eq!(prefixes.generate("http://purl.org/dc/terms/"), "terms");
+assert_eq!(prefixes.expand("terms:title").unwrap(), "http://purl.org/dc/terms/title");
+
+let mut output = Vec::new();
+prefixes.write_declarations(&mut output, PrefixStyle::Sparql).unwrap();
+assert!(String::from_utf8(output).unwrap().starts_with("PREFIX ex: <http://example.org/>\n"));
+```
+ */
+
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A map from prefixes to namespace IRIs, in the order in which they were added.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrefixMap {
+    mappings: Vec<(String, String)>,
+}
+
+///
+/// An IRI compacted to a prefix and a local name by [`PrefixMap::compact`]; it is displayed as a
+/// prefixed name.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrefixedName<'a> {
+    prefix: &'a str,
+    local_name: &'a str,
+}
+
+///
+/// The syntax used by [`PrefixMap::write_declarations`].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrefixStyle {
+    /// Declarations of the form `@prefix ex: <http://example.org/> .`
+    #[default]
+    Turtle,
+    /// Declarations of the form `PREFIX ex: <http://example.org/>`
+    Sparql,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Split `iri` into a namespace and a local name after the last `#` or `/`; if there is neither,
+/// the namespace is empty.
+///
+pub fn split_iri(iri: &str) -> (&str, &str) {
+    match iri.rfind(['#', '/']) {
+        Some(index) => iri.split_at(index + 1),
+        None => ("", iri),
+    }
+}
+
+///
+/// Returns `true` if `name` may be used as a prefix; that is, it is empty, or starts with a letter
+/// and contains only letters, digits, `-`, `_`, and `.`, and does not end with `.`.
+///
+pub fn is_valid_prefix(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        None => true,
+        Some(first) => {
+            first.is_alphabetic()
+                && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+                && !name.ends_with('.')
+        }
+    }
+}
+
+///
+/// Returns `true` if `name` may be used, without escapes, as a local name; that is, it is empty, or
+/// starts with a letter, digit, `_`, or `:`, and contains only those and `-` and `.`, and does
+/// not end with `.`.
+///
+pub fn is_valid_local_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        None => true,
+        Some(first) => {
+            (first.is_alphanumeric() || matches!(first, '_' | ':'))
+                && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+                && !name.ends_with('.')
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl PrefixMap {
+    ///
+    /// Returns a map containing the prefixes `rdf`, `rdfs`, `xsd`, and `owl`.
+    ///
+    pub fn common() -> Self {
+        let mut map = Self::default();
+        let _ = map.insert("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#");
+        let _ = map.insert("rdfs", "http://www.w3.org/2000/01/rdf-schema#");
+        let _ = map.insert("xsd", "http://www.w3.org/2001/XMLSchema#");
+        let _ = map.insert("owl", "http://www.w3.org/2002/07/owl#");
+        map
+    }
+
+    ///
+    /// Map `prefix` to `namespace`, returning the namespace it was previously mapped to, if any;
+    /// a replaced mapping keeps its position.
+    ///
+    pub fn insert<S1, S2>(&mut self, prefix: S1, namespace: S2) -> Option<String>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        let prefix = prefix.into();
+        let namespace = namespace.into();
+        match self.mappings.iter_mut().find(|(p, _)| *p == prefix) {
+            Some((_, existing)) => Some(std::mem::replace(existing, namespace)),
+            None => {
+                self.mappings.push((prefix, namespace));
+                None
+            }
+        }
+    }
+
+    ///
+    /// Remove the mapping for `prefix`, returning its namespace, if any.
+    ///
+    pub fn remove(&mut self, prefix: &str) -> Option<String> {
+        let index = self.mappings.iter().position(|(p, _)| p == prefix)?;
+        Some(self.mappings.remove(index).1)
+    }
+
+    ///
+    /// Returns the namespace mapped to `prefix`, if any.
+    ///
+    pub fn get(&self, prefix: &str) -> Option<&str> {
+        self.mappings
+            .iter()
+            .find(|(p, _)| p == prefix)
+            .map(|(_, namespace)| namespace.as_str())
+    }
+
+    ///
+    /// Returns the first prefix mapped to `namespace`, if any.
+    ///
+    pub fn prefix_for(&self, namespace: &str) -> Option<&str> {
+        self.mappings
+            .iter()
+            .find(|(_, n)| n == namespace)
+            .map(|(prefix, _)| prefix.as_str())
+    }
+
+    ///
+    /// Returns `true` if the map contains no prefixes.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    ///
+    /// Returns the number of prefixes in the map.
+    ///
+    pub fn len(&self) -> usize {
+        self.mappings.len()
+    }
+
+    ///
+    /// Returns an iterator over the prefixes and namespaces in the map, in the order they were
+    /// added.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.mappings
+            .iter()
+            .map(|(prefix, namespace)| (prefix.as_str(), namespace.as_str()))
+    }
+
+    ///
+    /// Compact `iri` using the longest namespace that it starts with, returning `None` if no
+    /// namespace matches or the remainder is not a valid local name. Where two prefixes map the
+    /// same namespace the first added is used.
+    ///
+    pub fn compact<'a>(&'a self, iri: &'a str) -> Option<PrefixedName<'a>> {
+        self.mappings
+            .iter()
+            .filter(|(_, namespace)| iri.starts_with(namespace.as_str()))
+            .filter(|(_, namespace)| is_valid_local_name(&iri[namespace.len()..]))
+            .rev()
+            .max_by_key(|(_, namespace)| namespace.len())
+            .map(|(prefix, namespace)| PrefixedName {
+                prefix,
+                local_name: &iri[namespace.len()..],
+            })
+    }
+
+    ///
+    /// Expand the prefixed name `name` to an IRI, returning `None` if it has no `:` or its prefix
+    /// is not in the map.
+    ///
+    pub fn expand(&self, name: &str) -> Option<String> {
+        let (prefix, local_name) = name.split_once(':')?;
+        self.get(prefix)
+            .map(|namespace| format!("{namespace}{local_name}"))
+    }
+
+    ///
+    /// Returns the prefix for `namespace`, adding one if it has none. A new prefix is taken from
+    /// the last segment of the namespace, if that is a valid prefix, else it is `ns`; a number is
+    /// appended if the prefix is already in use.
+    ///
+    pub fn generate(&mut self, namespace: &str) -> &str {
+        let index = match self.mappings.iter().position(|(_, n)| n == namespace) {
+            Some(index) => index,
+            None => {
+                let base = candidate_prefix(namespace);
+                let mut prefix = base.clone();
+                let mut suffix = 1;
+                while self.get(&prefix).is_some() {
+                    prefix = format!("{base}{suffix}");
+                    suffix += 1;
+                }
+                self.mappings.push((prefix, namespace.to_string()));
+                self.mappings.len() - 1
+            }
+        };
+        &self.mappings[index].0
+    }
+
+    ///
+    /// Write a declaration for each prefix, in the order they were added, each followed by a line
+    /// feed.
+    ///
+    pub fn write_declarations<W>(&self, w: &mut W, style: PrefixStyle) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        for (prefix, namespace) in self.iter() {
+            match style {
+                PrefixStyle::Turtle => writeln!(w, "@prefix {prefix}: <{namespace}> .")?,
+                PrefixStyle::Sparql => writeln!(w, "PREFIX {prefix}: <{namespace}>")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PrefixedName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.prefix, self.local_name)
+    }
+}
+
+impl<'a> PrefixedName<'a> {
+    ///
+    /// Returns the prefix.
+    ///
+    pub fn prefix(&self) -> &'a str {
+        self.prefix
+    }
+
+    ///
+    /// Returns the local name.
+    ///
+    pub fn local_name(&self) -> &'a str {
+        self.local_name
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn candidate_prefix(namespace: &str) -> String {
+    let (_, segment) = split_iri(namespace.trim_end_matches(['#', '/']));
+    let segment = segment.to_lowercase();
+    if !segment.is_empty() && is_valid_prefix(&segment) {
+        segment
+    } else {
+        "ns".to_string()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_longest_match() {
+        let mut prefixes = PrefixMap::common();
+        let _ = prefixes.insert("ex", "http://example.org/");
+        let _ = prefixes.insert("exa", "http://example.org/a/");
+        let _ = prefixes.insert("", "http://example.org/a/");
+        let name = prefixes.compact("http://example.org/a/b").unwrap();
+        assert_eq!((name.prefix(), name.local_name()), ("exa", "b"));
+        assert_eq!(
+            prefixes.compact("http://example.org/").unwrap().to_string(),
+            "ex:"
+        );
+        assert!(prefixes.compact("http://example.org/x.").is_none());
+        assert!(prefixes.compact("http://example.org/a/b?c").is_none());
+        assert_eq!(
+            prefixes
+                .compact("http://www.w3.org/2001/XMLSchema#string")
+                .unwrap()
+                .to_string(),
+            "xsd:string"
+        );
+    }
+
+    #[test]
+    fn test_insert_replaces_in_place() {
+        let mut prefixes = PrefixMap::default();
+        assert_eq!(prefixes.insert("a", "http://a/"), None);
+        let _ = prefixes.insert("b", "http://b/");
+        assert_eq!(prefixes.insert("a", "http://c/").unwrap(), "http://a/");
+        assert_eq!(prefixes.iter().next(), Some(("a", "http://c/")));
+        assert_eq!(prefixes.remove("b").unwrap(), "http://b/");
+        assert_eq!(prefixes.len(), 1);
+        assert_eq!(prefixes.prefix_for("http://c/"), Some("a"));
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut prefixes = PrefixMap::default();
+        assert_eq!(prefixes.generate("http://example.org/people#"), "people");
+        assert_eq!(prefixes.generate("http://other.org/people/"), "people1");
+        assert_eq!(prefixes.generate("http://example.org/people#"), "people");
+        assert_eq!(prefixes.generate("urn:x-1:"), "ns");
+        assert_eq!(prefixes.generate("http://example.org/2024/"), "ns1");
+        assert_eq!(split_iri("urn:isbn:123"), ("", "urn:isbn:123"));
+    }
+
+    #[test]
+    fn test_write_declarations() {
+        let mut prefixes = PrefixMap::default();
+        let _ = prefixes.insert("ex", "http://example.org/");
+        let mut output = Vec::new();
+        prefixes
+            .write_declarations(&mut output, PrefixStyle::Turtle)
+            .unwrap();
+        assert_eq!(output, b"@prefix ex: <http://example.org/> .\n");
+    }
+}
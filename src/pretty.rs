@@ -0,0 +1,370 @@
+/*!
+Provides a document model, and renderer, for Wadler-style pretty-printing.
+
+Writers build a [`Doc`] from text, line breaks, nested (indented) sections, and groups; the
+renderer then lays out the document within a target line width. Each group is rendered on a
+single line, with its line breaks replaced by their flat form, if it fits in the remaining width;
+otherwise its line breaks become new lines at the current nesting level. This gives good
+line-breaking for nested structures without each writer implementing its own layout logic.
+
+This module is only available with the `pretty` feature.
+
+# Example
+
+```rust
+use objio::pretty::Doc;
+
+fn list(items: &[&str]) -> Doc {
+    Doc::text("[")
+        .append(
+            Doc::softline()
+                .append(Doc::join(
+                    items.iter().map(|s| Doc::text(*s)),
+                    Doc::text(",").append(Doc::line()),
+                ))
+                .nest(2),
+        )
+        .append(Doc::softline())
+        .append(Doc::text("]"))
+        .group()
+}
+
+let doc = list(&["alpha", "beta", "gamma"]);
+
+assert_eq!(doc.render_to_string(80), "[alpha, beta, gamma]");
+assert_eq!(doc.render_to_string(10), "[\n  alpha,\n  beta,\n  gamma\n]");
+```
+ */
+
+use crate::options::{CommonWriterOptions, LineEnding};
+use std::io::Write;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A pretty-printable document.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Doc {
+    /// The empty document.
+    #[default]
+    Nil,
+    /// Literal text, which **must not** contain line breaks.
+    Text(String),
+    /// A line break that is rendered as the contained text when its group is flat.
+    Line(&'static str),
+    /// A line break that is always rendered as a new line, and forces enclosing groups to break.
+    HardLine,
+    /// A document whose line breaks are indented by an additional number of spaces.
+    Nest(usize, Box<Doc>),
+    /// A document that is rendered flat if it fits in the remaining width.
+    Group(Box<Doc>),
+    /// A sequence of documents.
+    Concat(Vec<Doc>),
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+type Command<'a> = (usize, Mode, &'a Doc);
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl From<&str> for Doc {
+    fn from(s: &str) -> Self {
+        Self::text(s)
+    }
+}
+
+impl From<String> for Doc {
+    fn from(s: String) -> Self {
+        Self::text(s)
+    }
+}
+
+impl Doc {
+    ///
+    /// Construct a text document.
+    ///
+    pub fn text<S>(s: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Text(s.into())
+    }
+
+    ///
+    /// Construct a line break that is rendered as a single space when flat.
+    ///
+    pub fn line() -> Self {
+        Self::Line(" ")
+    }
+
+    ///
+    /// Construct a line break that is rendered as nothing when flat.
+    ///
+    pub fn softline() -> Self {
+        Self::Line("")
+    }
+
+    ///
+    /// Construct a line break that is always rendered as a new line.
+    ///
+    pub fn hardline() -> Self {
+        Self::HardLine
+    }
+
+    ///
+    /// Construct a document from a sequence of documents.
+    ///
+    pub fn concat<I>(docs: I) -> Self
+    where
+        I: IntoIterator<Item = Doc>,
+    {
+        Self::Concat(docs.into_iter().collect())
+    }
+
+    ///
+    /// Construct a document from a sequence of documents with `separator` between each.
+    ///
+    pub fn join<I>(docs: I, separator: Doc) -> Self
+    where
+        I: IntoIterator<Item = Doc>,
+    {
+        let mut joined = Vec::new();
+        for (i, doc) in docs.into_iter().enumerate() {
+            if i > 0 {
+                joined.push(separator.clone());
+            }
+            joined.push(doc);
+        }
+        Self::Concat(joined)
+    }
+
+    ///
+    /// Returns a document consisting of this document followed by `other`.
+    ///
+    pub fn append<D>(self, other: D) -> Self
+    where
+        D: Into<Doc>,
+    {
+        match self {
+            Self::Nil => other.into(),
+            Self::Concat(mut docs) => {
+                docs.push(other.into());
+                Self::Concat(docs)
+            }
+            _ => Self::Concat(vec![self, other.into()]),
+        }
+    }
+
+    ///
+    /// Returns this document with its line breaks indented by an additional `indent` spaces.
+    ///
+    pub fn nest(self, indent: usize) -> Self {
+        Self::Nest(indent, Box::new(self))
+    }
+
+    ///
+    /// Returns this document as a group, to be rendered flat if it fits.
+    ///
+    pub fn group(self) -> Self {
+        Self::Group(Box::new(self))
+    }
+
+    ///
+    /// Render this document to `w` within the line width, and using the line ending, from
+    /// `options`.
+    ///
+    pub fn render_with_options<W>(
+        &self,
+        w: &mut W,
+        options: &CommonWriterOptions,
+    ) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        self.render_inner(w, options.line_width(), options.line_ending())
+    }
+
+    ///
+    /// Render this document to `w` within `width` columns.
+    ///
+    pub fn render<W>(&self, w: &mut W, width: usize) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        self.render_inner(w, width, LineEnding::Lf)
+    }
+
+    ///
+    /// Render this document to a string within `width` columns.
+    ///
+    pub fn render_to_string(&self, width: usize) -> String {
+        let mut buffer = Vec::new();
+        self.render(&mut buffer, width)
+            .expect("writing to a Vec<u8> cannot fail");
+        String::from_utf8(buffer).expect("documents are constructed from valid strings")
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    fn render_inner<W>(
+        &self,
+        w: &mut W,
+        width: usize,
+        line_ending: LineEnding,
+    ) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let mut column = 0;
+        let mut pending_indent: Option<usize> = None;
+        let mut stack: Vec<Command<'_>> = vec![(0, Mode::Break, self)];
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Nil => {}
+                Doc::Text(s) => {
+                    if s.is_empty() {
+                        continue;
+                    }
+                    if let Some(indent) = pending_indent.take() {
+                        write!(w, "{:indent$}", "")?;
+                    }
+                    w.write_all(s.as_bytes())?;
+                    column += s.chars().count();
+                }
+                Doc::Line(flat) if mode == Mode::Flat => {
+                    w.write_all(flat.as_bytes())?;
+                    column += flat.chars().count();
+                }
+                Doc::Line(_) | Doc::HardLine => {
+                    // indentation is deferred so that blank lines have no trailing whitespace.
+                    w.write_all(line_ending.as_str().as_bytes())?;
+                    pending_indent = Some(indent);
+                    column = indent;
+                }
+                Doc::Nest(nested, doc) => stack.push((indent + nested, mode, doc)),
+                Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+                Doc::Group(doc) => {
+                    let remaining = width as isize - column as isize;
+                    if mode == Mode::Flat || fits(remaining, (indent, Mode::Flat, doc), &stack) {
+                        stack.push((indent, Mode::Flat, doc));
+                    } else {
+                        stack.push((indent, Mode::Break, doc));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn fits(mut remaining: isize, next: Command<'_>, rest: &[Command<'_>]) -> bool {
+    let mut local = vec![next];
+    let mut rest = rest.iter().rev();
+    loop {
+        if remaining < 0 {
+            return false;
+        }
+        let (indent, mode, doc) = match local.pop() {
+            Some(command) => command,
+            None => match rest.next() {
+                Some(command) => *command,
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Nil => {}
+            Doc::Text(s) => remaining -= s.chars().count() as isize,
+            Doc::Line(flat) if mode == Mode::Flat => remaining -= flat.chars().count() as isize,
+            Doc::Line(_) => return true,
+            Doc::HardLine => return mode == Mode::Break,
+            Doc::Nest(nested, doc) => local.push((indent + nested, mode, doc)),
+            Doc::Concat(docs) => local.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+            Doc::Group(doc) => local.push((indent, mode, doc)),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(pairs: &[(&str, Doc)]) -> Doc {
+        Doc::text("{")
+            .append(
+                Doc::line()
+                    .append(Doc::join(
+                        pairs
+                            .iter()
+                            .map(|(k, v)| Doc::text(format!("{k}: ")).append(v.clone())),
+                        Doc::text(",").append(Doc::line()),
+                    ))
+                    .nest(2),
+            )
+            .append(Doc::line())
+            .append("}")
+            .group()
+    }
+
+    #[test]
+    fn test_nested_groups_break_outermost_first() {
+        let inner = object(&[("x", "1".into()), ("y", "2".into())]);
+        let outer = object(&[("name", "\"point\"".into()), ("at", inner)]);
+
+        assert_eq!(
+            outer.render_to_string(80),
+            r#"{ name: "point", at: { x: 1, y: 2 } }"#
+        );
+        assert_eq!(
+            outer.render_to_string(30),
+            "{\n  name: \"point\",\n  at: { x: 1, y: 2 }\n}"
+        );
+        assert_eq!(
+            outer.render_to_string(10),
+            "{\n  name: \"point\",\n  at: {\n    x: 1,\n    y: 2\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn test_hardline_forces_break() {
+        let doc = Doc::text("a")
+            .append(Doc::hardline())
+            .append(Doc::hardline())
+            .append("b")
+            .nest(2)
+            .group();
+        assert_eq!(doc.render_to_string(80), "a\n\n  b");
+    }
+
+    #[test]
+    fn test_render_with_options() {
+        let doc = Doc::text("a").append(Doc::line()).append("b").group();
+        let options = CommonWriterOptions::default()
+            .with_line_width(2)
+            .with_line_ending(LineEnding::CrLf);
+        let mut buffer = Vec::new();
+        doc.render_with_options(&mut buffer, &options).unwrap();
+        assert_eq!(buffer, b"a\r\nb".to_vec());
+    }
+}
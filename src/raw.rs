@@ -0,0 +1,250 @@
+/*!
+Provides passthrough readers and writers for payloads that are already serialized, so that they
+may flow through the same pipelines and file helpers as any other object.
+
+[`RawWriter`] writes the bytes of a [`RawObject`], `Vec<u8>`, or `String`, and with the `bytes`
+feature `bytes::Bytes`, unchanged; [`RawReader`] reads the entire source into any of these types.
+
+# Example
+
+```rust
+use objio::raw::{RawObject, RawReader, RawWriter};
+use objio::{ObjectReader, ObjectWriter};
+
+let payload = RawObject::from(br#"{"already": "serialized"}"#.to_vec());
+let bytes = RawWriter.write_to_bytes(&payload).unwrap();
+assert_eq!(bytes, payload.as_bytes());
+
+let payload: RawObject = RawReader.read_from_bytes(&bytes).unwrap();
+assert_eq!(payload.len(), 25);
+```
+ */
+
+use crate::{impl_has_options, ObjectReader, ObjectWriter};
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An already-serialized payload, in an unspecified format.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RawObject(Vec<u8>);
+
+///
+/// A writer that writes already-serialized payloads unchanged.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RawWriter;
+
+///
+/// A reader that reads the entire source as an already-serialized payload.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RawReader;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl From<Vec<u8>> for RawObject {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&[u8]> for RawObject {
+    fn from(value: &[u8]) -> Self {
+        Self(value.to_vec())
+    }
+}
+
+impl From<String> for RawObject {
+    fn from(value: String) -> Self {
+        Self(value.into_bytes())
+    }
+}
+
+impl From<RawObject> for Vec<u8> {
+    fn from(value: RawObject) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<[u8]> for RawObject {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Deref for RawObject {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl RawObject {
+    ///
+    /// Returns the bytes of this payload.
+    ///
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    ///
+    /// Returns the bytes of this payload, consuming it.
+    ///
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl_has_options!(RawWriter);
+
+impl ObjectWriter<RawObject> for RawWriter {
+    type Error = std::io::Error;
+
+    fn write<W>(&self, w: &mut W, object: &RawObject) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        w.write_all(object.as_bytes())
+    }
+
+    fn write_size_hint(&self, object: &RawObject) -> usize {
+        object.len()
+    }
+}
+
+impl ObjectWriter<Vec<u8>> for RawWriter {
+    type Error = std::io::Error;
+
+    fn write<W>(&self, w: &mut W, object: &Vec<u8>) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        w.write_all(object)
+    }
+
+    fn write_size_hint(&self, object: &Vec<u8>) -> usize {
+        object.len()
+    }
+}
+
+impl ObjectWriter<String> for RawWriter {
+    type Error = std::io::Error;
+
+    fn write<W>(&self, w: &mut W, object: &String) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        w.write_all(object.as_bytes())
+    }
+
+    fn write_size_hint(&self, object: &String) -> usize {
+        object.len()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ObjectWriter<bytes::Bytes> for RawWriter {
+    type Error = std::io::Error;
+
+    fn write<W>(&self, w: &mut W, object: &bytes::Bytes) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        w.write_all(object)
+    }
+
+    fn write_size_hint(&self, object: &bytes::Bytes) -> usize {
+        object.len()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl_has_options!(RawReader);
+
+impl ObjectReader<RawObject> for RawReader {
+    type Error = std::io::Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<RawObject, Self::Error>
+    where
+        R: Read,
+    {
+        ObjectReader::<Vec<u8>>::read(self, r).map(RawObject)
+    }
+}
+
+impl ObjectReader<Vec<u8>> for RawReader {
+    type Error = std::io::Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<Vec<u8>, Self::Error>
+    where
+        R: Read,
+    {
+        let mut buffer = Vec::new();
+        let _ = r.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+impl ObjectReader<String> for RawReader {
+    type Error = std::io::Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<String, Self::Error>
+    where
+        R: Read,
+    {
+        let mut buffer = String::new();
+        let _ = r.read_to_string(&mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ObjectReader<bytes::Bytes> for RawReader {
+    type Error = std::io::Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<bytes::Bytes, Self::Error>
+    where
+        R: Read,
+    {
+        ObjectReader::<Vec<u8>>::read(self, r).map(bytes::Bytes::from)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_string_payloads() {
+        let text = String::from("<a> <b> <c> .\n");
+        assert_eq!(RawWriter.write_to_string(&text).unwrap(), text);
+        let error = ObjectReader::<String>::read_from_bytes(&RawReader, [0xFF]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_bytes_payloads() {
+        let payload = bytes::Bytes::from_static(b"\x00\x01\x02");
+        let written = RawWriter.write_to_bytes(&payload).unwrap();
+        let read: bytes::Bytes = RawReader.read_from_bytes(&written).unwrap();
+        assert_eq!(read, payload);
+    }
+}
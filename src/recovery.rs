@@ -0,0 +1,439 @@
+/*!
+Provides an error-recovering stream reader, [`RecoveringReader`], that skips malformed records
+rather than failing the entire stream.
+
+The stream is divided into records at a delimiter byte, by default a newline, and each record is
+read independently by an inner [`ObjectReader`]. When a record cannot be read the
+[`RecoveryPolicy`] decides whether to fail, or to record the error, with its
+[`RecordLocation`], in a [`RecoveryReport`] and resynchronize at the start of the next record.
+Records that are empty or contain only whitespace are skipped. Errors reading the underlying
+source are never recovered.
+
+# Example
+
+```rust
+use objio::recovery::{RecoveringReader, RecoveryPolicy};
+use objio::stream::ObjectsReader;
+use objio::ObjectReader;
+use std::io::Read;
+
+#[derive(Debug, Default)]
+struct NumberReader;
+
+impl ObjectReader<u32> for NumberReader {
+    type Error = objio::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<u32, Self::Error> {
+        let mut s = String::new();
+        r.read_to_string(&mut s)?;
+        s.trim()
+            .parse()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e).into())
+    }
+}
+
+let reader = RecoveringReader::new(NumberReader).with_policy(RecoveryPolicy::Skip);
+let numbers = reader.read_all(&mut "1\n2\nthree\n4\n".as_bytes()).unwrap();
+assert_eq!(numbers, vec![1, 2, 4]);
+
+let report = reader.take_report();
+assert_eq!(report.records_read(), 3);
+assert_eq!(report.errors().len(), 1);
+assert_eq!(report.errors()[0].location().line(), 3);
+```
+ */
+
+use crate::stream::ObjectsReader;
+use crate::ObjectReader;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::sync::{Mutex, MutexGuard};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The default delimiter between records.
+///
+pub const DEFAULT_RECORD_DELIMITER: u8 = b'\n';
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The action taken by a [`RecoveringReader`] when a record cannot be read.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecoveryPolicy {
+    /// Return the error, ending the stream.
+    FailFast,
+    /// Record the error and continue with the next record.
+    #[default]
+    Skip,
+    /// Record the error and continue, unless more than the given number of records have been
+    /// skipped in which case the error is returned.
+    SkipAtMost(usize),
+}
+
+///
+/// The location of a record within a stream.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecordLocation {
+    record: usize,
+    line: usize,
+    offset: u64,
+}
+
+///
+/// An error recovered from while reading a stream.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecoveredError {
+    location: RecordLocation,
+    message: String,
+}
+
+///
+/// A report of the records read, and the errors recovered from, by a [`RecoveringReader`].
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    records_read: usize,
+    errors: Vec<RecoveredError>,
+}
+
+///
+/// A stream reader that reads delimited records with an inner [`ObjectReader`], recovering from
+/// malformed records according to a [`RecoveryPolicy`].
+///
+#[derive(Debug)]
+pub struct RecoveringReader<O> {
+    inner: O,
+    delimiter: u8,
+    policy: RecoveryPolicy,
+    state: Mutex<State>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+struct State {
+    records: usize,
+    lines: usize,
+    offset: u64,
+    report: RecoveryReport,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for RecoveryPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FailFast => write!(f, "fail-fast"),
+            Self::Skip => write!(f, "skip"),
+            Self::SkipAtMost(max) => write!(f, "skip-at-most({max})"),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for RecordLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "record {}, line {}, byte offset {}",
+            self.record, self.line, self.offset
+        )
+    }
+}
+
+impl RecordLocation {
+    ///
+    /// Returns the one-based index of the record within the stream, including skipped records.
+    ///
+    pub fn record(&self) -> usize {
+        self.record
+    }
+
+    ///
+    /// Returns the one-based line number on which the record starts.
+    ///
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    ///
+    /// Returns the offset, in bytes, at which the record starts.
+    ///
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for RecoveredError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at {}: {}", self.location, self.message)
+    }
+}
+
+impl RecoveredError {
+    ///
+    /// Returns the location of the record that could not be read.
+    ///
+    pub fn location(&self) -> RecordLocation {
+        self.location
+    }
+
+    ///
+    /// Returns the message of the error returned by the inner reader.
+    ///
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl RecoveryReport {
+    ///
+    /// Returns the number of records read successfully.
+    ///
+    pub fn records_read(&self) -> usize {
+        self.records_read
+    }
+
+    ///
+    /// Returns the errors recovered from, in stream order.
+    ///
+    pub fn errors(&self) -> &[RecoveredError] {
+        &self.errors
+    }
+
+    ///
+    /// Returns `true` if no records were skipped.
+    ///
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O> RecoveringReader<O> {
+    ///
+    /// Construct a new reader, with the default delimiter and policy, that reads each record with
+    /// `inner`.
+    ///
+    pub fn new(inner: O) -> Self {
+        Self {
+            inner,
+            delimiter: DEFAULT_RECORD_DELIMITER,
+            policy: Default::default(),
+            state: Default::default(),
+        }
+    }
+
+    ///
+    /// Returns this reader with records delimited by `delimiter`.
+    ///
+    pub fn with_delimiter(self, delimiter: u8) -> Self {
+        let mut self_mut = self;
+        self_mut.delimiter = delimiter;
+        self_mut
+    }
+
+    ///
+    /// Returns this reader with the recovery policy `policy`.
+    ///
+    pub fn with_policy(self, policy: RecoveryPolicy) -> Self {
+        let mut self_mut = self;
+        self_mut.policy = policy;
+        self_mut
+    }
+
+    ///
+    /// Returns the recovery policy of this reader.
+    ///
+    pub fn policy(&self) -> RecoveryPolicy {
+        self.policy
+    }
+
+    ///
+    /// Returns a reference to the inner reader.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Returns a copy of the report for the records read so far.
+    ///
+    pub fn report(&self) -> RecoveryReport {
+        self.lock().report.clone()
+    }
+
+    ///
+    /// Returns the report for the records read so far, and resets the reader so that the next
+    /// record read is treated as the start of a new stream.
+    ///
+    pub fn take_report(&self) -> RecoveryReport {
+        std::mem::take(&mut *self.lock()).report
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<O, T> ObjectsReader<T> for RecoveringReader<O>
+where
+    O: ObjectReader<T>,
+    O::Error: Display,
+{
+    type Error = O::Error;
+
+    fn read_next<R>(&self, r: &mut R) -> Result<Option<T>, Self::Error>
+    where
+        R: BufRead,
+    {
+        loop {
+            let mut record = Vec::new();
+            let length = r.read_until(self.delimiter, &mut record)?;
+            if length == 0 {
+                return Ok(None);
+            }
+
+            let mut state = self.lock();
+            state.records += 1;
+            let location = RecordLocation {
+                record: state.records,
+                line: state.lines + 1,
+                offset: state.offset,
+            };
+            state.lines += record.iter().filter(|b| **b == b'\n').count();
+            state.offset += length as u64;
+
+            if record.last() == Some(&self.delimiter) {
+                let _ = record.pop();
+            }
+            if record.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            match self.inner.read_from_bytes(&record) {
+                Ok(object) => {
+                    state.report.records_read += 1;
+                    return Ok(Some(object));
+                }
+                Err(e) => {
+                    let skipped = state.report.errors.len();
+                    match self.policy {
+                        RecoveryPolicy::FailFast => return Err(e),
+                        RecoveryPolicy::SkipAtMost(max) if skipped >= max => return Err(e),
+                        _ => state.report.errors.push(RecoveredError {
+                            location,
+                            message: e.to_string(),
+                        }),
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use std::io::{Error as IoError, ErrorKind, Read};
+
+    #[derive(Debug, Default)]
+    struct NumberReader;
+
+    impl ObjectReader<u8> for NumberReader {
+        type Error = Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<u8, Self::Error> {
+            let mut s = String::new();
+            r.read_to_string(&mut s)?;
+            s.trim()
+                .parse()
+                .map_err(|e| IoError::new(ErrorKind::InvalidData, e).into())
+        }
+    }
+
+    const INPUT: &str = "1\n\nx\n2;3\n300\n4";
+
+    #[test]
+    fn test_skip_locations() {
+        let reader = RecoveringReader::new(NumberReader);
+        assert_eq!(reader.read_all(&mut INPUT.as_bytes()).unwrap(), vec![1, 4]);
+        let report = reader.report();
+        assert_eq!(report.records_read(), 2);
+        let locations: Vec<_> = report.errors().iter().map(|e| e.location()).collect();
+        assert_eq!(
+            locations,
+            vec![
+                RecordLocation {
+                    record: 3,
+                    line: 3,
+                    offset: 3
+                },
+                RecordLocation {
+                    record: 4,
+                    line: 4,
+                    offset: 5
+                },
+                RecordLocation {
+                    record: 5,
+                    line: 5,
+                    offset: 9
+                },
+            ]
+        );
+        assert!(reader.take_report().errors()[0]
+            .to_string()
+            .starts_with("at record 3, line 3, byte offset 3: "));
+        assert!(reader.report().is_clean());
+    }
+
+    #[test]
+    fn test_custom_delimiter() {
+        let reader = RecoveringReader::new(NumberReader).with_delimiter(b';');
+        assert_eq!(
+            reader.read_all(&mut "1;x;2\n;3".as_bytes()).unwrap(),
+            vec![1, 2, 3]
+        );
+        let location = reader.report().errors()[0].location();
+        assert_eq!(location.record(), 2);
+        assert_eq!(location.line(), 1);
+    }
+
+    #[test]
+    fn test_fail_fast() {
+        let reader = RecoveringReader::new(NumberReader).with_policy(RecoveryPolicy::FailFast);
+        assert!(reader.read_all(&mut INPUT.as_bytes()).is_err());
+        assert_eq!(reader.report().records_read(), 1);
+    }
+
+    #[test]
+    fn test_skip_at_most() {
+        let reader = RecoveringReader::new(NumberReader).with_policy(RecoveryPolicy::SkipAtMost(2));
+        assert!(reader.read_all(&mut INPUT.as_bytes()).is_err());
+        assert_eq!(reader.report().errors().len(), 2);
+    }
+}
@@ -0,0 +1,679 @@
+/*!
+Provides [`FormatRegistry`], which maps format names and file extensions to readers and writers
+of a single object type so that a format can be chosen at runtime, for example from the
+extension of a file named on the command line.
+
+Readers and writers are generic, and so are stored behind the object-safe [`DynObjectReader`]
+and [`DynObjectWriter`] traits, which are implemented for every reader and writer whose error
+converts into a [`BoxedError`]. A reference to either dynamic trait is itself a reader or writer,
+so that a registered format may be passed to any function in this crate.
+
+With the `serde` feature, `FormatRegistry::with_serde_formats` registers each serde-based format
+enabled by the crate features. With the `inventory` feature, format crates may use the
+`register_format!` macro to add their formats to a global list, from which
+`FormatRegistry::with_registered_formats` registers each format for the registry's object type,
+without the application wiring each format crate by hand.
+
+# Example
+
+```rust
+use objio::raw::{RawReader, RawWriter};
+use objio::registry::FormatRegistry;
+use objio::ObjectReader;
+
+let mut registry: FormatRegistry<String> = FormatRegistry::default();
+registry.register("text", &["txt", "text"], RawReader, RawWriter);
+
+let reader = registry.reader_for_path("notes.TXT").unwrap();
+assert_eq!(reader.read_from_string("hello").unwrap(), "hello");
+assert!(registry.writer_for_path("notes.md").is_none());
+assert_eq!(registry.format_for_path("notes.text"), Some("text"));
+```
+
+A format crate registers its formats globally, and an application collects them, as follows.
+
+```rust
+# #[cfg(feature = "inventory")]
+# {
+use objio::raw::{RawReader, RawWriter};
+use objio::register_format;
+use objio::registry::FormatRegistry;
+
+register_format! {
+    object: Vec<u8>,
+    name: "binary",
+    extensions: ["bin"],
+    media_types: ["application/octet-stream"],
+    reader: RawReader,
+    writer: RawWriter,
+}
+
+let registry: FormatRegistry<Vec<u8>> = FormatRegistry::with_registered_formats();
+assert_eq!(registry.format_for_media_type("application/octet-stream"), Some("binary"));
+# }
+```
+ */
+
+use crate::error::BoxedError;
+use crate::options::Buffering;
+use crate::{ObjectReader, ObjectWriter};
+#[cfg(feature = "inventory")]
+use std::any::Any;
+use std::fmt::{Debug, Formatter};
+use std::io::{BufRead, Read, Write};
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An object-safe form of [`ObjectReader`], implemented for all readers whose error converts
+/// into a [`BoxedError`].
+///
+pub trait DynObjectReader<T> {
+    ///
+    /// Read an instance of `T` from the provided buffered source.
+    ///
+    fn read_dyn(&self, r: &mut dyn BufRead) -> Result<T, BoxedError>;
+
+    ///
+    /// Check that the options for this reader, if any, are consistent.
+    ///
+    fn validate_options_dyn(&self) -> Result<(), BoxedError>;
+}
+
+///
+/// An object-safe form of [`ObjectWriter`], implemented for all writers whose error converts
+/// into a [`BoxedError`].
+///
+pub trait DynObjectWriter<T> {
+    ///
+    /// Write an instance of `T` to the provided destination.
+    ///
+    fn write_dyn(&self, w: &mut dyn Write, object: &T) -> Result<(), BoxedError>;
+
+    ///
+    /// Returns an estimate of the number of bytes that writing `object` will produce.
+    ///
+    fn write_size_hint_dyn(&self, object: &T) -> usize;
+
+    ///
+    /// Returns the buffering applied to files written by this writer.
+    ///
+    fn buffering_dyn(&self) -> Buffering;
+
+    ///
+    /// Check that the options for this writer, if any, are consistent.
+    ///
+    fn validate_options_dyn(&self) -> Result<(), BoxedError>;
+}
+
+///
+/// A boxed dynamic reader, as stored in a [`FormatRegistry`].
+///
+pub type BoxedObjectReader<T> = Box<dyn DynObjectReader<T> + Send + Sync>;
+
+///
+/// A boxed dynamic writer, as stored in a [`FormatRegistry`].
+///
+pub type BoxedObjectWriter<T> = Box<dyn DynObjectWriter<T> + Send + Sync>;
+
+///
+/// A set of named formats, each with a reader and writer for `T` and a list of file extensions.
+///
+pub struct FormatRegistry<T> {
+    formats: Vec<RegisteredFormat<T>>,
+}
+
+///
+/// A format added to the global list by the [`register_format!`](crate::register_format) macro,
+/// which may register a reader and writer with a [`FormatRegistry`] of the matching object type.
+///
+#[cfg(feature = "inventory")]
+pub struct FormatRegistration {
+    name: &'static str,
+    register: fn(&mut dyn Any),
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+struct RegisteredFormat<T> {
+    name: String,
+    extensions: Vec<String>,
+    media_types: Vec<String>,
+    reader: BoxedObjectReader<T>,
+    writer: BoxedObjectWriter<T>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<R, T> DynObjectReader<T> for R
+where
+    R: ObjectReader<T>,
+    R::Error: Into<BoxedError>,
+{
+    fn read_dyn(&self, r: &mut dyn BufRead) -> Result<T, BoxedError> {
+        let mut r = r;
+        self.read_buffered(&mut r).map_err(Into::into)
+    }
+
+    fn validate_options_dyn(&self) -> Result<(), BoxedError> {
+        self.validate_options().map_err(Into::into)
+    }
+}
+
+impl<T> ObjectReader<T> for &(dyn DynObjectReader<T> + Send + Sync) {
+    type Error = BoxedError;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        (**self).read_dyn(&mut std::io::BufReader::new(r))
+    }
+
+    fn read_buffered<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: BufRead,
+    {
+        (**self).read_dyn(r)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        (**self).validate_options_dyn()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<W, T> DynObjectWriter<T> for W
+where
+    W: ObjectWriter<T>,
+    W::Error: Into<BoxedError>,
+{
+    fn write_dyn(&self, w: &mut dyn Write, object: &T) -> Result<(), BoxedError> {
+        let mut w = w;
+        self.write(&mut w, object).map_err(Into::into)
+    }
+
+    fn write_size_hint_dyn(&self, object: &T) -> usize {
+        self.write_size_hint(object)
+    }
+
+    fn buffering_dyn(&self) -> Buffering {
+        self.buffering()
+    }
+
+    fn validate_options_dyn(&self) -> Result<(), BoxedError> {
+        self.validate_options().map_err(Into::into)
+    }
+}
+
+impl<T> ObjectWriter<T> for &(dyn DynObjectWriter<T> + Send + Sync) {
+    type Error = BoxedError;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        (**self).write_dyn(w, object)
+    }
+
+    fn write_size_hint(&self, object: &T) -> usize {
+        (**self).write_size_hint_dyn(object)
+    }
+
+    fn buffering(&self) -> Buffering {
+        (**self).buffering_dyn()
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        (**self).validate_options_dyn()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Debug for FormatRegistry<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.formats
+                    .iter()
+                    .map(|format| (&format.name, &format.extensions)),
+            )
+            .finish()
+    }
+}
+
+impl<T> Default for FormatRegistry<T> {
+    fn default() -> Self {
+        Self {
+            formats: Default::default(),
+        }
+    }
+}
+
+impl<T> FormatRegistry<T> {
+    ///
+    /// Register the format `name`, used for files with any of `extensions`, read by `reader` and
+    /// written by `writer`. Names and extensions are matched without regard to case; a format
+    /// registered later takes precedence over an earlier one with the same name or extension.
+    ///
+    pub fn register<R, W>(&mut self, name: &str, extensions: &[&str], reader: R, writer: W)
+    where
+        R: DynObjectReader<T> + Send + Sync + 'static,
+        W: DynObjectWriter<T> + Send + Sync + 'static,
+    {
+        self.register_with_media_types(name, extensions, &[], reader, writer)
+    }
+
+    ///
+    /// Register the format `name` as for [`register`](Self::register), also used for content
+    /// with any of `media_types`. Media types are matched without regard to case or to any
+    /// parameters, such as `charset`.
+    ///
+    pub fn register_with_media_types<R, W>(
+        &mut self,
+        name: &str,
+        extensions: &[&str],
+        media_types: &[&str],
+        reader: R,
+        writer: W,
+    ) where
+        R: DynObjectReader<T> + Send + Sync + 'static,
+        W: DynObjectWriter<T> + Send + Sync + 'static,
+    {
+        self.formats.push(RegisteredFormat {
+            name: name.to_string(),
+            extensions: extensions
+                .iter()
+                .map(|extension| extension.trim_start_matches('.').to_ascii_lowercase())
+                .collect(),
+            media_types: media_types
+                .iter()
+                .map(|media_type| media_type_essence(media_type))
+                .collect(),
+            reader: Box::new(reader),
+            writer: Box::new(writer),
+        });
+    }
+
+    ///
+    /// Returns the names of the registered formats, in the order registered.
+    ///
+    pub fn format_names(&self) -> impl Iterator<Item = &str> {
+        self.formats.iter().map(|format| format.name.as_str())
+    }
+
+    ///
+    /// Returns the name of the format used for the extension of `path`, if any.
+    ///
+    pub fn format_for_path<P>(&self, path: P) -> Option<&str>
+    where
+        P: AsRef<Path>,
+    {
+        self.by_path(path.as_ref())
+            .map(|format| format.name.as_str())
+    }
+
+    ///
+    /// Returns the name of the format used for `media_type`, if any.
+    ///
+    pub fn format_for_media_type(&self, media_type: &str) -> Option<&str> {
+        self.by_media_type(media_type)
+            .map(|format| format.name.as_str())
+    }
+
+    ///
+    /// Returns the reader for the format `name`, if registered.
+    ///
+    pub fn reader_named(&self, name: &str) -> Option<&(dyn DynObjectReader<T> + Send + Sync)> {
+        self.by_name(name).map(|format| format.reader.as_ref())
+    }
+
+    ///
+    /// Returns the writer for the format `name`, if registered.
+    ///
+    pub fn writer_named(&self, name: &str) -> Option<&(dyn DynObjectWriter<T> + Send + Sync)> {
+        self.by_name(name).map(|format| format.writer.as_ref())
+    }
+
+    ///
+    /// Returns the reader for the format used for the extension of `path`, if any.
+    ///
+    pub fn reader_for_path<P>(&self, path: P) -> Option<&(dyn DynObjectReader<T> + Send + Sync)>
+    where
+        P: AsRef<Path>,
+    {
+        self.by_path(path.as_ref())
+            .map(|format| format.reader.as_ref())
+    }
+
+    ///
+    /// Returns the writer for the format used for the extension of `path`, if any.
+    ///
+    pub fn writer_for_path<P>(&self, path: P) -> Option<&(dyn DynObjectWriter<T> + Send + Sync)>
+    where
+        P: AsRef<Path>,
+    {
+        self.by_path(path.as_ref())
+            .map(|format| format.writer.as_ref())
+    }
+
+    ///
+    /// Returns the reader for the format used for `media_type`, if any.
+    ///
+    pub fn reader_for_media_type(
+        &self,
+        media_type: &str,
+    ) -> Option<&(dyn DynObjectReader<T> + Send + Sync)> {
+        self.by_media_type(media_type)
+            .map(|format| format.reader.as_ref())
+    }
+
+    ///
+    /// Returns the writer for the format used for `media_type`, if any.
+    ///
+    pub fn writer_for_media_type(
+        &self,
+        media_type: &str,
+    ) -> Option<&(dyn DynObjectWriter<T> + Send + Sync)> {
+        self.by_media_type(media_type)
+            .map(|format| format.writer.as_ref())
+    }
+
+    fn by_name(&self, name: &str) -> Option<&RegisteredFormat<T>> {
+        self.formats
+            .iter()
+            .rev()
+            .find(|format| format.name.eq_ignore_ascii_case(name))
+    }
+
+    fn by_path(&self, path: &Path) -> Option<&RegisteredFormat<T>> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        self.formats
+            .iter()
+            .rev()
+            .find(|format| format.extensions.contains(&extension))
+    }
+
+    fn by_media_type(&self, media_type: &str) -> Option<&RegisteredFormat<T>> {
+        let media_type = media_type_essence(media_type);
+        self.formats
+            .iter()
+            .rev()
+            .find(|format| format.media_types.contains(&media_type))
+    }
+}
+
+#[cfg(feature = "inventory")]
+impl<T> FormatRegistry<T>
+where
+    T: 'static,
+{
+    ///
+    /// Construct a new registry containing each format added to the global list by
+    /// [`register_format!`](crate::register_format) for the object type `T`.
+    ///
+    pub fn with_registered_formats() -> Self {
+        let mut registry = Self::default();
+        registry.register_global_formats();
+        registry
+    }
+
+    ///
+    /// Register each format added to the global list by
+    /// [`register_format!`](crate::register_format) for the object type `T`. The order in
+    /// which they are registered is unspecified.
+    ///
+    pub fn register_global_formats(&mut self) {
+        for registration in inventory::iter::<FormatRegistration> {
+            (registration.register)(self);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> FormatRegistry<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    ///
+    /// Construct a new registry containing each serde-based format enabled by the crate
+    /// features, named as the format's [`NAME`](crate::formats::SerdeFormat::NAME).
+    ///
+    pub fn with_serde_formats() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Self::default();
+        #[cfg(feature = "cbor")]
+        registry.register_serde::<crate::formats::cbor::Cbor>(&["cbor"]);
+        #[cfg(feature = "json")]
+        registry.register_serde::<crate::formats::json::Json>(&["json"]);
+        #[cfg(feature = "msgpack")]
+        registry.register_serde::<crate::formats::msgpack::MsgPack>(&["msgpack", "mpk"]);
+        #[cfg(feature = "ron")]
+        registry.register_serde::<crate::formats::ron::Ron>(&["ron"]);
+        #[cfg(feature = "toml")]
+        registry.register_serde::<crate::formats::toml::Toml>(&["toml"]);
+        #[cfg(feature = "yaml")]
+        registry.register_serde::<crate::formats::yaml::Yaml>(&["yaml", "yml"]);
+        registry
+    }
+
+    ///
+    /// Register the serde-based format `F`, named as its `NAME`, for files with any of
+    /// `extensions` and content of its `MEDIA_TYPE`.
+    ///
+    pub fn register_serde<F>(&mut self, extensions: &[&str])
+    where
+        F: crate::formats::SerdeFormat + Send + Sync + 'static,
+    {
+        self.register_with_media_types(
+            F::NAME,
+            extensions,
+            &[F::MEDIA_TYPE],
+            crate::formats::SerdeReader::<F>::default(),
+            crate::formats::SerdeWriter::<F>::default(),
+        );
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "inventory")]
+impl Debug for FormatRegistration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FormatRegistration")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "inventory")]
+inventory::collect!(FormatRegistration);
+
+#[cfg(feature = "inventory")]
+impl FormatRegistration {
+    ///
+    /// Construct a new registration for the format `name`; `register` is called with each
+    /// registry collecting global formats and registers the format if the registry is of its
+    /// object type. This is called by [`register_format!`](crate::register_format) and need
+    /// not be called directly.
+    ///
+    pub const fn new(name: &'static str, register: fn(&mut dyn Any)) -> Self {
+        Self { name, register }
+    }
+
+    ///
+    /// Returns the name of the registered format.
+    ///
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    ///
+    /// Returns all formats in the global list, for any object type.
+    ///
+    pub fn all() -> impl Iterator<Item = &'static FormatRegistration> {
+        inventory::iter::<FormatRegistration>.into_iter()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Macros
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Add a format, with a reader and writer for the given object type, to the global list from
+/// which [`with_registered_formats`](crate::registry::FormatRegistry::with_registered_formats)
+/// builds a registry. The `media_types` entry is optional, and the reader and writer expressions
+/// are evaluated each time a registry is built.
+///
+/// ```rust
+/// use objio::raw::{RawReader, RawWriter};
+///
+/// objio::register_format! {
+///     object: String,
+///     name: "text",
+///     extensions: ["txt", "text"],
+///     reader: RawReader,
+///     writer: RawWriter,
+/// }
+/// ```
+///
+#[cfg(feature = "inventory")]
+#[macro_export]
+macro_rules! register_format {
+    (
+        object: $object:ty,
+        name: $name:expr,
+        extensions: [$($extension:expr),* $(,)?],
+        $(media_types: [$($media_type:expr),* $(,)?],)?
+        reader: $reader:expr,
+        writer: $writer:expr $(,)?
+    ) => {
+        const _: () = {
+            fn register(registry: &mut dyn ::std::any::Any) {
+                if let Some(registry) =
+                    registry.downcast_mut::<$crate::registry::FormatRegistry<$object>>()
+                {
+                    registry.register_with_media_types(
+                        $name,
+                        &[$($extension),*],
+                        &[$($($media_type),*)?],
+                        $reader,
+                        $writer,
+                    );
+                }
+            }
+
+            $crate::inventory::submit! {
+                $crate::registry::FormatRegistration::new($name, register)
+            }
+        };
+    };
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn media_type_essence(media_type: &str) -> String {
+    media_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::{RawReader, RawWriter};
+
+    #[test]
+    fn test_lookup() {
+        let mut registry: FormatRegistry<Vec<u8>> = FormatRegistry::default();
+        registry.register("raw", &[".bin"], RawReader, RawWriter);
+        registry.register("bytes", &["BIN", "dat"], RawReader, RawWriter);
+
+        assert_eq!(
+            registry.format_names().collect::<Vec<_>>(),
+            ["raw", "bytes"]
+        );
+        assert_eq!(registry.format_for_path("a/b.bin"), Some("bytes"));
+        assert_eq!(registry.format_for_path("a/b"), None);
+        assert!(registry.reader_named("RAW").is_some());
+        assert!(registry.writer_named("text").is_none());
+
+        let writer = registry.writer_for_path("x.dat").unwrap();
+        assert_eq!(writer.write_to_bytes(&vec![1, 2]).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_media_types() {
+        let mut registry: FormatRegistry<Vec<u8>> = FormatRegistry::default();
+        registry.register_with_media_types(
+            "raw",
+            &["bin"],
+            &["Application/Octet-Stream"],
+            RawReader,
+            RawWriter,
+        );
+
+        assert_eq!(
+            registry.format_for_media_type("application/octet-stream; q=0.5"),
+            Some("raw")
+        );
+        assert!(registry.reader_for_media_type("text/plain").is_none());
+        assert!(registry
+            .writer_for_media_type("APPLICATION/OCTET-STREAM")
+            .is_some());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serde_formats() {
+        let registry: FormatRegistry<serde_json::Value> = FormatRegistry::with_serde_formats();
+        let reader = registry.reader_for_path("config.json").unwrap();
+        let value = reader.read_from_string(r#"{"a": 1}"#).unwrap();
+        assert_eq!(value["a"], 1);
+        assert!(registry.reader_for_media_type("application/json").is_some());
+    }
+
+    #[cfg(feature = "inventory")]
+    crate::register_format! {
+        object: Vec<u8>,
+        name: "registered",
+        extensions: ["reg"],
+        media_types: ["application/x-registered"],
+        reader: RawReader,
+        writer: RawWriter,
+    }
+
+    #[cfg(feature = "inventory")]
+    #[test]
+    fn test_registered_formats() {
+        let registry: FormatRegistry<Vec<u8>> = FormatRegistry::with_registered_formats();
+        assert_eq!(registry.format_for_path("x.reg"), Some("registered"));
+        assert_eq!(
+            registry.format_for_media_type("application/x-registered"),
+            Some("registered")
+        );
+
+        let registry: FormatRegistry<String> = FormatRegistry::with_registered_formats();
+        assert_eq!(registry.format_for_path("x.reg"), None);
+        assert!(FormatRegistration::all().any(|format| format.name() == "registered"));
+    }
+}
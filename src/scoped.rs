@@ -0,0 +1,191 @@
+/*!
+Provides scoped, thread-local, default options, so that deeply nested code, such as an
+implementation of `Display` that delegates to a writer, may pick up ambient preferences without
+options being passed through every function signature.
+
+The function [`with_default_options`] makes a value of an options type the default, on the current
+thread, for the duration of a closure; scopes may be nested, the innermost value of each options
+type taking precedence, and the previous value is restored when the closure returns or panics. A
+reader or writer adopts the current default with
+[`HasOptions::with_scoped_options`](crate::HasOptions::with_scoped_options), and any other code
+may ask for it with [`default_options`].
+
+# Example
+
+```rust
+use objio::options::CommonWriterOptions;
+use objio::scoped::{default_options, with_default_options};
+
+fn nested() -> usize {
+    default_options::<CommonWriterOptions>()
+        .map(|options| options.indent())
+        .unwrap_or_default()
+}
+
+assert_eq!(nested(), 0);
+let indent = with_default_options(CommonWriterOptions::default().with_indent(4), nested);
+assert_eq!(indent, 4);
+assert_eq!(nested(), 0);
+```
+ */
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Restores the previous default of an options type when a scope ends, including by a panic.
+///
+struct ScopeGuard {
+    type_id: TypeId,
+}
+
+thread_local! {
+    static DEFAULTS: RefCell<HashMap<TypeId, Vec<Box<dyn Any>>>> = RefCell::new(HashMap::new());
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Call `f` with `options` as the default value of its type on the current thread, returning the
+/// result of `f`.
+///
+pub fn with_default_options<O, F, R>(options: O, f: F) -> R
+where
+    O: Clone + 'static,
+    F: FnOnce() -> R,
+{
+    let type_id = TypeId::of::<O>();
+    DEFAULTS.with(|defaults| {
+        defaults
+            .borrow_mut()
+            .entry(type_id)
+            .or_default()
+            .push(Box::new(options))
+    });
+    let _guard = ScopeGuard { type_id };
+    f()
+}
+
+///
+/// Returns a copy of the innermost scoped default value of the options type `O` on the current
+/// thread, or `None` if no scope for `O` is active.
+///
+pub fn default_options<O>() -> Option<O>
+where
+    O: Clone + 'static,
+{
+    DEFAULTS.with(|defaults| {
+        defaults
+            .borrow()
+            .get(&TypeId::of::<O>())
+            .and_then(|stack| stack.last())
+            .and_then(|options| options.downcast_ref::<O>())
+            .cloned()
+    })
+}
+
+///
+/// Returns `true` if a scope for the options type `O` is active on the current thread.
+///
+pub fn has_default_options<O>() -> bool
+where
+    O: 'static,
+{
+    DEFAULTS.with(|defaults| {
+        defaults
+            .borrow()
+            .get(&TypeId::of::<O>())
+            .is_some_and(|stack| !stack.is_empty())
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        // The thread-local may already be destroyed if the scope ends during thread exit.
+        let _ = DEFAULTS.try_with(|defaults| {
+            let mut defaults = defaults.borrow_mut();
+            if let Some(stack) = defaults.get_mut(&self.type_id) {
+                let _ = stack.pop();
+                if stack.is_empty() {
+                    let _ = defaults.remove(&self.type_id);
+                }
+            }
+        });
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::{CommonReaderOptions, CommonWriterOptions};
+    use crate::{impl_has_options, HasOptions, ObjectWriter};
+    use std::fmt::{Display, Formatter};
+    use std::io::Write;
+
+    #[derive(Debug, Default)]
+    struct IndentWriter {
+        options: CommonWriterOptions,
+    }
+
+    impl_has_options!(IndentWriter, CommonWriterOptions);
+
+    impl ObjectWriter<&'static str> for IndentWriter {
+        type Error = std::io::Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &&'static str) -> Result<(), Self::Error> {
+            write!(w, "{:indent$}{object}", "", indent = self.options.indent())
+        }
+    }
+
+    struct Name(&'static str);
+
+    impl Display for Name {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            IndentWriter::default()
+                .with_scoped_options()
+                .write_to_formatter(f, &self.0)
+        }
+    }
+
+    #[test]
+    fn test_nested_scopes() {
+        let indent = |n| CommonWriterOptions::default().with_indent(n);
+        assert_eq!(Name("a").to_string(), "  a");
+        with_default_options(indent(0), || {
+            assert_eq!(Name("a").to_string(), "a");
+            with_default_options(indent(4), || assert_eq!(Name("b").to_string(), "    b"));
+            with_default_options(CommonReaderOptions::default(), || {
+                assert_eq!(Name("c").to_string(), "c")
+            });
+            assert!(!has_default_options::<CommonReaderOptions>());
+        });
+        assert!(!has_default_options::<CommonWriterOptions>());
+        assert!(default_options::<()>().is_none());
+    }
+
+    #[test]
+    fn test_scope_restored_on_panic() {
+        let result = std::panic::catch_unwind(|| {
+            with_default_options(CommonWriterOptions::default().with_indent(8), || {
+                panic!("inside scope")
+            })
+        });
+        assert!(result.is_err());
+        assert!(!has_default_options::<CommonWriterOptions>());
+    }
+}
@@ -0,0 +1,242 @@
+/*!
+Provides the [`ObjectSeekReader`] trait for readers of formats that must move around within their
+source, such as those with an index at the end of the content or a block-based layout.
+
+Such a reader implemented with [`ObjectReader`] can only read a plain `Read` source, and so must
+first buffer all of it; an [`ObjectSeekReader`] is given a source that also implements `Seek`,
+such as a file, and reads only the parts it needs. The adapter [`BufferingReader`] makes any
+[`ObjectSeekReader`] usable where an [`ObjectReader`] is expected, by buffering the source in
+memory.
+
+# Example
+
+```rust
+use objio::seek::{BufferingReader, ObjectSeekReader};
+use objio::ObjectReader;
+use std::io::{Read, Seek, SeekFrom};
+
+/// The content is a sequence of words followed by the single-byte offset of the last word.
+#[derive(Debug, Default)]
+struct LastWordReader;
+
+impl ObjectSeekReader<String> for LastWordReader {
+    type Error = std::io::Error;
+
+    fn read_seek<R: Read + Seek>(&self, r: &mut R) -> Result<String, Self::Error> {
+        let end = r.seek(SeekFrom::End(-1))?;
+        let mut offset = [0];
+        r.read_exact(&mut offset)?;
+        r.seek(SeekFrom::Start(u64::from(offset[0])))?;
+        let mut word = String::new();
+        r.take(end - u64::from(offset[0])).read_to_string(&mut word)?;
+        Ok(word)
+    }
+}
+
+assert_eq!(LastWordReader.read_from_bytes_seek(b"hello world\x06").unwrap(), "world");
+
+let reader = BufferingReader::new(LastWordReader);
+assert_eq!(reader.read(&mut &b"hello world\x06"[..]).unwrap(), "world");
+```
+ */
+
+use crate::ObjectReader;
+use std::fs::OpenOptions;
+use std::io::{BufReader, Cursor, Read, Seek};
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by types which read instances of `T` from sources that can seek.
+///
+pub trait ObjectSeekReader<T> {
+    ///
+    /// The type indicating errors, this **must** implement the conversion from `io::Error` as for
+    /// [`ObjectReader::Error`].
+    ///
+    type Error: From<::std::io::Error>;
+
+    ///
+    /// Read an instance of `T` from the provided implementation of `Read` and `Seek`. The source
+    /// is positioned at the start of the content, which may not be the start of the source.
+    ///
+    fn read_seek<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read + Seek;
+
+    ///
+    /// Check that the options for this reader, if any, are consistent; the default
+    /// implementation does nothing.
+    ///
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    ///
+    /// Read an instance of `T` from the provided bytes.
+    ///
+    fn read_from_bytes_seek<B>(&self, bytes: B) -> Result<T, Self::Error>
+    where
+        B: AsRef<[u8]>,
+    {
+        self.validate_options()?;
+        self.read_seek(&mut Cursor::new(bytes.as_ref()))
+    }
+
+    ///
+    /// Read an instance of `T` from the file identified by `path`.
+    ///
+    fn read_from_file_seek<P>(&self, path: P) -> Result<T, Self::Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.validate_options()?;
+        let file = OpenOptions::new().read(true).open(path.as_ref())?;
+        self.read_seek(&mut BufReader::new(file))
+    }
+}
+
+///
+/// A reader that adapts an [`ObjectSeekReader`] to the [`ObjectReader`] trait by reading the whole
+/// source into memory, where it may seek.
+///
+#[derive(Clone, Debug, Default)]
+pub struct BufferingReader<O> {
+    inner: O,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<O, T> ObjectReader<T> for BufferingReader<O>
+where
+    O: ObjectSeekReader<T>,
+{
+    type Error = O::Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        let mut buffer = Vec::new();
+        let _ = r.read_to_end(&mut buffer)?;
+        self.inner.read_seek(&mut Cursor::new(buffer))
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.inner.validate_options()
+    }
+
+    fn read_from_bytes<B>(&self, bytes: B) -> Result<T, Self::Error>
+    where
+        B: AsRef<[u8]>,
+    {
+        self.inner.read_from_bytes_seek(bytes)
+    }
+
+    fn read_from_file<P>(&self, path: P) -> Result<T, Self::Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.inner.read_from_file_seek(path)
+    }
+}
+
+impl<O> BufferingReader<O> {
+    ///
+    /// Construct a new reader adapting `inner`.
+    ///
+    pub fn new(inner: O) -> Self {
+        Self { inner }
+    }
+
+    ///
+    /// Returns a reference to the adapted reader.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Returns the adapted reader.
+    ///
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, SeekFrom, Write};
+
+    ///
+    /// Records separated by newlines, followed by a 4-byte index of the offset of each record and
+    /// the 4-byte count of records.
+    ///
+    #[derive(Debug)]
+    struct IndexedReader {
+        record: usize,
+    }
+
+    impl ObjectSeekReader<String> for IndexedReader {
+        type Error = crate::Error;
+
+        fn read_seek<R: Read + Seek>(&self, r: &mut R) -> Result<String, Self::Error> {
+            let mut word = [0; 4];
+            let start = r.stream_position()?;
+            let _ = r.seek(SeekFrom::End(-4))?;
+            r.read_exact(&mut word)?;
+            let count = u32::from_le_bytes(word) as i64;
+            if self.record as i64 >= count {
+                return Err(crate::Error::parse("indexed", "no such record"));
+            }
+            let _ = r.seek(SeekFrom::End(-4 * (count + 1 - self.record as i64)))?;
+            r.read_exact(&mut word)?;
+            let _ = r.seek(SeekFrom::Start(start + u64::from(u32::from_le_bytes(word))))?;
+            let mut line = String::new();
+            let _ = BufReader::new(r).read_line(&mut line)?;
+            Ok(line.trim_end().to_string())
+        }
+    }
+
+    fn indexed(records: &[&str]) -> Vec<u8> {
+        let mut content = Vec::new();
+        let mut offsets = Vec::new();
+        for record in records {
+            offsets.push(content.len() as u32);
+            writeln!(content, "{record}").unwrap();
+        }
+        for offset in &offsets {
+            content.extend_from_slice(&offset.to_le_bytes());
+        }
+        content.extend_from_slice(&(offsets.len() as u32).to_le_bytes());
+        content
+    }
+
+    #[test]
+    fn test_read_indexed() {
+        let content = indexed(&["zero", "one", "two"]);
+        assert_eq!(
+            IndexedReader { record: 1 }
+                .read_from_bytes_seek(&content)
+                .unwrap(),
+            "one"
+        );
+        assert!(IndexedReader { record: 3 }
+            .read_from_bytes_seek(&content)
+            .is_err());
+
+        let reader = BufferingReader::new(IndexedReader { record: 2 });
+        assert_eq!(reader.read(&mut content.as_slice()).unwrap(), "two");
+        assert_eq!(reader.read_from_bytes(&content).unwrap(), "two");
+    }
+}
@@ -0,0 +1,474 @@
+/*!
+Provides [`WriteSession`], for long-running streaming writes to a file that can be checkpointed,
+resumed after the process restarts, and either finished or aborted.
+
+Objects are written, using an [`ObjectsWriter`], to a partial file alongside the target, with the
+extension [`PARTIAL_FILE_EXTENSION`] appended. A [`checkpoint`](WriteSession::checkpoint) flushes
+and syncs the partial file and then atomically records its length, and the number of objects
+written, in a marker file with the extension [`CHECKPOINT_FILE_EXTENSION`] appended. A session
+is [`resumed`](WriteSession::resume) from the last checkpoint, discarding anything written after
+it, so that the objects written since must be written again. Finishing a session writes the end
+of the stream and renames the partial file to the target, so that the target is never observed
+partially written; aborting a session removes the partial and marker files.
+
+A session that is dropped, or whose process exits, without being finished or aborted leaves its
+files in place so that it can be resumed; [`read_checkpoint`] returns the last checkpoint of any
+such session. A session interrupted before its initial checkpoint has a partial file but no
+marker file, and is resumed from the start of the stream.
+
+# Example
+
+```rust
+use objio::session::WriteSession;
+use objio::stream::ObjectsWriter;
+use std::io::Write;
+
+#[derive(Debug, Default)]
+struct LinesWriter;
+
+impl ObjectsWriter<String> for LinesWriter {
+    type Error = std::io::Error;
+
+    fn write_next<W: Write>(&self, w: &mut W, object: &String, _: usize) -> Result<(), Self::Error> {
+        writeln!(w, "{object}")
+    }
+}
+
+let path = std::env::temp_dir().join(format!("objio-session-doc-{}.txt", std::process::id()));
+let mut session = WriteSession::start(&path, LinesWriter).unwrap();
+session.write(&"one".to_string()).unwrap();
+session.checkpoint().unwrap();
+session.write(&"lost".to_string()).unwrap();
+drop(session);
+
+let mut session = WriteSession::resume(&path, LinesWriter).unwrap();
+assert_eq!(session.count(), 1);
+session.write(&"two".to_string()).unwrap();
+assert_eq!(session.finish().unwrap(), 2);
+assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+# std::fs::remove_file(&path).unwrap();
+```
+ */
+
+use crate::stream::ObjectsWriter;
+use std::fmt::{Debug, Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Error as IoError, ErrorKind, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The extension appended to the path of a session's target to name its partial file.
+///
+pub const PARTIAL_FILE_EXTENSION: &str = "partial";
+
+///
+/// The extension appended to the path of a session's target to name its checkpoint marker file.
+///
+pub const CHECKPOINT_FILE_EXTENSION: &str = "checkpoint";
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The durable state of a [`WriteSession`] at a checkpoint.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    len: u64,
+    count: usize,
+}
+
+///
+/// A streaming write of objects of type `T`, using an [`ObjectsWriter`], to a file, which may be
+/// checkpointed and resumed.
+///
+pub struct WriteSession<O, T> {
+    inner: O,
+    path: PathBuf,
+    file: BufWriter<File>,
+    count: usize,
+    last_checkpoint: Checkpoint,
+    object: PhantomData<fn(&T)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the last checkpoint of an unfinished session writing to `path`, or `None` if there is
+/// no such session.
+///
+pub fn read_checkpoint<P>(path: P) -> Result<Option<Checkpoint>, IoError>
+where
+    P: AsRef<Path>,
+{
+    let content = match std::fs::read_to_string(checkpoint_path(path.as_ref())) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let invalid = || {
+        IoError::new(
+            ErrorKind::InvalidData,
+            format!("the checkpoint `{}` is not valid", content.trim_end()),
+        )
+    };
+    let (len, count) = content.trim_end().split_once(' ').ok_or_else(invalid)?;
+    Ok(Some(Checkpoint {
+        len: len.parse().map_err(|_| invalid())?,
+        count: count.parse().map_err(|_| invalid())?,
+    }))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Checkpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.len, self.count)
+    }
+}
+
+impl Checkpoint {
+    ///
+    /// Returns the length, in bytes, of the partial file at the checkpoint.
+    ///
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    ///
+    /// Returns `true` if nothing had been written to the partial file at the checkpoint.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    ///
+    /// Returns the number of objects written at the checkpoint.
+    ///
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O, T> Debug for WriteSession<O, T>
+where
+    O: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteSession")
+            .field("inner", &self.inner)
+            .field("path", &self.path)
+            .field("count", &self.count)
+            .field("last_checkpoint", &self.last_checkpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O, T> WriteSession<O, T>
+where
+    O: ObjectsWriter<T>,
+{
+    ///
+    /// Start a new session writing to `path` using `inner`, writing the start of the stream and
+    /// an initial checkpoint. This fails if an unfinished session for `path` exists; it must be
+    /// resumed, or aborted, first.
+    ///
+    pub fn start<P>(path: P, inner: O) -> Result<Self, O::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(partial_path(&path))?;
+        let mut self_mut = Self {
+            inner,
+            path,
+            file: BufWriter::new(file),
+            count: 0,
+            last_checkpoint: Checkpoint::default(),
+            object: PhantomData,
+        };
+        self_mut.inner.write_start(&mut self_mut.file)?;
+        let _ = self_mut.checkpoint()?;
+        Ok(self_mut)
+    }
+
+    ///
+    /// Resume the unfinished session writing to `path` using `inner`, from its last checkpoint;
+    /// anything written after the checkpoint is discarded. A session interrupted while starting,
+    /// before its initial checkpoint was recorded, is resumed from the start of the stream.
+    ///
+    pub fn resume<P>(path: P, inner: O) -> Result<Self, O::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        let partial = partial_path(&path);
+        let checkpoint = match read_checkpoint(&path)? {
+            Some(checkpoint) => checkpoint,
+            None if partial.exists() => Checkpoint::default(),
+            None => {
+                return Err(IoError::new(
+                    ErrorKind::NotFound,
+                    format!("no unfinished session writes to `{}`", path.display()),
+                )
+                .into())
+            }
+        };
+        let file = OpenOptions::new().append(true).open(partial)?;
+        if file.metadata()?.len() < checkpoint.len {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                "the partial file is shorter than its checkpoint",
+            )
+            .into());
+        }
+        file.set_len(checkpoint.len)?;
+        let mut self_mut = Self {
+            inner,
+            path,
+            file: BufWriter::new(file),
+            count: checkpoint.count,
+            last_checkpoint: checkpoint,
+            object: PhantomData,
+        };
+        if checkpoint.is_empty() {
+            self_mut.inner.write_start(&mut self_mut.file)?;
+        }
+        Ok(self_mut)
+    }
+
+    ///
+    /// Write `object` to the session.
+    ///
+    pub fn write(&mut self, object: &T) -> Result<(), O::Error> {
+        self.inner.write_next(&mut self.file, object, self.count)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    ///
+    /// Make everything written so far durable, and record it as the point from which the session
+    /// is resumed.
+    ///
+    pub fn checkpoint(&mut self) -> Result<Checkpoint, IoError> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()?;
+        let checkpoint = Checkpoint {
+            len: self.file.get_ref().metadata()?.len(),
+            count: self.count,
+        };
+        crate::update::replace_file(
+            &checkpoint_path(&self.path),
+            format!("{checkpoint}\n").as_bytes(),
+        )?;
+        self.last_checkpoint = checkpoint;
+        Ok(checkpoint)
+    }
+
+    ///
+    /// Write the end of the stream, replace the target with the partial file, and return the
+    /// number of objects written.
+    ///
+    pub fn finish(self) -> Result<usize, O::Error> {
+        let mut self_mut = self;
+        self_mut
+            .inner
+            .write_end(&mut self_mut.file, self_mut.count)?;
+        self_mut.file.flush()?;
+        self_mut.file.get_ref().sync_all()?;
+        std::fs::rename(partial_path(&self_mut.path), &self_mut.path)?;
+        match std::fs::remove_file(checkpoint_path(&self_mut.path)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        sync_directory(&self_mut.path);
+        Ok(self_mut.count)
+    }
+}
+
+impl<O, T> WriteSession<O, T> {
+    ///
+    /// Abandon the session, removing its partial and checkpoint files; the target is unchanged.
+    ///
+    pub fn abort(self) -> Result<(), IoError> {
+        let Self { path, file, .. } = self;
+        // The buffered content is discarded, rather than written, before the file is removed.
+        let _ = file.into_parts();
+        for path in [partial_path(&path), checkpoint_path(&path)] {
+            match std::fs::remove_file(path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the path of the session's target.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    ///
+    /// Returns the number of objects written, including those written before the session was
+    /// resumed.
+    ///
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    ///
+    /// Returns the last checkpoint of the session.
+    ///
+    pub fn last_checkpoint(&self) -> Checkpoint {
+        self.last_checkpoint
+    }
+
+    ///
+    /// Returns a reference to the inner writer.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn partial_path(path: &Path) -> PathBuf {
+    with_extension(path, PARTIAL_FILE_EXTENSION)
+}
+
+fn checkpoint_path(path: &Path) -> PathBuf {
+    with_extension(path, CHECKPOINT_FILE_EXTENSION)
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{extension}"));
+    PathBuf::from(name)
+}
+
+fn sync_directory(path: &Path) {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    // Persist the rename itself; directories cannot be opened for syncing everywhere.
+    if let Ok(directory) = File::open(directory) {
+        let _ = directory.sync_all();
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct ArrayWriter;
+
+    impl ObjectsWriter<u32> for ArrayWriter {
+        type Error = IoError;
+
+        fn write_start<W: Write>(&self, w: &mut W) -> Result<(), Self::Error> {
+            write!(w, "[")
+        }
+
+        fn write_next<W: Write>(
+            &self,
+            w: &mut W,
+            object: &u32,
+            index: usize,
+        ) -> Result<(), Self::Error> {
+            match index {
+                0 => write!(w, "{object}"),
+                _ => write!(w, ",{object}"),
+            }
+        }
+
+        fn write_end<W: Write>(&self, w: &mut W, _: usize) -> Result<(), Self::Error> {
+            write!(w, "]")
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_resume_finish() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("resume");
+        let mut session = WriteSession::start(&path, ArrayWriter).unwrap();
+        assert_eq!(read_checkpoint(&path).unwrap().unwrap().len(), 1);
+        session.write(&1).unwrap();
+        session.write(&2).unwrap();
+        let checkpoint = session.checkpoint().unwrap();
+        assert_eq!(checkpoint.count(), 2);
+        session.write(&3).unwrap();
+        session.checkpoint().unwrap();
+        session.write(&4).unwrap();
+        session.file.flush().unwrap();
+        drop(session);
+
+        assert!(matches!(
+            WriteSession::start(&path, ArrayWriter),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists
+        ));
+        let mut session = WriteSession::resume(&path, ArrayWriter).unwrap();
+        assert_eq!(session.count(), 3);
+        session.write(&5).unwrap();
+        assert_eq!(session.finish().unwrap(), 4);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[1,2,3,5]");
+        assert_eq!(read_checkpoint(&path).unwrap(), None);
+        assert!(!partial_path(&path).exists());
+    }
+
+    #[test]
+    fn test_resume_interrupted_start() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("interrupted");
+        std::fs::write(partial_path(&path), "[").unwrap();
+        assert_eq!(read_checkpoint(&path).unwrap(), None);
+
+        let mut session = WriteSession::resume(&path, ArrayWriter).unwrap();
+        assert_eq!(session.count(), 0);
+        session.write(&1).unwrap();
+        assert_eq!(session.finish().unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[1]");
+    }
+
+    #[test]
+    fn test_abort() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("abort");
+        std::fs::write(&path, "[0]").unwrap();
+        let mut session = WriteSession::start(&path, ArrayWriter).unwrap();
+        session.write(&1).unwrap();
+        session.checkpoint().unwrap();
+        session.abort().unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[0]");
+        assert!(!partial_path(&path).exists());
+        assert!(!checkpoint_path(&path).exists());
+        let error = WriteSession::resume(&path, ArrayWriter).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    }
+}
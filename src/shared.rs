@@ -0,0 +1,263 @@
+/*!
+Provides a thread-safe writer, [`SharedWriter`], that allows multiple threads to append objects to
+a single destination.
+
+A `SharedWriter` is cheap to clone, each clone sharing the same object writer and destination. By
+default each record is *atomic*: the object is serialized into a buffer outside of any lock, and
+the buffer is then written to the destination while holding the lock, so that records from
+different threads never interleave. When atomicity is disabled the object is serialized directly
+to the destination, taking the lock for each individual write, which avoids the buffer at the cost
+of records possibly interleaving.
+
+# Example
+
+```rust
+use objio::shared::SharedWriter;
+use objio::ObjectWriter;
+use std::io::Write;
+
+#[derive(Debug, Default)]
+struct LineWriter;
+
+impl ObjectWriter<u32> for LineWriter {
+    type Error = std::io::Error;
+
+    fn write<W: Write>(&self, w: &mut W, object: &u32) -> Result<(), Self::Error> {
+        writeln!(w, "record {object}")
+    }
+}
+
+let shared = SharedWriter::new(LineWriter, Vec::new());
+let threads: Vec<_> = (0..4)
+    .map(|i| {
+        let shared = shared.clone();
+        std::thread::spawn(move || shared.append(&i).unwrap())
+    })
+    .collect();
+threads.into_iter().for_each(|t| t.join().unwrap());
+
+let output = String::from_utf8(shared.into_inner().unwrap()).unwrap();
+assert_eq!(output.lines().count(), 4);
+assert!(output.lines().all(|line| line.starts_with("record ")));
+```
+ */
+
+use crate::ObjectWriter;
+use std::io::{Error as IoError, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A thread-safe handle for appending objects, serialized by an inner [`ObjectWriter`], to a
+/// shared destination.
+///
+#[derive(Debug)]
+pub struct SharedWriter<O, W> {
+    writer: Arc<O>,
+    destination: Arc<Mutex<W>>,
+    atomic: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+struct LockingWrite<'a, W> {
+    destination: &'a Mutex<W>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<O, W> Clone for SharedWriter<O, W> {
+    fn clone(&self) -> Self {
+        Self {
+            writer: self.writer.clone(),
+            destination: self.destination.clone(),
+            atomic: self.atomic,
+        }
+    }
+}
+
+impl<O, W> SharedWriter<O, W>
+where
+    W: Write,
+{
+    ///
+    /// Construct a new shared writer, with atomic records, appending objects serialized by
+    /// `writer` to `destination`.
+    ///
+    pub fn new(writer: O, destination: W) -> Self {
+        Self {
+            writer: Arc::new(writer),
+            destination: Arc::new(Mutex::new(destination)),
+            atomic: true,
+        }
+    }
+
+    ///
+    /// Returns this writer with per-record atomicity set to `atomic`; this affects only this
+    /// handle and clones made from it afterwards.
+    ///
+    pub fn with_atomic(self, atomic: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.atomic = atomic;
+        self_mut
+    }
+
+    ///
+    /// Returns `true` if records written by this handle are atomic.
+    ///
+    pub fn is_atomic(&self) -> bool {
+        self.atomic
+    }
+
+    ///
+    /// Serialize `object` and append it to the shared destination.
+    ///
+    pub fn append<T>(&self, object: &T) -> Result<(), O::Error>
+    where
+        O: ObjectWriter<T>,
+    {
+        if self.atomic {
+            let record = self.writer.write_to_bytes(object)?;
+            lock(&self.destination)?.write_all(&record)?;
+            Ok(())
+        } else {
+            self.writer.validate_options()?;
+            self.writer.write(
+                &mut LockingWrite {
+                    destination: &self.destination,
+                },
+                object,
+            )
+        }
+    }
+
+    ///
+    /// Flush the shared destination.
+    ///
+    pub fn flush(&self) -> Result<(), IoError> {
+        lock(&self.destination)?.flush()
+    }
+
+    ///
+    /// Returns the destination if this is the last handle sharing it, after flushing it, or
+    /// otherwise returns this handle.
+    ///
+    pub fn into_inner(self) -> Result<W, Self> {
+        match Arc::try_unwrap(self.destination) {
+            Ok(destination) => {
+                let mut destination = destination.into_inner().unwrap_or_else(|e| e.into_inner());
+                let _ = destination.flush();
+                Ok(destination)
+            }
+            Err(destination) => Err(Self {
+                writer: self.writer,
+                destination,
+                atomic: self.atomic,
+            }),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<W> Write for LockingWrite<'_, W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        lock(self.destination)?.write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        lock(self.destination)?.write_all(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        lock(self.destination)?.flush()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn lock<W>(destination: &Mutex<W>) -> Result<MutexGuard<'_, W>, IoError> {
+    destination
+        .lock()
+        .map_err(|_| IoError::other("a thread panicked while writing to the shared destination"))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    /// Writes each record as many small writes, to encourage interleaving.
+    #[derive(Debug, Default)]
+    struct SlowWriter;
+
+    impl ObjectWriter<char> for SlowWriter {
+        type Error = Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &char) -> Result<(), Self::Error> {
+            for _ in 0..100 {
+                write!(w, "{object}")?;
+                std::thread::yield_now();
+            }
+            Ok(w.write_all(b"\n")?)
+        }
+    }
+
+    fn write_records(shared: SharedWriter<SlowWriter, Vec<u8>>) -> String {
+        let threads: Vec<_> = ['a', 'b', 'c', 'd']
+            .into_iter()
+            .map(|c| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        shared.append(&c).unwrap();
+                    }
+                })
+            })
+            .collect();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+        String::from_utf8(shared.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_atomic_records() {
+        let output = write_records(SharedWriter::new(SlowWriter, Vec::new()));
+        assert_eq!(output.lines().count(), 40);
+        for line in output.lines() {
+            assert_eq!(line.len(), 100);
+            assert!(line.chars().all(|c| c == line.chars().next().unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_non_atomic_records() {
+        let output = write_records(SharedWriter::new(SlowWriter, Vec::new()).with_atomic(false));
+        assert_eq!(output.len(), 40 * 101);
+        assert_eq!(output.chars().filter(|c| *c == 'a').count(), 1000);
+    }
+
+    #[test]
+    fn test_into_inner_shared() {
+        let shared = SharedWriter::new(SlowWriter, Vec::new());
+        let other = shared.clone();
+        let shared = shared.into_inner().unwrap_err();
+        drop(other);
+        assert!(shared.into_inner().is_ok());
+    }
+}
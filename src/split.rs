@@ -0,0 +1,507 @@
+/*!
+Provides size-capped output that splits one serialization across multiple files, for delivery
+targets that refuse files over a fixed size.
+
+[`SplitSink`] writes the bytes of a serialization into numbered part files, `data.ttl.001`,
+`data.ttl.002`, and so on, starting a new part whenever the current one reaches the size cap. When
+finished it writes a manifest, `data.ttl.manifest`, listing each part and its size.
+[`SplitSource`] reads the parts named by a manifest back as a single stream, checking that each
+part has the recorded size. Parts are split at byte boundaries, not object boundaries, so
+individual parts are not meaningful alone.
+
+The functions [`write_split`] and [`read_split`] combine these with an object writer and reader.
+
+# Example
+
+```rust
+use objio::raw::{RawReader, RawWriter};
+use objio::split::{read_split, write_split};
+
+let path = std::env::temp_dir().join(format!("objio-split-doc-{}.txt", std::process::id()));
+let manifest = write_split(&RawWriter, &"0123456789".to_string(), &path, 4).unwrap();
+assert_eq!(manifest.parts().len(), 3);
+assert_eq!(manifest.total_size(), 10);
+
+let text: String = read_split(&RawReader, manifest.path()).unwrap();
+assert_eq!(text, "0123456789");
+# manifest.remove_files().unwrap();
+```
+ */
+
+use crate::{ObjectReader, ObjectWriter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Error as IoError, ErrorKind, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The extension appended to the output path to name the manifest.
+///
+pub const MANIFEST_EXTENSION: &str = "manifest";
+
+///
+/// The first line of every manifest, identifying its format.
+///
+pub const MANIFEST_HEADER: &str = "# objio split manifest v1";
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A destination that writes bytes into a sequence of part files, each no larger than a size cap.
+///
+#[derive(Debug)]
+pub struct SplitSink {
+    path: PathBuf,
+    max_part_size: u64,
+    current: BufWriter<File>,
+    current_size: u64,
+    parts: Vec<SplitPart>,
+}
+
+///
+/// The list of parts making up one split serialization.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitManifest {
+    path: PathBuf,
+    parts: Vec<SplitPart>,
+}
+
+///
+/// A single part file of a split serialization.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitPart {
+    path: PathBuf,
+    size: u64,
+}
+
+///
+/// A source that reads the parts listed in a [`SplitManifest`] as a single stream.
+///
+#[derive(Debug)]
+pub struct SplitSource {
+    parts: std::vec::IntoIter<SplitPart>,
+    current: Option<(File, SplitPart, u64)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Write `object` using `writer` into part files derived from `path`, each no larger than
+/// `max_part_size` bytes, returning the manifest which has also been written.
+///
+pub fn write_split<W, T, P>(
+    writer: &W,
+    object: &T,
+    path: P,
+    max_part_size: u64,
+) -> Result<SplitManifest, W::Error>
+where
+    W: ObjectWriter<T> + ?Sized,
+    P: AsRef<Path>,
+{
+    writer.validate_options()?;
+    let mut sink = SplitSink::create(path, max_part_size)?;
+    writer.write(&mut sink, object)?;
+    Ok(sink.finish()?)
+}
+
+///
+/// Read an instance of `T` using `reader` from the parts listed in the manifest at
+/// `manifest_path`.
+///
+pub fn read_split<R, T, P>(reader: &R, manifest_path: P) -> Result<T, R::Error>
+where
+    R: ObjectReader<T> + ?Sized,
+    P: AsRef<Path>,
+{
+    reader.validate_options()?;
+    let manifest = SplitManifest::read(manifest_path)?;
+    reader.read_buffered(&mut BufReader::new(SplitSource::new(manifest)))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Write for SplitSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.current_size == self.max_part_size {
+            self.next_part()?;
+        }
+        let remaining = self.max_part_size - self.current_size;
+        let count = buf
+            .len()
+            .min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        let count = self.current.write(&buf[..count])?;
+        self.current_size += count as u64;
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.current.flush()
+    }
+}
+
+impl SplitSink {
+    ///
+    /// Create the first part file for output to `path`, replacing any existing part and removing
+    /// any later parts left by a previous, longer, output. This will return an error of kind
+    /// `InvalidInput` if `max_part_size` is zero.
+    ///
+    pub fn create<P>(path: P, max_part_size: u64) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+    {
+        if max_part_size == 0 {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "the maximum part size must be greater than zero",
+            ));
+        }
+        let path = path.as_ref().to_path_buf();
+        let first = part_path(&path, 1);
+        let current = BufWriter::new(create(&first)?);
+        for number in 2.. {
+            match std::fs::remove_file(part_path(&path, number)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Self {
+            current,
+            path,
+            max_part_size,
+            current_size: 0,
+            parts: vec![SplitPart {
+                path: first,
+                size: 0,
+            }],
+        })
+    }
+
+    ///
+    /// Returns the maximum size, in bytes, of each part.
+    ///
+    pub fn max_part_size(&self) -> u64 {
+        self.max_part_size
+    }
+
+    ///
+    /// Returns the number of parts created so far.
+    ///
+    pub fn part_count(&self) -> usize {
+        self.parts.len()
+    }
+
+    ///
+    /// Flush and close the final part and write the manifest, returning it.
+    ///
+    pub fn finish(self) -> Result<SplitManifest, IoError> {
+        let mut self_mut = self;
+        self_mut.close_part()?;
+        let manifest = SplitManifest {
+            path: manifest_path(&self_mut.path),
+            parts: self_mut.parts,
+        };
+        manifest.write()?;
+        Ok(manifest)
+    }
+
+    fn close_part(&mut self) -> Result<(), IoError> {
+        self.current.flush()?;
+        self.current.get_ref().sync_all()?;
+        if let Some(part) = self.parts.last_mut() {
+            part.size = self.current_size;
+        }
+        Ok(())
+    }
+
+    fn next_part(&mut self) -> Result<(), IoError> {
+        self.close_part()?;
+        let next = part_path(&self.path, self.parts.len() + 1);
+        self.current = BufWriter::new(create(&next)?);
+        self.current_size = 0;
+        self.parts.push(SplitPart {
+            path: next,
+            size: 0,
+        });
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl SplitManifest {
+    ///
+    /// Read the manifest at `path`; part file names are resolved relative to its directory.
+    ///
+    pub fn read<P>(path: P) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        let directory = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let file = OpenOptions::new().read(true).open(&path)?;
+        let mut lines = BufReader::new(file).lines();
+        match lines.next().transpose()? {
+            Some(header) if header == MANIFEST_HEADER => {}
+            _ => return Err(invalid(&path, "it does not begin with the manifest header")),
+        }
+        let mut parts = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (size, name) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid(&path, "a part line is not `<size> <name>`"))?;
+            let size = size
+                .parse()
+                .map_err(|_| invalid(&path, "a part size is not a number"))?;
+            let mut components = Path::new(name).components();
+            if !matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            ) {
+                return Err(invalid(&path, "a part name is not a file name"));
+            }
+            parts.push(SplitPart {
+                path: directory.join(name),
+                size,
+            });
+        }
+        Ok(Self { path, parts })
+    }
+
+    ///
+    /// Returns the path of this manifest.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    ///
+    /// Returns the parts, in order.
+    ///
+    pub fn parts(&self) -> &[SplitPart] {
+        &self.parts
+    }
+
+    ///
+    /// Returns the combined size, in bytes, of all the parts.
+    ///
+    pub fn total_size(&self) -> u64 {
+        self.parts.iter().map(SplitPart::size).sum()
+    }
+
+    ///
+    /// Remove the manifest and all of its parts.
+    ///
+    pub fn remove_files(&self) -> Result<(), IoError> {
+        for part in &self.parts {
+            std::fs::remove_file(&part.path)?;
+        }
+        std::fs::remove_file(&self.path)
+    }
+
+    fn write(&self) -> Result<(), IoError> {
+        let mut file = BufWriter::new(create(&self.path)?);
+        writeln!(file, "{MANIFEST_HEADER}")?;
+        for part in &self.parts {
+            let name = part
+                .path
+                .file_name()
+                .expect("part paths always have a file name");
+            writeln!(file, "{} {}", part.size, name.to_string_lossy())?;
+        }
+        file.flush()?;
+        file.get_ref().sync_all()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl SplitPart {
+    ///
+    /// Returns the path of this part.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    ///
+    /// Returns the size, in bytes, of this part.
+    ///
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Read for SplitSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if self.current.is_none() {
+                match self.parts.next() {
+                    Some(part) => {
+                        let file = OpenOptions::new().read(true).open(&part.path)?;
+                        self.current = Some((file, part, 0));
+                    }
+                    None => return Ok(0),
+                }
+            }
+            let (file, part, read) = self.current.as_mut().expect("current part is open");
+            let count = file.read(buf)?;
+            *read += count as u64;
+            if *read > part.size {
+                return Err(size_mismatch(part));
+            } else if count > 0 {
+                return Ok(count);
+            } else if *read < part.size {
+                return Err(size_mismatch(part));
+            }
+            self.current = None;
+        }
+    }
+}
+
+impl SplitSource {
+    ///
+    /// Construct a new source reading the parts listed in `manifest`.
+    ///
+    pub fn new(manifest: SplitManifest) -> Self {
+        Self {
+            parts: manifest.parts.into_iter(),
+            current: None,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn part_path(path: &Path, number: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{number:03}"));
+    PathBuf::from(name)
+}
+
+fn manifest_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{MANIFEST_EXTENSION}"));
+    PathBuf::from(name)
+}
+
+fn create(path: &Path) -> Result<File, IoError> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+}
+
+fn invalid(path: &Path, reason: &str) -> IoError {
+    IoError::new(
+        ErrorKind::InvalidData,
+        format!(
+            "the split manifest `{}` is invalid; {reason}",
+            path.display()
+        ),
+    )
+}
+
+fn size_mismatch(part: &SplitPart) -> IoError {
+    IoError::new(
+        ErrorKind::InvalidData,
+        format!(
+            "the split part `{}` does not have the size, {} bytes, recorded in the manifest",
+            part.path.display(),
+            part.size
+        ),
+    )
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::{RawReader, RawWriter};
+
+    #[test]
+    fn test_part_boundaries() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("boundaries");
+        let manifest = write_split(&RawWriter, &vec![7u8; 8], &path, 4).unwrap();
+        let sizes: Vec<u64> = manifest.parts().iter().map(SplitPart::size).collect();
+        assert_eq!(sizes, vec![4, 4]);
+        assert_eq!(manifest.parts()[1].path(), part_path(&path, 2));
+        assert_eq!(SplitManifest::read(manifest.path()).unwrap(), manifest);
+        manifest.remove_files().unwrap();
+
+        let manifest = write_split(&RawWriter, &Vec::new(), &path, 4).unwrap();
+        assert_eq!(manifest.parts().len(), 1);
+        let empty: Vec<u8> = read_split(&RawReader, manifest.path()).unwrap();
+        assert!(empty.is_empty());
+        manifest.remove_files().unwrap();
+
+        assert!(SplitSink::create(&path, 0).is_err());
+    }
+
+    #[test]
+    fn test_stale_parts_removed() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("stale");
+        let manifest = write_split(&RawWriter, &vec![1u8; 10], &path, 4).unwrap();
+        assert_eq!(manifest.parts().len(), 3);
+        let manifest = write_split(&RawWriter, &vec![2u8; 3], &path, 4).unwrap();
+        assert_eq!(manifest.parts().len(), 1);
+        assert!(!part_path(&path, 2).exists());
+        assert!(!part_path(&path, 3).exists());
+        manifest.remove_files().unwrap();
+    }
+
+    #[test]
+    fn test_invalid_part_names() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("names");
+        for name in ["../outside", "/etc/passwd", "nested/part", ".."] {
+            std::fs::write(&path, format!("{MANIFEST_HEADER}\n1 {name}\n")).unwrap();
+            let error = SplitManifest::read(&path).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_truncated_part() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("truncated");
+        let manifest = write_split(&RawWriter, &vec![1u8; 10], &path, 3).unwrap();
+        assert_eq!(manifest.parts().len(), 4);
+        std::fs::write(manifest.parts()[1].path(), [1u8]).unwrap();
+        let error = read_split::<_, Vec<u8>, _>(&RawReader, manifest.path()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        manifest.remove_files().unwrap();
+    }
+}
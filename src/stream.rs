@@ -0,0 +1,295 @@
+/*!
+Provides traits for readers and writers of streams containing a sequence of objects, rather than a
+single object.
+
+An [`ObjectsReader`] reads one object at a time from a buffered source, returning `None` at the end
+of the stream; the [`Objects`] iterator, returned by [`ObjectsReader::objects`], wraps this in the
+standard `Iterator` interface. An [`ObjectsWriter`] writes one object at a time, with optional
+content at the start and end of the stream, for example the brackets of a JSON array.
+
+# Example
+
+```rust
+use objio::stream::{ObjectsReader, ObjectsWriter};
+use std::io::{BufRead, Write};
+
+#[derive(Debug, Default)]
+struct LinesIo;
+
+impl ObjectsReader<String> for LinesIo {
+    type Error = std::io::Error;
+
+    fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<String>, Self::Error> {
+        let mut line = String::new();
+        Ok(match r.read_line(&mut line)? {
+            0 => None,
+            _ => Some(line.trim_end().to_string()),
+        })
+    }
+}
+
+impl ObjectsWriter<String> for LinesIo {
+    type Error = std::io::Error;
+
+    fn write_next<W: Write>(&self, w: &mut W, object: &String, _: usize) -> Result<(), Self::Error> {
+        writeln!(w, "{object}")
+    }
+}
+
+let mut buffer: Vec<u8> = Vec::new();
+let count = LinesIo.write_all(&mut buffer, ["one".to_string(), "two".to_string()]).unwrap();
+assert_eq!(count, 2);
+
+let lines: Result<Vec<String>, _> = LinesIo.objects(buffer.as_slice()).collect();
+assert_eq!(lines.unwrap(), vec!["one", "two"]);
+```
+ */
+
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by readers of a stream of objects of type `T`.
+///
+pub trait ObjectsReader<T> {
+    type Error: From<::std::io::Error>;
+
+    ///
+    /// Read the next object from `r`, returning `None` if the stream has ended.
+    ///
+    fn read_next<R>(&self, r: &mut R) -> Result<Option<T>, Self::Error>
+    where
+        R: BufRead;
+
+    ///
+    /// Returns an iterator over the objects in `r`; the iterator ends after the first error.
+    ///
+    fn objects<R>(&self, r: R) -> Objects<'_, Self, T, R>
+    where
+        Self: Sized,
+        R: BufRead,
+    {
+        Objects {
+            reader: self,
+            source: r,
+            done: false,
+            object: PhantomData,
+        }
+    }
+
+    ///
+    /// Read all of the objects in `r`.
+    ///
+    fn read_all<R>(&self, r: &mut R) -> Result<Vec<T>, Self::Error>
+    where
+        Self: Sized,
+        R: Read,
+    {
+        self.objects(BufReader::new(r)).collect()
+    }
+}
+
+///
+/// The trait implemented by writers of a stream of objects of type `T`.
+///
+pub trait ObjectsWriter<T> {
+    type Error: From<::std::io::Error>;
+
+    ///
+    /// Write any content required at the start of the stream; the default does nothing.
+    ///
+    fn write_start<W>(&self, _w: &mut W) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+
+    ///
+    /// Write `object` to `w`, where `index` is the number of objects already written to the
+    /// stream.
+    ///
+    fn write_next<W>(&self, w: &mut W, object: &T, index: usize) -> Result<(), Self::Error>
+    where
+        W: Write;
+
+    ///
+    /// Write any content required at the end of the stream, where `count` is the number of
+    /// objects written; the default does nothing.
+    ///
+    fn write_end<W>(&self, _w: &mut W, _count: usize) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+
+    ///
+    /// Write a complete stream, containing each of `objects`, to `w` and return the number of
+    /// objects written.
+    ///
+    fn write_all<W, I>(&self, w: &mut W, objects: I) -> Result<usize, Self::Error>
+    where
+        W: Write,
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        self.write_start(w)?;
+        let mut count = 0;
+        for object in objects {
+            self.write_next(w, object.borrow(), count)?;
+            count += 1;
+        }
+        self.write_end(w, count)?;
+        Ok(count)
+    }
+}
+
+///
+/// An iterator over the objects read from a source by an [`ObjectsReader`].
+///
+pub struct Objects<'a, O, T, R> {
+    reader: &'a O,
+    source: R,
+    done: bool,
+    object: PhantomData<fn() -> T>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<O, T, R> Debug for Objects<'_, O, T, R>
+where
+    O: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Objects")
+            .field("reader", &self.reader)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O, T, R> Iterator for Objects<'_, O, T, R>
+where
+    O: ObjectsReader<T>,
+    R: BufRead,
+{
+    type Item = Result<T, O::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.reader.read_next(&mut self.source).transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+impl<O, T, R> std::iter::FusedIterator for Objects<'_, O, T, R>
+where
+    O: ObjectsReader<T>,
+    R: BufRead,
+{
+}
+
+impl<O, T, R> Objects<'_, O, T, R> {
+    ///
+    /// Returns the underlying source, positioned after the last object read.
+    ///
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Error, ErrorKind};
+
+    #[derive(Debug, Default)]
+    struct NumbersIo;
+
+    impl ObjectsReader<u8> for NumbersIo {
+        type Error = Error;
+
+        fn read_next<R: BufRead>(&self, r: &mut R) -> Result<Option<u8>, Self::Error> {
+            let mut line = String::new();
+            if r.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            line.trim()
+                .parse()
+                .map(Some)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+        }
+    }
+
+    impl ObjectsWriter<u8> for NumbersIo {
+        type Error = Error;
+
+        fn write_start<W: Write>(&self, w: &mut W) -> Result<(), Self::Error> {
+            w.write_all(b"[")
+        }
+
+        fn write_next<W: Write>(
+            &self,
+            w: &mut W,
+            object: &u8,
+            index: usize,
+        ) -> Result<(), Self::Error> {
+            if index > 0 {
+                w.write_all(b",")?;
+            }
+            write!(w, "{object}")
+        }
+
+        fn write_end<W: Write>(&self, w: &mut W, _: usize) -> Result<(), Self::Error> {
+            w.write_all(b"]")
+        }
+    }
+
+    #[test]
+    fn test_write_all() {
+        let mut buffer: Vec<u8> = Vec::new();
+        assert_eq!(NumbersIo.write_all(&mut buffer, [1, 2, 3]).unwrap(), 3);
+        assert_eq!(buffer, b"[1,2,3]");
+
+        let mut buffer: Vec<u8> = Vec::new();
+        assert_eq!(
+            NumbersIo.write_all(&mut buffer, Vec::<u8>::new()).unwrap(),
+            0
+        );
+        assert_eq!(buffer, b"[]");
+    }
+
+    #[test]
+    fn test_objects_stop_after_error() {
+        let mut objects = NumbersIo.objects("1\nx\n3\n".as_bytes());
+        assert_eq!(objects.next().unwrap().unwrap(), 1);
+        assert!(objects.next().unwrap().is_err());
+        assert!(objects.next().is_none());
+    }
+
+    #[test]
+    fn test_read_all() {
+        assert_eq!(
+            NumbersIo.read_all(&mut "1\n2\n".as_bytes()).unwrap(),
+            vec![1, 2]
+        );
+    }
+}
@@ -0,0 +1,518 @@
+/*!
+Provides ANSI styling options and helpers for writers whose output may be shown directly to a
+user in a terminal.
+
+The [`StyleOptions`] type, part of [`CommonWriterOptions`](crate::options::CommonWriterOptions),
+determines whether color is used and the [`Palette`] of styles for common syntactic roles. Writers
+should call [`StyleOptions::use_color_for`] with their sink, where the sink can be tested for a
+terminal, and then wrap spans of output with [`Style::paint`].
+
+When the color choice is [`ColorChoice::Auto`] color is only used if the sink is a terminal and
+the `NO_COLOR` environment variable is not set.
+
+# Example
+
+```rust
+use objio::style::{Color, ColorChoice, Style, StyleOptions};
+
+let options = StyleOptions::default().with_color(ColorChoice::Always);
+let enabled = options.use_color_for(&std::io::stdout());
+let keyword = options.palette().keyword();
+
+assert_eq!(
+    keyword.paint("@prefix", enabled).to_string(),
+    "\u{1b}[1;35m@prefix\u{1b}[0m"
+);
+assert_eq!(Style::new().fg(Color::Red).paint("x", false).to_string(), "x");
+```
+ */
+
+use crate::options::ParseOptionValueError;
+use std::fmt::{Display, Formatter};
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Determines whether a writer should use ANSI color codes.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum ColorChoice {
+    /// Use color only when writing to a terminal, and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    /// Always use color.
+    Always,
+    /// Never use color.
+    Never,
+}
+
+///
+/// The standard ANSI terminal colors.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+///
+/// A combination of foreground color and text attributes.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct Style {
+    foreground: Option<Color>,
+    bold: bool,
+    dimmed: bool,
+    italic: bool,
+    underline: bool,
+}
+
+///
+/// A span of text with a style, that implements `Display` by writing the text wrapped in the ANSI
+/// codes for the style, if enabled.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Painted<'a> {
+    style: Style,
+    text: &'a str,
+    enabled: bool,
+}
+
+///
+/// The styles to use for common syntactic roles in writer output.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct Palette {
+    keyword: Style,
+    identifier: Style,
+    literal: Style,
+    punctuation: Style,
+    comment: Style,
+    error: Style,
+}
+
+///
+/// Options determining the use of styled output.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct StyleOptions {
+    color: ColorChoice,
+    palette: Palette,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for ColorChoice {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(ParseOptionValueError::new(s, &["auto", "always", "never"])),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Color {
+    fn code(&self) -> u8 {
+        match self {
+            Self::Black => 30,
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::White => 37,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Style {
+    ///
+    /// Construct a new, plain, style.
+    ///
+    pub const fn new() -> Self {
+        Self {
+            foreground: None,
+            bold: false,
+            dimmed: false,
+            italic: false,
+            underline: false,
+        }
+    }
+
+    ///
+    /// Returns this style with the foreground color set.
+    ///
+    pub const fn fg(self, color: Color) -> Self {
+        let mut self_mut = self;
+        self_mut.foreground = Some(color);
+        self_mut
+    }
+
+    ///
+    /// Returns this style with the bold attribute set.
+    ///
+    pub const fn bold(self) -> Self {
+        let mut self_mut = self;
+        self_mut.bold = true;
+        self_mut
+    }
+
+    ///
+    /// Returns this style with the dimmed attribute set.
+    ///
+    pub const fn dimmed(self) -> Self {
+        let mut self_mut = self;
+        self_mut.dimmed = true;
+        self_mut
+    }
+
+    ///
+    /// Returns this style with the italic attribute set.
+    ///
+    pub const fn italic(self) -> Self {
+        let mut self_mut = self;
+        self_mut.italic = true;
+        self_mut
+    }
+
+    ///
+    /// Returns this style with the underline attribute set.
+    ///
+    pub const fn underline(self) -> Self {
+        let mut self_mut = self;
+        self_mut.underline = true;
+        self_mut
+    }
+
+    ///
+    /// Returns `true` if this style has no color or attributes.
+    ///
+    pub fn is_plain(&self) -> bool {
+        *self == Self::new()
+    }
+
+    ///
+    /// Returns a `Display` wrapper for `text` in this style; if `enabled` is `false` the text is
+    /// displayed without any ANSI codes.
+    ///
+    pub fn paint<'a>(&self, text: &'a str, enabled: bool) -> Painted<'a> {
+        Painted {
+            style: *self,
+            text,
+            enabled,
+        }
+    }
+
+    fn codes(&self) -> Vec<u8> {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push(1);
+        }
+        if self.dimmed {
+            codes.push(2);
+        }
+        if self.italic {
+            codes.push(3);
+        }
+        if self.underline {
+            codes.push(4);
+        }
+        if let Some(color) = self.foreground {
+            codes.push(color.code());
+        }
+        codes
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Painted<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.enabled || self.style.is_plain() {
+            return f.write_str(self.text);
+        }
+        let codes = self
+            .style
+            .codes()
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+        write!(f, "\u{1b}[{codes}m{}\u{1b}[0m", self.text)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            keyword: Style::new().fg(Color::Magenta).bold(),
+            identifier: Style::new().fg(Color::Blue),
+            literal: Style::new().fg(Color::Green),
+            punctuation: Style::new(),
+            comment: Style::new().dimmed().italic(),
+            error: Style::new().fg(Color::Red).bold(),
+        }
+    }
+}
+
+impl Palette {
+    ///
+    /// Returns a palette where every role is unstyled.
+    ///
+    pub fn plain() -> Self {
+        Self {
+            keyword: Style::new(),
+            identifier: Style::new(),
+            literal: Style::new(),
+            punctuation: Style::new(),
+            comment: Style::new(),
+            error: Style::new(),
+        }
+    }
+
+    ///
+    /// Returns the style for keywords and directives.
+    ///
+    pub fn keyword(&self) -> Style {
+        self.keyword
+    }
+
+    ///
+    /// Returns this palette with the style for keywords and directives set.
+    ///
+    pub fn with_keyword(self, style: Style) -> Self {
+        let mut self_mut = self;
+        self_mut.keyword = style;
+        self_mut
+    }
+
+    ///
+    /// Returns the style for identifiers, names, and IRIs.
+    ///
+    pub fn identifier(&self) -> Style {
+        self.identifier
+    }
+
+    ///
+    /// Returns this palette with the style for identifiers, names, and IRIs set.
+    ///
+    pub fn with_identifier(self, style: Style) -> Self {
+        let mut self_mut = self;
+        self_mut.identifier = style;
+        self_mut
+    }
+
+    ///
+    /// Returns the style for literal values.
+    ///
+    pub fn literal(&self) -> Style {
+        self.literal
+    }
+
+    ///
+    /// Returns this palette with the style for literal values set.
+    ///
+    pub fn with_literal(self, style: Style) -> Self {
+        let mut self_mut = self;
+        self_mut.literal = style;
+        self_mut
+    }
+
+    ///
+    /// Returns the style for punctuation and delimiters.
+    ///
+    pub fn punctuation(&self) -> Style {
+        self.punctuation
+    }
+
+    ///
+    /// Returns this palette with the style for punctuation and delimiters set.
+    ///
+    pub fn with_punctuation(self, style: Style) -> Self {
+        let mut self_mut = self;
+        self_mut.punctuation = style;
+        self_mut
+    }
+
+    ///
+    /// Returns the style for comments.
+    ///
+    pub fn comment(&self) -> Style {
+        self.comment
+    }
+
+    ///
+    /// Returns this palette with the style for comments set.
+    ///
+    pub fn with_comment(self, style: Style) -> Self {
+        let mut self_mut = self;
+        self_mut.comment = style;
+        self_mut
+    }
+
+    ///
+    /// Returns the style for errors and invalid content.
+    ///
+    pub fn error(&self) -> Style {
+        self.error
+    }
+
+    ///
+    /// Returns this palette with the style for errors and invalid content set.
+    ///
+    pub fn with_error(self, style: Style) -> Self {
+        let mut self_mut = self;
+        self_mut.error = style;
+        self_mut
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl StyleOptions {
+    ///
+    /// Returns a copy of these options with the color choice set to `color`.
+    ///
+    pub fn with_color(self, color: ColorChoice) -> Self {
+        let mut self_mut = self;
+        self_mut.color = color;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the palette set to `palette`.
+    ///
+    pub fn with_palette(self, palette: Palette) -> Self {
+        let mut self_mut = self;
+        self_mut.palette = palette;
+        self_mut
+    }
+
+    ///
+    /// Returns the color choice.
+    ///
+    pub fn color(&self) -> ColorChoice {
+        self.color
+    }
+
+    ///
+    /// Set the color choice.
+    ///
+    pub fn set_color(&mut self, color: ColorChoice) {
+        self.color = color;
+    }
+
+    ///
+    /// Returns the palette of styles.
+    ///
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    ///
+    /// Returns `true` if color should be used when writing to a sink for which `is_terminal`
+    /// describes whether it is a terminal.
+    ///
+    pub fn use_color(&self, is_terminal: bool) -> bool {
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+
+    ///
+    /// Returns `true` if color should be used when writing to `sink`.
+    ///
+    pub fn use_color_for<T>(&self, sink: &T) -> bool
+    where
+        T: IsTerminal,
+    {
+        self.use_color(sink.is_terminal())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_choice() {
+        assert!(StyleOptions::default()
+            .with_color(ColorChoice::Always)
+            .use_color(false));
+        assert!(!StyleOptions::default()
+            .with_color(ColorChoice::Never)
+            .use_color(true));
+        assert!(!StyleOptions::default().use_color(false));
+    }
+
+    #[test]
+    fn test_paint() {
+        let style = Style::new().fg(Color::Cyan).underline();
+        assert_eq!(
+            style.paint("text", true).to_string(),
+            "\u{1b}[4;36mtext\u{1b}[0m"
+        );
+        assert_eq!(Style::new().paint("text", true).to_string(), "text");
+    }
+
+    #[test]
+    fn test_plain_palette() {
+        let palette = Palette::plain().with_error(Style::new().bold());
+        assert!(palette.keyword().is_plain());
+        assert!(!palette.error().is_plain());
+    }
+}
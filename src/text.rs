@@ -0,0 +1,410 @@
+/*!
+Provides the [`ObjSink`] trait, a text destination implemented for both byte destinations
+(`io::Write`) and text destinations (`fmt::Write`, including `String` and `Formatter`), so that a
+writer of text formats may be implemented once for both.
+
+A writer implementing [`TextObjectWriter`] writes strings to an [`ObjSink`] rather than bytes to
+an `io::Write`. The adapter [`TextWriter`] makes it an [`ObjectWriter`]; writing to bytes goes
+through [`IoSink`], while [`write_to_string`](ObjectWriter::write_to_string) and
+[`write_to_formatter`](ObjectWriter::write_to_formatter) write directly to the string or
+formatter, with no intermediate buffer and no UTF-8 validation, as the output is already text.
+
+# Example
+
+```rust
+use objio::text::{ObjSink, TextObjectWriter, TextWriter};
+use objio::ObjectWriter;
+
+#[derive(Debug, Default)]
+struct ListWriter;
+
+impl TextObjectWriter<Vec<u32>> for ListWriter {
+    type Error = std::io::Error;
+
+    fn write_text<S>(&self, s: &mut S, object: &Vec<u32>) -> Result<(), Self::Error>
+    where
+        S: ObjSink + ?Sized,
+    {
+        s.write_char('(')?;
+        for (i, value) in object.iter().enumerate() {
+            write!(s, "{}{value}", if i > 0 { " " } else { "" })?;
+        }
+        s.write_char(')')
+    }
+}
+
+let list = vec![1, 2, 3];
+let mut text = String::new();
+ListWriter.write_text(&mut text, &list).unwrap();
+assert_eq!(text, "(1 2 3)");
+
+let writer = TextWriter::new(ListWriter);
+assert_eq!(writer.write_to_string(&list).unwrap(), "(1 2 3)");
+assert_eq!(writer.write_to_bytes(&list).unwrap(), b"(1 2 3)");
+```
+ */
+
+use crate::ObjectWriter;
+use std::fmt::{Arguments, Formatter, Write as FmtWrite};
+use std::io::{Error as IoError, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A destination for text, implemented for text destinations and, through [`IoSink`], for byte
+/// destinations. Errors from a text destination, which carry no detail, are reported as I/O errors
+/// of kind `Other`.
+///
+pub trait ObjSink {
+    ///
+    /// Write the whole of `s` to this destination.
+    ///
+    fn write_str(&mut self, s: &str) -> Result<(), IoError>;
+
+    ///
+    /// Write the single character `c` to this destination.
+    ///
+    fn write_char(&mut self, c: char) -> Result<(), IoError> {
+        self.write_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    ///
+    /// Write formatted text to this destination, allowing the use of the `write!` macro.
+    ///
+    fn write_fmt(&mut self, args: Arguments<'_>) -> Result<(), IoError> {
+        match args.as_str() {
+            Some(s) => self.write_str(s),
+            None => {
+                let mut adapter = FmtAdapter {
+                    sink: self,
+                    error: None,
+                };
+                adapter
+                    .write_fmt(args)
+                    .map_err(|_| adapter.error.take().unwrap_or_else(fmt_error))
+            }
+        }
+    }
+}
+
+///
+/// The trait implemented by types which write instances of `T` as text to an [`ObjSink`].
+///
+pub trait TextObjectWriter<T> {
+    ///
+    /// The type indicating errors, this **must** implement the conversion from `io::Error` as for
+    /// [`ObjectWriter::Error`].
+    ///
+    type Error: From<IoError>;
+
+    ///
+    /// Write an instance of `T` to the provided text destination.
+    ///
+    fn write_text<S>(&self, s: &mut S, object: &T) -> Result<(), Self::Error>
+    where
+        S: ObjSink + ?Sized;
+
+    ///
+    /// Check that the options for this writer, if any, are consistent; the default
+    /// implementation does nothing.
+    ///
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    ///
+    /// Returns an estimate of the number of bytes that writing `object` will produce; the default
+    /// implementation returns `0`.
+    ///
+    fn write_size_hint(&self, _object: &T) -> usize {
+        0
+    }
+}
+
+///
+/// An [`ObjSink`] that writes UTF-8 bytes to an inner byte destination.
+///
+#[derive(Clone, Debug, Default)]
+pub struct IoSink<W> {
+    inner: W,
+}
+
+///
+/// An [`ObjSink`] that writes to any inner text destination implementing `fmt::Write`; `String`
+/// and `Formatter` implement [`ObjSink`] directly.
+///
+#[derive(Clone, Debug, Default)]
+pub struct FmtSink<W> {
+    inner: W,
+}
+
+///
+/// A writer that adapts a [`TextObjectWriter`] to the [`ObjectWriter`] trait, writing directly to
+/// strings and formatters.
+///
+#[derive(Clone, Debug, Default)]
+pub struct TextWriter<O> {
+    inner: O,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+struct FmtAdapter<'a, S: ?Sized> {
+    sink: &'a mut S,
+    error: Option<IoError>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl ObjSink for String {
+    fn write_str(&mut self, s: &str) -> Result<(), IoError> {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> Result<(), IoError> {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl ObjSink for Formatter<'_> {
+    fn write_str(&mut self, s: &str) -> Result<(), IoError> {
+        FmtWrite::write_str(self, s).map_err(|_| fmt_error())
+    }
+}
+
+impl<S> ObjSink for &mut S
+where
+    S: ObjSink + ?Sized,
+{
+    fn write_str(&mut self, s: &str) -> Result<(), IoError> {
+        (**self).write_str(s)
+    }
+
+    fn write_char(&mut self, c: char) -> Result<(), IoError> {
+        (**self).write_char(c)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<W> ObjSink for IoSink<W>
+where
+    W: Write,
+{
+    fn write_str(&mut self, s: &str) -> Result<(), IoError> {
+        self.inner.write_all(s.as_bytes())
+    }
+
+    fn write_fmt(&mut self, args: Arguments<'_>) -> Result<(), IoError> {
+        self.inner.write_fmt(args)
+    }
+}
+
+impl<W> IoSink<W> {
+    ///
+    /// Construct a new sink writing to `inner`.
+    ///
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    ///
+    /// Returns the inner destination.
+    ///
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<W> ObjSink for FmtSink<W>
+where
+    W: FmtWrite,
+{
+    fn write_str(&mut self, s: &str) -> Result<(), IoError> {
+        self.inner.write_str(s).map_err(|_| fmt_error())
+    }
+}
+
+impl<W> FmtSink<W> {
+    ///
+    /// Construct a new sink writing to `inner`.
+    ///
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    ///
+    /// Returns the inner destination.
+    ///
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<O, T> ObjectWriter<T> for TextWriter<O>
+where
+    O: TextObjectWriter<T>,
+{
+    type Error = O::Error;
+
+    fn write<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        self.inner.write_text(&mut IoSink::new(w), object)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.inner.validate_options()
+    }
+
+    fn write_size_hint(&self, object: &T) -> usize {
+        self.inner.write_size_hint(object)
+    }
+
+    fn write_to_string(&self, object: &T) -> Result<String, Self::Error> {
+        self.inner.validate_options()?;
+        let mut buffer = String::with_capacity(self.inner.write_size_hint(object));
+        self.inner.write_text(&mut buffer, object)?;
+        Ok(buffer)
+    }
+
+    fn write_to_formatter(&self, f: &mut Formatter<'_>, object: &T) -> std::fmt::Result {
+        self.inner
+            .validate_options()
+            .and_then(|_| self.inner.write_text(f, object))
+            .map_err(|_| std::fmt::Error)
+    }
+}
+
+impl<O> TextWriter<O> {
+    ///
+    /// Construct a new writer adapting `inner`.
+    ///
+    pub fn new(inner: O) -> Self {
+        Self { inner }
+    }
+
+    ///
+    /// Returns a reference to the adapted writer.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    ///
+    /// Returns the adapted writer.
+    ///
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<S> FmtWrite for FmtAdapter<'_, S>
+where
+    S: ObjSink + ?Sized,
+{
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.sink.write_str(s).map_err(|e| {
+            self.error = Some(e);
+            std::fmt::Error
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn fmt_error() -> IoError {
+    IoError::other("an error occurred writing to a formatter")
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Display;
+
+    #[derive(Debug)]
+    struct PairWriter;
+
+    impl TextObjectWriter<(String, u32)> for PairWriter {
+        type Error = crate::Error;
+
+        fn write_text<S>(&self, s: &mut S, object: &(String, u32)) -> Result<(), Self::Error>
+        where
+            S: ObjSink + ?Sized,
+        {
+            write!(s, "{} = {}", object.0, object.1)?;
+            Ok(s.write_char(';')?)
+        }
+    }
+
+    struct Pair((String, u32));
+
+    impl Display for Pair {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            TextWriter::new(PairWriter).write_to_formatter(f, &self.0)
+        }
+    }
+
+    #[test]
+    fn test_write_text() {
+        let pair = ("größe".to_string(), 42);
+        let writer = TextWriter::new(PairWriter);
+        assert_eq!(writer.write_to_string(&pair).unwrap(), "größe = 42;");
+        assert_eq!(
+            writer.write_to_bytes(&pair).unwrap(),
+            "größe = 42;".as_bytes()
+        );
+        assert_eq!(format!("[{:>4}]", Pair(pair)), "[größe = 42;]");
+
+        let mut sink = IoSink::new(Vec::new());
+        PairWriter
+            .write_text(&mut sink, &("a".to_string(), 1))
+            .unwrap();
+        assert_eq!(sink.into_inner(), b"a = 1;");
+
+        let mut sink = FmtSink::new(String::new());
+        PairWriter
+            .write_text(&mut sink, &("b".to_string(), 2))
+            .unwrap();
+        assert_eq!(sink.into_inner(), "b = 2;");
+    }
+
+    struct FullSink;
+
+    impl ObjSink for FullSink {
+        fn write_str(&mut self, _s: &str) -> Result<(), IoError> {
+            Err(IoError::new(std::io::ErrorKind::StorageFull, "full"))
+        }
+    }
+
+    #[test]
+    fn test_write_error() {
+        let error = write!(FullSink, "{} and more", 1234).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::StorageFull);
+
+        let mut buffer = [0u8; 4];
+        let mut sink = IoSink::new(&mut buffer[..]);
+        assert!(write!(sink, "{} and more", 1234).is_err());
+    }
+}
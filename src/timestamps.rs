@@ -0,0 +1,661 @@
+/*!
+Provides timestamp formatting options and helpers, using the `chrono` crate, so that readers and
+writers of formats with temporal values share one implementation and one configuration.
+
+The [`TimestampOptions`] type determines the textual form of a timestamp, a [`TimestampFormat`],
+and the [`TimezonePolicy`] applied to timestamps as they are written or read. By default
+timestamps are written in [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339) form, with the
+fewest fractional second digits needed, and in the offset with which they were created. A
+`strftime`-style pattern may be used instead, as described in [`chrono::format::strftime`]; when
+a timestamp is read with a pattern that has no offset it is assumed to be in the offset of the
+timezone policy, or in UTC.
+
+This module is only available with the `chrono` feature.
+
+# Example
+
+```rust
+use chrono::{FixedOffset, TimeZone};
+use objio::timestamps::{TimestampFormat, TimestampOptions, TimezonePolicy};
+
+let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+let timestamp = offset.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+
+let options = TimestampOptions::default();
+assert_eq!(options.format_timestamp(&timestamp).unwrap(), "2024-03-01T12:30:00+02:00");
+
+let options = options.with_timezone(TimezonePolicy::Utc);
+assert_eq!(options.format_timestamp(&timestamp).unwrap(), "2024-03-01T10:30:00Z");
+
+let options = options.with_format(TimestampFormat::Pattern("%d/%m/%Y %H:%M".to_string()));
+assert_eq!(options.format_timestamp(&timestamp).unwrap(), "01/03/2024 10:30");
+assert_eq!(options.parse_timestamp("01/03/2024 10:30").unwrap(), timestamp);
+```
+ */
+
+use crate::error::Error;
+use crate::options::{
+    DescribeOptions, FromOptionStrings, MergeOptions, OptionDescriptor, OptionsOverlay,
+    ParseOptionValueError, ValidateOptions,
+};
+use chrono::format::{Item, ParseErrorKind, StrftimeItems};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, SecondsFormat, TimeZone, Utc};
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The textual form in which timestamps are written and read.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TimestampFormat {
+    /// The internet date/time format of RFC 3339, such as `2024-03-01T12:30:00Z`.
+    #[default]
+    Rfc3339,
+    /// The internet message format of RFC 2822, such as `Fri, 1 Mar 2024 12:30:00 +0000`.
+    Rfc2822,
+    /// The number of whole seconds since the Unix epoch.
+    UnixSeconds,
+    /// The number of milliseconds since the Unix epoch.
+    UnixMillis,
+    /// A `strftime`-style pattern, such as `%Y-%m-%d %H:%M:%S`.
+    Pattern(String),
+}
+
+///
+/// The timezone in which timestamps are written, and to which timestamps are converted when read.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum TimezonePolicy {
+    /// Timestamps keep the offset with which they were created, or read.
+    #[default]
+    Preserve,
+    /// Timestamps are converted to UTC.
+    Utc,
+    /// Timestamps are converted to a fixed offset, in seconds east of UTC.
+    Offset(i32),
+}
+
+///
+/// The number of fractional second digits written in RFC 3339 timestamps.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum SecondsPrecision {
+    /// The fewest digits, of 0, 3, 6, or 9, that represent the timestamp exactly.
+    #[default]
+    Auto,
+    /// Whole seconds only.
+    Seconds,
+    /// Three digits.
+    Millis,
+    /// Six digits.
+    Micros,
+    /// Nine digits.
+    Nanos,
+}
+
+///
+/// Options determining how timestamps are written and read.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct TimestampOptions {
+    format: TimestampFormat,
+    timezone: TimezonePolicy,
+    precision: SecondsPrecision,
+    use_z: bool,
+}
+
+///
+/// A partial set of values to be merged into an instance of [`TimestampOptions`].
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct TimestampOptionsOverlay {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub format: Option<TimestampFormat>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub timezone: Option<TimezonePolicy>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub precision: Option<SecondsPrecision>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub use_z: Option<bool>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for TimestampFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rfc3339 => write!(f, "rfc3339"),
+            Self::Rfc2822 => write!(f, "rfc2822"),
+            Self::UnixSeconds => write!(f, "unix_seconds"),
+            Self::UnixMillis => write!(f, "unix_millis"),
+            Self::Pattern(pattern) => write!(f, "{pattern}"),
+        }
+    }
+}
+
+impl FromStr for TimestampFormat {
+    type Err = ParseOptionValueError;
+
+    ///
+    /// Parses one of the named formats or, if `s` contains a `%`, a pattern.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rfc3339" => Ok(Self::Rfc3339),
+            "rfc2822" => Ok(Self::Rfc2822),
+            "unix_seconds" => Ok(Self::UnixSeconds),
+            "unix_millis" => Ok(Self::UnixMillis),
+            _ if s.contains('%') => Ok(Self::Pattern(s.to_string())),
+            _ => Err(ParseOptionValueError::new(
+                s,
+                &[
+                    "rfc3339",
+                    "rfc2822",
+                    "unix_seconds",
+                    "unix_millis",
+                    "a strftime pattern",
+                ],
+            )),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for TimezonePolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Preserve => write!(f, "preserve"),
+            Self::Utc => write!(f, "utc"),
+            Self::Offset(seconds) => {
+                let sign = if *seconds < 0 { '-' } else { '+' };
+                let minutes = seconds.unsigned_abs() / 60;
+                write!(f, "{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl FromStr for TimezonePolicy {
+    type Err = ParseOptionValueError;
+
+    ///
+    /// Parses `preserve`, `utc`, or an offset of the form `+hh:mm` or `-hh:mm`.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseOptionValueError::new(s, &["preserve", "utc", "+hh:mm", "-hh:mm"]);
+        match s.to_ascii_lowercase().as_str() {
+            "preserve" => Ok(Self::Preserve),
+            "utc" => Ok(Self::Utc),
+            _ => {
+                let (sign, rest) = match s.as_bytes().first() {
+                    Some(b'+') => (1, &s[1..]),
+                    Some(b'-') => (-1, &s[1..]),
+                    _ => return Err(error()),
+                };
+                let (hours, minutes) = rest.split_once(':').ok_or_else(error)?;
+                if hours.len() != 2 || minutes.len() != 2 {
+                    return Err(error());
+                }
+                match (hours.parse::<i32>(), minutes.parse::<i32>()) {
+                    (Ok(hours), Ok(minutes)) if minutes < 60 => {
+                        Ok(Self::Offset(sign * (hours * 3600 + minutes * 60)))
+                    }
+                    _ => Err(error()),
+                }
+            }
+        }
+    }
+}
+
+impl TimezonePolicy {
+    ///
+    /// Returns the offset timestamps are converted to, or `None` if they are preserved.
+    ///
+    pub fn offset(&self) -> Result<Option<FixedOffset>, Error> {
+        match self {
+            Self::Preserve => Ok(None),
+            Self::Utc => Ok(Some(Utc.fix())),
+            Self::Offset(seconds) => FixedOffset::east_opt(*seconds).map(Some).ok_or_else(|| {
+                Error::invalid_options(format!("timezone offset ({self}) is out of range"))
+            }),
+        }
+    }
+
+    ///
+    /// Returns `timestamp` converted according to this policy.
+    ///
+    pub fn apply<Tz>(&self, timestamp: &DateTime<Tz>) -> Result<DateTime<FixedOffset>, Error>
+    where
+        Tz: TimeZone,
+    {
+        Ok(match self.offset()? {
+            Some(offset) => timestamp.with_timezone(&offset),
+            None => timestamp.fixed_offset(),
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for SecondsPrecision {
+    type Err = ParseOptionValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "seconds" => Ok(Self::Seconds),
+            "millis" => Ok(Self::Millis),
+            "micros" => Ok(Self::Micros),
+            "nanos" => Ok(Self::Nanos),
+            _ => Err(ParseOptionValueError::new(
+                s,
+                &["auto", "seconds", "millis", "micros", "nanos"],
+            )),
+        }
+    }
+}
+
+impl From<SecondsPrecision> for SecondsFormat {
+    fn from(value: SecondsPrecision) -> Self {
+        match value {
+            SecondsPrecision::Auto => Self::AutoSi,
+            SecondsPrecision::Seconds => Self::Secs,
+            SecondsPrecision::Millis => Self::Millis,
+            SecondsPrecision::Micros => Self::Micros,
+            SecondsPrecision::Nanos => Self::Nanos,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Default for TimestampOptions {
+    fn default() -> Self {
+        Self {
+            format: Default::default(),
+            timezone: Default::default(),
+            precision: Default::default(),
+            use_z: true,
+        }
+    }
+}
+
+impl MergeOptions for TimestampOptions {
+    type Overlay = TimestampOptionsOverlay;
+
+    fn merge(&mut self, overlay: Self::Overlay) {
+        if let Some(format) = overlay.format {
+            self.format = format;
+        }
+        if let Some(timezone) = overlay.timezone {
+            self.timezone = timezone;
+        }
+        if let Some(precision) = overlay.precision {
+            self.precision = precision;
+        }
+        if let Some(use_z) = overlay.use_z {
+            self.use_z = use_z;
+        }
+    }
+}
+
+impl OptionsOverlay for TimestampOptionsOverlay {
+    fn overlay(self, other: Self) -> Self {
+        Self {
+            format: other.format.or(self.format),
+            timezone: other.timezone.or(self.timezone),
+            precision: other.precision.or(self.precision),
+            use_z: other.use_z.or(self.use_z),
+        }
+    }
+}
+
+impl ValidateOptions for TimestampOptions {
+    fn validate(&self) -> Result<(), Error> {
+        if let TimestampFormat::Pattern(pattern) = &self.format {
+            if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+                return Err(Error::invalid_options(format!(
+                    "timestamp pattern `{pattern}` is not valid"
+                )));
+            }
+        }
+        self.timezone.offset().map(|_| ())
+    }
+}
+
+impl DescribeOptions for TimestampOptions {
+    fn describe_options() -> Vec<OptionDescriptor> {
+        let defaults = Self::default();
+        vec![
+            OptionDescriptor::new(
+                "format",
+                "TimestampFormat",
+                defaults.format.to_string(),
+                "The form of timestamps; a named format, or a strftime pattern.",
+            ),
+            OptionDescriptor::new(
+                "timezone",
+                "TimezonePolicy",
+                defaults.timezone.to_string(),
+                "The timezone timestamps are converted to; `preserve`, `utc`, or an offset.",
+            ),
+            OptionDescriptor::new(
+                "precision",
+                "SecondsPrecision",
+                format!("{:?}", defaults.precision),
+                "The number of fractional second digits written in RFC 3339 timestamps.",
+            ),
+            OptionDescriptor::new(
+                "use_z",
+                "bool",
+                defaults.use_z.to_string(),
+                "Whether RFC 3339 timestamps in UTC are written with `Z` rather than `+00:00`.",
+            ),
+        ]
+    }
+}
+
+impl FromOptionStrings for TimestampOptions {
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        fn parse<T>(key: &str, value: &str) -> Result<T, Error>
+        where
+            T: FromStr,
+            T::Err: Into<crate::BoxedError>,
+        {
+            value
+                .parse()
+                .map_err(|e| Error::invalid_option_value(key, value, e))
+        }
+        match key.replace('-', "_").as_str() {
+            "format" => self.format = parse(key, value)?,
+            "timezone" => self.timezone = parse(key, value)?,
+            "precision" => self.precision = parse(key, value)?,
+            "use_z" => self.use_z = parse(key, value)?,
+            _ => return Err(Error::unknown_option(key)),
+        }
+        Ok(())
+    }
+}
+
+impl TimestampOptions {
+    ///
+    /// Returns a copy of these options with the timestamp format set to `format`.
+    ///
+    pub fn with_format(self, format: TimestampFormat) -> Self {
+        let mut self_mut = self;
+        self_mut.format = format;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the timezone policy set to `timezone`.
+    ///
+    pub fn with_timezone(self, timezone: TimezonePolicy) -> Self {
+        let mut self_mut = self;
+        self_mut.timezone = timezone;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the fractional second precision set to `precision`.
+    ///
+    pub fn with_precision(self, precision: SecondsPrecision) -> Self {
+        let mut self_mut = self;
+        self_mut.precision = precision;
+        self_mut
+    }
+
+    ///
+    /// Returns a copy of these options with the use of `Z` for UTC set to `use_z`.
+    ///
+    pub fn with_use_z(self, use_z: bool) -> Self {
+        let mut self_mut = self;
+        self_mut.use_z = use_z;
+        self_mut
+    }
+
+    ///
+    /// Returns the textual form of timestamps.
+    ///
+    pub fn format(&self) -> &TimestampFormat {
+        &self.format
+    }
+
+    ///
+    /// Set the textual form of timestamps.
+    ///
+    pub fn set_format(&mut self, format: TimestampFormat) {
+        self.format = format;
+    }
+
+    ///
+    /// Returns the timezone policy applied to timestamps.
+    ///
+    pub fn timezone(&self) -> TimezonePolicy {
+        self.timezone
+    }
+
+    ///
+    /// Set the timezone policy applied to timestamps.
+    ///
+    pub fn set_timezone(&mut self, timezone: TimezonePolicy) {
+        self.timezone = timezone;
+    }
+
+    ///
+    /// Returns the number of fractional second digits written in RFC 3339 timestamps.
+    ///
+    pub fn precision(&self) -> SecondsPrecision {
+        self.precision
+    }
+
+    ///
+    /// Set the number of fractional second digits written in RFC 3339 timestamps.
+    ///
+    pub fn set_precision(&mut self, precision: SecondsPrecision) {
+        self.precision = precision;
+    }
+
+    ///
+    /// Returns `true` if RFC 3339 timestamps in UTC are written with `Z` rather than `+00:00`.
+    ///
+    pub fn use_z(&self) -> bool {
+        self.use_z
+    }
+
+    ///
+    /// Set whether RFC 3339 timestamps in UTC are written with `Z` rather than `+00:00`.
+    ///
+    pub fn set_use_z(&mut self, use_z: bool) {
+        self.use_z = use_z;
+    }
+
+    ///
+    /// Format `timestamp`, converted according to the timezone policy, in the textual form of
+    /// these options.
+    ///
+    pub fn format_timestamp<Tz>(&self, timestamp: &DateTime<Tz>) -> Result<String, Error>
+    where
+        Tz: TimeZone,
+    {
+        let timestamp = self.timezone.apply(timestamp)?;
+        Ok(match &self.format {
+            TimestampFormat::Rfc3339 => {
+                timestamp.to_rfc3339_opts(self.precision.into(), self.use_z)
+            }
+            TimestampFormat::Rfc2822 => timestamp.to_rfc2822(),
+            TimestampFormat::UnixSeconds => timestamp.timestamp().to_string(),
+            TimestampFormat::UnixMillis => timestamp.timestamp_millis().to_string(),
+            TimestampFormat::Pattern(pattern) => {
+                let mut formatted = String::new();
+                write!(formatted, "{}", timestamp.format(pattern)).map_err(|_| {
+                    Error::invalid_options(format!("timestamp pattern `{pattern}` is not valid"))
+                })?;
+                formatted
+            }
+        })
+    }
+
+    ///
+    /// Parse `s`, in the textual form of these options, as a timestamp converted according to the
+    /// timezone policy.
+    ///
+    pub fn parse_timestamp(&self, s: &str) -> Result<DateTime<FixedOffset>, Error> {
+        let timestamp = match &self.format {
+            TimestampFormat::Rfc3339 => DateTime::parse_from_rfc3339(s).map_err(parse_error)?,
+            TimestampFormat::Rfc2822 => DateTime::parse_from_rfc2822(s).map_err(parse_error)?,
+            TimestampFormat::UnixSeconds => {
+                let seconds = s.trim().parse().map_err(parse_error)?;
+                DateTime::from_timestamp(seconds, 0)
+                    .ok_or(OUT_OF_RANGE)?
+                    .fixed_offset()
+            }
+            TimestampFormat::UnixMillis => {
+                let millis = s.trim().parse().map_err(parse_error)?;
+                DateTime::from_timestamp_millis(millis)
+                    .ok_or(OUT_OF_RANGE)?
+                    .fixed_offset()
+            }
+            TimestampFormat::Pattern(pattern) => match DateTime::parse_from_str(s, pattern) {
+                Ok(timestamp) => timestamp,
+                Err(e) if e.kind() == ParseErrorKind::NotEnough => {
+                    let naive = NaiveDateTime::parse_from_str(s, pattern).map_err(parse_error)?;
+                    let offset = self.timezone.offset()?.unwrap_or(Utc.fix());
+                    offset
+                        .from_local_datetime(&naive)
+                        .single()
+                        .ok_or(OUT_OF_RANGE)?
+                }
+                Err(e) => return Err(parse_error(e)),
+            },
+        };
+        self.timezone.apply(&timestamp)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+const FORMAT_NAME: &str = "timestamp";
+
+const OUT_OF_RANGE: Error = Error::parse(FORMAT_NAME, "timestamp is out of range");
+
+fn parse_error<E>(e: E) -> Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Error::deserialization(FORMAT_NAME, e)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp() -> DateTime<FixedOffset> {
+        FixedOffset::west_opt(5 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 12, 31, 22, 15, 30)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_format_and_parse_round_trip() {
+        let cases = [
+            (TimestampFormat::Rfc3339, "2024-12-31T22:15:30-05:00"),
+            (TimestampFormat::Rfc2822, "Tue, 31 Dec 2024 22:15:30 -0500"),
+            (TimestampFormat::UnixSeconds, "1735701330"),
+            (TimestampFormat::UnixMillis, "1735701330000"),
+            (
+                TimestampFormat::Pattern("%Y%m%d %H%M%S %z".to_string()),
+                "20241231 221530 -0500",
+            ),
+        ];
+        for (format, expected) in cases {
+            let options = TimestampOptions::default().with_format(format);
+            assert_eq!(options.format_timestamp(&timestamp()).unwrap(), expected);
+            let parsed = options.parse_timestamp(expected).unwrap();
+            assert_eq!(parsed, timestamp());
+        }
+    }
+
+    #[test]
+    fn test_timezone_policy() {
+        let options = TimestampOptions::default()
+            .with_timezone("+01:30".parse().unwrap())
+            .with_precision(SecondsPrecision::Millis);
+        assert_eq!(options.timezone(), TimezonePolicy::Offset(5400));
+        assert_eq!(options.timezone().to_string(), "+01:30");
+        assert_eq!(
+            options.format_timestamp(&timestamp()).unwrap(),
+            "2025-01-01T04:45:30.000+01:30"
+        );
+
+        let options = options.with_format(TimestampFormat::Pattern("%F %T".to_string()));
+        let parsed = options.parse_timestamp("2025-01-01 04:45:30").unwrap();
+        assert_eq!(parsed, timestamp());
+        assert_eq!(parsed.offset().local_minus_utc(), 5400);
+
+        assert!("+1:30:00".parse::<TimezonePolicy>().is_err());
+        assert!(TimestampOptions::default()
+            .with_timezone(TimezonePolicy::Offset(86_400))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_option_strings() {
+        let options =
+            TimestampOptions::from_option_strings("format=%d/%m/%Y,timezone=utc,use-z=false")
+                .unwrap();
+        assert_eq!(
+            options.format(),
+            &TimestampFormat::Pattern("%d/%m/%Y".to_string())
+        );
+        assert!(!options.use_z());
+        assert!(options.validate().is_ok());
+        assert!(options
+            .parse_timestamp("31-12-2024")
+            .is_err_and(|e| matches!(e, Error::Deserialization { .. })));
+
+        let invalid = options.with_format(TimestampFormat::Pattern("%Q".to_string()));
+        assert!(invalid.validate().is_err());
+        assert!(invalid.format_timestamp(&timestamp()).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+/*!
+Provides detection of trailing data, non-whitespace content that remains in a source after an
+object has been read from it.
+
+The function [`check_trailing_data`] may be called by a reader implementation once it has read an
+object, and the adapter [`TrailingDataReader`] adds the same check to any [`ObjectReader`]. In
+both cases the check is controlled by an [`UnknownHandling`] value, commonly the
+[`trailing_data`](CommonReaderOptions::trailing_data) reader option. Note that the check can only
+see content that has not been consumed by the reader; a reader that buffers its source, or reads
+it to the end, will hide any trailing data from the check.
+
+# Example
+
+```rust
+use objio::options::{CommonReaderOptions, UnknownHandling};
+use objio::trailing::TrailingDataReader;
+use objio::{HasOptions, ObjectReader};
+use std::io::Read;
+
+#[derive(Debug, Default)]
+struct WordReader;
+
+impl ObjectReader<String> for WordReader {
+    type Error = objio::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+        let mut word = String::new();
+        for byte in r.bytes() {
+            match byte? {
+                b' ' => break,
+                b => word.push(b as char),
+            }
+        }
+        Ok(word)
+    }
+}
+
+let reader = TrailingDataReader::new(WordReader, "word");
+assert_eq!(reader.read_from_string("hello  ").unwrap(), "hello");
+assert!(reader.read_from_string("hello world").is_err());
+
+let reader = reader.with_options(
+    CommonReaderOptions::default().with_trailing_data(UnknownHandling::Ignore),
+);
+assert_eq!(reader.read_from_string("hello world").unwrap(), "hello");
+```
+ */
+
+use crate::diagnostics::{DiagnosticSink, IgnoreDiagnostics};
+use crate::error::Error;
+use crate::options::{CommonReaderOptions, UnknownHandling};
+use crate::{HasOptions, ObjectReader};
+use std::fmt::{Debug, Formatter};
+use std::io::{BufRead, BufReader, Read};
+use std::sync::Arc;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A reader that checks for trailing data after an object has been read by an inner reader.
+///
+pub struct TrailingDataReader<O> {
+    inner: O,
+    format: &'static str,
+    options: CommonReaderOptions,
+    sink: Option<Arc<dyn DiagnosticSink + Send + Sync>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Check the remaining content of `r`, after an object in the named format has been read, for any
+/// non-whitespace bytes, which are handled according to `handling`. Trailing data results in a
+/// deserialization error for [`UnknownHandling::Error`] and in a warning reported to `sink` for
+/// [`UnknownHandling::Warn`]; for [`UnknownHandling::Ignore`] the source is not read.
+///
+pub fn check_trailing_data<R>(
+    r: &mut R,
+    handling: UnknownHandling,
+    format: &'static str,
+    sink: &dyn DiagnosticSink,
+) -> Result<(), Error>
+where
+    R: Read,
+{
+    if handling == UnknownHandling::Ignore {
+        return Ok(());
+    }
+    // The source may be unbounded, so it is read only as far as the first non-whitespace byte.
+    let mut r = BufReader::new(r);
+    loop {
+        let buffer = r.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(());
+        } else if buffer.iter().any(|b| !b.is_ascii_whitespace()) {
+            return handling.handle(format, "trailing data follows the object", sink);
+        }
+        let length = buffer.len();
+        r.consume(length);
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<O> Debug for TrailingDataReader<O>
+where
+    O: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrailingDataReader")
+            .field("inner", &self.inner)
+            .field("format", &self.format)
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O> HasOptions<CommonReaderOptions> for TrailingDataReader<O> {
+    fn options(&self) -> &CommonReaderOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonReaderOptions {
+        &mut self.options
+    }
+}
+
+impl<O, T> ObjectReader<T> for TrailingDataReader<O>
+where
+    O: ObjectReader<T>,
+    O::Error: From<Error>,
+{
+    type Error = O::Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        let object = self.inner.read(r)?;
+        self.check(r)?;
+        Ok(object)
+    }
+
+    fn read_buffered<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: BufRead,
+    {
+        let object = self.inner.read_buffered(r)?;
+        self.check(r)?;
+        Ok(object)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.inner.validate_options()
+    }
+}
+
+impl<O> TrailingDataReader<O> {
+    ///
+    /// Construct a new reader, with default options, that checks for trailing data after each
+    /// object in the named format is read by `inner`.
+    ///
+    pub fn new(inner: O, format: &'static str) -> Self {
+        Self {
+            inner,
+            format,
+            options: Default::default(),
+            sink: None,
+        }
+    }
+
+    ///
+    /// Returns this reader with warnings about trailing data reported to `sink`; by default they
+    /// are discarded.
+    ///
+    pub fn with_diagnostics(self, sink: Arc<dyn DiagnosticSink + Send + Sync>) -> Self {
+        let mut self_mut = self;
+        self_mut.sink = Some(sink);
+        self_mut
+    }
+
+    ///
+    /// Returns a reference to the inner reader.
+    ///
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    fn check<R>(&self, r: &mut R) -> Result<(), Error>
+    where
+        R: Read,
+    {
+        let sink: &dyn DiagnosticSink = match &self.sink {
+            Some(sink) => sink.as_ref(),
+            None => &IgnoreDiagnostics,
+        };
+        check_trailing_data(r, self.options.trailing_data(), self.format, sink)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::{CollectDiagnostics, Diagnostic};
+
+    #[derive(Debug, Default)]
+    struct ByteReader;
+
+    impl ObjectReader<u8> for ByteReader {
+        type Error = Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<u8, Self::Error> {
+            let mut byte = [0];
+            r.read_exact(&mut byte)?;
+            Ok(byte[0])
+        }
+    }
+
+    #[test]
+    fn test_whitespace_is_not_trailing_data() {
+        let reader = TrailingDataReader::new(ByteReader, "byte");
+        assert_eq!(reader.read_from_bytes(b"a \r\n\t").unwrap(), b'a');
+    }
+
+    #[test]
+    fn test_trailing_data_error() {
+        let reader = TrailingDataReader::new(ByteReader, "byte");
+        match reader.read_from_bytes(b"ab c") {
+            Err(Error::Deserialization { format, source }) => {
+                assert_eq!(format, "byte");
+                assert_eq!(source.to_string(), "trailing data follows the object");
+            }
+            result => panic!("expected a deserialization error, not {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unbounded_trailing_data() {
+        let mut source = std::io::repeat(b' ')
+            .take(64 * 1024)
+            .chain(std::io::repeat(b'x'));
+        assert!(check_trailing_data(
+            &mut source,
+            UnknownHandling::Error,
+            "byte",
+            &IgnoreDiagnostics
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_trailing_data_warning() {
+        let sink = Arc::new(CollectDiagnostics::default());
+        let reader = TrailingDataReader::new(ByteReader, "byte")
+            .with_options(CommonReaderOptions::default().with_trailing_data(UnknownHandling::Warn))
+            .with_diagnostics(sink.clone());
+        assert_eq!(reader.read_from_bytes(b"ab").unwrap(), b'a');
+        assert_eq!(
+            sink.diagnostics(),
+            vec![Diagnostic::warning("trailing data follows the object")]
+        );
+    }
+}
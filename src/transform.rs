@@ -0,0 +1,366 @@
+/*!
+Provides the [`Transform`] trait, describing a conversion between object types, and adapters that
+compose a transform with a reader or writer.
+
+A [`TransformReader`] combines an `ObjectReader<T>` with a `Transform<T, U>` to produce an
+`ObjectReader<U>`; a [`TransformWriter`] combines an `ObjectWriter<U>` with a
+`Transform<&T, U>` to produce an `ObjectWriter<T>`. Any closure `Fn(T) -> U` is an infallible
+transform, and [`TryTransform`] wraps a closure returning a `Result`. Errors from a transform are
+reported by the adapters as `InvalidData` I/O errors, converted to the error type of the reader or
+writer.
+
+# Example
+
+```rust
+use objio::transform::{TransformReader, TransformWriter, TryTransform};
+use objio::{ObjectReader, ObjectWriter};
+use std::io::{Read, Write};
+
+#[derive(Debug, Default)]
+struct TextIo;
+
+impl ObjectReader<String> for TextIo {
+    type Error = objio::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+        let mut s = String::new();
+        r.read_to_string(&mut s)?;
+        Ok(s)
+    }
+}
+
+impl ObjectWriter<String> for TextIo {
+    type Error = objio::Error;
+
+    fn write<W: Write>(&self, w: &mut W, object: &String) -> Result<(), Self::Error> {
+        Ok(w.write_all(object.as_bytes())?)
+    }
+}
+
+let reader = TransformReader::new(TextIo, TryTransform::new(|s: String| s.trim().parse::<u32>()));
+assert_eq!(reader.read_from_string(" 42 ").unwrap(), 42);
+assert!(reader.read_from_string("forty-two").is_err());
+
+let writer = TransformWriter::new(TextIo, |n: &u32| format!("<{n}>"));
+assert_eq!(writer.write_to_string(&42).unwrap(), "<42>");
+```
+ */
+
+use crate::error::BoxedError;
+use crate::options::Buffering;
+use crate::{ObjectReader, ObjectWriter};
+use std::convert::Infallible;
+use std::fmt::{Debug, Formatter};
+use std::io::{BufRead, Error as IoError, ErrorKind, Read, Write};
+use std::marker::PhantomData;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by conversions from objects of type `T` to objects of type `U`.
+///
+pub trait Transform<T, U> {
+    type Error: Into<BoxedError>;
+
+    ///
+    /// Convert `input` into an instance of `U`.
+    ///
+    fn transform(&self, input: T) -> Result<U, Self::Error>;
+
+    ///
+    /// Returns a transform that applies this transform and then `next`.
+    ///
+    fn and_then<V, N>(self, next: N) -> Chain<Self, N, U>
+    where
+        Self: Sized,
+        N: Transform<U, V>,
+    {
+        Chain {
+            first: self,
+            second: next,
+            intermediate: PhantomData,
+        }
+    }
+}
+
+///
+/// A transform wrapping a closure that returns a `Result`.
+///
+#[derive(Clone, Copy)]
+pub struct TryTransform<F> {
+    f: F,
+}
+
+///
+/// A transform that applies two transforms in sequence, returned by [`Transform::and_then`].
+///
+#[derive(Clone, Copy)]
+pub struct Chain<A, B, M> {
+    first: A,
+    second: B,
+    intermediate: PhantomData<fn() -> M>,
+}
+
+///
+/// A reader of objects of type `U`, read as objects of type `T` by an inner reader and then
+/// transformed.
+///
+pub struct TransformReader<R, F, T> {
+    inner: R,
+    transform: F,
+    object: PhantomData<fn() -> T>,
+}
+
+///
+/// A writer of objects of type `T`, transformed into objects of type `U` which are written by an
+/// inner writer.
+///
+pub struct TransformWriter<W, F, U> {
+    inner: W,
+    transform: F,
+    object: PhantomData<fn() -> U>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<T, U, F> Transform<T, U> for F
+where
+    F: Fn(T) -> U,
+{
+    type Error = Infallible;
+
+    fn transform(&self, input: T) -> Result<U, Self::Error> {
+        Ok(self(input))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<F> Debug for TryTransform<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TryTransform").finish_non_exhaustive()
+    }
+}
+
+impl<T, U, E, F> Transform<T, U> for TryTransform<F>
+where
+    E: Into<BoxedError>,
+    F: Fn(T) -> Result<U, E>,
+{
+    type Error = E;
+
+    fn transform(&self, input: T) -> Result<U, Self::Error> {
+        (self.f)(input)
+    }
+}
+
+impl<F> TryTransform<F> {
+    ///
+    /// Construct a new transform from the fallible closure `f`.
+    ///
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<A, B, M> Debug for Chain<A, B, M>
+where
+    A: Debug,
+    B: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Chain")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .finish()
+    }
+}
+
+impl<T, U, M, A, B> Transform<T, U> for Chain<A, B, M>
+where
+    A: Transform<T, M>,
+    B: Transform<M, U>,
+{
+    type Error = BoxedError;
+
+    fn transform(&self, input: T) -> Result<U, Self::Error> {
+        let intermediate = self.first.transform(input).map_err(Into::into)?;
+        self.second.transform(intermediate).map_err(Into::into)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<R, F, T> Debug for TransformReader<R, F, T>
+where
+    R: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransformReader")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R, F, T, U> ObjectReader<U> for TransformReader<R, F, T>
+where
+    R: ObjectReader<T>,
+    F: Transform<T, U>,
+{
+    type Error = R::Error;
+
+    fn read<Rd>(&self, r: &mut Rd) -> Result<U, Self::Error>
+    where
+        Rd: Read,
+    {
+        let object = self.inner.read(r)?;
+        Ok(self.transform.transform(object).map_err(transform_error)?)
+    }
+
+    fn read_buffered<Rd>(&self, r: &mut Rd) -> Result<U, Self::Error>
+    where
+        Rd: BufRead,
+    {
+        let object = self.inner.read_buffered(r)?;
+        Ok(self.transform.transform(object).map_err(transform_error)?)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.inner.validate_options()
+    }
+}
+
+impl<R, F, T> TransformReader<R, F, T> {
+    ///
+    /// Construct a new reader that applies `transform` to each object read by `inner`.
+    ///
+    pub fn new(inner: R, transform: F) -> Self {
+        Self {
+            inner,
+            transform,
+            object: PhantomData,
+        }
+    }
+
+    ///
+    /// Returns a reference to the inner reader.
+    ///
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<W, F, U> Debug for TransformWriter<W, F, U>
+where
+    W: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransformWriter")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W, F, T, U> ObjectWriter<T> for TransformWriter<W, F, U>
+where
+    W: ObjectWriter<U>,
+    F: for<'a> Transform<&'a T, U>,
+{
+    type Error = W::Error;
+
+    fn write<Wr>(&self, w: &mut Wr, object: &T) -> Result<(), Self::Error>
+    where
+        Wr: Write,
+    {
+        let object = self.transform.transform(object).map_err(transform_error)?;
+        self.inner.write(w, &object)
+    }
+
+    fn validate_options(&self) -> Result<(), Self::Error> {
+        self.inner.validate_options()
+    }
+
+    fn buffering(&self) -> Buffering {
+        self.inner.buffering()
+    }
+}
+
+impl<W, F, U> TransformWriter<W, F, U> {
+    ///
+    /// Construct a new writer that applies `transform` to each object before it is written by
+    /// `inner`.
+    ///
+    pub fn new(inner: W, transform: F) -> Self {
+        Self {
+            inner,
+            transform,
+            object: PhantomData,
+        }
+    }
+
+    ///
+    /// Returns a reference to the inner writer.
+    ///
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn transform_error<E>(e: E) -> IoError
+where
+    E: Into<BoxedError>,
+{
+    IoError::new(ErrorKind::InvalidData, e.into())
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[derive(Debug, Default)]
+    struct BytesIo;
+
+    impl ObjectReader<Vec<u8>> for BytesIo {
+        type Error = Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<Vec<u8>, Self::Error> {
+            let mut buffer = Vec::new();
+            r.read_to_end(&mut buffer)?;
+            Ok(buffer)
+        }
+    }
+
+    #[test]
+    fn test_chain() {
+        let transform = TryTransform::new(String::from_utf8).and_then(|s: String| s.len());
+        assert_eq!(transform.transform(b"four".to_vec()).unwrap(), 4);
+        assert!(transform.transform(vec![0xFF]).is_err());
+    }
+
+    #[test]
+    fn test_reader_error_kind() {
+        let reader = TransformReader::new(BytesIo, TryTransform::new(String::from_utf8));
+        assert_eq!(reader.read_from_bytes(b"ok").unwrap(), "ok");
+        match reader.read_from_bytes([0xFF]) {
+            Err(Error::Io(e)) => assert_eq!(e.kind(), ErrorKind::InvalidData),
+            result => panic!("expected an I/O error, not {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,381 @@
+/*!
+Provides a side-channel for trivia, the comments and formatting of a document that are not part
+of the object it represents, so that tools may edit a document without discarding them.
+
+A reader implementing [`ObjectTriviaReader`] returns, alongside the object, the [`Trivia`] it
+captured, as selected by the
+[`preserve_comments`](crate::options::CommonReaderOptions::preserve_comments) and
+[`preserve_formatting`](crate::options::CommonReaderOptions::preserve_formatting) options. A
+writer implementing [`ObjectTriviaWriter`] accepts trivia and re-emits each item next to the
+element it is anchored to. Both traits have default implementations that ignore trivia, so any
+reader or writer may implement them with an empty body.
+
+Each [`TriviaItem`] is anchored to an element by a path in the syntax of a JSON Pointer
+([RFC 6901](https://www.rfc-editor.org/rfc/rfc6901)), where the empty path is the document
+itself, and is placed before, after, or inside that element.
+
+# Example
+
+```rust
+use objio::options::CommonReaderOptions;
+use objio::trivia::{ObjectTriviaReader, Trivia, TriviaItem, TriviaPlacement};
+use objio::ObjectReader;
+use std::io::Read;
+
+#[derive(Debug, Default)]
+struct LinesReader {
+    options: CommonReaderOptions,
+}
+
+impl ObjectReader<Vec<String>> for LinesReader {
+    type Error = std::io::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<Vec<String>, Self::Error> {
+        self.read_with_trivia(r).map(|(lines, _)| lines)
+    }
+}
+
+impl ObjectTriviaReader<Vec<String>> for LinesReader {
+    fn read_with_trivia<R: Read>(&self, r: &mut R) -> Result<(Vec<String>, Trivia), Self::Error> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        let (mut lines, mut trivia) = (Vec::new(), Trivia::default());
+        for line in text.lines() {
+            match line.strip_prefix('#') {
+                Some(comment) => trivia.push(TriviaItem::comment(
+                    format!("/{}", lines.len()),
+                    TriviaPlacement::Before,
+                    comment,
+                )),
+                None => lines.push(line.to_string()),
+            }
+        }
+        Ok((lines, trivia.retained(&self.options)))
+    }
+}
+
+let reader = LinesReader {
+    options: CommonReaderOptions::default().with_preserve_comments(true),
+};
+let (lines, trivia) = reader.read_with_trivia(&mut "one\n# the second\ntwo".as_bytes()).unwrap();
+assert_eq!(lines, ["one", "two"]);
+assert_eq!(trivia.anchored_to("/1").next().unwrap().text(), " the second");
+```
+ */
+
+use crate::options::CommonReaderOptions;
+use crate::{ObjectReader, ObjectWriter};
+use std::io::{Read, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by readers that can capture trivia while reading.
+///
+pub trait ObjectTriviaReader<T>: ObjectReader<T> {
+    ///
+    /// Read an instance of `T`, and any trivia captured, from the provided implementation of
+    /// `Read`. The default implementation captures no trivia.
+    ///
+    fn read_with_trivia<R>(&self, r: &mut R) -> Result<(T, Trivia), Self::Error>
+    where
+        R: Read,
+    {
+        Ok((self.read(r)?, Trivia::default()))
+    }
+}
+
+///
+/// The trait implemented by writers that can re-emit trivia while writing.
+///
+pub trait ObjectTriviaWriter<T>: ObjectWriter<T> {
+    ///
+    /// Write an instance of `T`, and `trivia`, to the provided implementation of `Write`. Trivia
+    /// anchored to elements that are not written is discarded. The default implementation
+    /// discards all trivia.
+    ///
+    fn write_with_trivia<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        trivia: &Trivia,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        let _ = trivia;
+        self.write(w, object)
+    }
+}
+
+///
+/// The kind of a [`TriviaItem`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TriviaKind {
+    /// A comment; the text excludes the comment delimiters.
+    Comment,
+    /// Whitespace, or other formatting, such as blank lines or alignment.
+    Formatting,
+}
+
+///
+/// Where a [`TriviaItem`] appears relative to the element it is anchored to.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TriviaPlacement {
+    /// Before the element, such as a comment on the preceding line.
+    Before,
+    /// After the element, such as a comment at the end of the same line.
+    After,
+    /// Inside the element, after its last child, such as a comment before a closing bracket.
+    Inside,
+}
+
+///
+/// A single comment, or piece of formatting, anchored to an element of the document.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TriviaItem {
+    kind: TriviaKind,
+    anchor: String,
+    placement: TriviaPlacement,
+    text: String,
+}
+
+///
+/// The trivia captured from a document, in document order.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trivia {
+    items: Vec<TriviaItem>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl TriviaItem {
+    ///
+    /// Construct a new item of `kind`, placed relative to the element at the path `anchor`.
+    ///
+    pub fn new<A, S>(kind: TriviaKind, anchor: A, placement: TriviaPlacement, text: S) -> Self
+    where
+        A: Into<String>,
+        S: Into<String>,
+    {
+        Self {
+            kind,
+            anchor: anchor.into(),
+            placement,
+            text: text.into(),
+        }
+    }
+
+    ///
+    /// Construct a new comment placed relative to the element at the path `anchor`.
+    ///
+    pub fn comment<A, S>(anchor: A, placement: TriviaPlacement, text: S) -> Self
+    where
+        A: Into<String>,
+        S: Into<String>,
+    {
+        Self::new(TriviaKind::Comment, anchor, placement, text)
+    }
+
+    ///
+    /// Construct a new piece of formatting placed relative to the element at the path `anchor`.
+    ///
+    pub fn formatting<A, S>(anchor: A, placement: TriviaPlacement, text: S) -> Self
+    where
+        A: Into<String>,
+        S: Into<String>,
+    {
+        Self::new(TriviaKind::Formatting, anchor, placement, text)
+    }
+
+    ///
+    /// Returns the kind of this item.
+    ///
+    pub fn kind(&self) -> TriviaKind {
+        self.kind
+    }
+
+    ///
+    /// Returns the path of the element this item is anchored to.
+    ///
+    pub fn anchor(&self) -> &str {
+        &self.anchor
+    }
+
+    ///
+    /// Returns where this item appears relative to the element it is anchored to.
+    ///
+    pub fn placement(&self) -> TriviaPlacement {
+        self.placement
+    }
+
+    ///
+    /// Returns the text of this item.
+    ///
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl FromIterator<TriviaItem> for Trivia {
+    fn from_iter<I: IntoIterator<Item = TriviaItem>>(iter: I) -> Self {
+        Self {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for Trivia {
+    type Item = TriviaItem;
+    type IntoIter = std::vec::IntoIter<TriviaItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl Trivia {
+    ///
+    /// Add `item` after all those already captured.
+    ///
+    pub fn push(&mut self, item: TriviaItem) {
+        self.items.push(item);
+    }
+
+    ///
+    /// Returns `true` if no trivia was captured.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    ///
+    /// Returns the number of items captured.
+    ///
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    ///
+    /// Returns an iterator over all items, in document order.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &TriviaItem> {
+        self.items.iter()
+    }
+
+    ///
+    /// Returns an iterator over the items anchored to the element at the path `anchor`.
+    ///
+    pub fn anchored_to<'a>(&'a self, anchor: &'a str) -> impl Iterator<Item = &'a TriviaItem> {
+        self.items.iter().filter(move |item| item.anchor == anchor)
+    }
+
+    ///
+    /// Returns the items anchored to the element at `anchor` with `placement`, in document order;
+    /// this is the form most writers need when re-emitting trivia.
+    ///
+    pub fn placed<'a>(&'a self, anchor: &str, placement: TriviaPlacement) -> Vec<&'a TriviaItem> {
+        self.items
+            .iter()
+            .filter(|item| item.anchor == anchor && item.placement == placement)
+            .collect()
+    }
+
+    ///
+    /// Returns this trivia keeping only the kinds that `options` ask a reader to preserve.
+    ///
+    pub fn retained(self, options: &CommonReaderOptions) -> Self {
+        let mut self_mut = self;
+        self_mut.items.retain(|item| match item.kind {
+            TriviaKind::Comment => options.preserve_comments(),
+            TriviaKind::Formatting => options.preserve_formatting(),
+        });
+        self_mut
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct LinesWriter;
+
+    impl ObjectWriter<Vec<&str>> for LinesWriter {
+        type Error = std::io::Error;
+
+        fn write<W: Write>(&self, w: &mut W, object: &Vec<&str>) -> Result<(), Self::Error> {
+            self.write_with_trivia(w, object, &Trivia::default())
+        }
+    }
+
+    impl ObjectTriviaWriter<Vec<&str>> for LinesWriter {
+        fn write_with_trivia<W: Write>(
+            &self,
+            w: &mut W,
+            object: &Vec<&str>,
+            trivia: &Trivia,
+        ) -> Result<(), Self::Error> {
+            for (i, line) in object.iter().enumerate() {
+                let anchor = format!("/{i}");
+                for item in trivia.placed(&anchor, TriviaPlacement::Before) {
+                    writeln!(w, "#{}", item.text())?;
+                }
+                write!(w, "{line}")?;
+                for item in trivia.placed(&anchor, TriviaPlacement::After) {
+                    write!(w, " #{}", item.text())?;
+                }
+                writeln!(w)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_with_trivia() {
+        let trivia: Trivia = [
+            TriviaItem::comment("/0", TriviaPlacement::After, " first"),
+            TriviaItem::comment("/1", TriviaPlacement::Before, " second"),
+            TriviaItem::comment("/9", TriviaPlacement::Before, " discarded"),
+        ]
+        .into_iter()
+        .collect();
+        let mut buffer = Vec::new();
+        LinesWriter
+            .write_with_trivia(&mut buffer, &vec!["a", "b"], &trivia)
+            .unwrap();
+        assert_eq!(buffer, b"a # first\n# second\nb\n");
+    }
+
+    #[test]
+    fn test_retained() {
+        let trivia: Trivia = [
+            TriviaItem::comment("", TriviaPlacement::Inside, "comment"),
+            TriviaItem::formatting("/0", TriviaPlacement::After, "\n\n"),
+        ]
+        .into_iter()
+        .collect();
+        let options = CommonReaderOptions::default().with_preserve_formatting(true);
+        let retained = trivia.clone().retained(&options);
+        assert_eq!(retained.len(), 1);
+        assert_eq!(
+            retained.iter().next().unwrap().kind(),
+            TriviaKind::Formatting
+        );
+        assert!(trivia.retained(&CommonReaderOptions::default()).is_empty());
+    }
+}
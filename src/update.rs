@@ -0,0 +1,233 @@
+/*!
+Provides [`update_file`], which performs the read-modify-write of an object persisted in a file
+safely, and [`write_file_atomic`] on which it is built.
+
+The object is read, modified by a closure, serialized, and, only if the serialized content
+differs from the content of the file, written back. Writing is atomic: the content is written to
+a temporary file in the same directory, synced, and renamed over the original, so that readers
+never observe a partially written file and a failure leaves the original intact.
+[`update_file_locked`] additionally holds an exclusive lock on a `.lock` file alongside the
+target, so that concurrent updates by cooperating processes are serialized rather than lost.
+
+# Example
+
+```rust
+use objio::raw::{RawReader, RawWriter};
+use objio::update::update_file;
+use objio::ObjectWriter;
+
+let path = std::env::temp_dir().join(format!("objio-update-doc-{}.txt", std::process::id()));
+RawWriter.write_to_file(&"counter=1".to_string(), &path).unwrap();
+
+let changed = update_file(&path, &RawReader, &RawWriter, |text: &mut String| {
+    *text = text.replace("=1", "=2");
+})
+.unwrap();
+assert!(changed);
+
+let changed = update_file(&path, &RawReader, &RawWriter, |_: &mut String| {}).unwrap();
+assert!(!changed);
+# std::fs::remove_file(&path).unwrap();
+```
+ */
+
+use crate::{ObjectReader, ObjectWriter};
+use std::fs::{File, OpenOptions};
+use std::io::{Error as IoError, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The extension appended to the path of a file to name its lock file.
+///
+pub const LOCK_FILE_EXTENSION: &str = "lock";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read the object in the file at `path` using `reader`, apply `update` to it, and write it back
+/// atomically using `writer`, returning `true` if the content of the file changed.
+///
+/// Change is determined by comparing the serialized object with the original content, so a
+/// writer that normalizes its output may report a change even when `update` did nothing.
+///
+pub fn update_file<P, R, W, T, F>(
+    path: P,
+    reader: &R,
+    writer: &W,
+    update: F,
+) -> Result<bool, W::Error>
+where
+    P: AsRef<Path>,
+    R: ObjectReader<T> + ?Sized,
+    W: ObjectWriter<T> + ?Sized,
+    W::Error: From<R::Error>,
+    F: FnOnce(&mut T),
+{
+    let path = path.as_ref();
+    writer.validate_options()?;
+    let original = std::fs::read(path)?;
+    let mut object = reader.read_from_bytes(&original)?;
+    update(&mut object);
+    let updated = writer.write_to_bytes(&object)?;
+    if updated == original {
+        Ok(false)
+    } else {
+        replace_file(path, &updated)?;
+        Ok(true)
+    }
+}
+
+///
+/// Perform [`update_file`] while holding an exclusive lock on the lock file alongside `path`,
+/// blocking until the lock is available. The lock file is created if necessary and is not
+/// removed, as removing it would allow two processes to hold locks on different files.
+///
+pub fn update_file_locked<P, R, W, T, F>(
+    path: P,
+    reader: &R,
+    writer: &W,
+    update: F,
+) -> Result<bool, W::Error>
+where
+    P: AsRef<Path>,
+    R: ObjectReader<T> + ?Sized,
+    W: ObjectWriter<T> + ?Sized,
+    W::Error: From<R::Error>,
+    F: FnOnce(&mut T),
+{
+    let lock = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(lock_path(path.as_ref()))?;
+    lock.lock()?;
+    let result = update_file(path, reader, writer, update);
+    lock.unlock()?;
+    result
+}
+
+///
+/// Write `object` using `writer` into the file at `path`, atomically replacing any existing
+/// file, whose permissions are retained.
+///
+pub fn write_file_atomic<W, T, P>(writer: &W, object: &T, path: P) -> Result<(), W::Error>
+where
+    W: ObjectWriter<T> + ?Sized,
+    P: AsRef<Path>,
+{
+    writer.validate_options()?;
+    let content = writer.write_to_bytes(object)?;
+    Ok(replace_file(path.as_ref(), &content)?)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+pub(crate) fn replace_file(path: &Path, content: &[u8]) -> Result<(), IoError> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = path.file_name().ok_or_else(|| {
+        IoError::new(
+            ErrorKind::InvalidInput,
+            format!("the path `{}` does not name a file", path.display()),
+        )
+    })?;
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let temp_path = directory.join(format!(
+        ".{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)?;
+        file.write_all(content)?;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&temp_path, path)
+    })();
+    match result {
+        Ok(()) => {
+            // Persist the rename itself; directories cannot be opened for syncing everywhere.
+            if let Ok(directory) = File::open(&directory) {
+                let _ = directory.sync_all();
+            }
+            Ok(())
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp_path);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{LOCK_FILE_EXTENSION}"));
+    PathBuf::from(name)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::{RawReader, RawWriter};
+
+    #[test]
+    fn test_failed_update_leaves_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("invalid");
+        std::fs::write(&path, [0xFF]).unwrap();
+        let error = update_file(&path, &RawReader, &RawWriter, |text: &mut String| {
+            text.push('!');
+        })
+        .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(std::fs::read(&path).unwrap(), vec![0xFF]);
+        std::fs::remove_file(&path).unwrap();
+
+        let error = update_file(&path, &RawReader, &RawWriter, |_: &mut Vec<u8>| {}).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_concurrent_locked_updates() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("locked");
+        write_file_atomic(&RawWriter, &"0".to_string(), &path).unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_file_locked(&path, &RawReader, &RawWriter, |text: &mut String| {
+                        *text = (text.parse::<u32>().unwrap() + 1).to_string();
+                    })
+                    .unwrap()
+                })
+            })
+            .collect();
+        assert!(threads.into_iter().all(|thread| thread.join().unwrap()));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "8");
+    }
+}
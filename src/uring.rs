@@ -0,0 +1,451 @@
+/*!
+Provides [`UringFiles`], enabled by the `uring` feature on Linux, which reads and writes whole
+files using [io_uring](https://kernel.dk/io_uring.pdf), submitting the IO for many files at once so
+that the cost of system calls is shared across a batch.
+
+The functions [`read_objects_from_files`] and [`write_objects_to_files`] apply a reader or writer
+to a batch of files in this manner. As io_uring may be unavailable, for example on older kernels
+or where it is disabled by a security policy, both fall back to the standard file IO used by
+[`read_from_file`](crate::ObjectReader::read_from_file) and
+[`write_to_file`](crate::ObjectWriter::write_to_file) when a ring cannot be created.
+
+# Example
+
+```rust,no_run
+use objio::uring::{read_objects_from_files, write_objects_to_files};
+use objio::{ObjectReader, ObjectWriter};
+use std::io::{Read, Write};
+
+#[derive(Debug, Default)]
+struct TextIo;
+
+impl ObjectReader<String> for TextIo {
+    type Error = std::io::Error;
+
+    fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        Ok(text)
+    }
+}
+
+impl ObjectWriter<String> for TextIo {
+    type Error = std::io::Error;
+
+    fn write<W: Write>(&self, w: &mut W, object: &String) -> Result<(), Self::Error> {
+        w.write_all(object.as_bytes())
+    }
+}
+
+let objects = ["one".to_string(), "two".to_string()];
+let paths = ["one.txt", "two.txt"];
+write_objects_to_files(&TextIo, paths.iter().zip(&objects));
+
+let read: Vec<String> = read_objects_from_files(&TextIo, paths)
+    .into_iter()
+    .collect::<Result<_, _>>()
+    .unwrap();
+assert_eq!(read, objects);
+```
+ */
+
+use crate::{ObjectReader, ObjectWriter};
+use io_uring::{opcode, types, IoUring};
+use std::fmt::{Debug, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{Error as IoError, ErrorKind, Read};
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An io_uring instance used to read and write batches of whole files.
+///
+pub struct UringFiles {
+    ring: IoUring,
+}
+
+///
+/// The default number of submission queue entries used by [`UringFiles::new`].
+///
+pub const DEFAULT_QUEUE_DEPTH: u32 = 64;
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The transfer of a whole buffer to or from a file, which may take several operations.
+///
+struct Transfer {
+    file: Option<File>,
+    buffer: Vec<u8>,
+    position: usize,
+    result: Option<Result<(), IoError>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Read,
+    Write,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read an instance of `T` from each of `paths`, in order, using `reader`. The content of the files
+/// is read with io_uring if available, and then parsed.
+///
+pub fn read_objects_from_files<O, T, I, P>(reader: &O, paths: I) -> Vec<Result<T, O::Error>>
+where
+    O: ObjectReader<T>,
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let paths: Vec<P> = paths.into_iter().collect();
+    match UringFiles::new() {
+        Ok(mut files) => files
+            .read_files(&paths)
+            .into_iter()
+            .map(|bytes| reader.read_from_bytes(bytes?))
+            .collect(),
+        Err(_) => paths
+            .iter()
+            .map(|path| reader.read_from_file(path))
+            .collect(),
+    }
+}
+
+///
+/// Write each object to its path, in order, using `writer`. The objects are serialized, and then
+/// written with io_uring if available.
+///
+pub fn write_objects_to_files<'a, O, T, I, P>(writer: &O, files: I) -> Vec<Result<(), O::Error>>
+where
+    O: ObjectWriter<T>,
+    T: 'a,
+    I: IntoIterator<Item = (P, &'a T)>,
+    P: AsRef<Path>,
+{
+    let mut uring = UringFiles::new().ok();
+    let mut results: Vec<Result<(), O::Error>> = Vec::new();
+    let mut pending = Vec::new();
+    for (path, object) in files {
+        match writer.write_to_bytes(object) {
+            Ok(bytes) if uring.is_some() => {
+                pending.push((results.len(), path, bytes));
+                results.push(Ok(()));
+            }
+            Ok(bytes) => results.push(std::fs::write(path, bytes).map_err(Into::into)),
+            Err(e) => results.push(Err(e)),
+        }
+    }
+    if let Some(files) = uring.as_mut() {
+        let indices: Vec<usize> = pending.iter().map(|(index, _, _)| *index).collect();
+        let written = files.write_files(pending.into_iter().map(|(_, path, bytes)| (path, bytes)));
+        for (index, result) in indices.into_iter().zip(written) {
+            results[index] = result.map_err(Into::into);
+        }
+    }
+    results
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for UringFiles {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UringFiles")
+            .field("queue_depth", &self.ring.params().sq_entries())
+            .finish()
+    }
+}
+
+impl UringFiles {
+    ///
+    /// Create a new ring with [`DEFAULT_QUEUE_DEPTH`] entries; this fails if io_uring is not
+    /// available.
+    ///
+    pub fn new() -> Result<Self, IoError> {
+        Self::with_queue_depth(DEFAULT_QUEUE_DEPTH)
+    }
+
+    ///
+    /// Create a new ring with `entries` submission queue entries, which the kernel rounds up to a
+    /// power of two; this fails if io_uring is not available.
+    ///
+    pub fn with_queue_depth(entries: u32) -> Result<Self, IoError> {
+        Ok(Self {
+            ring: IoUring::new(entries.max(1))?,
+        })
+    }
+
+    ///
+    /// Read the whole of each of `paths`, returning the content or error for each in order. Files
+    /// whose size is not known in advance, such as those reporting a size of zero, are read with
+    /// standard file IO.
+    ///
+    pub fn read_files<I, P>(&mut self, paths: I) -> Vec<Result<Vec<u8>, IoError>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut transfers: Vec<Transfer> = paths
+            .into_iter()
+            .map(|path| Transfer::open_for_read(path.as_ref()))
+            .collect();
+        self.run(&mut transfers, Direction::Read);
+        transfers
+            .into_iter()
+            .map(|transfer| {
+                let buffer = transfer.buffer;
+                transfer.result.unwrap_or(Ok(())).map(|_| buffer)
+            })
+            .collect()
+    }
+
+    ///
+    /// Create, or truncate, each file and write the whole of its content, returning the result
+    /// for each in order.
+    ///
+    pub fn write_files<I, P, B>(&mut self, files: I) -> Vec<Result<(), IoError>>
+    where
+        I: IntoIterator<Item = (P, B)>,
+        P: AsRef<Path>,
+        B: Into<Vec<u8>>,
+    {
+        let mut transfers: Vec<Transfer> = files
+            .into_iter()
+            .map(|(path, content)| Transfer::open_for_write(path.as_ref(), content.into()))
+            .collect();
+        self.run(&mut transfers, Direction::Write);
+        transfers
+            .into_iter()
+            .map(|transfer| transfer.result.unwrap_or(Ok(())))
+            .collect()
+    }
+
+    ///
+    /// Submit operations for every incomplete transfer, resubmitting short transfers, until all
+    /// are complete.
+    ///
+    fn run(&mut self, transfers: &mut [Transfer], direction: Direction) {
+        let mut queued: Vec<usize> = (0..transfers.len())
+            .filter(|i| transfers[*i].result.is_none())
+            .rev()
+            .collect();
+        let mut in_flight = 0;
+        while !queued.is_empty() || in_flight > 0 {
+            {
+                let mut submission = self.ring.submission();
+                while !submission.is_full() {
+                    let Some(index) = queued.pop() else {
+                        break;
+                    };
+                    let entry = transfers[index].entry(direction).user_data(index as u64);
+                    // SAFETY: the file and the buffer of the transfer outlive the operation, as
+                    // this function does not return until every operation it submits completes,
+                    // and neither is moved or resized while the operation is in flight.
+                    #[allow(unsafe_code)]
+                    let pushed = unsafe { submission.push(&entry) };
+                    if pushed.is_err() {
+                        queued.push(index);
+                        break;
+                    }
+                    in_flight += 1;
+                }
+            }
+            match self.ring.submit_and_wait(1) {
+                Ok(_) => {}
+                Err(e) if matches!(e.kind(), ErrorKind::Interrupted | ErrorKind::ResourceBusy) => {}
+                Err(e) => {
+                    // The operations in flight cannot be cancelled, so their transfers are leaked
+                    // rather than released while the kernel may still use them.
+                    for transfer in transfers.iter_mut().filter(|t| t.result.is_none()) {
+                        std::mem::forget(std::mem::take(&mut transfer.buffer));
+                        std::mem::forget(transfer.file.take());
+                        transfer.result = Some(Err(IoError::new(e.kind(), e.to_string())));
+                    }
+                    return;
+                }
+            }
+            for completion in self.ring.completion() {
+                in_flight -= 1;
+                let index = completion.user_data() as usize;
+                let transfer = &mut transfers[index];
+                match completion.result() {
+                    result if result < 0 => {
+                        transfer.result = Some(Err(IoError::from_raw_os_error(-result)))
+                    }
+                    0 if direction == Direction::Read => {
+                        // The file was truncated after its size was read.
+                        transfer.buffer.truncate(transfer.position);
+                        transfer.result = Some(Ok(()));
+                    }
+                    0 => transfer.result = Some(Err(ErrorKind::WriteZero.into())),
+                    count => {
+                        transfer.position += count as usize;
+                        if transfer.position == transfer.buffer.len() {
+                            transfer.result = Some(Ok(()));
+                        } else {
+                            queued.push(index);
+                        }
+                    }
+                }
+                if transfer.result.is_some() {
+                    transfer.file = None;
+                }
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Transfer {
+    fn open_for_read(path: &Path) -> Self {
+        let opened = File::open(path).and_then(|file| {
+            let length = file.metadata()?.len();
+            Ok((file, usize::try_from(length).map_err(IoError::other)?))
+        });
+        match opened {
+            Ok((mut file, 0)) => {
+                let mut buffer = Vec::new();
+                let result = file.read_to_end(&mut buffer).map(|_| ());
+                Self::complete(buffer, result)
+            }
+            Ok((file, length)) => Self {
+                file: Some(file),
+                buffer: vec![0; length],
+                position: 0,
+                result: None,
+            },
+            Err(e) => Self::complete(Vec::new(), Err(e)),
+        }
+    }
+
+    fn open_for_write(path: &Path, buffer: Vec<u8>) -> Self {
+        let opened = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path);
+        match opened {
+            Ok(_) if buffer.is_empty() => Self::complete(buffer, Ok(())),
+            Ok(file) => Self {
+                file: Some(file),
+                buffer,
+                position: 0,
+                result: None,
+            },
+            Err(e) => Self::complete(buffer, Err(e)),
+        }
+    }
+
+    fn complete(buffer: Vec<u8>, result: Result<(), IoError>) -> Self {
+        Self {
+            file: None,
+            buffer,
+            position: 0,
+            result: Some(result),
+        }
+    }
+
+    fn entry(&mut self, direction: Direction) -> io_uring::squeue::Entry {
+        let fd = types::Fd(
+            self.file
+                .as_ref()
+                .expect("incomplete transfers have a file")
+                .as_raw_fd(),
+        );
+        // Each operation transfers at most 1 GiB, within the limits of both io_uring and Linux.
+        let length = (self.buffer.len() - self.position).min(1 << 30) as u32;
+        let offset = self.position as u64;
+        match direction {
+            Direction::Read => {
+                let buffer = self.buffer[self.position..].as_mut_ptr();
+                opcode::Read::new(fd, buffer, length).offset(offset).build()
+            }
+            Direction::Write => {
+                let buffer = self.buffer[self.position..].as_ptr();
+                opcode::Write::new(fd, buffer, length)
+                    .offset(offset)
+                    .build()
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TextIo;
+
+    impl ObjectReader<String> for TextIo {
+        type Error = crate::Error;
+
+        fn read<R: Read>(&self, r: &mut R) -> Result<String, Self::Error> {
+            let mut text = String::new();
+            let _ = r.read_to_string(&mut text)?;
+            Ok(text)
+        }
+    }
+
+    impl ObjectWriter<String> for TextIo {
+        type Error = crate::Error;
+
+        fn write<W: std::io::Write>(&self, w: &mut W, object: &String) -> Result<(), Self::Error> {
+            Ok(w.write_all(object.as_bytes())?)
+        }
+    }
+
+    #[test]
+    fn test_round_trip_objects() {
+        let directory = std::env::temp_dir().join(format!("objio-uring-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let objects: Vec<String> = (0..10)
+            .map(|i| format!("object {i}\n").repeat(i * 1000))
+            .collect();
+        let paths: Vec<_> = (0..10)
+            .map(|i| directory.join(format!("{i}.txt")))
+            .collect();
+
+        let written = write_objects_to_files(&TextIo, paths.iter().zip(&objects));
+        assert!(written.iter().all(Result::is_ok));
+
+        let mut missing = paths.clone();
+        missing.push(directory.join("missing.txt"));
+        let mut read = read_objects_from_files(&TextIo, &missing);
+        assert!(read.pop().unwrap().is_err());
+        let read: Vec<String> = read.into_iter().map(Result::unwrap).collect();
+        assert_eq!(read, objects);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_read_files() {
+        let Ok(mut files) = UringFiles::with_queue_depth(2) else {
+            // io_uring is not available in this environment.
+            return;
+        };
+        let path = std::env::temp_dir().join(format!("objio-uring-{}.bin", std::process::id()));
+        let content: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+        assert!(files.write_files([(&path, content.clone())])[0].is_ok());
+        let read = files.read_files([&path, &path, &path, &path]);
+        assert!(read.iter().all(|bytes| bytes.as_ref().unwrap() == &content));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
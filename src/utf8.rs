@@ -0,0 +1,427 @@
+/*!
+Provides [`Utf8Sink`], an in-memory destination that validates UTF-8 incrementally as bytes are
+written, so that a writer emitting invalid bytes fails at the offending write, with the offset of
+the invalid sequence, rather than when the complete output is converted to a string.
+
+A multi-byte character may be split across writes; the incomplete sequence is held until the
+following write completes it, and reported by [`finish`](Utf8Sink::finish) if it never is. This
+sink is used by [`ObjectWriter::write_to_string`](crate::ObjectWriter::write_to_string).
+
+Validation is performed by [`from_utf8`]; with the `simdutf8` feature enabled it uses the SIMD
+implementation of the `simdutf8` crate, falling back to the standard library only to describe an
+invalid sequence, which on large documents is considerably faster.
+
+For reading, [`decode_utf8`] converts bytes into a string according to a
+[`ReadMode`]; in lossy mode each invalid sequence is replaced with `U+FFFD` and reported as a
+warning, rather than failing the read. [`Utf8Reader`] reads text using the
+[`read_mode`](crate::options::CommonReaderOptions::read_mode) reader option.
+
+# Example
+
+```rust
+use objio::utf8::{InvalidUtf8, Utf8Sink};
+use std::io::Write;
+
+let mut sink = Utf8Sink::default();
+sink.write_all("caf".as_bytes()).unwrap();
+sink.write_all(&[0xC3]).unwrap();
+sink.write_all(&[0xA9]).unwrap();
+assert_eq!(sink.finish().unwrap(), "café");
+
+let mut sink = Utf8Sink::default();
+let error = sink.write_all(b"ok \xFF").unwrap_err();
+let invalid = error.get_ref().unwrap().downcast_ref::<InvalidUtf8>().unwrap();
+assert_eq!(invalid.offset(), 3);
+```
+
+```rust
+use objio::options::{CommonReaderOptions, ReadMode};
+use objio::utf8::Utf8Reader;
+use objio::{HasOptions, ObjectReader};
+
+let reader = Utf8Reader::default();
+assert!(reader.read_from_bytes(b"caf\xE9").is_err());
+
+let reader = reader.with_options(CommonReaderOptions::default().with_read_mode(ReadMode::Lossy));
+assert_eq!(reader.read_from_bytes(b"caf\xE9").unwrap(), "caf\u{FFFD}");
+```
+ */
+
+use crate::diagnostics::{DiagnosticSink, IgnoreDiagnostics};
+use crate::error::Error;
+use crate::options::{CommonReaderOptions, ReadMode};
+use crate::{HasOptions, ObjectReader};
+use std::fmt::{Debug, Display, Formatter};
+use std::io::{Error as IoError, ErrorKind, Read, Write};
+use std::str::Utf8Error;
+use std::sync::Arc;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A destination that accumulates written bytes into a `String`, validating them as UTF-8.
+///
+#[derive(Debug, Default)]
+pub struct Utf8Sink {
+    output: String,
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+///
+/// The error, carried within an I/O error of kind `InvalidData`, returned by [`Utf8Sink`] when the
+/// bytes written are not valid UTF-8.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidUtf8 {
+    offset: usize,
+    incomplete: bool,
+}
+
+///
+/// A reader of UTF-8 text that, in lossy mode, replaces invalid sequences rather than failing.
+///
+#[derive(Clone, Default)]
+pub struct Utf8Reader {
+    options: CommonReaderOptions,
+    sink: Option<Arc<dyn DiagnosticSink + Send + Sync>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Convert `bytes` into a string slice, as `std::str::from_utf8` does, using SIMD validation when
+/// the `simdutf8` feature is enabled.
+///
+#[inline]
+pub fn from_utf8(bytes: &[u8]) -> Result<&str, Utf8Error> {
+    #[cfg(feature = "simdutf8")]
+    if let Ok(text) = simdutf8::basic::from_utf8(bytes) {
+        return Ok(text);
+    }
+    std::str::from_utf8(bytes)
+}
+
+///
+/// Convert `bytes` into a string. An invalid sequence is a deserialization error of the named
+/// format for [`ReadMode::Lossless`]; for [`ReadMode::Lossy`] each is replaced with `U+FFFD` and
+/// reported as a warning to `sink`.
+///
+pub fn decode_utf8(
+    bytes: Vec<u8>,
+    mode: ReadMode,
+    format: &'static str,
+    sink: &dyn DiagnosticSink,
+) -> Result<String, Error> {
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return Ok(text),
+        Err(e) => e.into_bytes(),
+    };
+    let mut text = String::with_capacity(bytes.len());
+    let mut offset = 0;
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(chunk.valid());
+        offset += chunk.valid().len();
+        if !chunk.invalid().is_empty() {
+            text.push(mode.substitute(
+                format,
+                format!("invalid UTF-8 sequence at byte offset {offset}"),
+                char::REPLACEMENT_CHARACTER,
+                sink,
+            )?);
+            offset += chunk.invalid().len();
+        }
+    }
+    Ok(text)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Write for Utf8Sink {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.pending_len == 0 {
+            if let Ok(s) = from_utf8(buf) {
+                self.output.push_str(s);
+                return Ok(buf.len());
+            }
+        }
+        let mut input = buf;
+        while self.pending_len > 0 {
+            let Some((first, rest)) = input.split_first() else {
+                return Ok(buf.len());
+            };
+            self.pending[self.pending_len] = *first;
+            self.pending_len += 1;
+            input = rest;
+            match from_utf8(&self.pending[..self.pending_len]) {
+                Ok(s) => {
+                    self.output.push_str(s);
+                    self.pending_len = 0;
+                }
+                Err(e) if e.error_len().is_none() => {}
+                Err(_) => return Err(self.invalid(false)),
+            }
+        }
+        match from_utf8(input) {
+            Ok(s) => self.output.push_str(s),
+            Err(e) => {
+                let (valid, rest) = input.split_at(e.valid_up_to());
+                self.output
+                    .push_str(from_utf8(valid).expect("validated prefix"));
+                if e.error_len().is_some() {
+                    return Err(self.invalid(false));
+                }
+                self.pending[..rest.len()].copy_from_slice(rest);
+                self.pending_len = rest.len();
+            }
+        }
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.write(buf).map(|_| ())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Utf8Sink {
+    ///
+    /// Construct a new sink with space for at least `capacity` bytes.
+    ///
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            output: String::with_capacity(capacity),
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Returns the number of valid bytes written so far, excluding any incomplete character.
+    ///
+    pub fn len(&self) -> usize {
+        self.output.len()
+    }
+
+    ///
+    /// Returns `true` if no valid bytes have been written.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.output.is_empty()
+    }
+
+    ///
+    /// Returns the accumulated string, or an error if the output ends part-way through a
+    /// character.
+    ///
+    pub fn finish(self) -> Result<String, IoError> {
+        if self.pending_len > 0 {
+            Err(self.invalid(true))
+        } else {
+            Ok(self.output)
+        }
+    }
+
+    fn invalid(&self, incomplete: bool) -> IoError {
+        IoError::new(
+            ErrorKind::InvalidData,
+            InvalidUtf8 {
+                offset: self.output.len(),
+                incomplete,
+            },
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for InvalidUtf8 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.incomplete {
+            write!(
+                f,
+                "output ends with an incomplete UTF-8 sequence at byte offset {}",
+                self.offset
+            )
+        } else {
+            write!(f, "invalid UTF-8 sequence at byte offset {}", self.offset)
+        }
+    }
+}
+
+impl std::error::Error for InvalidUtf8 {}
+
+impl InvalidUtf8 {
+    ///
+    /// Returns the offset, in bytes from the start of the output, of the invalid sequence.
+    ///
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    ///
+    /// Returns `true` if the output ended part-way through a character, rather than containing an
+    /// invalid sequence.
+    ///
+    pub fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for Utf8Reader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Utf8Reader")
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HasOptions<CommonReaderOptions> for Utf8Reader {
+    fn options(&self) -> &CommonReaderOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonReaderOptions {
+        &mut self.options
+    }
+}
+
+impl ObjectReader<String> for Utf8Reader {
+    type Error = Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<String, Self::Error>
+    where
+        R: Read,
+    {
+        let mut bytes = Vec::new();
+        let _ = r.read_to_end(&mut bytes)?;
+        let sink: &dyn DiagnosticSink = match &self.sink {
+            Some(sink) => sink.as_ref(),
+            None => &IgnoreDiagnostics,
+        };
+        decode_utf8(bytes, self.options.read_mode(), "UTF-8", sink)
+    }
+}
+
+impl Utf8Reader {
+    ///
+    /// Returns this reader with warnings about replaced sequences reported to `sink`; by default
+    /// they are discarded.
+    ///
+    pub fn with_diagnostics(self, sink: Arc<dyn DiagnosticSink + Send + Sync>) -> Self {
+        let mut self_mut = self;
+        self_mut.sink = Some(sink);
+        self_mut
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid(e: &IoError) -> InvalidUtf8 {
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+        *e.get_ref().unwrap().downcast_ref::<InvalidUtf8>().unwrap()
+    }
+
+    #[test]
+    fn test_split_characters() {
+        let text = "a€𝄞b";
+        for split in text.bytes().enumerate().map(|(i, _)| i) {
+            let mut sink = Utf8Sink::default();
+            for byte in &text.as_bytes()[..split] {
+                sink.write_all(&[*byte]).unwrap();
+            }
+            sink.write_all(&text.as_bytes()[split..]).unwrap();
+            assert_eq!(sink.finish().unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn test_invalid_in_pending() {
+        let mut sink = Utf8Sink::default();
+        sink.write_all(b"ab\xE2\x82").unwrap();
+        let error = sink.write_all(b"c").unwrap_err();
+        assert_eq!(
+            invalid(&error),
+            InvalidUtf8 {
+                offset: 2,
+                incomplete: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_lossy() {
+        use crate::diagnostics::{CollectDiagnostics, Diagnostic};
+
+        let sink = Arc::new(CollectDiagnostics::default());
+        let reader = Utf8Reader::default()
+            .with_options(CommonReaderOptions::default().with_read_mode(ReadMode::Lossy))
+            .with_diagnostics(sink.clone());
+        assert_eq!(
+            reader.read_from_bytes(b"a\xFF\xFEb\xE2\x82").unwrap(),
+            "a\u{FFFD}\u{FFFD}b\u{FFFD}"
+        );
+        let diagnostics = sink.diagnostics();
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(
+            diagnostics[1],
+            Diagnostic::warning(
+                "invalid UTF-8 sequence at byte offset 2; a replacement value was substituted"
+            )
+        );
+
+        let error = decode_utf8(
+            b"a\xFF".to_vec(),
+            ReadMode::Lossless,
+            "text",
+            &IgnoreDiagnostics,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Deserialization { format: "text", .. }
+        ));
+    }
+
+    #[test]
+    fn test_from_utf8() {
+        let text = "héllo wörld ".repeat(100);
+        assert_eq!(from_utf8(text.as_bytes()).unwrap(), text);
+        let mut bytes = text.into_bytes();
+        bytes[500] = 0xFF;
+        let error = from_utf8(&bytes).unwrap_err();
+        assert_eq!(error, std::str::from_utf8(&bytes).unwrap_err());
+        assert!(error.valid_up_to() <= 500);
+    }
+
+    #[test]
+    fn test_incomplete_at_end() {
+        let mut sink = Utf8Sink::default();
+        sink.write_all(b"abc\xF0\x9D").unwrap();
+        assert_eq!(sink.len(), 3);
+        let error = sink.finish().unwrap_err();
+        assert!(invalid(&error).is_incomplete());
+        assert_eq!(
+            error.to_string(),
+            "output ends with an incomplete UTF-8 sequence at byte offset 3"
+        );
+    }
+}
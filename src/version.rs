@@ -0,0 +1,269 @@
+/*!
+Provides traits for readers and writers of formats that have evolved through a number of
+versions.
+
+A [`VersionedReader`] detects the version of its input, usually from a header, and dispatches to
+version-specific parsing; content with no detectable version is read as the current version. A
+[`VersionedWriter`] writes the current version unless an older [`FormatVersion`] is requested,
+usually through [`CommonWriterOptions::target_version`](crate::options::CommonWriterOptions::target_version).
+Requests for versions that are not supported are reported as [`Error::UnsupportedVersion`],
+carried through `io::Error` so that any reader or writer error type can represent it.
+
+# Example
+
+```rust
+use objio::options::FormatVersion;
+use objio::version::VersionedReader;
+use objio::ObjectReader;
+use std::io::{BufRead, Read};
+
+#[derive(Debug, Default)]
+struct NamesReader;
+
+impl ObjectReader<Vec<String>> for NamesReader {
+    type Error = objio::Error;
+
+    fn read<R>(&self, r: &mut R) -> Result<Vec<String>, Self::Error>
+    where
+        R: Read,
+    {
+        self.read_versioned(r)
+    }
+}
+
+impl VersionedReader<Vec<String>> for NamesReader {
+    fn current_version(&self) -> FormatVersion {
+        FormatVersion::new(2, 0)
+    }
+
+    fn detect_version<R>(&self, r: &mut R) -> Result<Option<FormatVersion>, Self::Error>
+    where
+        R: BufRead,
+    {
+        if r.fill_buf()?.starts_with(b"#v") {
+            let mut header = String::new();
+            r.read_line(&mut header)?;
+            Ok(Some(header[2..].trim().parse().map_err(std::io::Error::other)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn read_version<R>(&self, r: &mut R, version: FormatVersion) -> Result<Vec<String>, Self::Error>
+    where
+        R: BufRead,
+    {
+        let separator = if version.major() == 1 { ',' } else { '\n' };
+        let mut content = String::new();
+        r.read_to_string(&mut content)?;
+        Ok(content.split(separator).map(str::to_string).collect())
+    }
+}
+
+let reader = NamesReader;
+
+assert_eq!(reader.read_from_string("#v1\na,b").unwrap(), vec!["a", "b"]);
+assert_eq!(reader.read_from_string("a\nb").unwrap(), vec!["a", "b"]);
+assert!(reader.read_from_string("#v3.0\na").is_err());
+```
+ */
+
+use crate::error::Error;
+use crate::options::FormatVersion;
+use crate::{ObjectReader, ObjectWriter};
+use std::io::{BufRead, BufReader, Read, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The trait implemented by readers of a format with multiple versions.
+///
+pub trait VersionedReader<T>: ObjectReader<T> {
+    ///
+    /// Returns the latest version of the format supported by this reader.
+    ///
+    fn current_version(&self) -> FormatVersion;
+
+    ///
+    /// Returns `true` if this reader can read `version` of the format; the default
+    /// implementation supports any version up to, and including, the current version.
+    ///
+    fn is_supported_version(&self, version: FormatVersion) -> bool {
+        version <= self.current_version()
+    }
+
+    ///
+    /// Detect the version of the content in `r`, returning `None` if the content does not
+    /// identify its version. An implementation may consume a version header, but must not
+    /// consume any content required by [`read_version`](Self::read_version).
+    ///
+    fn detect_version<R>(&self, r: &mut R) -> Result<Option<FormatVersion>, Self::Error>
+    where
+        R: BufRead;
+
+    ///
+    /// Read an instance of `T` from `r` that is known to be in `version` of the format.
+    ///
+    fn read_version<R>(&self, r: &mut R, version: FormatVersion) -> Result<T, Self::Error>
+    where
+        R: BufRead;
+
+    ///
+    /// Read an instance of `T` from `r` by detecting the version of the content and dispatching
+    /// to [`read_version`](Self::read_version). Implementations of [`ObjectReader::read`] will
+    /// usually call this method.
+    ///
+    fn read_versioned<R>(&self, r: &mut R) -> Result<T, Self::Error>
+    where
+        R: Read,
+    {
+        let mut r = BufReader::new(r);
+        let version = self
+            .detect_version(&mut r)?
+            .unwrap_or_else(|| self.current_version());
+        if !self.is_supported_version(version) {
+            return Err(std::io::Error::from(Error::unsupported_version(version)).into());
+        }
+        self.read_version(&mut r, version)
+    }
+}
+
+///
+/// The trait implemented by writers of a format with multiple versions.
+///
+pub trait VersionedWriter<T>: ObjectWriter<T> {
+    ///
+    /// Returns the latest version of the format supported by this writer.
+    ///
+    fn current_version(&self) -> FormatVersion;
+
+    ///
+    /// Returns `true` if this writer can write `version` of the format; the default
+    /// implementation supports any version up to, and including, the current version.
+    ///
+    fn is_supported_version(&self, version: FormatVersion) -> bool {
+        version <= self.current_version()
+    }
+
+    ///
+    /// Returns the version of the format that has been requested, or `None` for the current
+    /// version. Writers with [`CommonWriterOptions`](crate::options::CommonWriterOptions) should
+    /// return the value of its `target_version`; the default implementation returns `None`.
+    ///
+    fn target_version(&self) -> Option<FormatVersion> {
+        None
+    }
+
+    ///
+    /// Write an instance of `T` to `w` in `version` of the format.
+    ///
+    fn write_version<W>(
+        &self,
+        w: &mut W,
+        object: &T,
+        version: FormatVersion,
+    ) -> Result<(), Self::Error>
+    where
+        W: Write;
+
+    ///
+    /// Write an instance of `T` to `w` in the target version of the format, dispatching to
+    /// [`write_version`](Self::write_version). Implementations of [`ObjectWriter::write`] will
+    /// usually call this method.
+    ///
+    fn write_versioned<W>(&self, w: &mut W, object: &T) -> Result<(), Self::Error>
+    where
+        W: Write,
+    {
+        let version = self
+            .target_version()
+            .unwrap_or_else(|| self.current_version());
+        if !self.is_supported_version(version) {
+            return Err(std::io::Error::from(Error::unsupported_version(version)).into());
+        }
+        self.write_version(w, object, version)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::CommonWriterOptions;
+    use crate::{impl_has_options, HasOptions};
+
+    #[derive(Debug, Default)]
+    struct TestWriter {
+        options: CommonWriterOptions,
+    }
+
+    impl_has_options!(TestWriter, CommonWriterOptions);
+
+    impl ObjectWriter<u32> for TestWriter {
+        type Error = Error;
+
+        fn write<W>(&self, w: &mut W, object: &u32) -> Result<(), Self::Error>
+        where
+            W: Write,
+        {
+            self.write_versioned(w, object)
+        }
+    }
+
+    impl VersionedWriter<u32> for TestWriter {
+        fn current_version(&self) -> FormatVersion {
+            FormatVersion::new(2, 1)
+        }
+
+        fn target_version(&self) -> Option<FormatVersion> {
+            self.options.target_version()
+        }
+
+        fn write_version<W>(
+            &self,
+            w: &mut W,
+            object: &u32,
+            version: FormatVersion,
+        ) -> Result<(), Self::Error>
+        where
+            W: Write,
+        {
+            if version.major() < 2 {
+                write!(w, "{object}")?;
+            } else {
+                write!(w, "#v{version}\n{object:#x}")?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_current_version() {
+        let writer = TestWriter::default();
+        assert_eq!(writer.write_to_string(&255).unwrap(), "#v2.1\n0xff");
+    }
+
+    #[test]
+    fn test_write_target_version() {
+        let writer = TestWriter::default().with_options(
+            CommonWriterOptions::default().with_target_version(Some(FormatVersion::new(1, 0))),
+        );
+        assert_eq!(writer.write_to_string(&255).unwrap(), "255");
+    }
+
+    #[test]
+    fn test_write_unsupported_version() {
+        let writer = TestWriter::default().with_options(
+            CommonWriterOptions::default().with_target_version(Some(FormatVersion::new(3, 0))),
+        );
+        assert!(matches!(
+            writer.write_to_string(&255),
+            Err(Error::UnsupportedVersion { version }) if version == FormatVersion::new(3, 0)
+        ));
+    }
+}
@@ -0,0 +1,345 @@
+/*!
+Provides utilities for writers that produce XML, including escaping of text and attribute values,
+management of the stack of open elements, and emission of namespace declarations.
+
+The [`XmlWriter`] type wraps any implementation of `Write` and ensures that elements are properly
+nested and closed, start tags are completed before content is written, and that namespaces
+declared with [`XmlWriter::declare_namespace`] are emitted on the next start tag. If the
+[`CommonWriterOptions`] specify pretty-printing then element content is indented, except within
+elements that also contain text where whitespace would be significant.
+
+# Example
+
+```rust
+use objio::xml::XmlWriter;
+
+let mut xml = XmlWriter::new(Vec::new());
+xml.declare_namespace(Some("ex"), "http://example.org/");
+xml.start_element("ex:root").unwrap();
+xml.attribute("id", "a&b").unwrap();
+xml.start_element("ex:child").unwrap();
+xml.text("1 < 2").unwrap();
+xml.end_element().unwrap();
+xml.start_element("ex:empty").unwrap();
+let bytes = xml.finish().unwrap();
+
+assert_eq!(
+    String::from_utf8(bytes).unwrap(),
+    r#"<ex:root xmlns:ex="http://example.org/" id="a&amp;b"><ex:child>1 &lt; 2</ex:child><ex:empty/></ex:root>"#
+);
+```
+ */
+
+use crate::escape::{escape_xml_attribute, escape_xml_text};
+use crate::options::CommonWriterOptions;
+use crate::HasOptions;
+use std::borrow::Cow;
+use std::io::{Error as IoError, ErrorKind, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A wrapper around an implementation of `Write` that manages the state required to write
+/// well-formed XML.
+///
+#[derive(Debug)]
+pub struct XmlWriter<W: Write> {
+    inner: W,
+    options: CommonWriterOptions,
+    stack: Vec<OpenElement>,
+    namespaces: Vec<(Option<String>, String)>,
+    start_tag_open: bool,
+    written: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct OpenElement {
+    name: String,
+    has_elements: bool,
+    has_text: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Escape the characters in `text` that may not appear literally in XML character data.
+///
+pub fn escape_text(text: &str) -> Cow<'_, str> {
+    escape_xml_text(text)
+}
+
+///
+/// Escape the characters in `value` that may not appear literally within a double-quoted
+/// attribute value.
+///
+pub fn escape_attribute(value: &str) -> Cow<'_, str> {
+    escape_xml_attribute(value)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<W: Write> HasOptions<CommonWriterOptions> for XmlWriter<W> {
+    fn options(&self) -> &CommonWriterOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut CommonWriterOptions {
+        &mut self.options
+    }
+}
+
+impl<W: Write> XmlWriter<W> {
+    ///
+    /// Construct a new XML writer wrapping `inner`.
+    ///
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            options: Default::default(),
+            stack: Default::default(),
+            namespaces: Default::default(),
+            start_tag_open: false,
+            written: false,
+        }
+    }
+
+    ///
+    /// Returns the number of currently open elements.
+    ///
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    ///
+    /// Write the standard XML declaration; this **must** be called before any other content is
+    /// written.
+    ///
+    pub fn xml_declaration(&mut self) -> Result<(), IoError> {
+        if self.written {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "the XML declaration must precede all other content",
+            ));
+        }
+        self.inner
+            .write_all(br#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        self.written = true;
+        Ok(())
+    }
+
+    ///
+    /// Declare a namespace, with an optional prefix, to be emitted on the next start tag. A
+    /// prefix of `None` declares the default namespace.
+    ///
+    pub fn declare_namespace<S>(&mut self, prefix: Option<S>, namespace: &str)
+    where
+        S: Into<String>,
+    {
+        self.namespaces
+            .push((prefix.map(|s| s.into()), namespace.to_string()));
+    }
+
+    ///
+    /// Write the start tag for a new element named `name`, which becomes the current element.
+    /// The start tag remains open for calls to [`Self::attribute`] until content is written or
+    /// the element is closed.
+    ///
+    pub fn start_element(&mut self, name: &str) -> Result<(), IoError> {
+        self.close_start_tag()?;
+        if let Some(parent) = self.stack.last_mut() {
+            parent.has_elements = true;
+        }
+        self.new_line()?;
+        write!(self.inner, "<{name}")?;
+        for (prefix, namespace) in std::mem::take(&mut self.namespaces) {
+            match prefix {
+                None => write!(self.inner, r#" xmlns="{}""#, escape_attribute(&namespace))?,
+                Some(prefix) => write!(
+                    self.inner,
+                    r#" xmlns:{prefix}="{}""#,
+                    escape_attribute(&namespace)
+                )?,
+            }
+        }
+        self.stack.push(OpenElement {
+            name: name.to_string(),
+            has_elements: false,
+            has_text: false,
+        });
+        self.start_tag_open = true;
+        self.written = true;
+        Ok(())
+    }
+
+    ///
+    /// Write an attribute on the current element; this will return an error if content has been
+    /// written to the current element.
+    ///
+    pub fn attribute(&mut self, name: &str, value: &str) -> Result<(), IoError> {
+        if !self.start_tag_open {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "attributes may only be written before element content",
+            ));
+        }
+        write!(self.inner, r#" {name}="{}""#, escape_attribute(value))
+    }
+
+    ///
+    /// Write escaped character data as content of the current element.
+    ///
+    pub fn text(&mut self, text: &str) -> Result<(), IoError> {
+        self.close_start_tag()?;
+        if let Some(current) = self.stack.last_mut() {
+            current.has_text = true;
+        }
+        self.inner.write_all(escape_text(text).as_bytes())
+    }
+
+    ///
+    /// Write a comment; the text **must not** contain the sequence `--`.
+    ///
+    pub fn comment(&mut self, text: &str) -> Result<(), IoError> {
+        if text.contains("--") {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "XML comments may not contain '--'",
+            ));
+        }
+        self.close_start_tag()?;
+        if let Some(parent) = self.stack.last_mut() {
+            parent.has_elements = true;
+        }
+        self.new_line()?;
+        self.written = true;
+        write!(self.inner, "<!--{text}-->")
+    }
+
+    ///
+    /// Close the current element, writing an empty-element tag if no content was written.
+    ///
+    pub fn end_element(&mut self) -> Result<(), IoError> {
+        let element = self.stack.pop().ok_or_else(|| {
+            IoError::new(ErrorKind::InvalidInput, "there is no open element to end")
+        })?;
+        if self.start_tag_open {
+            self.start_tag_open = false;
+            self.inner.write_all(b"/>")
+        } else {
+            if element.has_elements && !element.has_text && self.is_pretty() {
+                self.indent_line(self.stack.len())?;
+            }
+            write!(self.inner, "</{}>", element.name)
+        }
+    }
+
+    ///
+    /// Close all open elements, flush, and return the wrapped implementation of `Write`.
+    ///
+    pub fn finish(mut self) -> Result<W, IoError> {
+        while !self.stack.is_empty() {
+            self.end_element()?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    fn is_pretty(&self) -> bool {
+        self.options.is_pretty() && !self.stack.iter().any(|e| e.has_text)
+    }
+
+    fn close_start_tag(&mut self) -> Result<(), IoError> {
+        if self.start_tag_open {
+            self.start_tag_open = false;
+            self.inner.write_all(b">")?;
+        }
+        Ok(())
+    }
+
+    fn new_line(&mut self) -> Result<(), IoError> {
+        if self.written && self.is_pretty() {
+            self.indent_line(self.stack.len())?;
+        }
+        Ok(())
+    }
+
+    fn indent_line(&mut self, depth: usize) -> Result<(), IoError> {
+        let width = depth * self.options.indent();
+        let line_ending = self.options.line_ending().as_str();
+        write!(self.inner, "{line_ending}{:width$}", "")
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_string(xml: XmlWriter<Vec<u8>>) -> String {
+        String::from_utf8(xml.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert!(matches!(escape_text("plain"), Cow::Borrowed("plain")));
+        assert_eq!(escape_text(r#"a < "b" & c"#), r#"a &lt; "b" &amp; c"#);
+        assert_eq!(
+            escape_attribute("a \"b\"\n"),
+            "a &quot;b&quot;&#xA;".to_string()
+        );
+    }
+
+    #[test]
+    fn test_default_namespace_and_declaration() {
+        let mut xml = XmlWriter::new(Vec::new());
+        xml.xml_declaration().unwrap();
+        xml.declare_namespace(None::<String>, "http://example.org/");
+        xml.start_element("root").unwrap();
+        assert_eq!(
+            to_string(xml),
+            r#"<?xml version="1.0" encoding="UTF-8"?><root xmlns="http://example.org/"/>"#
+        );
+    }
+
+    #[test]
+    fn test_pretty_printing() {
+        let mut xml =
+            XmlWriter::new(Vec::new()).with_options(CommonWriterOptions::default().pretty());
+        xml.start_element("a").unwrap();
+        xml.start_element("b").unwrap();
+        xml.text("text").unwrap();
+        xml.end_element().unwrap();
+        xml.comment(" note ").unwrap();
+        xml.start_element("c").unwrap();
+        assert_eq!(
+            to_string(xml),
+            "<a>\n  <b>text</b>\n  <!-- note -->\n  <c/>\n</a>"
+        );
+    }
+
+    #[test]
+    fn test_invalid_sequences() {
+        let mut xml = XmlWriter::new(Vec::new());
+        assert!(xml.end_element().is_err());
+        xml.start_element("a").unwrap();
+        xml.text("text").unwrap();
+        assert!(xml.attribute("late", "value").is_err());
+        assert!(xml.comment("bad -- comment").is_err());
+        assert!(xml.xml_declaration().is_err());
+    }
+}